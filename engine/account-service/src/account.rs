@@ -1,8 +1,10 @@
 //! AccountService implementation
 
+use crate::api_key::ApiKeyRepository;
 use crate::balance::Balance;
 use crate::config::AccountServiceConfig;
 use crate::oauth::GoogleOAuthClient;
+use crate::order::OrderRepository;
 use crate::position::{Position, TradeSide};
 use crate::reservation::{Reservation, ReservationId, ReservationManager};
 use crate::sleeper::{LeagueOption, SleeperClient};
@@ -382,6 +384,25 @@ impl AccountService {
         Ok(())
     }
 
+    /// Release every active reservation held for an order; returns how many were released
+    pub async fn release_order_reservations(&self, order_id: i64) -> Result<usize> {
+        let released: Vec<i64> = sqlx::query_scalar!(
+            "UPDATE reservations SET status = 'cancelled'
+             WHERE order_id = $1 AND status = 'active'
+             RETURNING id",
+            order_id
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut manager = self.reservation_manager.lock().await;
+        for id in &released {
+            manager.remove_reservation(ReservationId(*id as u64));
+        }
+
+        Ok(released.len())
+    }
+
     /// Settle a trade (update balances and positions)
     pub async fn settle_trade(&self, trade_details: &TradeDetails) -> Result<()> {
         // Update account balance
@@ -604,6 +625,21 @@ impl AccountService {
         Ok(())
     }
 
+    /// API key repository backed by this service's database pool
+    pub fn api_keys(&self) -> ApiKeyRepository {
+        ApiKeyRepository::new(self.db_pool.clone())
+    }
+
+    /// Order record repository backed by this service's database pool
+    pub fn orders(&self) -> OrderRepository {
+        OrderRepository::new(self.db_pool.clone())
+    }
+
+    /// This service's database pool, for records kept alongside accounts (e.g. admin actions)
+    pub fn db_pool(&self) -> PgPool {
+        self.db_pool.clone()
+    }
+
     /// Health check
     pub async fn health_check(&self) -> Result<()> {
         // Check database connectivity
@@ -0,0 +1,442 @@
+//! Persistent API keys for programmatic access
+//!
+//! Secrets are never stored. A key's secret is derived with HKDF-SHA256 from the
+//! server master key (`API_KEY_MASTER_KEY`), a random per-key salt and the key
+//! identifier; the secret is the HMAC-SHA256 signing key for signed logins. The
+//! `api_keys` table keeps only the salt and a salted verifier for plain-secret
+//! logins, so reading it is not enough to sign requests. The plaintext secret is
+//! only returned once, when a key is created or rotated.
+
+use crate::{AccountServiceError, Result};
+use chrono::{DateTime, Utc};
+use hkdf::Hkdf;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Prefix for public API key identifiers
+pub const KEY_ID_PREFIX: &str = "ak_";
+
+/// Prefix for API secrets
+pub const SECRET_PREFIX: &str = "sk_";
+
+/// Environment variable holding the server master key secrets are derived from
+pub const MASTER_KEY_ENV: &str = "API_KEY_MASTER_KEY";
+
+/// Postgres notification channel carrying the ID of each key revoked or rotated, so
+/// processes holding sessions opened with the key can end them
+pub const INVALIDATED_CHANNEL: &str = "api_key_invalidated";
+
+/// HKDF info string for the stored verifier
+const VERIFIER_INFO: &[u8] = b"waiver-exchange api key verifier";
+
+/// Stored API key (never contains the secret)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    pub key_id: String,
+    pub account_id: i64,
+    pub label: String,
+    #[serde(skip_serializing)]
+    pub secret_hash: String,
+    #[serde(skip_serializing)]
+    pub secret_salt: String,
+    pub permissions: Vec<String>,
+    pub allowed_ips: Vec<String>,
+    pub allowed_symbols: Option<Vec<i32>>,
+    pub orders_per_second: i32,
+    pub market_data_per_second: i32,
+    pub burst_limit: i32,
+    pub created_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Check if the key has been revoked
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Check if the key is past its expiry time
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    /// Check if the key can currently be used to authenticate
+    pub fn is_usable(&self) -> bool {
+        !self.is_revoked() && !self.is_expired()
+    }
+
+    /// Check if the key grants a permission
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+}
+
+/// Parameters for creating a new API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewApiKey {
+    pub account_id: i64,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default = "default_permissions")]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
+    #[serde(default)]
+    pub allowed_symbols: Option<Vec<i32>>,
+    #[serde(default = "default_orders_per_second")]
+    pub orders_per_second: i32,
+    #[serde(default = "default_market_data_per_second")]
+    pub market_data_per_second: i32,
+    #[serde(default = "default_burst_limit")]
+    pub burst_limit: i32,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+fn default_permissions() -> Vec<String> {
+    vec!["trade".to_string(), "market_data".to_string()]
+}
+
+fn default_orders_per_second() -> i32 {
+    100
+}
+
+fn default_market_data_per_second() -> i32 {
+    1000
+}
+
+fn default_burst_limit() -> i32 {
+    10
+}
+
+/// Credentials handed back to the caller on create/rotate (shown once)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyCredentials {
+    pub key_id: String,
+    pub api_secret: String,
+}
+
+/// Salted verifier of an API secret for storage (hex-encoded HKDF-SHA256)
+pub fn hash_secret(secret: &str, salt: &str) -> String {
+    hex::encode(hkdf_sha256(salt, secret.as_bytes(), VERIFIER_INFO))
+}
+
+/// Derive the secret of a key from the master key, the key's salt and its identifier
+///
+/// The secret is also the key clients sign requests with, so the server recomputes it
+/// to verify a signature instead of storing it.
+pub fn derive_secret(master_key: &[u8], salt: &str, key_id: &str) -> String {
+    format!("{}{}", SECRET_PREFIX, hex::encode(hkdf_sha256(salt, master_key, key_id.as_bytes())))
+}
+
+fn hkdf_sha256(salt: &str, ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut okm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt.as_bytes()), ikm)
+        .expand(info, &mut okm)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    okm
+}
+
+/// Generate a new per-key salt
+pub fn generate_salt() -> String {
+    random_hex(16)
+}
+
+/// Generate a new public key identifier
+pub fn generate_key_id() -> String {
+    format!("{}{}", KEY_ID_PREFIX, random_hex(12))
+}
+
+fn random_hex(num_bytes: usize) -> String {
+    let mut bytes = vec![0u8; num_bytes];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Server master key API secrets are derived from (redacted in debug output)
+#[derive(Clone)]
+struct MasterKey(Arc<[u8]>);
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MasterKey(..)")
+    }
+}
+
+/// Database access for API keys
+#[derive(Debug, Clone)]
+pub struct ApiKeyRepository {
+    db_pool: PgPool,
+    master_key: Option<MasterKey>,
+}
+
+impl ApiKeyRepository {
+    /// Create a repository over an existing pool, taking the master key from
+    /// `API_KEY_MASTER_KEY`
+    ///
+    /// Without a master key, keys cannot be created, rotated or used for signed logins.
+    pub fn new(db_pool: PgPool) -> Self {
+        let master_key = std::env::var(MASTER_KEY_ENV).ok().filter(|key| !key.is_empty());
+        if master_key.is_none() {
+            tracing::warn!(
+                "{} is not set; API keys cannot be issued or sign requests",
+                MASTER_KEY_ENV
+            );
+        }
+        Self { db_pool, master_key: master_key.map(|key| MasterKey(key.into_bytes().into())) }
+    }
+
+    /// Create a repository with an explicit master key
+    pub fn with_master_key(db_pool: PgPool, master_key: &[u8]) -> Self {
+        Self { db_pool, master_key: Some(MasterKey(master_key.into())) }
+    }
+
+    /// The secret (and signing key) of a stored key
+    pub fn signing_key(&self, key: &ApiKey) -> Result<String> {
+        Ok(derive_secret(self.master_key()?, &key.secret_salt, &key.key_id))
+    }
+
+    fn master_key(&self) -> Result<&[u8]> {
+        self.master_key.as_ref().map(|key| &*key.0).ok_or_else(|| AccountServiceError::Internal {
+            message: format!("{MASTER_KEY_ENV} is not configured"),
+        })
+    }
+
+    /// Create a new API key and return its one-time credentials
+    ///
+    /// Fails with `AccountNotFound` if the key's account does not exist.
+    pub async fn create(&self, new_key: &NewApiKey) -> Result<(ApiKey, ApiKeyCredentials)> {
+        let key_id = generate_key_id();
+        let salt = generate_salt();
+        let secret = derive_secret(self.master_key()?, &salt, &key_id);
+
+        let key = sqlx::query_as!(
+            ApiKey,
+            "INSERT INTO api_keys
+             (key_id, account_id, label, secret_hash, secret_salt, permissions, allowed_ips,
+              allowed_symbols, orders_per_second, market_data_per_second, burst_limit, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             RETURNING *",
+            key_id,
+            new_key.account_id,
+            new_key.label,
+            hash_secret(&secret, &salt),
+            salt,
+            &new_key.permissions,
+            &new_key.allowed_ips,
+            new_key.allowed_symbols.as_deref(),
+            new_key.orders_per_second,
+            new_key.market_data_per_second,
+            new_key.burst_limit,
+            new_key.expires_at
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                AccountServiceError::AccountNotFound { account_id: new_key.account_id }
+            }
+            e => e.into(),
+        })?;
+
+        Ok((key, ApiKeyCredentials { key_id, api_secret: secret }))
+    }
+
+    /// Look up a key by its public identifier (including revoked keys)
+    pub async fn get(&self, key_id: &str) -> Result<Option<ApiKey>> {
+        let key = sqlx::query_as!(ApiKey, "SELECT * FROM api_keys WHERE key_id = $1", key_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+
+        Ok(key)
+    }
+
+    /// List keys, optionally restricted to one account
+    pub async fn list(&self, account_id: Option<i64>) -> Result<Vec<ApiKey>> {
+        let keys = sqlx::query_as!(
+            ApiKey,
+            "SELECT * FROM api_keys
+             WHERE ($1::BIGINT IS NULL OR account_id = $1)
+             ORDER BY created_at DESC",
+            account_id
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Revoke a key; returns false if it did not exist or was already revoked
+    pub async fn revoke(&self, key_id: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE api_keys SET revoked_at = now() WHERE key_id = $1 AND revoked_at IS NULL",
+            key_id
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        let revoked = result.rows_affected() > 0;
+        if revoked {
+            self.notify_invalidated(key_id).await?;
+        }
+        Ok(revoked)
+    }
+
+    /// Replace the secret of an active key, keeping its identifier and scopes; `None` if
+    /// there is no active key with that identifier
+    pub async fn rotate(&self, key_id: &str) -> Result<Option<ApiKeyCredentials>> {
+        let salt = generate_salt();
+        let secret = derive_secret(self.master_key()?, &salt, key_id);
+
+        let result = sqlx::query!(
+            "UPDATE api_keys SET secret_hash = $2, secret_salt = $3, rotated_at = now()
+             WHERE key_id = $1 AND revoked_at IS NULL",
+            key_id,
+            hash_secret(&secret, &salt),
+            salt
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        self.notify_invalidated(key_id).await?;
+        Ok(Some(ApiKeyCredentials { key_id: key_id.to_string(), api_secret: secret }))
+    }
+
+    /// Announce on `INVALIDATED_CHANNEL` that sessions opened with a key must end
+    async fn notify_invalidated(&self, key_id: &str) -> Result<()> {
+        sqlx::query!("SELECT pg_notify($1, $2)", INVALIDATED_CHANNEL, key_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Listen for keys revoked or rotated by any process (see `INVALIDATED_CHANNEL`)
+    pub async fn listen_invalidated(&self) -> Result<sqlx::postgres::PgListener> {
+        let mut listener = sqlx::postgres::PgListener::connect_with(&self.db_pool).await?;
+        listener.listen(INVALIDATED_CHANNEL).await?;
+        Ok(listener)
+    }
+
+    /// Record a successful authentication
+    pub async fn touch(&self, key_id: &str) -> Result<()> {
+        sqlx::query!("UPDATE api_keys SET last_used_at = now() WHERE key_id = $1", key_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_secret_is_salted() {
+        let verifier = hash_secret("abc", "salt-1");
+
+        assert_eq!(verifier.len(), 64);
+        assert_eq!(verifier, hash_secret("abc", "salt-1"));
+        assert_ne!(verifier, hash_secret("abd", "salt-1"));
+        assert_ne!(verifier, hash_secret("abc", "salt-2"));
+    }
+
+    #[test]
+    fn test_generated_credentials_are_prefixed_and_unique() {
+        let key_id = generate_key_id();
+        let salt = generate_salt();
+        let secret = derive_secret(b"master", &salt, &key_id);
+
+        assert!(key_id.starts_with(KEY_ID_PREFIX));
+        assert_eq!(key_id.len(), KEY_ID_PREFIX.len() + 24);
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert_eq!(secret.len(), SECRET_PREFIX.len() + 64);
+        assert_ne!(generate_salt(), salt);
+        assert_ne!(derive_secret(b"master", &generate_salt(), &key_id), secret);
+    }
+
+    #[test]
+    fn test_secret_needs_master_key_and_differs_from_verifier() {
+        let salt = generate_salt();
+        let secret = derive_secret(b"master", &salt, "ak_1");
+
+        // Stable for the same inputs, so the server can recompute it
+        assert_eq!(derive_secret(b"master", &salt, "ak_1"), secret);
+        assert_ne!(derive_secret(b"other", &salt, "ak_1"), secret);
+        assert_ne!(derive_secret(b"master", &salt, "ak_2"), secret);
+        // The stored verifier is not the signing key
+        assert_ne!(hash_secret(&secret, &salt), secret.trim_start_matches(SECRET_PREFIX));
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn test_rotate_and_revoke_are_announced() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        let keys = ApiKeyRepository::with_master_key(pool.clone(), b"master");
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('api-key-notify-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let new_key = NewApiKey {
+            account_id,
+            label: String::new(),
+            permissions: default_permissions(),
+            allowed_ips: vec![],
+            allowed_symbols: None,
+            orders_per_second: default_orders_per_second(),
+            market_data_per_second: default_market_data_per_second(),
+            burst_limit: default_burst_limit(),
+            expires_at: None,
+        };
+        let (key, _) = keys.create(&new_key).await.unwrap();
+        let mut listener = keys.listen_invalidated().await.unwrap();
+
+        keys.rotate(&key.key_id).await.unwrap().unwrap();
+        let rotated = listener.recv().await.unwrap();
+        let revoked = keys.revoke(&key.key_id).await.unwrap();
+        let notified = listener.recv().await.unwrap();
+        // Revoking again changes nothing and announces nothing; a revoked key cannot rotate
+        let revoked_again = keys.revoke(&key.key_id).await.unwrap();
+        let rotated_again = keys.rotate(&key.key_id).await.unwrap();
+        let unknown_account = keys.create(&NewApiKey { account_id: -1, ..new_key }).await;
+
+        sqlx::query("DELETE FROM api_keys WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(rotated.payload(), key.key_id);
+        assert!(revoked);
+        assert_eq!(notified.payload(), key.key_id);
+        assert!(!revoked_again);
+        assert!(rotated_again.is_none());
+        assert!(matches!(
+            unknown_account,
+            Err(AccountServiceError::AccountNotFound { account_id: -1 })
+        ));
+    }
+}
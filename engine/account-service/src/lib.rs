@@ -4,18 +4,22 @@
 //! positions, and risk validation in the waiver-exchange system.
 
 pub mod account;
+pub mod api_key;
 pub mod balance;
 pub mod config;
 pub mod error;
 pub mod oauth;
+pub mod order;
 pub mod position;
 pub mod reservation;
 pub mod sleeper;
 pub mod trade;
 
 pub use account::AccountService;
+pub use api_key::{ApiKey, ApiKeyCredentials, ApiKeyRepository, NewApiKey};
 pub use config::AccountServiceConfig;
 pub use error::AccountServiceError;
+pub use order::{NewOrderRecord, OrderInsert, OrderRecord, OrderRepository, OrderStatus};
 
 // Re-export commonly used types
 pub use balance::Balance;
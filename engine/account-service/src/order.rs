@@ -0,0 +1,563 @@
+//! Persistent order records
+//!
+//! The gateway records every order it routes so that `client_order_id` retries can be
+//! deduplicated across restarts, and the execution manager keeps the status current as
+//! fills and cancels come back from the engine.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Lifecycle status of an order record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    /// Recorded by the gateway, not yet routed
+    Pending,
+    /// Routed to the engine
+    Accepted,
+    /// Some quantity has traded
+    PartiallyFilled,
+    /// Fully traded
+    Filled,
+    /// Cancelled before filling completely
+    Cancelled,
+    /// Rejected by the gateway or router
+    Rejected,
+}
+
+impl OrderStatus {
+    /// Database/API representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "PENDING",
+            OrderStatus::Accepted => "ACCEPTED",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Cancelled => "CANCELLED",
+            OrderStatus::Rejected => "REJECTED",
+        }
+    }
+
+    /// Whether an order in this status may still rest on the book
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::Pending | OrderStatus::Accepted | OrderStatus::PartiallyFilled)
+    }
+}
+
+/// Stored order record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderRecord {
+    pub order_id: i64,
+    pub account_id: i64,
+    pub symbol_id: i32,
+    pub client_order_id: Option<String>,
+    pub side: String,
+    pub order_type: String,
+    pub price: i64,
+    pub quantity: i64,
+    pub filled_quantity: i64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// `GTC`, or `DAY` for orders cancelled when the trading session closes
+    pub time_in_force: String,
+}
+
+impl OrderRecord {
+    /// Check if the order may still rest on the book
+    pub fn is_open(&self) -> bool {
+        matches!(self.status.as_str(), "PENDING" | "ACCEPTED" | "PARTIALLY_FILLED")
+    }
+}
+
+/// Parameters for recording a new order
+#[derive(Debug, Clone)]
+pub struct NewOrderRecord {
+    pub order_id: i64,
+    pub account_id: i64,
+    pub symbol_id: i32,
+    pub client_order_id: Option<String>,
+    pub side: String,
+    pub order_type: String,
+    pub price: i64,
+    pub quantity: i64,
+    pub time_in_force: String,
+}
+
+/// Outcome of recording an order
+#[derive(Debug, Clone)]
+pub enum OrderInsert {
+    /// The order was recorded
+    Inserted(OrderRecord),
+    /// Another order already holds the `client_order_id`; nothing was recorded
+    Duplicate(OrderRecord),
+}
+
+/// Database access for order records
+#[derive(Debug, Clone)]
+pub struct OrderRepository {
+    db_pool: PgPool,
+}
+
+impl OrderRepository {
+    /// Create a repository over an existing pool
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Record a new order in `PENDING` status unless its `client_order_id` is taken
+    ///
+    /// A `client_order_id` is held by one order per account until `expires_at`, through its
+    /// `client_order_ids` mapping. An expired mapping is replaced by this order; a live one
+    /// makes this a duplicate and the order holding it is returned instead. Concurrent
+    /// submissions of the same key are settled by the mapping's primary key, so exactly one
+    /// of them is recorded. Orders keep their `client_order_id` after the mapping moves on.
+    pub async fn insert(
+        &self,
+        order: &NewOrderRecord,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OrderInsert> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let record = sqlx::query_as!(
+            OrderRecord,
+            "INSERT INTO orders
+             (order_id, account_id, symbol_id, client_order_id, side, order_type, price, quantity,
+              time_in_force)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING *",
+            order.order_id,
+            order.account_id,
+            order.symbol_id,
+            order.client_order_id,
+            order.side,
+            order.order_type,
+            order.price,
+            order.quantity,
+            order.time_in_force
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let Some(client_order_id) = &order.client_order_id else {
+            tx.commit().await?;
+            return Ok(OrderInsert::Inserted(record));
+        };
+
+        sqlx::query!(
+            "DELETE FROM client_order_ids
+             WHERE account_id = $1 AND client_order_id = $2 AND expires_at <= now()",
+            order.account_id,
+            client_order_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mapped = sqlx::query!(
+            "INSERT INTO client_order_ids (account_id, client_order_id, order_id, expires_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (account_id, client_order_id) DO NOTHING",
+            order.account_id,
+            client_order_id,
+            order.order_id,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if mapped {
+            tx.commit().await?;
+            return Ok(OrderInsert::Inserted(record));
+        }
+
+        // The key is held: record nothing and hand back its order
+        tx.rollback().await?;
+        let existing = sqlx::query_as!(
+            OrderRecord,
+            "SELECT o.* FROM orders o
+             JOIN client_order_ids c ON c.order_id = o.order_id
+             WHERE c.account_id = $1 AND c.client_order_id = $2",
+            order.account_id,
+            client_order_id
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+        Ok(OrderInsert::Duplicate(existing))
+    }
+
+    /// Look up an order by engine order ID
+    pub async fn get(&self, order_id: i64) -> Result<Option<OrderRecord>> {
+        let record =
+            sqlx::query_as!(OrderRecord, "SELECT * FROM orders WHERE order_id = $1", order_id)
+                .fetch_optional(&self.db_pool)
+                .await?;
+
+        Ok(record)
+    }
+
+    /// Find the order holding a client order ID, while its mapping has not expired
+    pub async fn find_duplicate(
+        &self,
+        account_id: i64,
+        client_order_id: &str,
+    ) -> Result<Option<OrderRecord>> {
+        let record = sqlx::query_as!(
+            OrderRecord,
+            "SELECT o.* FROM orders o
+             JOIN client_order_ids c ON c.order_id = o.order_id
+             WHERE c.account_id = $1 AND c.client_order_id = $2 AND c.expires_at > now()",
+            account_id,
+            client_order_id
+        )
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Find the most recent order placed with a client order ID, however long ago
+    pub async fn find_by_client_order_id(
+        &self,
+        account_id: i64,
+        client_order_id: &str,
+    ) -> Result<Option<OrderRecord>> {
+        let record = sqlx::query_as!(
+            OrderRecord,
+            "SELECT * FROM orders
+             WHERE account_id = $1 AND client_order_id = $2
+             ORDER BY created_at DESC
+             LIMIT 1",
+            account_id,
+            client_order_id
+        )
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// List an account's orders, newest first, optionally only those still open
+    pub async fn list(
+        &self,
+        account_id: i64,
+        open_only: bool,
+        limit: i64,
+    ) -> Result<Vec<OrderRecord>> {
+        let records = sqlx::query_as!(
+            OrderRecord,
+            "SELECT * FROM orders
+             WHERE account_id = $1
+               AND (NOT $2 OR status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED'))
+             ORDER BY created_at DESC
+             LIMIT $3",
+            account_id,
+            open_only,
+            limit
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Every account's open `DAY` orders, for expiry at the session close
+    pub async fn list_open_day_orders(&self) -> Result<Vec<OrderRecord>> {
+        let records = sqlx::query_as!(
+            OrderRecord,
+            "SELECT * FROM orders
+             WHERE time_in_force = 'DAY'
+               AND status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED')
+             ORDER BY order_id"
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// A symbol's open orders across all accounts, oldest first
+    pub async fn list_open_for_symbol(&self, symbol_id: i32) -> Result<Vec<OrderRecord>> {
+        let records = sqlx::query_as!(
+            OrderRecord,
+            "SELECT * FROM orders
+             WHERE symbol_id = $1
+               AND status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED')
+             ORDER BY order_id",
+            symbol_id
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Set an order's status
+    pub async fn set_status(&self, order_id: i64, status: OrderStatus) -> Result<()> {
+        sqlx::query!(
+            "UPDATE orders SET status = $2, updated_at = now() WHERE order_id = $1",
+            order_id,
+            status.as_str()
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark an open order cancelled (filled/rejected orders are left untouched)
+    pub async fn mark_cancelled(&self, order_id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE orders SET status = 'CANCELLED', updated_at = now()
+             WHERE order_id = $1 AND status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED')",
+            order_id
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Apply a fill, moving the order to `PARTIALLY_FILLED` or `FILLED`
+    pub async fn record_fill(&self, order_id: i64, quantity: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE orders
+             SET filled_quantity = filled_quantity + $2,
+                 status = CASE WHEN filled_quantity + $2 >= quantity
+                               THEN 'FILLED' ELSE 'PARTIALLY_FILLED' END,
+                 updated_at = now()
+             WHERE order_id = $1",
+            order_id,
+            quantity
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_status_strings_round_trip_through_serde() {
+        for status in [
+            OrderStatus::Pending,
+            OrderStatus::Accepted,
+            OrderStatus::PartiallyFilled,
+            OrderStatus::Filled,
+            OrderStatus::Cancelled,
+            OrderStatus::Rejected,
+        ] {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", status.as_str()));
+            assert_eq!(serde_json::from_str::<OrderStatus>(&json).unwrap(), status);
+        }
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn test_same_client_order_id_is_recorded_once() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        let orders = OrderRepository::new(pool.clone());
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('coid-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let new_order = |order_id: i64| NewOrderRecord {
+            order_id,
+            account_id,
+            symbol_id: 1,
+            client_order_id: Some("retry-1".to_string()),
+            side: "BUY".to_string(),
+            order_type: "LIMIT".to_string(),
+            price: 100,
+            quantity: 1,
+            time_in_force: "GTC".to_string(),
+        };
+        let base = account_id * 1_000;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        // Two concurrent submissions: one is recorded, the other gets it back
+        let (order_a, order_b) = (new_order(base + 1), new_order(base + 2));
+        let (first, second) =
+            tokio::join!(orders.insert(&order_a, expires_at), orders.insert(&order_b, expires_at));
+        let outcomes = [first.unwrap(), second.unwrap()];
+        let inserted: Vec<_> = outcomes
+            .iter()
+            .filter_map(|o| match o {
+                OrderInsert::Inserted(record) => Some(record.order_id),
+                OrderInsert::Duplicate(_) => None,
+            })
+            .collect();
+        assert_eq!(inserted.len(), 1);
+        for outcome in &outcomes {
+            if let OrderInsert::Duplicate(existing) = outcome {
+                assert_eq!(existing.order_id, inserted[0]);
+            }
+        }
+        // The duplicate left no order record behind
+        let recorded = [orders.get(base + 1).await.unwrap(), orders.get(base + 2).await.unwrap()];
+        assert_eq!(recorded.iter().flatten().count(), 1);
+
+        // Once the mapping expires the key moves to the new order
+        sqlx::query("UPDATE client_order_ids SET expires_at = now() WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let reused = orders.insert(&new_order(base + 3), expires_at).await.unwrap();
+        assert!(matches!(reused, OrderInsert::Inserted(ref record) if record.order_id == base + 3));
+
+        // The earlier order keeps its key
+        let earlier = orders.get(inserted[0]).await.unwrap().unwrap();
+        assert_eq!(earlier.client_order_id.as_deref(), Some("retry-1"));
+
+        sqlx::query("DELETE FROM orders WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn test_old_orders_are_found_by_client_order_id_after_the_window() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        let orders = OrderRepository::new(pool.clone());
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('coid-gtc-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let order_id = account_id * 1_000 + 1;
+        let order = NewOrderRecord {
+            order_id,
+            account_id,
+            symbol_id: 1,
+            client_order_id: Some("gtc-1".to_string()),
+            side: "BUY".to_string(),
+            order_type: "LIMIT".to_string(),
+            price: 100,
+            quantity: 1,
+            time_in_force: "GTC".to_string(),
+        };
+        orders.insert(&order, Utc::now() + chrono::Duration::hours(1)).await.unwrap();
+
+        // A month-old order whose dedupe window has long passed
+        sqlx::query(
+            "UPDATE orders SET created_at = now() - INTERVAL '30 days' WHERE order_id = $1",
+        )
+        .bind(order_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE client_order_ids SET expires_at = now() WHERE order_id = $1")
+            .bind(order_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let duplicate = orders.find_duplicate(account_id, "gtc-1").await;
+        let found = orders.find_by_client_order_id(account_id, "gtc-1").await;
+
+        sqlx::query("DELETE FROM orders WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(duplicate.unwrap().is_none());
+        assert_eq!(found.unwrap().map(|record| record.order_id), Some(order_id));
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn test_list_open_for_symbol() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        let orders = OrderRepository::new(pool.clone());
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('open-symbol-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let new_order = |order_id: i64, symbol_id: i32| NewOrderRecord {
+            order_id,
+            account_id,
+            symbol_id,
+            client_order_id: None,
+            side: "BUY".to_string(),
+            order_type: "LIMIT".to_string(),
+            price: 100,
+            quantity: 1,
+            time_in_force: "GTC".to_string(),
+        };
+        let base = account_id * 1_000;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        for order in [new_order(base + 1, 7), new_order(base + 2, 7), new_order(base + 3, 8)] {
+            orders.insert(&order, expires_at).await.unwrap();
+        }
+        orders.mark_cancelled(base + 2).await.unwrap();
+
+        let open = orders.list_open_for_symbol(7).await;
+
+        sqlx::query("DELETE FROM orders WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let ours: Vec<i64> = open
+            .unwrap()
+            .into_iter()
+            .filter(|record| record.account_id == account_id)
+            .map(|record| record.order_id)
+            .collect();
+        assert_eq!(ours, vec![base + 1]);
+    }
+
+    #[test]
+    fn test_open_statuses() {
+        assert!(OrderStatus::Pending.is_open());
+        assert!(OrderStatus::PartiallyFilled.is_open());
+        assert!(!OrderStatus::Filled.is_open());
+        assert!(!OrderStatus::Cancelled.is_open());
+        assert!(!OrderStatus::Rejected.is_open());
+    }
+}
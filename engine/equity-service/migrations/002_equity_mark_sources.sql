@@ -0,0 +1,6 @@
+-- Record how each position was marked in every equity snapshot
+-- Snapshots marked differently (e.g. book mid vs fair price) are not directly comparable
+
+ALTER TABLE equity_timeseries ADD COLUMN IF NOT EXISTS mark_sources JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+COMMENT ON COLUMN equity_timeseries.mark_sources IS 'Mark source per symbol: last_trade, book_mid, fair_price, blend, cost_basis or zero';
@@ -0,0 +1,54 @@
+-- Contests and leaderboards: contests over a season or week, their entrants and final results
+-- Entrants are ranked by return on the starting bankroll, measured from equity_timeseries
+
+-- ============================================================================
+-- 1. contests
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS contests (
+  id                 BIGSERIAL PRIMARY KEY,
+  name               TEXT NOT NULL,
+  period             TEXT NOT NULL CHECK (period IN ('season', 'week')),
+  season             INTEGER NOT NULL,
+  week               INTEGER,                 -- set for weekly contests
+  starts_at          TIMESTAMPTZ NOT NULL,
+  ends_at            TIMESTAMPTZ NOT NULL,
+  starting_bankroll  BIGINT NOT NULL,         -- in cents
+  finalized_at       TIMESTAMPTZ,             -- set once results are written
+  created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CHECK (ends_at > starts_at),
+  CHECK (starting_bankroll > 0),
+  CHECK ((period = 'week') = (week IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_contests_open ON contests(ends_at) WHERE finalized_at IS NULL;
+
+-- ============================================================================
+-- 2. contest_entrants
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS contest_entrants (
+  contest_id  BIGINT NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  account_id  BIGINT NOT NULL,
+  joined_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (contest_id, account_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_entrants_account ON contest_entrants(account_id);
+
+-- ============================================================================
+-- 3. contest_results
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS contest_results (
+  contest_id      BIGINT NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  account_id      BIGINT NOT NULL,
+  rank            INTEGER NOT NULL,
+  pnl             BIGINT NOT NULL,            -- in cents, since the contest started
+  final_equity    BIGINT NOT NULL,            -- starting bankroll + pnl, in cents
+  return_percent  DOUBLE PRECISION NOT NULL,
+  max_drawdown    DOUBLE PRECISION NOT NULL,  -- fraction of peak contest equity
+  joined_at       TIMESTAMPTZ NOT NULL,
+  PRIMARY KEY (contest_id, account_id),
+  UNIQUE (contest_id, rank)
+);
@@ -1,6 +1,9 @@
 //! Configuration for Equity Valuation Service
 
+use crate::marking::{MarkPolicy, UntradedMark};
+use crate::risk::RiskConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// Configuration for the Equity Valuation Service
@@ -17,6 +20,14 @@ pub struct EquityServiceConfig {
     
     /// Performance configuration
     pub performance: PerformanceConfig,
+    
+    /// Mark-to-market configuration
+    #[serde(default)]
+    pub marking: MarkingConfig,
+    
+    /// Portfolio risk configuration
+    #[serde(default)]
+    pub risk: RiskConfig,
 }
 
 /// Cache configuration
@@ -61,6 +72,22 @@ pub struct PerformanceConfig {
     pub enable_metrics: bool,
 }
 
+/// Mark-to-market configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkingConfig {
+    /// Policy for symbols without an override
+    pub default_policy: MarkPolicy,
+    
+    /// Per-symbol policy overrides
+    pub symbol_policies: HashMap<u32, MarkPolicy>,
+    
+    /// How symbols missing their policy's inputs are valued
+    pub untraded: UntradedMark,
+    
+    /// Age at which a `Blend` input counts half as much as a fresh one
+    pub blend_half_life: Duration,
+}
+
 impl Default for EquityServiceConfig {
     fn default() -> Self {
         Self {
@@ -68,6 +95,8 @@ impl Default for EquityServiceConfig {
             cache: CacheConfig::default(),
             websocket: WebSocketConfig::default(),
             performance: PerformanceConfig::default(),
+            marking: MarkingConfig::default(),
+            risk: RiskConfig::default(),
         }
     }
 }
@@ -103,6 +132,54 @@ impl Default for PerformanceConfig {
     }
 }
 
+impl Default for MarkingConfig {
+    fn default() -> Self {
+        Self {
+            default_policy: MarkPolicy::LastTrade,
+            symbol_policies: HashMap::new(),
+            untraded: UntradedMark::FairPrice,
+            blend_half_life: Duration::from_secs(900), // 15 minutes
+        }
+    }
+}
+
+impl MarkingConfig {
+    /// Policy for a symbol
+    pub fn policy(&self, symbol_id: u32) -> MarkPolicy {
+        self.symbol_policies.get(&symbol_id).copied().unwrap_or(self.default_policy)
+    }
+    
+    /// Read `EVS_MARK_POLICY`, `EVS_SYMBOL_MARK_POLICIES` (`764=book_mid,12=blend`),
+    /// `EVS_UNTRADED_MARK` and `EVS_BLEND_HALF_LIFE_SECS`, defaulting whatever is unset
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut config = Self::default();
+        
+        if let Ok(policy) = std::env::var("EVS_MARK_POLICY") {
+            config.default_policy = policy.parse().map_err(anyhow::Error::msg)?;
+        }
+        
+        if let Ok(policies) = std::env::var("EVS_SYMBOL_MARK_POLICIES") {
+            for entry in policies.split(',').filter(|entry| !entry.trim().is_empty()) {
+                let (symbol_id, policy) = entry
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Invalid symbol mark policy '{entry}'"))?;
+                let symbol_id = symbol_id.trim().parse::<u32>()?;
+                config.symbol_policies.insert(symbol_id, policy.parse().map_err(anyhow::Error::msg)?);
+            }
+        }
+        
+        if let Ok(untraded) = std::env::var("EVS_UNTRADED_MARK") {
+            config.untraded = untraded.parse().map_err(anyhow::Error::msg)?;
+        }
+        
+        if let Ok(secs) = std::env::var("EVS_BLEND_HALF_LIFE_SECS") {
+            config.blend_half_life = Duration::from_secs(secs.trim().parse()?);
+        }
+        
+        Ok(config)
+    }
+}
+
 impl EquityServiceConfig {
     /// Create configuration from environment variables
     pub fn from_env() -> anyhow::Result<Self> {
@@ -114,6 +191,8 @@ impl EquityServiceConfig {
             cache: CacheConfig::default(),
             websocket: WebSocketConfig::default(),
             performance: PerformanceConfig::default(),
+            marking: MarkingConfig::from_env()?,
+            risk: RiskConfig::default(),
         })
     }
 }
@@ -0,0 +1,760 @@
+//! Contests and leaderboards
+//!
+//! A contest runs over an NFL season or week between fixed times. Every entrant starts with
+//! the contest's bankroll and is ranked by return on it: the change in the account's P&L
+//! (realized + unrealized, as EVS snapshots it) since the contest started, over the bankroll.
+//! Cash credited to the account, e.g. for fantasy points, is not a return. Ties go to the
+//! smaller drawdown, then the earlier entry, then the lower account ID.
+//!
+//! Standings are live while the contest runs, fed by EVS equity updates. When it ends they
+//! are written to `contest_results` and the contest is final. After a restart running
+//! contests are rebuilt from `equity_timeseries`.
+
+use crate::error::{EquityServiceError, Result};
+use crate::types::EquityUpdate;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+
+/// What a contest spans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContestPeriod {
+    Season,
+    Week,
+}
+
+/// Where a contest is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContestStatus {
+    Scheduled,
+    Running,
+    Final,
+}
+
+/// A contest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contest {
+    pub id: i64,
+    pub name: String,
+    pub period: ContestPeriod,
+    pub season: i32,
+    /// Set for weekly contests
+    pub week: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Every entrant's starting equity, in cents
+    pub starting_bankroll: i64,
+    pub status: ContestStatus,
+}
+
+/// A contest to create
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewContest {
+    pub name: String,
+    pub period: ContestPeriod,
+    pub season: i32,
+    #[serde(default)]
+    pub week: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub starting_bankroll: i64,
+    /// Accounts entered at creation
+    #[serde(default)]
+    pub entrants: Vec<i64>,
+}
+
+impl NewContest {
+    /// Check the window, bankroll and period
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(EquityServiceError::InvalidContest(reason.to_string()));
+        if self.name.trim().is_empty() {
+            return invalid("Contest name is empty");
+        }
+        if self.ends_at <= self.starts_at {
+            return invalid("Contest must end after it starts");
+        }
+        if self.starting_bankroll <= 0 {
+            return invalid("Starting bankroll must be positive");
+        }
+        match (self.period, self.week) {
+            (ContestPeriod::Week, None) => invalid("Weekly contests need a week"),
+            (ContestPeriod::Season, Some(_)) => invalid("Season contests have no week"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An entrant's P&L and drawdown since the contest started for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entrant {
+    pub account_id: i64,
+    pub joined_at: DateTime<Utc>,
+    /// Account P&L when measuring started; `None` before the contest starts
+    baseline: Option<i64>,
+    /// P&L since the start, in cents
+    pnl: i64,
+    peak_equity: i64,
+    max_drawdown: f64,
+}
+
+impl Entrant {
+    pub fn new(account_id: i64, joined_at: DateTime<Utc>) -> Self {
+        Self { account_id, joined_at, baseline: None, pnl: 0, peak_equity: 0, max_drawdown: 0.0 }
+    }
+
+    /// Start measuring from the account's current P&L
+    pub fn start(&mut self, account_pnl: i64, bankroll: i64) {
+        self.baseline = Some(account_pnl);
+        self.pnl = 0;
+        self.peak_equity = bankroll;
+        self.max_drawdown = 0.0;
+    }
+
+    /// Record the account's P&L; ignored until the entrant has started
+    pub fn observe(&mut self, account_pnl: i64, bankroll: i64) {
+        let Some(baseline) = self.baseline else {
+            return;
+        };
+        self.pnl = account_pnl - baseline;
+
+        let equity = bankroll + self.pnl;
+        self.peak_equity = self.peak_equity.max(equity);
+        if self.peak_equity > 0 {
+            let drawdown = (self.peak_equity - equity) as f64 / self.peak_equity as f64;
+            self.max_drawdown = self.max_drawdown.max(drawdown);
+        }
+    }
+}
+
+/// One entrant's place on a leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub rank: u32,
+    pub account_id: i64,
+    /// P&L since the contest started, in cents
+    pub pnl: i64,
+    /// Starting bankroll plus P&L, in cents
+    pub equity: i64,
+    pub return_percent: f64,
+    /// Largest fall of contest equity from its peak, as a fraction of the peak
+    pub max_drawdown: f64,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// A contest's standings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub contest: Contest,
+    pub as_of: DateTime<Utc>,
+    pub standings: Vec<Standing>,
+}
+
+/// Rank entrants by return, then smaller drawdown, earlier entry and lower account ID
+pub fn rank<'a>(bankroll: i64, entrants: impl IntoIterator<Item = &'a Entrant>) -> Vec<Standing> {
+    let mut entrants: Vec<&Entrant> = entrants.into_iter().collect();
+    // Every entrant has the same bankroll, so P&L orders returns exactly
+    entrants.sort_by(|a, b| {
+        b.pnl
+            .cmp(&a.pnl)
+            .then_with(|| a.max_drawdown.total_cmp(&b.max_drawdown))
+            .then_with(|| a.joined_at.cmp(&b.joined_at))
+            .then_with(|| a.account_id.cmp(&b.account_id))
+    });
+
+    entrants
+        .into_iter()
+        .zip(1..)
+        .map(|(entrant, rank)| Standing {
+            rank,
+            account_id: entrant.account_id,
+            pnl: entrant.pnl,
+            equity: bankroll + entrant.pnl,
+            return_percent: entrant.pnl as f64 / bankroll as f64 * 100.0,
+            max_drawdown: entrant.max_drawdown,
+            joined_at: entrant.joined_at,
+        })
+        .collect()
+}
+
+/// A contest that has not been finalized
+struct LiveContest {
+    contest: Contest,
+    entrants: HashMap<i64, Entrant>,
+    /// Entrants are measured from the start
+    started: bool,
+    /// Standings changed since they were last published
+    changed: bool,
+}
+
+impl LiveContest {
+    fn leaderboard(&self, as_of: DateTime<Utc>) -> Leaderboard {
+        Leaderboard {
+            contest: self.contest.clone(),
+            as_of,
+            standings: rank(self.contest.starting_bankroll, self.entrants.values()),
+        }
+    }
+}
+
+/// Contest row as stored
+struct ContestRow {
+    id: i64,
+    name: String,
+    period: String,
+    season: i32,
+    week: Option<i32>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    starting_bankroll: i64,
+    finalized_at: Option<DateTime<Utc>>,
+}
+
+impl ContestRow {
+    fn into_contest(self, now: DateTime<Utc>) -> Result<Contest> {
+        let status = if self.finalized_at.is_some() {
+            ContestStatus::Final
+        } else if now < self.starts_at {
+            ContestStatus::Scheduled
+        } else {
+            ContestStatus::Running
+        };
+
+        Ok(Contest {
+            id: self.id,
+            name: self.name,
+            period: self.period.parse()?,
+            season: self.season,
+            week: self.week,
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            starting_bankroll: self.starting_bankroll,
+            status,
+        })
+    }
+}
+
+/// Runs contests and publishes their leaderboards
+pub struct ContestService {
+    db_pool: PgPool,
+    live: RwLock<HashMap<i64, LiveContest>>,
+    leaderboards: broadcast::Sender<Leaderboard>,
+}
+
+impl ContestService {
+    /// Load the contests that have not been finalized; running ones are rebuilt from
+    /// `equity_timeseries`
+    pub async fn load(db_pool: PgPool) -> Result<Self> {
+        let (leaderboards, _) = broadcast::channel(256);
+        let service = Self { db_pool, live: RwLock::new(HashMap::new()), leaderboards };
+
+        let rows = sqlx::query_as!(
+            ContestRow,
+            "SELECT id, name, period, season, week, starts_at, ends_at, starting_bankroll,
+                    finalized_at
+             FROM contests WHERE finalized_at IS NULL"
+        )
+        .fetch_all(&service.db_pool)
+        .await?;
+
+        let now = Utc::now();
+        let mut live = service.live.write().await;
+        for row in rows {
+            let contest = row.into_contest(now)?;
+            let entrants = sqlx::query!(
+                "SELECT account_id, joined_at FROM contest_entrants WHERE contest_id = $1",
+                contest.id
+            )
+            .fetch_all(&service.db_pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.account_id, Entrant::new(row.account_id, row.joined_at)))
+            .collect();
+
+            let mut contest = LiveContest { contest, entrants, started: false, changed: true };
+            if now >= contest.contest.starts_at {
+                service.start_contest(&mut contest).await?;
+            }
+            live.insert(contest.contest.id, contest);
+        }
+        tracing::info!("Loaded {} open contests", live.len());
+        drop(live);
+
+        Ok(service)
+    }
+
+    /// Subscribe to leaderboards, published when standings change and when a contest ends
+    pub fn subscribe(&self) -> broadcast::Receiver<Leaderboard> {
+        self.leaderboards.subscribe()
+    }
+
+    /// Create a contest and enter its initial entrants
+    pub async fn create_contest(&self, new: NewContest) -> Result<Contest> {
+        new.validate()?;
+
+        let mut tx = self.db_pool.begin().await?;
+        let id = sqlx::query_scalar!(
+            "INSERT INTO contests (name, period, season, week, starts_at, ends_at, starting_bankroll)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id",
+            new.name.trim(),
+            new.period.as_str(),
+            new.season,
+            new.week,
+            new.starts_at,
+            new.ends_at,
+            new.starting_bankroll
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let now = Utc::now();
+        let mut entrants = HashMap::new();
+        for &account_id in &new.entrants {
+            sqlx::query!(
+                "INSERT INTO contest_entrants (contest_id, account_id, joined_at)
+                 VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+                id,
+                account_id,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+            entrants.insert(account_id, Entrant::new(account_id, now));
+        }
+        tx.commit().await?;
+
+        let row = ContestRow {
+            id,
+            name: new.name.trim().to_string(),
+            period: new.period.as_str().to_string(),
+            season: new.season,
+            week: new.week,
+            starts_at: new.starts_at,
+            ends_at: new.ends_at,
+            starting_bankroll: new.starting_bankroll,
+            finalized_at: None,
+        };
+        let mut contest = LiveContest {
+            contest: row.into_contest(now)?,
+            entrants,
+            started: false,
+            changed: true,
+        };
+        if now >= contest.contest.starts_at {
+            self.start_contest(&mut contest).await?;
+        }
+
+        let created = contest.contest.clone();
+        self.live.write().await.insert(id, contest);
+        tracing::info!("Created contest {} '{}'", id, created.name);
+        Ok(created)
+    }
+
+    /// Enter an account into a contest that has not ended; entering twice is a no-op
+    pub async fn add_entrant(&self, contest_id: i64, account_id: i64) -> Result<Leaderboard> {
+        let now = Utc::now();
+        let mut live = self.live.write().await;
+        let Some(contest) = live.get_mut(&contest_id) else {
+            drop(live);
+            // Finalized contests are no longer live
+            self.contest(contest_id).await?;
+            return Err(EquityServiceError::InvalidContest(format!(
+                "Contest {contest_id} has ended"
+            )));
+        };
+        if now >= contest.contest.ends_at {
+            return Err(EquityServiceError::InvalidContest(format!(
+                "Contest {contest_id} has ended"
+            )));
+        }
+
+        if !contest.entrants.contains_key(&account_id) {
+            sqlx::query!(
+                "INSERT INTO contest_entrants (contest_id, account_id, joined_at)
+                 VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+                contest_id,
+                account_id,
+                now
+            )
+            .execute(&self.db_pool)
+            .await?;
+
+            let mut entrant = Entrant::new(account_id, now);
+            if contest.started {
+                self.start_entrant(&contest.contest, &mut entrant).await?;
+            }
+            contest.entrants.insert(account_id, entrant);
+            contest.changed = true;
+        }
+
+        Ok(contest.leaderboard(now))
+    }
+
+    /// All contests, latest start first
+    pub async fn contests(&self) -> Result<Vec<Contest>> {
+        let now = Utc::now();
+        sqlx::query_as!(
+            ContestRow,
+            "SELECT id, name, period, season, week, starts_at, ends_at, starting_bankroll,
+                    finalized_at
+             FROM contests ORDER BY starts_at DESC, id DESC"
+        )
+        .fetch_all(&self.db_pool)
+        .await?
+        .into_iter()
+        .map(|row| row.into_contest(now))
+        .collect()
+    }
+
+    /// A contest's live standings, or its results once final
+    pub async fn leaderboard(&self, contest_id: i64) -> Result<Leaderboard> {
+        if let Some(contest) = self.live.read().await.get(&contest_id) {
+            return Ok(contest.leaderboard(Utc::now()));
+        }
+
+        let contest = self.contest(contest_id).await?;
+        let standings = sqlx::query!(
+            "SELECT rank, account_id, pnl, final_equity, return_percent, max_drawdown, joined_at
+             FROM contest_results WHERE contest_id = $1 ORDER BY rank",
+            contest_id
+        )
+        .fetch_all(&self.db_pool)
+        .await?
+        .into_iter()
+        .map(|row| Standing {
+            rank: row.rank as u32,
+            account_id: row.account_id,
+            pnl: row.pnl,
+            equity: row.final_equity,
+            return_percent: row.return_percent,
+            max_drawdown: row.max_drawdown,
+            joined_at: row.joined_at,
+        })
+        .collect();
+
+        Ok(Leaderboard { as_of: contest.ends_at, contest, standings })
+    }
+
+    /// Apply an account's equity update to the running contests it is entered in
+    pub async fn observe(&self, update: &EquityUpdate) {
+        let now = Utc::now();
+        let account_pnl = update.realized_pnl + update.unrealized_pnl;
+
+        let mut live = self.live.write().await;
+        for contest in live.values_mut() {
+            if !contest.started || now >= contest.contest.ends_at {
+                continue;
+            }
+            if let Some(entrant) = contest.entrants.get_mut(&update.account_id) {
+                entrant.observe(account_pnl, contest.contest.starting_bankroll);
+                contest.changed = true;
+            }
+        }
+    }
+
+    /// Start contests that reached their start, finalize those past their end and publish
+    /// every leaderboard that changed. The database work runs without holding the live
+    /// contests; a contest that fails is logged and retried on the next pass.
+    pub async fn advance(&self) {
+        let now = Utc::now();
+
+        let starting: Vec<(Contest, Vec<Entrant>)> = {
+            let live = self.live.read().await;
+            live.values()
+                .filter(|contest| !contest.started && now >= contest.contest.starts_at)
+                .map(|contest| {
+                    let unstarted = contest
+                        .entrants
+                        .values()
+                        .filter(|entrant| entrant.baseline.is_none())
+                        .cloned()
+                        .collect();
+                    (contest.contest.clone(), unstarted)
+                })
+                .collect()
+        };
+        for (contest, mut entrants) in starting {
+            if let Err(e) = self.start_entrants(&contest, &mut entrants).await {
+                tracing::error!("Failed to start contest {}: {}", contest.id, e);
+                continue;
+            }
+
+            let mut live = self.live.write().await;
+            let Some(live_contest) = live.get_mut(&contest.id) else {
+                continue;
+            };
+            for entrant in entrants {
+                live_contest.entrants.insert(entrant.account_id, entrant);
+            }
+            // Entrants who joined meanwhile are started on the next pass
+            if live_contest.entrants.values().all(|entrant| entrant.baseline.is_some()) {
+                live_contest.contest.status = ContestStatus::Running;
+                live_contest.started = true;
+            }
+            live_contest.changed = true;
+        }
+
+        let ended: Vec<Leaderboard> = {
+            let live = self.live.read().await;
+            live.values()
+                .filter(|contest| contest.started && now >= contest.contest.ends_at)
+                .map(|contest| contest.leaderboard(contest.contest.ends_at))
+                .collect()
+        };
+        for leaderboard in ended {
+            let contest_id = leaderboard.contest.id;
+            match self.finalize(leaderboard).await {
+                Ok(leaderboard) => {
+                    self.live.write().await.remove(&contest_id);
+                    let _ = self.leaderboards.send(leaderboard);
+                }
+                Err(e) => tracing::error!("Failed to finalize contest {}: {}", contest_id, e),
+            }
+        }
+
+        let mut live = self.live.write().await;
+        for contest in live.values_mut().filter(|contest| contest.changed) {
+            contest.changed = false;
+            let _ = self.leaderboards.send(contest.leaderboard(now));
+        }
+    }
+
+    /// Feed equity updates into the contests and advance them every `interval`
+    pub fn start(
+        self: Arc<Self>,
+        mut updates: broadcast::Receiver<EquityUpdate>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    received = updates.recv() => match received {
+                        Ok(update) => self.observe(&update).await,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Contests lagged, skipped {} equity updates", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = ticker.tick() => self.advance().await,
+                }
+            }
+        })
+    }
+
+    /// A stored contest
+    async fn contest(&self, contest_id: i64) -> Result<Contest> {
+        sqlx::query_as!(
+            ContestRow,
+            "SELECT id, name, period, season, week, starts_at, ends_at, starting_bankroll,
+                    finalized_at
+             FROM contests WHERE id = $1",
+            contest_id
+        )
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or(EquityServiceError::ContestNotFound(contest_id))?
+        .into_contest(Utc::now())
+    }
+
+    /// Measure every entrant from the contest start
+    async fn start_contest(&self, contest: &mut LiveContest) -> Result<()> {
+        for entrant in contest.entrants.values_mut() {
+            self.start_entrant(&contest.contest, entrant).await?;
+        }
+        contest.contest.status = ContestStatus::Running;
+        contest.started = true;
+        contest.changed = true;
+        Ok(())
+    }
+
+    /// Measure each of a contest's entrants
+    async fn start_entrants(&self, contest: &Contest, entrants: &mut [Entrant]) -> Result<()> {
+        for entrant in entrants {
+            self.start_entrant(contest, entrant).await?;
+        }
+        Ok(())
+    }
+
+    /// Measure an entrant from its later of the contest start and its entry, replaying the
+    /// snapshots since
+    async fn start_entrant(&self, contest: &Contest, entrant: &mut Entrant) -> Result<()> {
+        let from = contest.starts_at.max(entrant.joined_at);
+        let until = contest.ends_at.min(Utc::now());
+        let bankroll = contest.starting_bankroll;
+
+        let baseline = sqlx::query_scalar!(
+            r#"SELECT realized_pnl + unrealized_pnl AS "pnl!" FROM equity_timeseries
+               WHERE account_id = $1 AND timestamp <= $2
+               ORDER BY timestamp DESC LIMIT 1"#,
+            entrant.account_id,
+            from
+        )
+        .fetch_optional(&self.db_pool)
+        .await?
+        .unwrap_or(0);
+        entrant.start(baseline, bankroll);
+
+        let history = sqlx::query_scalar!(
+            r#"SELECT realized_pnl + unrealized_pnl AS "pnl!" FROM equity_timeseries
+               WHERE account_id = $1 AND timestamp > $2 AND timestamp <= $3
+               ORDER BY timestamp ASC"#,
+            entrant.account_id,
+            from,
+            until
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        for account_pnl in history {
+            entrant.observe(account_pnl, bankroll);
+        }
+        Ok(())
+    }
+
+    /// Write a contest's final standings and mark it final
+    async fn finalize(&self, mut leaderboard: Leaderboard) -> Result<Leaderboard> {
+        leaderboard.contest.status = ContestStatus::Final;
+
+        let mut tx = self.db_pool.begin().await?;
+        for standing in &leaderboard.standings {
+            sqlx::query!(
+                "INSERT INTO contest_results
+                 (contest_id, account_id, rank, pnl, final_equity, return_percent, max_drawdown,
+                  joined_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                leaderboard.contest.id,
+                standing.account_id,
+                standing.rank as i32,
+                standing.pnl,
+                standing.equity,
+                standing.return_percent,
+                standing.max_drawdown,
+                standing.joined_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "UPDATE contests SET finalized_at = NOW() WHERE id = $1",
+            leaderboard.contest.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "Contest {} final with {} entrants",
+            leaderboard.contest.id,
+            leaderboard.standings.len()
+        );
+        Ok(leaderboard)
+    }
+}
+
+impl ContestPeriod {
+    fn as_str(self) -> &'static str {
+        match self {
+            ContestPeriod::Season => "season",
+            ContestPeriod::Week => "week",
+        }
+    }
+}
+
+impl FromStr for ContestPeriod {
+    type Err = EquityServiceError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "season" => Ok(ContestPeriod::Season),
+            "week" => Ok(ContestPeriod::Week),
+            other => {
+                Err(EquityServiceError::InvalidContest(format!("Unknown contest period '{other}'")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANKROLL: i64 = 100_000;
+
+    fn entrant(account_id: i64, joined_secs: i64, pnl_path: &[i64]) -> Entrant {
+        let joined_at = DateTime::from_timestamp(1_700_000_000 + joined_secs, 0).unwrap();
+        let mut entrant = Entrant::new(account_id, joined_at);
+        // Account P&L was 5000 when the contest started
+        entrant.start(5_000, BANKROLL);
+        for pnl in pnl_path {
+            entrant.observe(5_000 + pnl, BANKROLL);
+        }
+        entrant
+    }
+
+    #[test]
+    fn entrants_measure_pnl_and_drawdown_from_the_start() {
+        let mut waiting = Entrant::new(1, Utc::now());
+        waiting.observe(9_000, BANKROLL);
+        assert_eq!(rank(BANKROLL, [&waiting])[0].pnl, 0);
+
+        // 100k -> 120k -> 90k -> 110k: drawdown 25% from the 120k peak
+        let standing = &rank(BANKROLL, [&entrant(1, 0, &[20_000, -10_000, 10_000])])[0];
+        assert_eq!(standing.pnl, 10_000);
+        assert_eq!(standing.equity, 110_000);
+        assert!((standing.return_percent - 10.0).abs() < 1e-9);
+        assert!((standing.max_drawdown - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ranking_breaks_ties_by_drawdown_entry_and_account() {
+        let entrants = [
+            entrant(1, 0, &[5_000]),
+            // Same return with a drawdown on the way
+            entrant(2, 0, &[-5_000, 5_000]),
+            // Same return and drawdown as 1, entered later
+            entrant(3, 60, &[5_000]),
+            // Same as 3 with a higher account ID
+            entrant(4, 60, &[5_000]),
+            entrant(5, 0, &[12_000]),
+            entrant(6, 0, &[-2_000]),
+        ];
+
+        let order: Vec<(u32, i64)> =
+            rank(BANKROLL, &entrants).iter().map(|s| (s.rank, s.account_id)).collect();
+        assert_eq!(order, vec![(1, 5), (2, 1), (3, 3), (4, 4), (5, 2), (6, 6)]);
+    }
+
+    #[test]
+    fn new_contests_are_validated() {
+        let starts_at = Utc::now();
+        let contest = NewContest {
+            name: "Week 5 Showdown".to_string(),
+            period: ContestPeriod::Week,
+            season: 2025,
+            week: Some(5),
+            starts_at,
+            ends_at: starts_at + chrono::Duration::days(7),
+            starting_bankroll: BANKROLL,
+            entrants: vec![],
+        };
+        assert!(contest.validate().is_ok());
+
+        for invalid in [
+            NewContest { week: None, ..contest.clone() },
+            NewContest { period: ContestPeriod::Season, ..contest.clone() },
+            NewContest { ends_at: starts_at, ..contest.clone() },
+            NewContest { starting_bankroll: 0, ..contest.clone() },
+        ] {
+            assert!(matches!(invalid.validate(), Err(EquityServiceError::InvalidContest(_))));
+        }
+    }
+}
@@ -23,6 +23,12 @@ pub enum EquityServiceError {
     #[error("Position not found for account {0} and symbol {1}")]
     PositionNotFound(i64, u32),
 
+    #[error("Contest not found: {0}")]
+    ContestNotFound(i64),
+
+    #[error("Invalid contest: {0}")]
+    InvalidContest(String),
+
     #[error("Invalid trade data: {0}")]
     InvalidTradeData(String),
 
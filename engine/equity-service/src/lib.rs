@@ -2,18 +2,30 @@
 //!
 //! This service subscribes to ExecutionManager events and calculates real-time equity
 //! for all accounts. It maintains in-memory caches for performance and broadcasts
-//! equity updates via WebSocket to the frontend.
+//! equity updates via WebSocket to the frontend. The `risk` module assesses each account's
+//! concentration, volatility, drawdown and VaR; the `contest` module ranks contest entrants
+//! on the equity updates.
 
 mod config;
+pub mod contest;
 mod error;
+mod marking;
+pub mod risk;
 mod types;
 mod service;
 
-pub use config::EquityServiceConfig;
+pub use config::{EquityServiceConfig, MarkingConfig};
 pub use error::{EquityServiceError, Result};
+pub use marking::{
+    Mark, MarkInputs, MarkPolicy, MarkSource, MarkSources, Observation, UntradedMark,
+};
 pub use types::{
     AccountEquityData, EquitySnapshot, Position, EquityUpdate, EquityBroadcaster,
 };
+pub use contest::{
+    Contest, ContestPeriod, ContestService, ContestStatus, Leaderboard, NewContest, Standing,
+};
+pub use risk::{AccountRisk, Holding, RiskConfig};
 pub use service::EquityValuationService;
 
 /// Re-export commonly used types
@@ -0,0 +1,267 @@
+//! Mark-to-market policies
+//!
+//! Each symbol is valued by a `MarkPolicy`. The inputs are the last trade, the mid of the
+//! live book and the RPE fair price, each with the time it was observed. When a policy's
+//! inputs are missing the symbol is marked by the configured `UntradedMark`, never by a
+//! made-up price.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+use whistle::{Price, Qty, Side};
+
+/// How a symbol's positions are valued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkPolicy {
+    /// Last trade price
+    LastTrade,
+    /// Mid of the best bid and ask; the last trade while the book is one-sided
+    BookMid,
+    /// RPE fair price; the last trade until one is published
+    FairPrice,
+    /// Average of every available input, each weighted down by its age
+    Blend,
+}
+
+/// How a symbol with none of its policy's inputs is valued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UntradedMark {
+    /// RPE fair price, or cost basis if there is none
+    FairPrice,
+    /// Average cost, so the position shows no unrealized P&L
+    CostBasis,
+    /// Worth nothing until it trades
+    Zero,
+}
+
+/// Where a mark came from, recorded with every equity snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkSource {
+    LastTrade,
+    BookMid,
+    FairPrice,
+    Blend,
+    CostBasis,
+    Zero,
+}
+
+/// Per-symbol mark sources of one snapshot
+pub type MarkSources = BTreeMap<u32, MarkSource>;
+
+/// A price and when it was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Observation {
+    pub price: i64,
+    pub at: DateTime<Utc>,
+}
+
+impl Observation {
+    pub fn new(price: i64, at: DateTime<Utc>) -> Self {
+        Self { price, at }
+    }
+}
+
+/// Everything known about a symbol's price
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarkInputs {
+    pub last_trade: Option<Observation>,
+    pub book_mid: Option<Observation>,
+    pub fair_price: Option<Observation>,
+}
+
+/// A symbol's price for valuation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    pub price: i64,
+    pub source: MarkSource,
+}
+
+impl Mark {
+    fn new(price: i64, source: MarkSource) -> Self {
+        Self { price, source }
+    }
+}
+
+impl MarkPolicy {
+    /// Mark a symbol held at `avg_cost`, at `now`
+    pub fn mark(
+        self,
+        inputs: &MarkInputs,
+        untraded: UntradedMark,
+        blend_half_life: Duration,
+        avg_cost: i64,
+        now: DateTime<Utc>,
+    ) -> Mark {
+        let last_trade = inputs.last_trade.map(|o| Mark::new(o.price, MarkSource::LastTrade));
+        let book_mid = inputs.book_mid.map(|o| Mark::new(o.price, MarkSource::BookMid));
+        let fair_price = inputs.fair_price.map(|o| Mark::new(o.price, MarkSource::FairPrice));
+
+        let mark = match self {
+            MarkPolicy::LastTrade => last_trade,
+            MarkPolicy::BookMid => book_mid.or(last_trade),
+            MarkPolicy::FairPrice => fair_price.or(last_trade),
+            MarkPolicy::Blend => blend(inputs, blend_half_life, now),
+        };
+        mark.unwrap_or_else(|| untraded.mark(inputs, avg_cost))
+    }
+}
+
+impl UntradedMark {
+    fn mark(self, inputs: &MarkInputs, avg_cost: i64) -> Mark {
+        match (self, inputs.fair_price) {
+            (UntradedMark::FairPrice, Some(fair)) => Mark::new(fair.price, MarkSource::FairPrice),
+            (UntradedMark::FairPrice | UntradedMark::CostBasis, _) => {
+                Mark::new(avg_cost, MarkSource::CostBasis)
+            }
+            (UntradedMark::Zero, _) => Mark::new(0, MarkSource::Zero),
+        }
+    }
+}
+
+/// Staleness-weighted average: an input `half_life` old counts half as much as a fresh one
+fn blend(inputs: &MarkInputs, half_life: Duration, now: DateTime<Utc>) -> Option<Mark> {
+    let half_life = half_life.as_secs_f64().max(f64::EPSILON);
+    let (weighted, total) = [inputs.last_trade, inputs.book_mid, inputs.fair_price]
+        .into_iter()
+        .flatten()
+        .fold((0.0, 0.0), |(weighted, total), observation| {
+            let age = (now - observation.at).num_milliseconds().max(0) as f64 / 1000.0;
+            let weight = 0.5f64.powf(age / half_life);
+            (weighted + weight * observation.price as f64, total + weight)
+        });
+
+    // Everything decayed to nothing (or nothing observed): fall back to the untraded mark
+    (total > 0.0).then(|| Mark::new((weighted / total).round() as i64, MarkSource::Blend))
+}
+
+/// Resting quantity per price level of one symbol's book, kept for its mid
+#[derive(Debug, Default)]
+pub(crate) struct BookLevels {
+    bids: BTreeMap<Price, Qty>,
+    asks: BTreeMap<Price, Qty>,
+}
+
+impl BookLevels {
+    /// Set the quantity resting at a level; returns whether the mid moved
+    pub(crate) fn apply(&mut self, side: Side, price: Price, quantity: Qty) -> bool {
+        let before = self.mid();
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if quantity == 0 {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, quantity);
+        }
+        self.mid() != before
+    }
+
+    /// Mid of the best bid and ask, if the book is two-sided
+    pub(crate) fn mid(&self) -> Option<i64> {
+        let bid = *self.bids.keys().next_back()? as i64;
+        let ask = *self.asks.keys().next()? as i64;
+        (bid < ask).then_some((bid + ask) / 2)
+    }
+}
+
+impl FromStr for MarkPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "last_trade" => Ok(MarkPolicy::LastTrade),
+            "book_mid" => Ok(MarkPolicy::BookMid),
+            "fair_price" => Ok(MarkPolicy::FairPrice),
+            "blend" => Ok(MarkPolicy::Blend),
+            other => Err(format!("Unknown mark policy '{other}'")),
+        }
+    }
+}
+
+impl FromStr for UntradedMark {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "fair_price" => Ok(UntradedMark::FairPrice),
+            "cost_basis" => Ok(UntradedMark::CostBasis),
+            "zero" => Ok(UntradedMark::Zero),
+            other => Err(format!("Unknown untraded mark '{other}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF_LIFE: Duration = Duration::from_secs(600);
+
+    fn at(secs_ago: i64, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - chrono::Duration::seconds(secs_ago)
+    }
+
+    #[test]
+    fn policies_fall_back_to_the_last_trade() {
+        let now = Utc::now();
+        let inputs = MarkInputs {
+            last_trade: Some(Observation::new(1200, at(5, now))),
+            ..MarkInputs::default()
+        };
+
+        for policy in [MarkPolicy::LastTrade, MarkPolicy::BookMid, MarkPolicy::FairPrice] {
+            let mark = policy.mark(&inputs, UntradedMark::Zero, HALF_LIFE, 900, now);
+            assert_eq!(mark, Mark::new(1200, MarkSource::LastTrade), "{policy:?}");
+        }
+
+        let with_mid = MarkInputs { book_mid: Some(Observation::new(1250, now)), ..inputs };
+        let mark = MarkPolicy::BookMid.mark(&with_mid, UntradedMark::Zero, HALF_LIFE, 900, now);
+        assert_eq!(mark, Mark::new(1250, MarkSource::BookMid));
+    }
+
+    #[test]
+    fn untraded_symbols_use_the_untraded_mark() {
+        let now = Utc::now();
+        let nothing = MarkInputs::default();
+        let fair_only =
+            MarkInputs { fair_price: Some(Observation::new(800, now)), ..MarkInputs::default() };
+
+        let mark = |inputs: &MarkInputs, untraded| {
+            MarkPolicy::LastTrade.mark(inputs, untraded, HALF_LIFE, 900, now)
+        };
+        assert_eq!(
+            mark(&fair_only, UntradedMark::FairPrice),
+            Mark::new(800, MarkSource::FairPrice)
+        );
+        assert_eq!(mark(&nothing, UntradedMark::FairPrice), Mark::new(900, MarkSource::CostBasis));
+        assert_eq!(
+            mark(&fair_only, UntradedMark::CostBasis),
+            Mark::new(900, MarkSource::CostBasis)
+        );
+        assert_eq!(mark(&fair_only, UntradedMark::Zero), Mark::new(0, MarkSource::Zero));
+    }
+
+    #[test]
+    fn blend_weights_inputs_by_staleness() {
+        let now = Utc::now();
+        let inputs = MarkInputs {
+            // One half-life old: counts half as much as the fresh fair price
+            last_trade: Some(Observation::new(1000, at(600, now))),
+            book_mid: None,
+            fair_price: Some(Observation::new(1300, now)),
+        };
+
+        let mark = MarkPolicy::Blend.mark(&inputs, UntradedMark::Zero, HALF_LIFE, 0, now);
+        assert_eq!(mark, Mark::new(1200, MarkSource::Blend));
+
+        let nothing = MarkInputs::default();
+        let mark = MarkPolicy::Blend.mark(&nothing, UntradedMark::CostBasis, HALF_LIFE, 700, now);
+        assert_eq!(mark, Mark::new(700, MarkSource::CostBasis));
+    }
+}
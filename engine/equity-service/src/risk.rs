@@ -0,0 +1,488 @@
+//! Portfolio risk
+//!
+//! Per-account concentration (by player, position and NFL team), volatility, max drawdown and
+//! a Sharpe-like ratio of the account's daily equity, and a historical-simulation VaR of its
+//! current positions. Positions are valued at the marks the equity service values them at
+//! (see `marking`); VaR scenarios apply each symbol's `price_history` returns to that value.
+//!
+//! Returns are simple period returns. Volatility and the Sharpe-like ratio are per day and not
+//! annualized; VaR covers one `price_history` bar.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+// 10000 basis points = 1 share (matches database schema)
+const QTY_SCALE: i64 = 10000;
+
+/// Risk calculation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    /// VaR confidence level, e.g. 0.95
+    pub var_confidence: f64,
+
+    /// Days of equity history behind volatility, drawdown and the Sharpe-like ratio
+    pub equity_lookback_days: i64,
+
+    /// Price-history bars per symbol behind VaR
+    pub price_history_bars: i64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self { var_confidence: 0.95, equity_lookback_days: 90, price_history_bars: 250 }
+    }
+}
+
+/// A held position and the mark it is valued at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holding {
+    pub symbol_id: u32,
+    /// Quantity in basis points
+    pub quantity: i64,
+    /// Mark in cents
+    pub mark: i64,
+}
+
+/// A held position with what risk groups it by
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskPosition {
+    pub symbol_id: u32,
+    pub player_name: String,
+    pub position: String,
+    pub team: String,
+    /// Quantity in basis points
+    pub quantity: i64,
+    /// Mark in cents
+    pub mark: i64,
+}
+
+impl RiskPosition {
+    /// Market value in cents
+    pub fn value(&self) -> i64 {
+        (self.quantity * self.mark) / QTY_SCALE
+    }
+}
+
+/// Share of gross exposure held in one bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConcentrationBucket {
+    pub key: String,
+    /// Gross value in cents
+    pub value: i64,
+    /// Fraction of gross exposure
+    pub weight: f64,
+}
+
+/// Exposure grouped one way, largest bucket first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Concentration {
+    pub buckets: Vec<ConcentrationBucket>,
+    /// Herfindahl index of the weights: 1.0 is everything in one bucket
+    pub herfindahl: f64,
+}
+
+/// Historical-simulation value at risk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueAtRisk {
+    pub confidence: f64,
+    /// Loss in cents not exceeded in `confidence` of scenarios
+    pub amount: i64,
+    /// Number of scenarios
+    pub scenarios: usize,
+}
+
+/// Risk summary of one account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountRisk {
+    pub account_id: i64,
+    /// Sum of absolute position values in cents
+    pub gross_exposure: i64,
+    pub by_player: Concentration,
+    pub by_position: Concentration,
+    pub by_team: Concentration,
+    /// Standard deviation of daily equity returns
+    pub volatility: Option<f64>,
+    /// Largest peak-to-trough fall of daily equity, as a fraction of the peak
+    pub max_drawdown: f64,
+    /// Mean over standard deviation of daily equity returns
+    pub sharpe_ratio: Option<f64>,
+    pub value_at_risk: ValueAtRisk,
+    /// Daily equity closes behind the history-based figures
+    pub equity_observations: usize,
+}
+
+/// Group gross exposure by `key`
+pub fn concentration<F>(positions: &[RiskPosition], key: F) -> Concentration
+where
+    F: Fn(&RiskPosition) -> String,
+{
+    let mut values: HashMap<String, i64> = HashMap::new();
+    for position in positions {
+        *values.entry(key(position)).or_default() += position.value().abs();
+    }
+
+    let gross: i64 = values.values().sum();
+    let mut buckets: Vec<ConcentrationBucket> = values
+        .into_iter()
+        .filter(|(_, value)| *value > 0)
+        .map(|(key, value)| ConcentrationBucket { key, value, weight: value as f64 / gross as f64 })
+        .collect();
+    buckets.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.key.cmp(&b.key)));
+
+    let herfindahl = buckets.iter().map(|bucket| bucket.weight * bucket.weight).sum();
+    Concentration { buckets, herfindahl }
+}
+
+/// Simple returns of a series, skipping periods that start at or below zero
+pub fn returns(series: &[i64]) -> Vec<f64> {
+    series
+        .windows(2)
+        .filter(|pair| pair[0] > 0)
+        .map(|pair| (pair[1] - pair[0]) as f64 / pair[0] as f64)
+        .collect()
+}
+
+/// Sample standard deviation; needs at least two returns
+pub fn volatility(returns: &[f64]) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Mean return over volatility, with no risk-free rate; none for a flat series
+pub fn sharpe_ratio(returns: &[f64]) -> Option<f64> {
+    let volatility = volatility(returns).filter(|v| *v > f64::EPSILON)?;
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    Some(mean / volatility)
+}
+
+/// Largest peak-to-trough fall as a fraction of the peak
+pub fn max_drawdown(series: &[i64]) -> f64 {
+    let mut peak = i64::MIN;
+    let mut drawdown = 0.0f64;
+    for &value in series {
+        peak = peak.max(value);
+        if peak > 0 {
+            drawdown = drawdown.max((peak - value) as f64 / peak as f64);
+        }
+    }
+    drawdown
+}
+
+/// Historical-simulation VaR of the positions
+///
+/// Scenario `t` applies every symbol's `t`-th most recent return to its current value; a symbol
+/// with a shorter history contributes nothing to older scenarios. `symbol_returns` are oldest
+/// first.
+pub fn value_at_risk(
+    positions: &[RiskPosition],
+    symbol_returns: &HashMap<u32, Vec<f64>>,
+    confidence: f64,
+) -> ValueAtRisk {
+    let scenarios = positions
+        .iter()
+        .filter_map(|position| symbol_returns.get(&position.symbol_id))
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+
+    let mut pnl: Vec<f64> = (0..scenarios)
+        .map(|age| {
+            positions
+                .iter()
+                .filter_map(|position| {
+                    let returns = symbol_returns.get(&position.symbol_id)?;
+                    let r = returns.len().checked_sub(age + 1).map(|i| returns[i])?;
+                    Some(position.value() as f64 * r)
+                })
+                .sum()
+        })
+        .collect();
+    pnl.sort_by(f64::total_cmp);
+
+    // Loss at the (1 - confidence) quantile of outcomes; gains are no risk
+    let amount = if pnl.is_empty() {
+        0
+    } else {
+        let tail = ((1.0 - confidence).clamp(0.0, 1.0) * pnl.len() as f64).round() as usize;
+        (-pnl[tail.saturating_sub(1).min(pnl.len() - 1)]).max(0.0).round() as i64
+    };
+
+    ValueAtRisk { confidence, amount, scenarios }
+}
+
+/// Risk of an account's current positions and equity history
+pub fn assess(
+    account_id: i64,
+    positions: &[RiskPosition],
+    symbol_returns: &HashMap<u32, Vec<f64>>,
+    daily_equity: &[i64],
+    config: &RiskConfig,
+) -> AccountRisk {
+    let equity_returns = returns(daily_equity);
+
+    AccountRisk {
+        account_id,
+        gross_exposure: positions.iter().map(|position| position.value().abs()).sum(),
+        by_player: concentration(positions, |p| p.player_name.clone()),
+        by_position: concentration(positions, |p| p.position.clone()),
+        by_team: concentration(positions, |p| p.team.clone()),
+        volatility: volatility(&equity_returns),
+        max_drawdown: max_drawdown(daily_equity),
+        sharpe_ratio: sharpe_ratio(&equity_returns),
+        value_at_risk: value_at_risk(positions, symbol_returns, config.var_confidence),
+        equity_observations: daily_equity.len(),
+    }
+}
+
+/// Assess the risk of an account's marked holdings, loading what it is grouped by and the
+/// price and equity history behind it
+pub async fn account_risk(
+    db_pool: &PgPool,
+    account_id: i64,
+    holdings: &[Holding],
+    config: &RiskConfig,
+) -> Result<AccountRisk> {
+    let symbol_ids: Vec<i32> = holdings.iter().map(|holding| holding.symbol_id as i32).collect();
+
+    let metadata: HashMap<u32, (String, String, String)> = sqlx::query!(
+        "SELECT symbol_id AS \"symbol_id!\", name, position, team FROM player_metadata
+         WHERE symbol_id = ANY($1)",
+        &symbol_ids
+    )
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.symbol_id as u32, (row.name, row.position, row.team)))
+    .collect();
+
+    let positions: Vec<RiskPosition> = holdings
+        .iter()
+        .map(|holding| {
+            let symbol_id = holding.symbol_id;
+            let (player_name, position, team) =
+                metadata.get(&symbol_id).cloned().unwrap_or_else(|| {
+                    (format!("Player #{symbol_id}"), "UNKNOWN".to_string(), "UNKNOWN".to_string())
+                });
+            RiskPosition {
+                symbol_id,
+                player_name,
+                position,
+                team,
+                quantity: holding.quantity,
+                mark: holding.mark,
+            }
+        })
+        .collect();
+
+    // Latest bars of every held symbol in one query, oldest first
+    let bars = sqlx::query!(
+        "SELECT symbol_id AS \"symbol_id!\", close_price AS \"close_price!\" FROM (
+             SELECT symbol_id, timestamp, close_price,
+                    ROW_NUMBER() OVER (PARTITION BY symbol_id ORDER BY timestamp DESC) AS bar
+             FROM price_history
+             WHERE symbol_id = ANY($1)
+         ) recent
+         WHERE bar <= $2
+         ORDER BY symbol_id, timestamp ASC",
+        &symbol_ids,
+        config.price_history_bars + 1
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut closes: HashMap<u32, Vec<i64>> = HashMap::new();
+    for bar in bars {
+        closes.entry(bar.symbol_id as u32).or_default().push(bar.close_price);
+    }
+    let symbol_returns =
+        closes.into_iter().map(|(symbol_id, closes)| (symbol_id, returns(&closes))).collect();
+
+    // Last equity snapshot of each day, oldest first
+    let daily_equity: Vec<i64> = sqlx::query_scalar!(
+        "SELECT total_equity FROM (
+             SELECT DISTINCT ON (DATE(timestamp)) timestamp, total_equity
+             FROM equity_timeseries
+             WHERE account_id = $1 AND timestamp >= NOW() - make_interval(days => $2)
+             ORDER BY DATE(timestamp), timestamp DESC
+         ) daily
+         ORDER BY timestamp ASC",
+        account_id,
+        config.equity_lookback_days as i32
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(assess(account_id, &positions, &symbol_returns, &daily_equity, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(
+        symbol_id: u32,
+        position: &str,
+        team: &str,
+        shares: i64,
+        mark: i64,
+    ) -> RiskPosition {
+        RiskPosition {
+            symbol_id,
+            player_name: format!("Player {symbol_id}"),
+            position: position.to_string(),
+            team: team.to_string(),
+            quantity: shares * QTY_SCALE,
+            mark,
+        }
+    }
+
+    /// A price path compounding the given returns from `start`
+    fn path(start: i64, returns: &[f64]) -> Vec<i64> {
+        let mut price = start as f64;
+        let mut path = vec![start];
+        for r in returns {
+            price *= 1.0 + r;
+            path.push(price.round() as i64);
+        }
+        path
+    }
+
+    #[test]
+    fn concentration_groups_gross_exposure() {
+        let positions = [
+            position(1, "QB", "KC", 10, 3000),
+            position(2, "WR", "KC", 10, 1000),
+            position(3, "WR", "BUF", -10, 1000),
+        ];
+
+        let by_team = concentration(&positions, |p| p.team.clone());
+        assert_eq!(by_team.buckets[0].key, "KC");
+        assert_eq!(by_team.buckets[0].value, 40000);
+        assert!((by_team.buckets[0].weight - 0.8).abs() < 1e-9);
+        assert!((by_team.herfindahl - (0.8f64.powi(2) + 0.2f64.powi(2))).abs() < 1e-9);
+
+        let by_position = concentration(&positions, |p| p.position.clone());
+        assert_eq!(by_position.buckets.len(), 2);
+        assert!((by_position.buckets[0].weight - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_and_alternating_paths() {
+        let flat = vec![10_000; 30];
+        assert_eq!(volatility(&returns(&flat)), Some(0.0));
+        assert_eq!(sharpe_ratio(&returns(&flat)), None);
+        assert_eq!(max_drawdown(&flat), 0.0);
+
+        // Alternating +10% / -10% returns have zero mean
+        let swings: Vec<f64> = (0..20).map(|i| if i % 2 == 0 { 0.1 } else { -0.1 }).collect();
+        let vol = volatility(&swings).unwrap();
+        assert!((vol - 0.1 * (20.0f64 / 19.0).sqrt()).abs() < 1e-9);
+        assert!(sharpe_ratio(&swings).unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn drawdown_measures_the_worst_fall_from_a_peak() {
+        // Worst fall is 150 -> 90, not 200 -> 160
+        let equity = [100, 150, 120, 90, 200, 160];
+        assert!((max_drawdown(&equity) - 0.4).abs() < 1e-9);
+
+        let rising = path(1000, &[0.01; 50]);
+        assert_eq!(max_drawdown(&rising), 0.0);
+        assert!(sharpe_ratio(&returns(&rising)).unwrap() > 10.0);
+    }
+
+    #[test]
+    fn value_at_risk_takes_the_tail_scenario() {
+        // 100 returns: 95 of +1%, then -2% .. -10% and one -20%
+        let mut symbol_path: Vec<f64> = vec![0.01; 95];
+        symbol_path.extend([-0.02, -0.04, -0.06, -0.08, -0.20]);
+        let closes = path(100_000, &symbol_path);
+        let positions = [position(7, "RB", "SF", 100, *closes.last().unwrap())];
+        let value = positions[0].value() as f64;
+        let symbol_returns = HashMap::from([(7, returns(&closes))]);
+
+        let var = value_at_risk(&positions, &symbol_returns, 0.95);
+        assert_eq!(var.scenarios, 100);
+        // The 5th-worst of 100 scenarios is the -2% day
+        let expected = (value * (closes[96] - closes[95]) as f64 / closes[95] as f64).abs();
+        assert!((var.amount as f64 - expected).abs() <= 1.0, "{} vs {expected}", var.amount);
+
+        // A short position gains on those days and loses on the +1% ones
+        let short = [position(7, "RB", "SF", -100, *closes.last().unwrap())];
+        let var = value_at_risk(&short, &symbol_returns, 0.95);
+        assert!((var.amount as f64 - value * 0.01).abs() <= value * 0.0005);
+
+        assert_eq!(value_at_risk(&positions, &HashMap::new(), 0.95).amount, 0);
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn account_risk_loads_every_symbols_latest_bars() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('risk-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // Symbols no real player uses, so no metadata or other history can match them
+        let symbols =
+            [900_000 + (account_id % 50_000) as i32, 950_000 + (account_id % 50_000) as i32];
+        let start = chrono::Utc::now().naive_utc() - chrono::Duration::hours(1);
+        for (symbol_id, closes) in
+            symbols.iter().zip([[1000, 1100, 990, 1089], [500, 400, 500, 500]])
+        {
+            for (minutes, close) in closes.into_iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO price_history
+                     (symbol_id, timestamp, open_price, high_price, low_price, close_price, volume)
+                     VALUES ($1, $2, $3, $3, $3, $3, 1)",
+                )
+                .bind(symbol_id)
+                .bind(start + chrono::Duration::minutes(minutes as i64))
+                .bind(close)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        }
+
+        let holdings = [
+            Holding { symbol_id: symbols[0] as u32, quantity: 10 * QTY_SCALE, mark: 1400 },
+            Holding { symbol_id: symbols[1] as u32, quantity: -10 * QTY_SCALE, mark: 450 },
+        ];
+        let config = RiskConfig { price_history_bars: 2, ..RiskConfig::default() };
+        let risk = account_risk(&pool, account_id, &holdings, &config).await;
+
+        sqlx::query("DELETE FROM price_history WHERE symbol_id = ANY($1)")
+            .bind(&symbols[..])
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let risk = risk.unwrap();
+        // Positions are valued at the marks given, not the last close
+        assert_eq!(risk.gross_exposure, 14_000 + 4_500);
+        assert_eq!(risk.by_player.buckets[0].key, format!("Player #{}", symbols[0]));
+        // Three latest bars per symbol: two return scenarios
+        assert_eq!(risk.value_at_risk.scenarios, 2);
+        // The worse scenario is the long leg's -10% with the short leg's +25%
+        assert_eq!(risk.value_at_risk.amount, 1_400 + 1_125);
+    }
+}
@@ -11,8 +11,10 @@ const CENTS: i64 = 1;          // 1 cent = 1 unit
 
 use crate::config::EquityServiceConfig;
 use crate::error::{EquityServiceError, Result};
+use crate::risk::{self, AccountRisk, Holding};
+use crate::marking::{BookLevels, Mark, MarkInputs, MarkPolicy, MarkSources, Observation};
 use crate::types::{
-    AccountEquityData, EquitySnapshot, Position,
+    AccountEquityData, EquitySnapshot, EquityUpdate, Position,
 };
 use account_service::position::TradeSide;
 use async_trait::async_trait;
@@ -20,10 +22,11 @@ use dashmap::DashMap;
 use execution_manager::{DispatchEvent, TradeEvent, BookDelta, TickBoundaryEvent, PostSettlementCallback};
 use sqlx::PgPool;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use bigdecimal::{BigDecimal, FromPrimitive};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use whistle::TickId;
 
 /// Equity Valuation Service - calculates real-time equity for all accounts
@@ -33,11 +36,24 @@ pub struct EquityValuationService {
     
     // In-memory caches for performance
     account_cache: Arc<DashMap<i64, AccountEquityData>>,
-    price_cache: Arc<RwLock<HashMap<u32, i64>>>, // symbol_id -> price in cents
+    price_cache: Arc<RwLock<HashMap<u32, Option<Observation>>>>, // symbol_id -> last trade (None: never traded)
+    fair_prices: Arc<RwLock<HashMap<u32, Option<Observation>>>>, // symbol_id -> RPE fair price (None: none published)
+    books: Arc<RwLock<HashMap<u32, BookLevels>>>, // symbol_id -> resting levels, for the book mid
+    
+    // Marking policy overrides by symbol (seeded from config)
+    mark_policies: Arc<DashMap<u32, MarkPolicy>>,
+    
+    // Last tick completed by the exchange
+    current_tick: Arc<AtomicU64>,
     
     // Equity cache for snapshots
     equity_cache: Arc<RwLock<HashMap<i64, EquitySnapshot>>>,
     
+    // Prior close per account: (day it was looked up for, equity of the prior daily snapshot)
+    prior_closes: Arc<DashMap<i64, (NaiveDate, Option<i64>)>>,
+    
+    // Per-account updates published after each tick
+    updates: broadcast::Sender<EquityUpdate>,
     
     // Double counting prevention
     accounts_updated_this_tick: Arc<RwLock<HashSet<i64>>>,
@@ -53,12 +69,21 @@ impl EquityValuationService {
         // Run migrations
         sqlx::migrate!("./migrations").run(&db_pool).await?;
         
+        let mark_policies = config.marking.symbol_policies.clone().into_iter().collect();
+        let (updates, _) = broadcast::channel(config.websocket.buffer_size.max(1));
+        
         Ok(Self {
             config,
             db_pool,
             account_cache: Arc::new(DashMap::new()),
             price_cache: Arc::new(RwLock::new(HashMap::new())),
+            fair_prices: Arc::new(RwLock::new(HashMap::new())),
+            books: Arc::new(RwLock::new(HashMap::new())),
+            mark_policies: Arc::new(mark_policies),
+            current_tick: Arc::new(AtomicU64::new(0)),
             equity_cache: Arc::new(RwLock::new(HashMap::new())),
+            prior_closes: Arc::new(DashMap::new()),
+            updates,
             accounts_updated_this_tick: Arc::new(RwLock::new(HashSet::new())),
             symbols_updated_this_tick: Arc::new(RwLock::new(HashSet::new())),
         })
@@ -85,16 +110,65 @@ impl EquityValuationService {
     }
 
 
-    /// Process a book delta event (price update)
-    async fn process_book_delta(&self, delta: &BookDelta) -> Result<()> {
-        // Update price cache
-        {
-            let mut prices = self.price_cache.write().await;
-            prices.insert(delta.symbol, delta.price_level as i64);
+    /// Record a pushed fair price
+    ///
+    /// Holders of the symbol are revalued at the next tick.
+    pub async fn update_fair_price(&self, symbol_id: u32, fair_cents: i64) {
+        let observation = Observation::new(fair_cents, Utc::now());
+        self.fair_prices.write().await.insert(symbol_id, Some(observation));
+        self.symbols_updated_this_tick.write().await.insert(symbol_id);
+    }
+
+    /// Override how a symbol is marked; `None` restores the configured policy
+    pub fn set_mark_policy(&self, symbol_id: u32, policy: Option<MarkPolicy>) {
+        let policy = policy.or_else(|| self.config.marking.symbol_policies.get(&symbol_id).copied());
+        match policy {
+            Some(policy) => {
+                self.mark_policies.insert(symbol_id, policy);
+            }
+            None => {
+                self.mark_policies.remove(&symbol_id);
+            }
         }
+    }
+
+    /// Policy a symbol is marked by
+    pub fn mark_policy(&self, symbol_id: u32) -> MarkPolicy {
+        self.mark_policies
+            .get(&symbol_id)
+            .map_or(self.config.marking.default_policy, |policy| *policy)
+    }
+
+    /// Subscribe to equity updates
+    ///
+    /// One update is published per account revalued at the end of a tick, i.e. whenever the
+    /// account traded or a symbol it holds was repriced. Slow subscribers lag and skip updates.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<EquityUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Database pool shared with the services built on EVS snapshots
+    pub fn db_pool(&self) -> &PgPool {
+        &self.db_pool
+    }
+
+    /// Last tick completed by the exchange
+    pub fn current_tick(&self) -> TickId {
+        self.current_tick.load(Ordering::Acquire)
+    }
+
+    /// Process a book delta event (resting quantity at one level)
+    async fn process_book_delta(&self, delta: &BookDelta) -> Result<()> {
+        let mid_moved = {
+            let mut books = self.books.write().await;
+            books
+                .entry(delta.symbol)
+                .or_default()
+                .apply(delta.side, delta.price_level, delta.new_quantity)
+        };
         
-        // Mark symbol as updated this tick
-        {
+        // Holders are revalued only when the mid moves
+        if mid_moved {
             let mut symbols_updated = self.symbols_updated_this_tick.write().await;
             symbols_updated.insert(delta.symbol);
         }
@@ -103,42 +177,35 @@ impl EquityValuationService {
     }
 
     /// Process a tick boundary event
-    async fn process_tick_boundary(&self, _boundary: &TickBoundaryEvent) -> Result<()> {
-        // Recalculate equity for all accounts that were updated this tick
-        let accounts_to_update = {
-            let accounts_updated = self.accounts_updated_this_tick.read().await;
-            accounts_updated.clone()
-        };
-        
-        let _symbols_to_update = {
-            let symbols_updated = self.symbols_updated_this_tick.read().await;
-            symbols_updated.clone()
-        };
-        
-        // Recalculate equity for affected accounts
-        for account_id in accounts_to_update {
-            if let Ok(snapshot) = self.recalculate_equity(account_id).await {
-                // Persist to database
-                self.persist_equity_snapshot(&snapshot).await?;
-                
-                tracing::info!("🔥 EVS: Equity update persisted for account {}: ${}", account_id, snapshot.total_equity);
-            }
-        }
-        
-        // Clear tick tracking
-        {
-            let mut accounts_updated = self.accounts_updated_this_tick.write().await;
-            accounts_updated.clear();
-        }
-        
-        {
-            let mut symbols_updated = self.symbols_updated_this_tick.write().await;
-            symbols_updated.clear();
-        }
-        
+    ///
+    /// ExecutionManager reports the same boundary through `on_tick_complete`, which does the
+    /// revaluation; here it only advances the tick.
+    async fn process_tick_boundary(&self, boundary: &TickBoundaryEvent) -> Result<()> {
+        self.current_tick.fetch_max(boundary.tick, Ordering::AcqRel);
         Ok(())
     }
 
+    /// Accounts to revalue at the end of a tick: those that traded, plus every holder of a
+    /// symbol whose price inputs changed. Clears the tick tracking.
+    async fn take_accounts_to_update(&self) -> HashSet<i64> {
+        let mut accounts = std::mem::take(&mut *self.accounts_updated_this_tick.write().await);
+        let symbols = std::mem::take(&mut *self.symbols_updated_this_tick.write().await);
+        
+        if !symbols.is_empty() {
+            accounts.extend(
+                self.account_cache
+                    .iter()
+                    .filter(|entry| {
+                        entry.positions.iter().any(|(symbol_id, position)| {
+                            position.quantity != 0 && symbols.contains(symbol_id)
+                        })
+                    })
+                    .map(|entry| *entry.key()),
+            );
+        }
+        accounts
+    }
+
     /// Update account data from a trade
     async fn update_account_from_trade(
         &self,
@@ -181,55 +248,9 @@ impl EquityValuationService {
         Ok(())
     }
 
-    /// Recalculate equity for an account
+    /// Recalculate equity for an account at the current tick
     async fn recalculate_equity(&self, account_id: i64) -> Result<EquitySnapshot> {
-        let account_data = self.get_account_data(account_id).await?;
-        
-        // Calculate position value using proper basis point scaling
-        let mut position_value = 0i64;
-        let mut unrealized_pnl = 0i64;
-        
-        for (symbol_id, position) in &account_data.positions {
-            // Get current price - try cache first, then fetch from latest trade
-            let current_price = self.get_current_price(*symbol_id).await?;
-            
-            // Use proper basis point scaling: (quantity_bp * price_cents) / QTY_SCALE
-            let current_value = (position.quantity * current_price) / QTY_SCALE;
-            position_value += current_value;
-            
-            // Unrealized P&L = (current_price - avg_cost) * quantity_bp / QTY_SCALE
-            let cost_basis = (position.quantity * position.avg_cost) / QTY_SCALE;
-            unrealized_pnl += current_value - cost_basis;
-        }
-        
-        // Total equity = cash + position value
-        let total_equity = account_data.cash_balance + position_value;
-        
-        // Calculate day change (vs previous day's equity)
-        let day_change = self.calculate_day_change(account_id, total_equity).await?;
-        let day_change_percent = if day_change != 0 {
-            (day_change as f64 / (total_equity - day_change) as f64) * 100.0
-        } else {
-            0.0
-        };
-        
-        let snapshot = EquitySnapshot {
-            account_id,
-            timestamp: chrono::Utc::now(),
-            tick: self.get_current_tick().await?,
-            total_equity,
-            cash_balance: account_data.cash_balance,
-            position_value,
-            unrealized_pnl,
-            realized_pnl: account_data.realized_pnl,
-            day_change,
-            day_change_percent,
-        };
-        
-        // Update equity cache
-        self.equity_cache.write().await.insert(account_id, snapshot.clone());
-        
-        Ok(snapshot)
+        self.recalculate_equity_with_tick(account_id, self.current_tick()).await
     }
 
     /// Recalculate equity for an account with a specific tick ID
@@ -239,13 +260,20 @@ impl EquityValuationService {
         // Calculate position value using proper basis point scaling
         let mut position_value = 0i64;
         let mut unrealized_pnl = 0i64;
+        let mut mark_sources = MarkSources::new();
         
         for (symbol_id, position) in &account_data.positions {
-            // Get current price - try cache first, then fetch from latest trade
-            let current_price = self.get_current_price(*symbol_id).await?;
+            if position.quantity == 0 {
+                continue;
+            }
+            
+            // Mark the position under the symbol's policy
+            let mark = self.mark(*symbol_id, position.avg_cost).await?;
+            let current_price = mark.price;
+            mark_sources.insert(*symbol_id, mark.source);
             
-            tracing::info!("🔥 EVS: Position value calculation - Symbol: {}, Quantity: {} bp, Current Price: {} cents (${:.2}), Position Value: {} cents", 
-                symbol_id, position.quantity, current_price, current_price as f64 / 100.0, (position.quantity * current_price) / QTY_SCALE);
+            tracing::info!("🔥 EVS: Position value calculation - Symbol: {}, Quantity: {} bp, Mark: {} cents (${:.2}, {:?}), Position Value: {} cents", 
+                symbol_id, position.quantity, current_price, current_price as f64 / 100.0, mark.source, (position.quantity * current_price) / QTY_SCALE);
             
             // Use proper basis point scaling: (quantity_bp * price_cents) / QTY_SCALE
             let current_value = (position.quantity * current_price) / QTY_SCALE;
@@ -259,13 +287,9 @@ impl EquityValuationService {
         // Total equity = cash + position value
         let total_equity = account_data.cash_balance + position_value;
         
-        // Calculate day change (vs previous day's equity)
-        let day_change = self.calculate_day_change(account_id, total_equity).await?;
-        let day_change_percent = if day_change != 0 {
-            (day_change as f64 / (total_equity - day_change) as f64) * 100.0
-        } else {
-            0.0
-        };
+        // Calculate day change (vs the prior daily snapshot)
+        let (day_change, day_change_percent) =
+            self.calculate_day_change(account_id, total_equity).await?;
         
         let snapshot = EquitySnapshot {
             account_id,
@@ -278,6 +302,7 @@ impl EquityValuationService {
             realized_pnl: account_data.realized_pnl,
             day_change,
             day_change_percent,
+            mark_sources,
         };
         
         // Update equity cache
@@ -370,71 +395,140 @@ impl EquityValuationService {
         })
     }
 
-    /// Calculate day change for an account
-    async fn calculate_day_change(&self, account_id: i64, current_equity: i64) -> Result<i64> {
-        // Get the first equity snapshot of the day for this account
-        let today = chrono::Utc::now().date_naive();
+    /// Calculate day change for an account: cents and percent against the latest daily
+    /// snapshot before today. Accounts without one have no change yet.
+    async fn calculate_day_change(
+        &self,
+        account_id: i64,
+        current_equity: i64,
+    ) -> Result<(i64, f64)> {
+        let Some(prior_close) = self.prior_close(account_id).await? else {
+            return Ok((0, 0.0));
+        };
         
-        let opening_equity = sqlx::query!(
-            "SELECT total_equity FROM equity_timeseries 
-             WHERE account_id = $1 AND DATE(timestamp) = $2 
-             ORDER BY timestamp ASC LIMIT 1",
+        let day_change = current_equity - prior_close;
+        let day_change_percent = if prior_close > 0 {
+            (day_change as f64 / prior_close as f64) * 100.0
+        } else {
+            0.0
+        };
+        Ok((day_change, day_change_percent))
+    }
+
+    /// Equity of an account's latest daily snapshot before today, looked up once a day
+    async fn prior_close(&self, account_id: i64) -> Result<Option<i64>> {
+        let today = Utc::now().date_naive();
+        if let Some(entry) = self.prior_closes.get(&account_id) {
+            if entry.0 == today {
+                return Ok(entry.1);
+            }
+        }
+        
+        let prior_close = sqlx::query!(
+            "SELECT total_equity FROM daily_equity_snapshots
+             WHERE account_id = $1 AND date < $2
+             ORDER BY date DESC LIMIT 1",
             account_id,
             today
         )
         .fetch_optional(&self.db_pool)
-        .await?;
+        .await?
+        .map(|row| row.total_equity);
         
-        match opening_equity {
-            Some(record) => Ok(current_equity - record.total_equity),
-            None => {
-                // No opening equity found, assume this is the first snapshot of the day
-                Ok(0)
-            }
-        }
+        self.prior_closes.insert(account_id, (today, prior_close));
+        Ok(prior_close)
     }
 
-    /// Get current price for a symbol
-    async fn get_current_price(&self, symbol_id: u32) -> Result<i64> {
-        // Try cache first
-        {
-            let prices = self.price_cache.read().await;
-            if let Some(price) = prices.get(&symbol_id) {
-                return Ok(*price);
-            }
+    /// Mark a symbol held at `avg_cost` under its policy
+    async fn mark(&self, symbol_id: u32, avg_cost: i64) -> Result<Mark> {
+        let inputs = self.mark_inputs(symbol_id).await?;
+        let marking = &self.config.marking;
+        Ok(self.mark_policy(symbol_id).mark(
+            &inputs,
+            marking.untraded,
+            marking.blend_half_life,
+            avg_cost,
+            Utc::now(),
+        ))
+    }
+
+    /// Last trade, book mid and fair price of a symbol
+    async fn mark_inputs(&self, symbol_id: u32) -> Result<MarkInputs> {
+        // The book is live, so its mid is never stale
+        let book_mid = {
+            let books = self.books.read().await;
+            books
+                .get(&symbol_id)
+                .and_then(BookLevels::mid)
+                .map(|mid| Observation::new(mid, Utc::now()))
+        };
+        
+        Ok(MarkInputs {
+            last_trade: self.last_trade(symbol_id).await?,
+            book_mid,
+            fair_price: self.fair_price(symbol_id).await?,
+        })
+    }
+
+    /// Last trade of a symbol, from the cache or the latest trade in the database
+    async fn last_trade(&self, symbol_id: u32) -> Result<Option<Observation>> {
+        if let Some(observation) = self.price_cache.read().await.get(&symbol_id) {
+            return Ok(*observation);
         }
         
-        // Cache miss - fetch from latest trade
         let latest_trade = sqlx::query!(
-            "SELECT price FROM trades WHERE symbol_id = $1 ORDER BY timestamp DESC LIMIT 1",
+            "SELECT price, timestamp FROM trades WHERE symbol_id = $1 ORDER BY timestamp DESC LIMIT 1",
             symbol_id as i32
         )
         .fetch_optional(&self.db_pool)
         .await?;
         
-        match latest_trade {
-            Some(trade) => {
-                let price = trade.price as i64;
-                // Update cache
-                {
-                    let mut prices = self.price_cache.write().await;
-                    prices.insert(symbol_id, price);
-                }
-                Ok(price)
-            }
-            None => {
-                // No trades found, use a default price or return error
-                tracing::warn!("No price found for symbol {}", symbol_id);
-                Ok(1000) // Default price of $10.00
-            }
+        // Remember untraded symbols too; settlements fill them in
+        let observation = latest_trade.map(|trade| {
+            let at = trade.timestamp.map_or_else(Utc::now, |ts| ts.and_utc());
+            Observation::new(trade.price, at)
+        });
+        self.price_cache.write().await.insert(symbol_id, observation);
+        Ok(observation)
+    }
+
+    /// Fair price of a symbol: the last one pushed, else the RPE's in the database
+    async fn fair_price(&self, symbol_id: u32) -> Result<Option<Observation>> {
+        if let Some(observation) = self.fair_prices.read().await.get(&symbol_id) {
+            return Ok(*observation);
         }
+        
+        // rpe_fair_prices is keyed by symbol ID; fair_prices holds the composite prices
+        let rpe = sqlx::query!(
+            "SELECT fair_cents, ts FROM rpe_fair_prices WHERE player_id = $1",
+            symbol_id as i32
+        )
+        .fetch_optional(&self.db_pool)
+        .await?
+        .map(|row| Observation::new(row.fair_cents, row.ts));
+        
+        let observation = match rpe {
+            Some(observation) => Some(observation),
+            None => sqlx::query!(
+                "SELECT fair_price_cents, calculated_at FROM fair_prices
+                 WHERE symbol_id = $1 ORDER BY calculated_at DESC LIMIT 1",
+                symbol_id as i32
+            )
+            .fetch_optional(&self.db_pool)
+            .await?
+            .map(|row| Observation::new(row.fair_price_cents, row.calculated_at)),
+        };
+        
+        // Pushed prices replace whatever was loaded here
+        self.fair_prices.write().await.entry(symbol_id).or_insert(observation);
+        Ok(observation)
     }
 
-    /// Get current tick (placeholder implementation)
-    async fn get_current_tick(&self) -> Result<TickId> {
-        // In a real implementation, this would get the current tick from the system
-        // For now, we'll use a simple counter
-        Ok(1)
+    /// Record a trade as the symbol's last trade
+    async fn record_last_trade(&self, symbol_id: u32, price: i64) {
+        let observation = Observation::new(price, Utc::now());
+        self.price_cache.write().await.insert(symbol_id, Some(observation));
+        self.symbols_updated_this_tick.write().await.insert(symbol_id);
     }
 
     /// Persist equity snapshot to database
@@ -446,8 +540,8 @@ impl EquityValuationService {
             r#"
             INSERT INTO equity_timeseries 
             (account_id, timestamp, tick, total_equity, cash_balance, position_value, 
-             unrealized_pnl, realized_pnl, day_change, day_change_percent)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             unrealized_pnl, realized_pnl, day_change, day_change_percent, mark_sources)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             "#,
             snapshot.account_id,
             snapshot.timestamp,
@@ -458,7 +552,8 @@ impl EquityValuationService {
             snapshot.unrealized_pnl,
             snapshot.realized_pnl,
             snapshot.day_change,
-            BigDecimal::from_f64(snapshot.day_change_percent).unwrap_or(BigDecimal::from(0))
+            BigDecimal::from_f64(snapshot.day_change_percent).unwrap_or(BigDecimal::from(0)),
+            serde_json::to_value(&snapshot.mark_sources)?
         )
         .execute(&self.db_pool)
         .await
@@ -474,6 +569,22 @@ impl EquityValuationService {
 
 
 
+    /// Assess an account's portfolio risk, with positions marked as they are for equity
+    pub async fn account_risk(&self, account_id: i64) -> Result<AccountRisk> {
+        let account_data = self.get_account_data(account_id).await?;
+        
+        let mut holdings = Vec::new();
+        for (symbol_id, position) in &account_data.positions {
+            if position.quantity == 0 {
+                continue;
+            }
+            let mark = self.mark(*symbol_id, position.avg_cost).await?;
+            holdings.push(Holding { symbol_id: *symbol_id, quantity: position.quantity, mark: mark.price });
+        }
+        
+        risk::account_risk(&self.db_pool, account_id, &holdings, &self.config.risk).await
+    }
+
     /// Get current equity for an account
     pub async fn get_current_equity(&self, account_id: i64) -> Result<EquitySnapshot> {
         if let Some(snapshot) = self.equity_cache.read().await.get(&account_id) {
@@ -503,6 +614,7 @@ impl PostSettlementCallback for EquityValuationService {
         
         // Update account data from the settled trade
         self.update_account_from_trade(account_id, symbol_id, price, quantity_basis_points, side).await?;
+        self.record_last_trade(symbol_id, price).await;
         
         // Mark account as updated this tick
         {
@@ -519,19 +631,8 @@ impl PostSettlementCallback for EquityValuationService {
     async fn on_price_updated(&self, symbol_id: u32, price: i64) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("🔥 EVS: Received price update callback - Symbol: {}, Price: {}", symbol_id, price);
         
-        // Update price cache
-        {
-            let mut prices = self.price_cache.write().await;
-            prices.insert(symbol_id, price);
-        }
-        
-        // Mark symbol as updated this tick
-        {
-            let mut symbols_updated = self.symbols_updated_this_tick.write().await;
-            symbols_updated.insert(symbol_id);
-            tracing::info!("🔥 EVS: Marked symbol {} as updated this tick. Total symbols updated: {}", 
-                symbol_id, symbols_updated.len());
-        }
+        // Record the last trade; holders are revalued at the end of the tick
+        self.record_last_trade(symbol_id, price).await;
         
         tracing::info!("🔥 EVS: Successfully processed price update for symbol {}", symbol_id);
         Ok(())
@@ -539,14 +640,11 @@ impl PostSettlementCallback for EquityValuationService {
     
     async fn on_tick_complete(&self, tick_id: TickId) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("🔥 EVS: Received tick complete callback - Tick: {}", tick_id);
+        self.current_tick.fetch_max(tick_id, Ordering::AcqRel);
         
-        // Recalculate equity for all accounts that were updated this tick
-        let accounts_to_update = {
-            let accounts_updated = self.accounts_updated_this_tick.read().await;
-            let accounts = accounts_updated.clone();
-            tracing::info!("🔥 EVS: Found {} accounts to update for tick {}", accounts.len(), tick_id);
-            accounts
-        };
+        // Recalculate equity for accounts that traded or hold a repriced symbol
+        let accounts_to_update = self.take_accounts_to_update().await;
+        tracing::info!("🔥 EVS: Found {} accounts to update for tick {}", accounts_to_update.len(), tick_id);
         
         // Recalculate equity for affected accounts
         for account_id in accounts_to_update {
@@ -558,23 +656,15 @@ impl PostSettlementCallback for EquityValuationService {
                 // Persist to database
                 self.persist_equity_snapshot(&snapshot).await?;
                 
+                // Publish to subscribers; none listening is not an error
+                let _ = self.updates.send(EquityUpdate::from(&snapshot));
+                
                 tracing::info!("🔥 EVS: Equity update persisted for account {}: ${}", account_id, snapshot.total_equity);
             } else {
                 tracing::error!("🔥 EVS: Failed to recalculate equity for account {}", account_id);
             }
         }
         
-        // Clear tick tracking
-        {
-            let mut accounts_updated = self.accounts_updated_this_tick.write().await;
-            accounts_updated.clear();
-        }
-        
-        {
-            let mut symbols_updated = self.symbols_updated_this_tick.write().await;
-            symbols_updated.clear();
-        }
-        
         tracing::info!("🔥 EVS: Completed tick {} processing", tick_id);
         Ok(())
     }
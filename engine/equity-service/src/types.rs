@@ -7,6 +7,7 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use whistle::TickId;
 use crate::error::EquityServiceError;
+use crate::marking::MarkSources;
 
 /// Account equity data with positions and cash balance
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +42,7 @@ pub struct EquitySnapshot {
     pub realized_pnl: i64,      // Realized P&L in cents
     pub day_change: i64,        // $ change today in cents
     pub day_change_percent: f64, // % change today
+    pub mark_sources: MarkSources, // How each position was marked
 }
 
 /// Real-time equity update for WebSocket broadcasting
@@ -58,6 +60,23 @@ pub struct EquityUpdate {
     pub realized_pnl: i64,      // Realized P&L in cents (actual trading profits/losses)
 }
 
+impl From<&EquitySnapshot> for EquityUpdate {
+    fn from(snapshot: &EquitySnapshot) -> Self {
+        Self {
+            account_id: snapshot.account_id,
+            tick: snapshot.tick,
+            timestamp: snapshot.timestamp.timestamp(),
+            total_equity: snapshot.total_equity,
+            cash_balance: snapshot.cash_balance,
+            position_value: snapshot.position_value,
+            day_change: snapshot.day_change,
+            day_change_percent: snapshot.day_change_percent,
+            unrealized_pnl: snapshot.unrealized_pnl,
+            realized_pnl: snapshot.realized_pnl,
+        }
+    }
+}
+
 /// WebSocket message for equity updates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EquityWebSocketMessage {
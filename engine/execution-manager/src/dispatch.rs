@@ -7,7 +7,7 @@ use crate::metrics::MetricsCollector;
 use account_service::position::TradeSide;
 use async_trait::async_trait;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use whistle::TickId;
 
 /// Trait for services that need to be notified after trade settlement
@@ -27,6 +27,9 @@ pub trait PostSettlementCallback: Send + Sync {
     async fn on_tick_complete(&self, tick_id: TickId) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
 }
 
+/// Capacity of the live event broadcast; slower subscribers see `Lagged`
+const EVENT_BROADCAST_CAPACITY: usize = 8192;
+
 /// Event dispatcher for fanning out events to downstream systems
 pub struct EventDispatcher {
     #[allow(dead_code)]
@@ -39,6 +42,9 @@ pub struct EventDispatcher {
     
     // Post-settlement callbacks
     post_settlement_callbacks: Vec<Arc<dyn PostSettlementCallback>>,
+
+    // Live event subscribers (e.g. the FIX acceptor)
+    event_broadcast: broadcast::Sender<DispatchEvent>,
 }
 
 impl EventDispatcher {
@@ -49,6 +55,7 @@ impl EventDispatcher {
             analytics_converter: AnalyticsConverter::new(100), // Sample every 100 ticks
             analytics_sender: None,
             post_settlement_callbacks: Vec::new(),
+            event_broadcast: broadcast::channel(EVENT_BROADCAST_CAPACITY).0,
         }
     }
 
@@ -65,6 +72,11 @@ impl EventDispatcher {
         self.post_settlement_callbacks.push(callback);
     }
 
+    /// Subscribe to every dispatched event, from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<DispatchEvent> {
+        self.event_broadcast.subscribe()
+    }
+
     /// Notify all callbacks of a trade settlement
     pub async fn notify_trade_settled(
         &self, 
@@ -117,6 +129,9 @@ impl EventDispatcher {
             }
         }
 
+        // Live subscribers; having none is not an error
+        let _ = self.event_broadcast.send(event);
+
         // TODO: Dispatch to other systems (ReplayEngine, WebUI, etc.)
 
         Ok(())
@@ -243,6 +243,18 @@ impl ExecutionManager {
                         tracing::error!("Failed to settle trade: {}", e);
                         return Err(e);
                     }
+
+                    self.record_order_fills(trade).await;
+                }
+                DispatchEvent::OrderCancelled(cancelled) => {
+                    let orders = self.account_service.orders();
+                    if let Err(e) = orders.mark_cancelled(cancelled.order_id as i64).await {
+                        tracing::warn!(
+                            "Failed to mark order {} cancelled: {}",
+                            cancelled.order_id,
+                            e
+                        );
+                    }
                 }
                 DispatchEvent::TickBoundary(boundary) => {
                     tracing::info!("📢 ExecutionManager: Processing TickBoundary event for tick {}", boundary.tick);
@@ -290,6 +302,48 @@ impl ExecutionManager {
         Ok(())
     }
 
+    /// Cancel every open order of a symbol whose engine was lost
+    ///
+    /// Used when an engine is dropped without emitting cancels for its resting orders: each
+    /// open order record is marked cancelled, its reservations are released and an
+    /// `OrderCancelled` event is published with `reason`. Returns the number of orders
+    /// cancelled.
+    pub async fn cancel_symbol_orders(
+        &self,
+        symbol_id: u32,
+        tick: TickId,
+        reason: &str,
+    ) -> Result<usize, ExecutionError> {
+        let orders = self.account_service.orders();
+        let open = orders
+            .list_open_for_symbol(symbol_id as i32)
+            .await
+            .map_err(|e| ExecutionError::SettlementFailed(e.to_string()))?;
+
+        for order in &open {
+            if let Err(e) = orders.mark_cancelled(order.order_id).await {
+                tracing::warn!("Failed to mark order {} cancelled: {}", order.order_id, e);
+            }
+            if let Err(e) = self.account_service.release_order_reservations(order.order_id).await {
+                tracing::warn!("Failed to release reservations of order {}: {}", order.order_id, e);
+            }
+
+            let cancelled = DispatchEvent::OrderCancelled(OrderCancelled {
+                order_id: order.order_id as u64,
+                reason: Some(reason.to_string()),
+                logical_timestamp: tick,
+                wall_clock_timestamp: Instant::now(),
+                symbol: symbol_id,
+            });
+            if let Err(e) = self.write_event_to_wal(&cancelled, symbol_id) {
+                tracing::warn!("Failed to write event to WAL: {}", e);
+            }
+            self.dispatcher.dispatch(cancelled).map_err(ExecutionError::DispatchFailed)?;
+        }
+
+        Ok(open.len())
+    }
+
     /// Check if a tick is ready to be flushed
     ///
     /// Returns true if all registered symbols have completed the specified tick.
@@ -426,6 +480,19 @@ impl ExecutionManager {
     }
 
 
+    /// Apply a trade's quantity to both orders' persisted records
+    ///
+    /// Order records are bookkeeping for the gateway, so failures are logged rather than
+    /// failing the event pipeline.
+    async fn record_order_fills(&self, trade_event: &TradeEvent) {
+        let orders = self.account_service.orders();
+        for order_id in [trade_event.maker_order_id, trade_event.taker_order_id] {
+            if let Err(e) = orders.record_fill(order_id as i64, trade_event.quantity as i64).await {
+                tracing::warn!("Failed to record fill for order {}: {}", order_id, e);
+            }
+        }
+    }
+
     /// Get statistics about the current state
     pub fn get_stats(&self) -> ExecutionStats {
         ExecutionStats {
@@ -563,6 +630,14 @@ impl ExecutionManager {
         Ok(())
     }
 
+    /// Subscribe to the live stream of dispatched events
+    ///
+    /// Events are delivered after trade settlement and order record updates, so a
+    /// subscriber that looks up an order sees the event already applied.
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<DispatchEvent> {
+        self.dispatcher.subscribe()
+    }
+
     /// Add a post-settlement callback (like EVS)
     pub fn add_post_settlement_callback(&mut self, callback: Arc<dyn PostSettlementCallback>) {
         self.dispatcher.add_post_settlement_callback(callback);
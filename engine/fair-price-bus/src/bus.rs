@@ -0,0 +1,194 @@
+//! In-process fair price bus
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Updates buffered per subscriber before it lags and resyncs from the latest prices
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// A new fair price for one symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairPriceUpdate {
+    pub symbol_id: u32,
+    pub fair_cents: i64,
+    pub delta_cents: i64,
+    /// Producer of the price, e.g. `rpe` or `rpe_live`
+    pub source: String,
+    pub ts: DateTime<Utc>,
+}
+
+/// Broadcast of fair price updates that also remembers the latest price per symbol
+#[derive(Clone)]
+pub struct FairPriceBus {
+    sender: broadcast::Sender<FairPriceUpdate>,
+    latest: Arc<RwLock<HashMap<u32, FairPriceUpdate>>>,
+}
+
+impl Default for FairPriceBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FairPriceBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender, latest: Arc::new(RwLock::new(HashMap::new())) }
+    }
+
+    /// Record an update and deliver it to every subscriber
+    pub fn publish(&self, update: FairPriceUpdate) {
+        self.latest.write().insert(update.symbol_id, update.clone());
+        // No subscribers is fine; late subscribers start from `snapshot`
+        let _ = self.sender.send(update);
+    }
+
+    /// Receive every update published from now on
+    pub fn subscribe(&self) -> Subscription {
+        Subscription {
+            receiver: self.sender.subscribe(),
+            latest: self.latest.clone(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Latest fair price for a symbol
+    pub fn latest(&self, symbol_id: u32) -> Option<FairPriceUpdate> {
+        self.latest.read().get(&symbol_id).cloned()
+    }
+
+    /// Latest fair price for every symbol, ordered by symbol
+    pub fn snapshot(&self) -> Vec<FairPriceUpdate> {
+        snapshot(&self.latest)
+    }
+}
+
+/// Receiving end of the bus
+///
+/// A subscriber that falls more than the channel capacity behind is handed the latest price of
+/// every symbol instead of the updates it missed.
+pub struct Subscription {
+    receiver: broadcast::Receiver<FairPriceUpdate>,
+    latest: Arc<RwLock<HashMap<u32, FairPriceUpdate>>>,
+    pending: VecDeque<FairPriceUpdate>,
+}
+
+impl Subscription {
+    /// Next update, or `None` once every `FairPriceBus` handle has been dropped
+    pub async fn recv(&mut self) -> Option<FairPriceUpdate> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Some(update);
+            }
+            match self.receiver.recv().await {
+                Ok(update) => return Some(update),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Fair price subscriber lagged by {} updates, resyncing", missed);
+                    self.resync();
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Next update if one is already waiting
+    pub fn try_recv(&mut self) -> Option<FairPriceUpdate> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Some(update);
+            }
+            match self.receiver.try_recv() {
+                Ok(update) => return Some(update),
+                Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                    warn!("Fair price subscriber lagged by {} updates, resyncing", missed);
+                    self.resync();
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Latest fair price for every symbol, ordered by symbol
+    pub fn snapshot(&self) -> Vec<FairPriceUpdate> {
+        snapshot(&self.latest)
+    }
+
+    fn resync(&mut self) {
+        // Skip the stale backlog; the latest prices supersede it
+        self.receiver = self.receiver.resubscribe();
+        self.pending.extend(snapshot(&self.latest));
+    }
+}
+
+fn snapshot(latest: &RwLock<HashMap<u32, FairPriceUpdate>>) -> Vec<FairPriceUpdate> {
+    let mut updates: Vec<_> = latest.read().values().cloned().collect();
+    updates.sort_by_key(|update| update.symbol_id);
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(symbol_id: u32, fair_cents: i64) -> FairPriceUpdate {
+        FairPriceUpdate {
+            symbol_id,
+            fair_cents,
+            delta_cents: 0,
+            source: "test".to_string(),
+            ts: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_updates_and_latest_is_kept() {
+        let bus = FairPriceBus::new();
+        let mut sub = bus.subscribe();
+
+        bus.publish(update(7, 1500));
+        bus.publish(update(7, 1550));
+        bus.publish(update(3, 900));
+
+        assert_eq!(sub.recv().await.unwrap().fair_cents, 1500);
+        assert_eq!(sub.try_recv().unwrap().fair_cents, 1550);
+        assert_eq!(bus.latest(7).unwrap().fair_cents, 1550);
+        let symbols: Vec<_> = bus.snapshot().iter().map(|u| u.symbol_id).collect();
+        assert_eq!(symbols, vec![3, 7]);
+    }
+
+    #[tokio::test]
+    async fn lagged_subscriber_resyncs_from_latest() {
+        let bus = FairPriceBus::new();
+        let mut sub = bus.subscribe();
+
+        for i in 0..(CHANNEL_CAPACITY as i64 + 10) {
+            bus.publish(update((i % 3) as u32, i));
+        }
+
+        // The lag is replaced by the latest price of each symbol, with no stale backlog after it
+        let resync: Vec<_> = [sub.recv().await, sub.recv().await, sub.recv().await]
+            .into_iter()
+            .map(|u| u.unwrap())
+            .collect();
+        assert_eq!(resync, bus.snapshot());
+
+        bus.publish(update(0, -1));
+        assert_eq!(sub.recv().await.unwrap().fair_cents, -1);
+    }
+
+    #[tokio::test]
+    async fn recv_ends_when_bus_is_dropped() {
+        let bus = FairPriceBus::new();
+        let mut sub = bus.subscribe();
+        bus.publish(update(1, 100));
+        drop(bus);
+
+        assert!(sub.recv().await.is_some());
+        assert!(sub.recv().await.is_none());
+    }
+}
@@ -0,0 +1,23 @@
+//! # Fair Price Bus
+//!
+//! Pushes fair price updates from the RPE engine to the exchange as they are calculated, so
+//! consumers (market maker quotes, Whistle price bands, equity valuation, the `fair_price`
+//! WebSocket stream) no longer poll `rpe_fair_prices`.
+//!
+//! Within one process the bus is a broadcast channel plus the latest price per symbol. Across
+//! processes the exchange listens on a Unix socket (`FAIR_PRICE_BUS_SOCKET`) and RPE processes
+//! forward their local bus to it as JSON lines.
+
+pub mod bus;
+pub mod unix;
+
+pub use bus::{FairPriceBus, FairPriceUpdate, Subscription};
+pub use unix::{forward_unix, serve_unix};
+
+/// Environment variable naming the exchange's fair price socket
+pub const SOCKET_ENV: &str = "FAIR_PRICE_BUS_SOCKET";
+
+/// Socket path from `FAIR_PRICE_BUS_SOCKET`, if configured
+pub fn socket_from_env() -> Option<std::path::PathBuf> {
+    std::env::var_os(SOCKET_ENV).filter(|path| !path.is_empty()).map(Into::into)
+}
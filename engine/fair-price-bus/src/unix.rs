@@ -0,0 +1,148 @@
+//! Unix socket transport between RPE processes and the exchange
+//!
+//! The exchange serves the socket and republishes what it reads into its own bus; RPE
+//! processes forward their bus to it. Each message is one JSON-encoded [`FairPriceUpdate`]
+//! per line.
+
+use crate::bus::{FairPriceBus, FairPriceUpdate, Subscription};
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{info, warn};
+
+/// Accept producers on `path` and publish everything they send into `bus`
+///
+/// A stale socket file left by a previous run is replaced. Runs until the listener fails.
+pub async fn serve_unix(bus: FairPriceBus, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    info!("Fair price bus listening on {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ingest(stream, &bus).await {
+                warn!("Fair price producer disconnected: {}", e);
+            }
+        });
+    }
+}
+
+async fn ingest(stream: UnixStream, bus: &FairPriceBus) -> io::Result<()> {
+    let mut lines = BufReader::new(stream).lines();
+    while let Some(line) = lines.next_line().await? {
+        match serde_json::from_str::<FairPriceUpdate>(&line) {
+            Ok(update) => bus.publish(update),
+            Err(e) => warn!("Ignoring malformed fair price update: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Forward every update from `subscription` to the exchange socket at `path`
+///
+/// Never blocks the producer on the exchange: while the socket is unavailable updates are
+/// dropped (they are also stored in `rpe_fair_prices`), and each (re)connect starts with the
+/// latest price of every symbol. Returns once the subscribed bus is dropped and drained.
+pub async fn forward_unix(mut subscription: Subscription, path: impl Into<PathBuf>) {
+    let path = path.into();
+    let mut stream: Option<UnixStream> = None;
+    let mut warned = false;
+
+    while let Some(update) = subscription.recv().await {
+        let batch = match stream {
+            Some(_) => vec![update],
+            None => match UnixStream::connect(&path).await {
+                Ok(connected) => {
+                    info!("Forwarding fair prices to {}", path.display());
+                    stream = Some(connected);
+                    warned = false;
+                    // Already includes this update
+                    subscription.snapshot()
+                }
+                Err(e) => {
+                    if !warned {
+                        warn!("Fair price bus at {} unavailable: {}", path.display(), e);
+                        warned = true;
+                    }
+                    continue;
+                }
+            },
+        };
+
+        if let Some(connected) = stream.as_mut() {
+            if let Err(e) = write_lines(connected, &batch).await {
+                warn!("Lost fair price bus at {}: {}", path.display(), e);
+                stream = None;
+            }
+        }
+    }
+}
+
+async fn write_lines(stream: &mut UnixStream, updates: &[FairPriceUpdate]) -> io::Result<()> {
+    let mut buf = Vec::new();
+    for update in updates {
+        serde_json::to_writer(&mut buf, update)?;
+        buf.push(b'\n');
+    }
+    stream.write_all(&buf).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn update(symbol_id: u32, fair_cents: i64) -> FairPriceUpdate {
+        FairPriceUpdate {
+            symbol_id,
+            fair_cents,
+            delta_cents: 0,
+            source: "test".to_string(),
+            ts: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_cross_the_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fair-price.sock");
+
+        let exchange = FairPriceBus::new();
+        let mut received = exchange.subscribe();
+        tokio::spawn(serve_unix(exchange.clone(), path.clone()));
+        while !path.exists() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let rpe = FairPriceBus::new();
+        let forwarder = tokio::spawn(forward_unix(rpe.subscribe(), path));
+        rpe.publish(update(11, 2400));
+        rpe.publish(update(12, 1800));
+        drop(rpe);
+        forwarder.await.unwrap();
+
+        let first = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap();
+        let second = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap();
+        assert_eq!(first.unwrap().symbol_id, 11);
+        assert_eq!(second.unwrap().fair_cents, 1800);
+        assert_eq!(exchange.latest(11).unwrap().fair_cents, 2400);
+    }
+
+    #[tokio::test]
+    async fn forwarder_does_not_wait_for_a_missing_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpe = FairPriceBus::new();
+        let forwarder = tokio::spawn(forward_unix(rpe.subscribe(), dir.path().join("none.sock")));
+
+        rpe.publish(update(1, 100));
+        drop(rpe);
+        tokio::time::timeout(Duration::from_secs(5), forwarder).await.unwrap().unwrap();
+    }
+}
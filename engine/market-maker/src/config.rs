@@ -1,3 +1,6 @@
+use crate::ladder::LadderProfile;
+use crate::strategy::InventorySkewParams;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -7,6 +10,9 @@ pub struct MarketMakerConfig {
     pub database: DatabaseConfig,
     pub market_maker: MarketMakerParameters,
     pub cache: CacheConfig,
+    pub strategy: StrategyConfig,
+    pub circuit_breakers: CircuitBreakerConfig,
+    pub ladder: LadderConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,7 +50,7 @@ pub struct MarketMakerParameters {
     /// Order quantity in basis points (e.g., 10000 = 1 share)
     pub order_quantity_bp: i64,
     
-    /// API credentials for order submission
+    /// API credentials for order submission (provisioned via the admin API-key endpoints)
     pub api_key: String,
     pub api_secret: String,
     
@@ -52,6 +58,91 @@ pub struct MarketMakerParameters {
     pub websocket_gateway_url: String,
 }
 
+/// Quoting strategy selection (see `crate::strategy`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    pub kind: StrategyKind,
+    pub inventory_skew: InventorySkewParams,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// Fixed `fair ± spread_bps` quotes
+    #[default]
+    Symmetric,
+    /// Avellaneda–Stoikov inventory-skewed quotes
+    InventorySkew,
+}
+
+impl std::str::FromStr for StrategyKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "symmetric" => Ok(Self::Symmetric),
+            "inventory_skew" => Ok(Self::InventorySkew),
+            other => anyhow::bail!("Unknown MARKET_MAKER_STRATEGY '{other}' (expected symmetric or inventory_skew)"),
+        }
+    }
+}
+
+/// Ladder quoting (see `crate::ladder`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LadderConfig {
+    /// Profile for every symbol without an override
+    pub default: LadderProfile,
+    /// Per-symbol profiles, e.g. deeper ladders for star players
+    pub overrides: HashMap<u32, LadderProfile>,
+}
+
+impl LadderConfig {
+    /// Profile to quote `symbol_id` with
+    pub fn profile(&self, symbol_id: u32) -> &LadderProfile {
+        self.overrides.get(&symbol_id).unwrap_or(&self.default)
+    }
+}
+
+/// Circuit breakers from `docs/backend/dynamic_pricing_bots_design.md` §10
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Stop quoting a symbol once the house takes more than this share of its volume
+    pub max_house_share: f64,
+    
+    /// Rolling window the house share is measured over (seconds)
+    pub house_share_window_seconds: u64,
+    
+    /// Volume needed in the window before the house share is judged (basis points)
+    pub house_share_min_volume_bp: i64,
+    
+    /// How long the house share breaker keeps a symbol switched off (seconds)
+    pub disable_seconds: u64,
+    
+    /// Largest fair price move within the volatility window before quotes are widened
+    /// (basis points, highest over lowest price)
+    pub max_fair_move_bps: u32,
+    
+    /// Volatility window (seconds)
+    pub volatility_window_seconds: u64,
+    
+    /// Extra distance from fair while the volatility breaker is tripped (basis points)
+    pub volatility_widen_bps: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_house_share: 0.6,
+            house_share_window_seconds: 600, // 10 minutes
+            house_share_min_volume_bp: 100_000, // 10 shares
+            disable_seconds: 600, // 10 minutes
+            max_fair_move_bps: 500, // 5%
+            volatility_window_seconds: 120,
+            volatility_widen_bps: 1000, // 10%
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
     /// Cache TTL in seconds
@@ -81,8 +172,8 @@ impl Default for MarketMakerConfig {
                 max_spread_bps: 1200, // 12%
                 min_fair_price_change_bps: 100, // 1%
                 order_quantity_bp: 10000, // 1 share
-                api_key: "ak_market_maker_1234567890abcdef".to_string(),
-                api_secret: "sk_market_maker_abcdef1234567890".to_string(),
+                api_key: String::new(),
+                api_secret: String::new(),
                 websocket_gateway_url: "ws://localhost:8081/orders".to_string(),
             },
             cache: CacheConfig {
@@ -90,6 +181,9 @@ impl Default for MarketMakerConfig {
                 max_size: 1000,
                 refresh_interval_seconds: 30, // 30 seconds
             },
+            strategy: StrategyConfig::default(),
+            circuit_breakers: CircuitBreakerConfig::default(),
+            ladder: LadderConfig::default(),
         }
     }
 }
@@ -114,10 +208,85 @@ impl MarketMakerConfig {
         if let Ok(frequency) = std::env::var("MARKET_MAKER_UPDATE_FREQUENCY_SECONDS") {
             config.market_maker.update_frequency_seconds = frequency.parse().unwrap_or(30);
         }
+
+        if let Ok(api_key) = std::env::var("MARKET_MAKER_API_KEY") {
+            config.market_maker.api_key = api_key;
+        }
+
+        if let Ok(api_secret) = std::env::var("MARKET_MAKER_API_SECRET") {
+            config.market_maker.api_secret = api_secret;
+        }
+        
+        if let Ok(max_position) = std::env::var("MARKET_MAKER_MAX_POSITION") {
+            config.market_maker.max_position_per_player = max_position.parse().unwrap_or(100);
+        }
+        
+        if let Ok(strategy) = std::env::var("MARKET_MAKER_STRATEGY") {
+            config.strategy.kind = strategy.parse()?;
+        }
+        
+        let skew = &mut config.strategy.inventory_skew;
+        if let Ok(gamma) = std::env::var("MARKET_MAKER_RISK_AVERSION") {
+            skew.risk_aversion = gamma.parse().unwrap_or(skew.risk_aversion);
+        }
+        
+        if let Ok(sigma) = std::env::var("MARKET_MAKER_VOLATILITY_BPS") {
+            skew.volatility_bps = sigma.parse().unwrap_or(skew.volatility_bps);
+        }
+        
+        if let Ok(kappa) = std::env::var("MARKET_MAKER_ARRIVAL_INTENSITY") {
+            skew.arrival_intensity = kappa.parse().unwrap_or(skew.arrival_intensity);
+        }
+        
+        if let Ok(limit) = std::env::var("MARKET_MAKER_DAILY_NOTIONAL_LIMIT_CENTS") {
+            config.market_maker.daily_notional_limit_cents = limit.parse().unwrap_or(10_000_000);
+        }
+        
+        let breakers = &mut config.circuit_breakers;
+        if let Ok(share) = std::env::var("MARKET_MAKER_MAX_HOUSE_SHARE") {
+            breakers.max_house_share = share.parse().unwrap_or(breakers.max_house_share);
+        }
+        
+        if let Ok(move_bps) = std::env::var("MARKET_MAKER_VOLATILITY_MAX_MOVE_BPS") {
+            breakers.max_fair_move_bps = move_bps.parse().unwrap_or(breakers.max_fair_move_bps);
+        }
+        
+        if let Ok(widen) = std::env::var("MARKET_MAKER_VOLATILITY_WIDEN_BPS") {
+            breakers.volatility_widen_bps = widen.parse().unwrap_or(breakers.volatility_widen_bps);
+        }
+        
+        let ladder = &mut config.ladder.default;
+        if let Ok(levels) = std::env::var("MARKET_MAKER_LADDER_LEVELS") {
+            ladder.levels = levels.parse().unwrap_or(ladder.levels);
+        }
+        
+        if let Ok(step) = std::env::var("MARKET_MAKER_LADDER_STEP_BPS") {
+            ladder.spread_step_bps = step.parse().unwrap_or(ladder.spread_step_bps);
+        }
+        
+        if let Ok(multiplier) = std::env::var("MARKET_MAKER_LADDER_SIZE_MULTIPLIER") {
+            ladder.size_multiplier = multiplier.parse().unwrap_or(ladder.size_multiplier);
+        }
+        
+        if let Ok(fraction) = std::env::var("MARKET_MAKER_LADDER_MIN_SIZE_FRACTION") {
+            ladder.min_size_fraction = fraction.parse().unwrap_or(ladder.min_size_fraction);
+        }
+        
+        // JSON object of symbol ID -> profile, e.g. {"101": {"levels": 5, "size_multiplier": 2.0}};
+        // fields left out take the built-in profile defaults
+        if let Ok(overrides) = std::env::var("MARKET_MAKER_LADDER_OVERRIDES") {
+            config.ladder.overrides = serde_json::from_str(&overrides)
+                .map_err(|e| anyhow::anyhow!("Invalid MARKET_MAKER_LADDER_OVERRIDES: {e}"))?;
+        }
         
         Ok(config)
     }
     
+    /// Get risk check interval as Duration
+    pub fn risk_check_interval(&self) -> Duration {
+        Duration::from_secs(self.market_maker.risk_check_interval_seconds)
+    }
+    
     /// Get update frequency as Duration
     pub fn update_frequency(&self) -> Duration {
         Duration::from_secs(self.market_maker.update_frequency_seconds)
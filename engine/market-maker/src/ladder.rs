@@ -0,0 +1,182 @@
+//! Ladder quoting
+//!
+//! The strategy decides the best bid and ask; a [`LadderProfile`] stacks further levels
+//! behind them so a large order is absorbed over several prices instead of sweeping past a
+//! single house quote. Level `i` (0 = the strategy's quote) sits `i · spread_step_bps` of the
+//! fair price further out and is `size_multiplier^i` times as large.
+//!
+//! Sizes are scaled by the confidence of the fair price (`fair_prices.confidence`): at full
+//! confidence the ladder is quoted at full size, at zero confidence at `min_size_fraction`.
+
+use crate::models::{MarketMakerDecision, MarketMakerQuote, QuoteSide};
+use serde::{Deserialize, Serialize};
+
+/// Shape of the ladder quoted on each side
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LadderProfile {
+    /// Levels per side (1 = the strategy's quote only)
+    pub levels: usize,
+    /// Distance between levels (bps of the fair price)
+    pub spread_step_bps: u32,
+    /// Size of each level relative to the one in front of it
+    pub size_multiplier: f64,
+    /// Share of the full size quoted at zero confidence (1.0 = ignore confidence)
+    pub min_size_fraction: f64,
+}
+
+impl Default for LadderProfile {
+    fn default() -> Self {
+        Self { levels: 1, spread_step_bps: 200, size_multiplier: 1.5, min_size_fraction: 1.0 }
+    }
+}
+
+impl LadderProfile {
+    /// Size factor for a fair price confidence between 0 and 1
+    pub fn confidence_scale(&self, confidence: f64) -> f64 {
+        let floor = self.min_size_fraction.clamp(0.0, 1.0);
+        floor + (1.0 - floor) * confidence.clamp(0.0, 1.0)
+    }
+
+    /// Stack levels behind the strategy's quotes
+    pub fn build(&self, decision: MarketMakerDecision, confidence: f64) -> QuoteLadder {
+        let top = QuoteLadder::from(decision);
+        let scale = self.confidence_scale(confidence);
+        let expand = |top: Option<&MarketMakerQuote>| {
+            let Some(top) = top else {
+                return Vec::new();
+            };
+            (0..self.levels.max(1)).filter_map(|level| self.level(top, level, scale)).collect()
+        };
+        QuoteLadder { bids: expand(top.bids.first()), asks: expand(top.asks.first()) }
+    }
+
+    fn level(&self, top: &MarketMakerQuote, level: usize, scale: f64) -> Option<MarketMakerQuote> {
+        let step_bps = self.spread_step_bps as i64 * level as i64;
+        let offset = top.fair_price_cents * step_bps / 10_000;
+        let price_cents = match top.side {
+            QuoteSide::Bid => top.price_cents - offset,
+            QuoteSide::Ask => top.price_cents + offset,
+        };
+        if price_cents <= 0 {
+            return None;
+        }
+
+        let size = top.quantity_bp as f64 * self.size_multiplier.powi(level as i32) * scale;
+        Some(MarketMakerQuote {
+            price_cents,
+            quantity_bp: (size.round() as i64).max(1),
+            spread_bps: top.spread_bps + step_bps as u32,
+            level,
+            ..top.clone()
+        })
+    }
+}
+
+/// Quotes for one symbol, best level first on each side
+#[derive(Debug, Clone, Default)]
+pub struct QuoteLadder {
+    pub bids: Vec<MarketMakerQuote>,
+    pub asks: Vec<MarketMakerQuote>,
+}
+
+impl QuoteLadder {
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Every quote, bids first
+    pub fn quotes(&self) -> impl Iterator<Item = &MarketMakerQuote> {
+        self.bids.iter().chain(self.asks.iter())
+    }
+}
+
+impl From<MarketMakerDecision> for QuoteLadder {
+    /// A single-level ladder
+    fn from(decision: MarketMakerDecision) -> Self {
+        let (bid, ask) = match decision {
+            MarketMakerDecision::PostBoth { bid, ask } => (Some(bid), Some(ask)),
+            MarketMakerDecision::PostBid { bid } => (Some(bid), None),
+            MarketMakerDecision::PostAsk { ask } => (None, Some(ask)),
+            MarketMakerDecision::DoNothing => (None, None),
+        };
+        Self { bids: bid.into_iter().collect(), asks: ask.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn both(fair_cents: i64) -> MarketMakerDecision {
+        MarketMakerDecision::PostBoth {
+            bid: MarketMakerQuote::new(1, QuoteSide::Bid, fair_cents, 400, 10_000),
+            ask: MarketMakerQuote::new(1, QuoteSide::Ask, fair_cents, 400, 10_000),
+        }
+    }
+
+    fn levels(quotes: &[MarketMakerQuote]) -> Vec<(i64, i64)> {
+        quotes.iter().map(|q| (q.price_cents, q.quantity_bp)).collect()
+    }
+
+    #[test]
+    fn default_profile_is_the_single_strategy_quote() {
+        let ladder = LadderProfile::default().build(both(2000), 0.2);
+        assert_eq!(levels(&ladder.bids), vec![(1920, 10_000)]);
+        assert_eq!(levels(&ladder.asks), vec![(2080, 10_000)]);
+    }
+
+    #[test]
+    fn levels_step_out_and_grow() {
+        let profile = LadderProfile {
+            levels: 3,
+            spread_step_bps: 200,
+            size_multiplier: 2.0,
+            ..Default::default()
+        };
+        let ladder = profile.build(both(2000), 1.0);
+        assert_eq!(levels(&ladder.bids), vec![(1920, 10_000), (1880, 20_000), (1840, 40_000)]);
+        assert_eq!(levels(&ladder.asks), vec![(2080, 10_000), (2120, 20_000), (2160, 40_000)]);
+        let level_ids: Vec<_> = ladder.asks.iter().map(|q| (q.level, q.spread_bps)).collect();
+        assert_eq!(level_ids, vec![(0, 400), (1, 600), (2, 800)]);
+    }
+
+    #[test]
+    fn low_confidence_shrinks_the_ladder() {
+        let profile = LadderProfile {
+            levels: 2,
+            size_multiplier: 1.0,
+            min_size_fraction: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            levels(&profile.build(both(2000), 1.0).bids),
+            vec![(1920, 10_000), (1880, 10_000)]
+        );
+        assert_eq!(
+            levels(&profile.build(both(2000), 0.5).bids),
+            vec![(1920, 7_500), (1880, 7_500)]
+        );
+        assert_eq!(
+            levels(&profile.build(both(2000), 0.0).bids),
+            vec![(1920, 5_000), (1880, 5_000)]
+        );
+    }
+
+    #[test]
+    fn missing_sides_and_non_positive_prices_are_skipped() {
+        let decision = MarketMakerDecision::PostBid {
+            bid: MarketMakerQuote::new(1, QuoteSide::Bid, 100, 400, 10_000),
+        };
+        let profile = LadderProfile { levels: 4, spread_step_bps: 3_000, ..Default::default() };
+        let ladder = profile.build(decision, 1.0);
+        assert!(ladder.asks.is_empty());
+        assert_eq!(
+            ladder.bids.iter().map(|q| q.price_cents).collect::<Vec<_>>(),
+            vec![96, 66, 36, 6]
+        );
+
+        let profile = LadderProfile { levels: 5, ..profile };
+        assert_eq!(profile.build(MarketMakerDecision::DoNothing, 1.0).quotes().count(), 0);
+    }
+}
@@ -5,11 +5,22 @@
 //! market making orders when conditions are met.
 
 pub mod config;
+pub mod ladder;
 pub mod service;
 pub mod cache;
 pub mod models;
+pub mod performance;
+pub mod quotes;
+pub mod risk;
+pub mod session;
+pub mod strategy;
 
 pub use config::MarketMakerConfig;
 pub use service::MarketMakerService;
 pub use cache::FairPriceCache;
 pub use models::*;
+pub use ladder::{LadderProfile, QuoteLadder};
+pub use quotes::WorkingQuotes;
+pub use risk::{KillSwitch, RiskManager};
+pub use session::GatewaySession;
+pub use strategy::{InventorySkewStrategy, QuotingStrategy, SymmetricStrategy};
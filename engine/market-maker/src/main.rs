@@ -1,15 +1,87 @@
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use clap::{Parser, Subcommand};
+use market_maker::performance::{self, PerformanceReport};
 use market_maker::{MarketMakerConfig, MarketMakerService};
 use tracing::{info, error};
 
+#[derive(Parser)]
+#[command(name = "market-maker")]
+#[command(about = "House market maker")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the market maker (the default)
+    Run,
+    /// Print the house account's P&L attribution and quoting performance
+    Report {
+        /// House account (defaults to the account behind MARKET_MAKER_API_KEY)
+        #[arg(long)]
+        account_id: Option<i64>,
+
+        /// Only report this symbol
+        #[arg(long)]
+        symbol: Option<u32>,
+
+        /// Start of the window (RFC 3339); defaults to `--hours` before `--until`
+        #[arg(long)]
+        since: Option<DateTime<Utc>>,
+
+        /// End of the window (RFC 3339); defaults to now
+        #[arg(long)]
+        until: Option<DateTime<Utc>>,
+
+        /// Window length when `--since` is not given
+        #[arg(long, default_value = "24")]
+        hours: i64,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
     
-    info!("Starting Market Maker service");
-    
     // Load configuration
     let config = MarketMakerConfig::from_env()?;
+    
+    match Cli::parse().command.unwrap_or(Command::Run) {
+        Command::Run => run(config).await,
+        Command::Report { account_id, symbol, since, until, hours, json } => {
+            let until = until.unwrap_or_else(Utc::now);
+            let since = since.unwrap_or(until - Duration::hours(hours));
+            
+            let pool = sqlx::PgPool::connect(&config.database.url)
+                .await
+                .context("Failed to connect to database")?;
+            let account_id = match account_id {
+                Some(account_id) => account_id,
+                None => performance::house_account_id(&pool, &config.market_maker.api_key)
+                    .await?
+                    .context("No active API key for the market maker; pass --account-id")?,
+            };
+            
+            let report = performance::report(&pool, account_id, symbol, since, until).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_report(&report);
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn run(config: MarketMakerConfig) -> anyhow::Result<()> {
+    info!("Starting Market Maker service");
     info!("Loaded configuration: {:?}", config);
     
     // Create market maker service
@@ -23,3 +95,42 @@ async fn main() -> anyhow::Result<()> {
     
     Ok(())
 }
+
+fn dollars(cents: i64) -> String {
+    format!("{}${:.2}", if cents < 0 { "-" } else { "" }, cents.abs() as f64 / 100.0)
+}
+
+fn percent(share: Option<f64>) -> String {
+    share.map_or_else(|| "-".to_string(), |share| format!("{:.1}%", share * 100.0))
+}
+
+fn print_report(report: &PerformanceReport) {
+    println!("House account {} from {} to {}", report.account_id, report.since, report.until);
+    println!();
+    println!("P&L            {} realized, {} unrealized, {} total",
+             dollars(report.realized_pnl_cents), dollars(report.unrealized_pnl_cents),
+             dollars(report.total_pnl_cents));
+    println!("Spread capture {}", dollars(report.spread_capture_cents));
+    for markout in &report.markouts {
+        println!("Markout {:>2} ticks {} ({} adverse selection, {} fills)",
+                 markout.ticks, dollars(markout.markout_cents),
+                 dollars(markout.adverse_selection_cents), markout.fills_measured);
+    }
+    println!("Fills          {} from {} quotes, fill ratio {}",
+             report.fills, report.quotes_posted, percent(report.fill_ratio));
+    println!();
+    
+    println!("{:>8} {:>6} {:>12} {:>12} {:>12} {:>12} {:>12} {:>8} {:>10} {:>8}",
+             "symbol", "fills", "position_bp", "realized", "unrealized", "capture", "markout_5",
+             "fill", "avg_hold_s", "uptime");
+    for symbol in &report.symbols {
+        let markout_5 = symbol.markouts.iter().find(|m| m.ticks == 5).map_or(0, |m| m.markout_cents);
+        println!("{:>8} {:>6} {:>12} {:>12} {:>12} {:>12} {:>12} {:>8} {:>10} {:>8}",
+                 symbol.symbol_id, symbol.fills, symbol.position_bp,
+                 dollars(symbol.realized_pnl_cents), dollars(symbol.unrealized_pnl_cents),
+                 dollars(symbol.spread_capture_cents), dollars(markout_5),
+                 percent(symbol.fill_ratio),
+                 symbol.avg_hold_seconds.map_or_else(|| "-".to_string(), |s| format!("{s:.0}")),
+                 percent(Some(symbol.quote_uptime)));
+    }
+}
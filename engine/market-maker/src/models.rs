@@ -33,6 +33,8 @@ pub struct MarketMakerQuote {
     pub quantity_bp: i64,
     pub fair_price_cents: i64,
     pub spread_bps: u32,
+    /// Ladder level (0 = best)
+    pub level: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -124,6 +126,27 @@ impl MarketMakerQuote {
             quantity_bp,
             fair_price_cents,
             spread_bps,
+            level: 0,
+        }
+    }
+    
+    /// Create a quote at an explicit price (e.g. skewed away from the fair price)
+    pub fn at_price(
+        symbol_id: u32,
+        side: QuoteSide,
+        price_cents: i64,
+        quantity_bp: i64,
+        fair_price_cents: i64,
+        spread_bps: u32,
+    ) -> Self {
+        Self {
+            symbol_id,
+            side,
+            price_cents,
+            quantity_bp,
+            fair_price_cents,
+            spread_bps,
+            level: 0,
         }
     }
 }
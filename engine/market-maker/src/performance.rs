@@ -0,0 +1,665 @@
+//! Performance attribution
+//!
+//! Replays the house account's trades against the fair price history to show whether the
+//! market maker earns its spread:
+//!
+//! - realized P&L (average cost) and unrealized P&L marked to the last fair price
+//! - spread capture: the edge against the fair price at the time of each fill
+//! - markouts: the same edge measured 1, 5 and 30 fair price ticks later; what was given
+//!   back in between is adverse selection
+//! - fill ratio: the share of the quoted quantity that traded
+//! - inventory hold time and quote uptime over the report window
+//!
+//! Money is in cents. Fill quantities are basis points as stored in `trades`; quote
+//! quantities are in order units as stored in `orders`, so only their ratio is reported.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+
+/// Fair price ticks after a fill at which markouts are measured
+pub const MARKOUT_TICKS: [usize; 3] = [1, 5, 30];
+
+/// One of the house account's fills
+#[derive(Debug, Clone)]
+pub struct HouseFill {
+    pub symbol_id: u32,
+    pub ts: DateTime<Utc>,
+    /// Signed quantity in basis points (positive when the house bought)
+    pub quantity_bp: i64,
+    pub price_cents: i64,
+}
+
+/// A published fair price
+#[derive(Debug, Clone)]
+pub struct FairTick {
+    pub symbol_id: u32,
+    pub ts: DateTime<Utc>,
+    pub fair_cents: i64,
+}
+
+/// A quote (order) placed by the house account
+#[derive(Debug, Clone)]
+pub struct QuoteRecord {
+    pub symbol_id: u32,
+    pub placed_at: DateTime<Utc>,
+    /// When it was filled or cancelled; `None` while still working
+    pub closed_at: Option<DateTime<Utc>>,
+    pub quantity: i64,
+    pub filled_quantity: i64,
+}
+
+/// Everything the report is computed from
+///
+/// Fills must include those before the window so positions carry into it; fair ticks
+/// after the window are used for markouts.
+#[derive(Debug, Clone, Default)]
+pub struct PerformanceInputs {
+    pub fills: Vec<HouseFill>,
+    pub fair_ticks: Vec<FairTick>,
+    pub quotes: Vec<QuoteRecord>,
+}
+
+/// Edge of the window's fills a number of fair price ticks after they traded
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Markout {
+    pub ticks: usize,
+    /// Fills followed by enough ticks to be measured
+    pub fills_measured: usize,
+    /// Edge against the fair price `ticks` later (cents)
+    pub markout_cents: i64,
+    /// Spread capture of the measured fills minus their markout (cents, positive = lost)
+    pub adverse_selection_cents: i64,
+}
+
+/// Performance of one symbol
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SymbolPerformance {
+    pub symbol_id: u32,
+    /// Fills in the window
+    pub fills: usize,
+    pub bought_bp: i64,
+    pub sold_bp: i64,
+    /// Position at the end of the window
+    pub position_bp: i64,
+    pub average_cost_cents: Option<f64>,
+    /// Fair price the position is marked at
+    pub mark_cents: Option<i64>,
+    pub realized_pnl_cents: i64,
+    pub unrealized_pnl_cents: i64,
+    /// Edge against the fair price at fill time (cents)
+    pub spread_capture_cents: i64,
+    pub markouts: Vec<Markout>,
+    /// Quotes placed in the window
+    pub quotes_posted: usize,
+    /// Filled share of the quantity quoted in the window
+    pub fill_ratio: Option<f64>,
+    /// Mean time from opening a position to flattening it, for positions closed in the window
+    pub avg_hold_seconds: Option<f64>,
+    /// Share of the window spent holding a position
+    pub inventory_time_share: f64,
+    /// Share of the window with at least one working quote
+    pub quote_uptime: f64,
+}
+
+/// House account performance over a window
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceReport {
+    pub account_id: i64,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub realized_pnl_cents: i64,
+    pub unrealized_pnl_cents: i64,
+    pub total_pnl_cents: i64,
+    pub spread_capture_cents: i64,
+    pub markouts: Vec<Markout>,
+    pub fills: usize,
+    pub quotes_posted: usize,
+    pub fill_ratio: Option<f64>,
+    pub symbols: Vec<SymbolPerformance>,
+}
+
+/// Compute the report for `[since, until]`
+pub fn compute(
+    account_id: i64,
+    inputs: &PerformanceInputs,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> PerformanceReport {
+    let symbol_ids: BTreeSet<u32> = inputs
+        .fills
+        .iter()
+        .filter(|fill| fill.ts <= until)
+        .map(|fill| fill.symbol_id)
+        .chain(inputs.quotes.iter().map(|quote| quote.symbol_id))
+        .collect();
+
+    let symbols: Vec<SymbolPerformance> = symbol_ids
+        .into_iter()
+        .map(|symbol_id| {
+            let mut fills: Vec<&HouseFill> =
+                inputs.fills.iter().filter(|f| f.symbol_id == symbol_id && f.ts <= until).collect();
+            fills.sort_by_key(|fill| fill.ts);
+            let mut ticks: Vec<&FairTick> =
+                inputs.fair_ticks.iter().filter(|t| t.symbol_id == symbol_id).collect();
+            ticks.sort_by_key(|tick| tick.ts);
+            let quotes: Vec<&QuoteRecord> =
+                inputs.quotes.iter().filter(|q| q.symbol_id == symbol_id).collect();
+            symbol_performance(symbol_id, &fills, &ticks, &quotes, since, until)
+        })
+        .filter(|symbol| symbol.fills > 0 || symbol.quotes_posted > 0 || symbol.position_bp != 0)
+        .collect();
+
+    let mut markouts: Vec<Markout> =
+        MARKOUT_TICKS.iter().map(|&ticks| Markout { ticks, ..Default::default() }).collect();
+    for symbol in &symbols {
+        for (total, markout) in markouts.iter_mut().zip(&symbol.markouts) {
+            total.fills_measured += markout.fills_measured;
+            total.markout_cents += markout.markout_cents;
+            total.adverse_selection_cents += markout.adverse_selection_cents;
+        }
+    }
+
+    let in_window = |quote: &&QuoteRecord| quote.placed_at >= since && quote.placed_at <= until;
+    let realized_pnl_cents = symbols.iter().map(|s| s.realized_pnl_cents).sum();
+    let unrealized_pnl_cents = symbols.iter().map(|s| s.unrealized_pnl_cents).sum();
+    PerformanceReport {
+        account_id,
+        since,
+        until,
+        realized_pnl_cents,
+        unrealized_pnl_cents,
+        total_pnl_cents: realized_pnl_cents + unrealized_pnl_cents,
+        spread_capture_cents: symbols.iter().map(|s| s.spread_capture_cents).sum(),
+        markouts,
+        fills: symbols.iter().map(|s| s.fills).sum(),
+        quotes_posted: symbols.iter().map(|s| s.quotes_posted).sum(),
+        fill_ratio: fill_ratio(inputs.quotes.iter().filter(in_window)),
+        symbols,
+    }
+}
+
+fn symbol_performance(
+    symbol_id: u32,
+    fills: &[&HouseFill],
+    ticks: &[&FairTick],
+    quotes: &[&QuoteRecord],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> SymbolPerformance {
+    let mut perf = SymbolPerformance {
+        symbol_id,
+        markouts: MARKOUT_TICKS
+            .iter()
+            .map(|&ticks| Markout { ticks, ..Default::default() })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut position = 0i64;
+    let mut average_cost = 0.0f64;
+    let mut realized = 0.0f64;
+    let mut opened_at: Option<DateTime<Utc>> = None;
+    let mut holds: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+
+    for fill in fills {
+        let in_window = fill.ts >= since;
+        let quantity = fill.quantity_bp;
+        let price = fill.price_cents as f64;
+
+        if position == 0 || position.signum() == quantity.signum() {
+            let held = position.abs() as f64;
+            average_cost = (average_cost * held + price * quantity.abs() as f64)
+                / (held + quantity.abs() as f64);
+        } else {
+            let closed = quantity.abs().min(position.abs());
+            if in_window {
+                realized += (price - average_cost) * (closed * position.signum()) as f64 / 10_000.0;
+            }
+            if quantity.abs() > closed {
+                // Flipped through flat: the remainder opens a new position at this price
+                average_cost = price;
+            }
+        }
+
+        let before = position;
+        position += quantity;
+        if before == 0 && position != 0 {
+            opened_at = Some(fill.ts);
+        } else if before != 0 && (position == 0 || position.signum() != before.signum()) {
+            if let Some(start) = opened_at.take() {
+                holds.push((start, fill.ts));
+            }
+            if position != 0 {
+                opened_at = Some(fill.ts);
+            }
+        }
+
+        if in_window {
+            perf.fills += 1;
+            if quantity > 0 {
+                perf.bought_bp += quantity;
+            } else {
+                perf.sold_bp -= quantity;
+            }
+            measure_edge(&mut perf, fill, ticks);
+        }
+    }
+    if position == 0 {
+        average_cost = 0.0;
+    }
+
+    let mark = ticks.iter().take_while(|tick| tick.ts <= until).last().map(|tick| tick.fair_cents);
+    perf.position_bp = position;
+    perf.average_cost_cents = (position != 0).then_some(average_cost);
+    perf.mark_cents = mark;
+    perf.realized_pnl_cents = realized.round() as i64;
+    perf.unrealized_pnl_cents = match mark {
+        Some(mark) if position != 0 => {
+            ((mark as f64 - average_cost) * position as f64 / 10_000.0).round() as i64
+        }
+        _ => 0,
+    };
+
+    let closed_holds: Vec<f64> = holds
+        .iter()
+        .filter(|(_, end)| *end >= since)
+        .map(|(start, end)| (*end - *start).num_milliseconds() as f64 / 1000.0)
+        .collect();
+    if !closed_holds.is_empty() {
+        perf.avg_hold_seconds = Some(closed_holds.iter().sum::<f64>() / closed_holds.len() as f64);
+    }
+    holds.extend(opened_at.map(|start| (start, until)));
+    perf.inventory_time_share = covered_share(holds, since, until);
+
+    let window_quotes: Vec<&&QuoteRecord> =
+        quotes.iter().filter(|q| q.placed_at >= since && q.placed_at <= until).collect();
+    perf.quotes_posted = window_quotes.len();
+    perf.fill_ratio = fill_ratio(window_quotes.into_iter().copied());
+    perf.quote_uptime = covered_share(
+        quotes.iter().map(|q| (q.placed_at, q.closed_at.unwrap_or(until))).collect(),
+        since,
+        until,
+    );
+    perf
+}
+
+/// Add a fill's spread capture and markouts
+fn measure_edge(perf: &mut SymbolPerformance, fill: &HouseFill, ticks: &[&FairTick]) {
+    // Ticks published at or before the fill; the last of them is the fair price at fill time
+    let known = ticks.partition_point(|tick| tick.ts <= fill.ts);
+    let Some(fair_at_fill) = known.checked_sub(1).map(|i| ticks[i].fair_cents) else {
+        return;
+    };
+
+    let edge = |fair: i64| (fair - fill.price_cents) * fill.quantity_bp / 10_000;
+    perf.spread_capture_cents += edge(fair_at_fill);
+    for markout in &mut perf.markouts {
+        if let Some(later) = ticks.get(known + markout.ticks - 1) {
+            markout.fills_measured += 1;
+            markout.markout_cents += edge(later.fair_cents);
+            markout.adverse_selection_cents += edge(fair_at_fill) - edge(later.fair_cents);
+        }
+    }
+}
+
+fn fill_ratio<'a>(quotes: impl Iterator<Item = &'a QuoteRecord>) -> Option<f64> {
+    let (quoted, filled) = quotes.fold((0i64, 0i64), |(quoted, filled), q| {
+        (quoted + q.quantity, filled + q.filled_quantity)
+    });
+    (quoted > 0).then(|| filled as f64 / quoted as f64)
+}
+
+/// Share of `[since, until]` covered by the union of the intervals
+fn covered_share(
+    mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> f64 {
+    let window = (until - since).num_milliseconds();
+    if window <= 0 {
+        return 0.0;
+    }
+    intervals.sort();
+
+    let mut covered = 0;
+    let mut reached = since;
+    for (start, end) in intervals {
+        let start = start.max(reached);
+        let end = end.min(until);
+        if end > start {
+            covered += (end - start).num_milliseconds();
+            reached = end;
+        }
+    }
+    covered as f64 / window as f64
+}
+
+/// Account behind the market maker's API key
+pub async fn house_account_id(pool: &PgPool, api_key: &str) -> Result<Option<i64>> {
+    let row = sqlx::query!(
+        "SELECT account_id FROM api_keys WHERE key_id = $1 AND revoked_at IS NULL",
+        api_key
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to resolve market maker account")?;
+    Ok(row.map(|row| row.account_id))
+}
+
+/// Load the house account's fills, quotes and the fair prices of the symbols it traded
+pub async fn load_inputs(
+    pool: &PgPool,
+    account_id: i64,
+    symbol_id: Option<u32>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<PerformanceInputs> {
+    let symbol_id = symbol_id.map(i64::from);
+
+    let trades = sqlx::query!(
+        r#"
+        SELECT symbol_id, side, quantity, price, timestamp AS "timestamp!"
+        FROM trades
+        WHERE account_id = $1 AND timestamp <= $2 AND ($3::BIGINT IS NULL OR symbol_id = $3)
+        ORDER BY timestamp, id
+        "#,
+        account_id,
+        until.naive_utc(),
+        symbol_id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to load house trades")?;
+
+    let fills: Vec<HouseFill> = trades
+        .into_iter()
+        .map(|row| HouseFill {
+            symbol_id: row.symbol_id as u32,
+            ts: row.timestamp.and_utc(),
+            quantity_bp: if row.side.eq_ignore_ascii_case("buy") {
+                row.quantity
+            } else {
+                -row.quantity
+            },
+            price_cents: row.price,
+        })
+        .collect();
+
+    let orders = sqlx::query!(
+        r#"
+        SELECT symbol_id, quantity, filled_quantity, status, created_at, updated_at
+        FROM orders
+        WHERE account_id = $1
+          AND created_at <= $2
+          AND (status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED') OR updated_at >= $3)
+          AND ($4::BIGINT IS NULL OR symbol_id = $4)
+        "#,
+        account_id,
+        until,
+        since,
+        symbol_id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to load house orders")?;
+
+    let quotes: Vec<QuoteRecord> = orders
+        .into_iter()
+        .map(|row| {
+            let working =
+                matches!(row.status.as_str(), "PENDING" | "ACCEPTED" | "PARTIALLY_FILLED");
+            QuoteRecord {
+                symbol_id: row.symbol_id as u32,
+                placed_at: row.created_at,
+                closed_at: (!working).then_some(row.updated_at),
+                quantity: row.quantity,
+                filled_quantity: row.filled_quantity,
+            }
+        })
+        .collect();
+
+    let symbols: Vec<i32> = fills
+        .iter()
+        .map(|fill| fill.symbol_id)
+        .chain(quotes.iter().map(|quote| quote.symbol_id))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|symbol_id| symbol_id as i32)
+        .collect();
+
+    // fair_price_history.player_id is the symbol ID, like rpe_fair_prices.player_id
+    let ticks = sqlx::query!(
+        r#"
+        SELECT player_id, ts, fair_cents
+        FROM fair_price_history
+        WHERE player_id = ANY($1)
+        ORDER BY ts, id
+        "#,
+        &symbols
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to load fair price history")?;
+
+    let fair_ticks = ticks
+        .into_iter()
+        .map(|row| FairTick {
+            symbol_id: row.player_id as u32,
+            ts: row.ts,
+            fair_cents: row.fair_cents,
+        })
+        .collect();
+
+    Ok(PerformanceInputs { fills, fair_ticks, quotes })
+}
+
+/// Load and compute the report for `[since, until]`, optionally for one symbol
+pub async fn report(
+    pool: &PgPool,
+    account_id: i64,
+    symbol_id: Option<u32>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<PerformanceReport> {
+    let inputs = load_inputs(pool, account_id, symbol_id, since, until).await?;
+    Ok(compute(account_id, &inputs, since, until))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    const SHARE: i64 = 10_000;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 12, 17, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn fill(minutes: i64, quantity_bp: i64, price_cents: i64) -> HouseFill {
+        HouseFill { symbol_id: 1, ts: at(minutes), quantity_bp, price_cents }
+    }
+
+    fn tick(minutes: i64, fair_cents: i64) -> FairTick {
+        FairTick { symbol_id: 1, ts: at(minutes), fair_cents }
+    }
+
+    fn quote(placed: i64, closed: Option<i64>, quantity: i64, filled: i64) -> QuoteRecord {
+        QuoteRecord {
+            symbol_id: 1,
+            placed_at: at(placed),
+            closed_at: closed.map(at),
+            quantity,
+            filled_quantity: filled,
+        }
+    }
+
+    #[test]
+    fn round_trip_realizes_the_spread() {
+        let inputs = PerformanceInputs {
+            fills: vec![fill(10, 2 * SHARE, 1900), fill(40, -2 * SHARE, 2100)],
+            fair_ticks: vec![tick(0, 2000)],
+            quotes: vec![quote(5, Some(10), SHARE, SHARE), quote(5, Some(40), SHARE, SHARE)],
+        };
+        let report = compute(7, &inputs, at(0), at(60));
+        let symbol = &report.symbols[0];
+
+        assert_eq!(report.realized_pnl_cents, 400);
+        assert_eq!(report.unrealized_pnl_cents, 0);
+        assert_eq!(symbol.position_bp, 0);
+        assert_eq!(symbol.average_cost_cents, None);
+        // 100 cents of edge on each side of two shares
+        assert_eq!(report.spread_capture_cents, 400);
+        assert_eq!(symbol.avg_hold_seconds, Some(1800.0));
+        assert!((symbol.inventory_time_share - 0.5).abs() < 1e-9);
+        assert_eq!(report.fill_ratio, Some(1.0));
+    }
+
+    #[test]
+    fn open_position_is_marked_to_the_last_fair_price() {
+        let inputs = PerformanceInputs {
+            fills: vec![fill(10, SHARE, 1900), fill(20, SHARE, 2000), fill(30, -SHARE, 2050)],
+            fair_ticks: vec![tick(0, 2000), tick(50, 2100), tick(90, 9999)],
+            quotes: vec![],
+        };
+        let report = compute(7, &inputs, at(0), at(60));
+        let symbol = &report.symbols[0];
+
+        // Average cost 1950; sold one share at 2050, one share marked at 2100
+        assert_eq!(symbol.realized_pnl_cents, 100);
+        assert_eq!(symbol.position_bp, SHARE);
+        assert_eq!(symbol.average_cost_cents, Some(1950.0));
+        assert_eq!(symbol.mark_cents, Some(2100));
+        assert_eq!(symbol.unrealized_pnl_cents, 150);
+        assert_eq!(report.total_pnl_cents, 250);
+    }
+
+    #[test]
+    fn fills_before_the_window_carry_position_but_not_pnl() {
+        let inputs = PerformanceInputs {
+            fills: vec![fill(-30, SHARE, 1000), fill(10, -2 * SHARE, 1200)],
+            fair_ticks: vec![tick(-60, 1100)],
+            quotes: vec![],
+        };
+        let report = compute(7, &inputs, at(0), at(60));
+        let symbol = &report.symbols[0];
+
+        // Closing the earlier long books 200; the remaining short is opened at 1200
+        assert_eq!(symbol.fills, 1);
+        assert_eq!(symbol.realized_pnl_cents, 200);
+        assert_eq!(symbol.position_bp, -SHARE);
+        assert_eq!(symbol.unrealized_pnl_cents, 100);
+        assert_eq!(symbol.avg_hold_seconds, Some(2400.0));
+    }
+
+    #[test]
+    fn markouts_split_spread_capture_from_adverse_selection() {
+        // We buy at 1950 with fair at 2000, then the fair price keeps falling
+        let mut fair_ticks = vec![tick(0, 2000)];
+        fair_ticks.extend((1..=6).map(|i| tick(10 + i, 2000 - 20 * i)));
+        let inputs =
+            PerformanceInputs { fills: vec![fill(10, SHARE, 1950)], fair_ticks, quotes: vec![] };
+        let report = compute(7, &inputs, at(0), at(60));
+
+        assert_eq!(report.spread_capture_cents, 50);
+        let markouts: Vec<_> = report
+            .markouts
+            .iter()
+            .map(|m| (m.ticks, m.fills_measured, m.markout_cents, m.adverse_selection_cents))
+            .collect();
+        // One tick later the fair is 1980 (edge 30), five ticks later 1900 (edge -50);
+        // there are not yet 30 ticks after the fill
+        assert_eq!(markouts, vec![(1, 1, 30, 20), (5, 1, -50, 100), (30, 0, 0, 0)]);
+    }
+
+    #[test]
+    fn fill_ratio_and_uptime_cover_the_window() {
+        let inputs = PerformanceInputs {
+            fills: vec![],
+            fair_ticks: vec![],
+            quotes: vec![
+                // Overlapping quotes count once towards uptime
+                quote(0, Some(20), 4, 1),
+                quote(10, Some(30), 4, 0),
+                // Still working at the end of the window
+                quote(45, None, 2, 2),
+                // Before the window: uptime only
+                quote(-10, Some(5), 10, 10),
+            ],
+        };
+        let report = compute(7, &inputs, at(0), at(60));
+        let symbol = &report.symbols[0];
+
+        assert_eq!(symbol.quotes_posted, 3);
+        assert_eq!(symbol.fill_ratio, Some(0.3));
+        assert!((symbol.quote_uptime - 0.75).abs() < 1e-9);
+    }
+
+    /// Needs a database with the migrations applied; skipped without `DATABASE_URL`
+    #[tokio::test]
+    async fn fair_price_history_is_keyed_by_symbol_id() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+
+        let account_id: i64 = sqlx::query_scalar(
+            "INSERT INTO accounts (display_name) VALUES ('mm-performance-test') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // A symbol no real player uses, so no mapping row can match it
+        let symbol_id = 900_000 + (account_id % 100_000) as i32;
+        sqlx::query(
+            "INSERT INTO trades (account_id, symbol_id, side, quantity, price, timestamp, order_id)
+             VALUES ($1, $2, 'BUY', $3, 1000, $4, 1)",
+        )
+        .bind(account_id)
+        .bind(i64::from(symbol_id))
+        .bind(SHARE)
+        .bind(at(0).naive_utc())
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (minutes, fair_cents) in [(0, 1010_i64), (5, 1030)] {
+            sqlx::query(
+                "INSERT INTO fair_price_history (player_id, ts, fair_cents, source)
+                 VALUES ($1, $2, $3, 'test')",
+            )
+            .bind(symbol_id)
+            .bind(at(minutes))
+            .bind(fair_cents)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let inputs = load_inputs(&pool, account_id, None, at(-60), at(60)).await;
+
+        sqlx::query("DELETE FROM fair_price_history WHERE player_id = $1")
+            .bind(symbol_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM trades WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let inputs = inputs.unwrap();
+        assert_eq!(inputs.fills.len(), 1);
+        let ticks: Vec<_> =
+            inputs.fair_ticks.iter().map(|tick| (tick.symbol_id, tick.fair_cents)).collect();
+        assert_eq!(ticks, vec![(symbol_id as u32, 1010), (symbol_id as u32, 1030)]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Working quote tracking
+//!
+//! The market maker keeps at most one working order per ladder level per side per symbol.
+//! Each cycle the wanted ladder is reconciled against them level by level: levels no longer
+//! wanted are cancelled, missing levels are placed, and levels whose fair price or price has
+//! moved by `min_fair_price_change_bps` or more (or whose size changed) are cancelled and
+//! replaced. Levels that still match are left alone.
+
+use crate::ladder::QuoteLadder;
+use crate::models::{MarketMakerQuote, OrderBookState, QuoteSide};
+use std::collections::{BTreeMap, HashMap};
+
+/// A house order resting on the book
+#[derive(Debug, Clone)]
+pub struct WorkingQuote {
+    /// Gateway order ID (`ord_123`)
+    pub order_id: String,
+    pub quote: MarketMakerQuote,
+    /// Quantity filled so far (bp)
+    pub filled_bp: i64,
+}
+
+impl WorkingQuote {
+    /// Quantity still resting (bp)
+    pub fn remaining_bp(&self) -> i64 {
+        (self.quote.quantity_bp - self.filled_bp).max(0)
+    }
+}
+
+/// Working bid and ask levels for one symbol
+#[derive(Debug, Clone, Default)]
+pub struct SymbolQuotes {
+    pub bids: BTreeMap<usize, WorkingQuote>,
+    pub asks: BTreeMap<usize, WorkingQuote>,
+}
+
+impl SymbolQuotes {
+    fn side_mut(&mut self, side: &QuoteSide) -> &mut BTreeMap<usize, WorkingQuote> {
+        match side {
+            QuoteSide::Bid => &mut self.bids,
+            QuoteSide::Ask => &mut self.asks,
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &WorkingQuote> {
+        self.bids.values().chain(self.asks.values())
+    }
+
+    fn len(&self) -> usize {
+        self.bids.len() + self.asks.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Orders to cancel and quotes to place for one symbol
+#[derive(Debug, Clone, Default)]
+pub struct QuotePlan {
+    pub cancel: Vec<String>,
+    pub place: Vec<MarketMakerQuote>,
+}
+
+impl QuotePlan {
+    pub fn is_empty(&self) -> bool {
+        self.cancel.is_empty() && self.place.is_empty()
+    }
+}
+
+/// A fill of a working quote
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteFill {
+    pub symbol_id: u32,
+    pub side: QuoteSide,
+    pub level: usize,
+    pub quantity_bp: i64,
+    /// The quote has no quantity left and is no longer working
+    pub completed: bool,
+}
+
+/// The market maker's working orders, by symbol
+#[derive(Debug, Clone, Default)]
+pub struct WorkingQuotes {
+    symbols: HashMap<u32, SymbolQuotes>,
+}
+
+impl WorkingQuotes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Working quotes for a symbol
+    pub fn get(&self, symbol_id: u32) -> Option<&SymbolQuotes> {
+        self.symbols.get(&symbol_id)
+    }
+
+    /// Number of working orders
+    pub fn len(&self) -> usize {
+        self.symbols.values().map(SymbolQuotes::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Symbols with working quotes
+    pub fn symbol_ids(&self) -> Vec<u32> {
+        self.symbols.keys().copied().collect()
+    }
+
+    /// Record a quote the gateway accepted
+    pub fn insert(&mut self, order_id: String, quote: MarketMakerQuote) {
+        let quotes = self.symbols.entry(quote.symbol_id).or_default();
+        let (side, level) = (quote.side.clone(), quote.level);
+        quotes.side_mut(&side).insert(level, WorkingQuote { order_id, quote, filled_bp: 0 });
+    }
+
+    /// Stop tracking an order (cancelled, or its cancel was rejected because it is gone)
+    pub fn remove(&mut self, order_id: &str) -> Option<WorkingQuote> {
+        let (symbol_id, side, level) = self.find(order_id)?;
+        let quotes = self.symbols.get_mut(&symbol_id)?;
+        let removed = quotes.side_mut(&side).remove(&level);
+        if quotes.is_empty() {
+            self.symbols.remove(&symbol_id);
+        }
+        removed
+    }
+
+    /// Apply a fill reported by the gateway; `None` if the order is not a working quote
+    pub fn on_fill(&mut self, order_id: &str, quantity_bp: i64) -> Option<QuoteFill> {
+        let (symbol_id, side, level) = self.find(order_id)?;
+        let quotes = self.symbols.get_mut(&symbol_id)?;
+        let levels = quotes.side_mut(&side);
+        let working = levels.get_mut(&level)?;
+        working.filled_bp += quantity_bp;
+
+        let completed = working.remaining_bp() == 0;
+        if completed {
+            levels.remove(&level);
+            if quotes.is_empty() {
+                self.symbols.remove(&symbol_id);
+            }
+        }
+        Some(QuoteFill { symbol_id, side, level, quantity_bp, completed })
+    }
+
+    /// Forget every working order (the gateway cancels them when the session drops)
+    pub fn clear(&mut self) -> usize {
+        let count = self.len();
+        self.symbols.clear();
+        count
+    }
+
+    /// Copy of `book` without the market maker's own remaining quantity
+    ///
+    /// Strategies decide where the book needs liquidity; our own quotes must not count
+    /// as that liquidity or they would never be refreshed.
+    pub fn exclude_from(&self, book: &OrderBookState) -> OrderBookState {
+        let mut book = book.clone();
+        let Some(quotes) = self.symbols.get(&book.symbol_id) else {
+            return book;
+        };
+
+        for working in quotes.iter() {
+            let levels = match working.quote.side {
+                QuoteSide::Bid => &mut book.buy_orders,
+                QuoteSide::Ask => &mut book.sell_orders,
+            };
+            let price = working.quote.price_cents as u64;
+            if let Some(quantity) = levels.get_mut(&price) {
+                *quantity = quantity.saturating_sub(working.remaining_bp() as u64);
+                if *quantity == 0 {
+                    levels.remove(&price);
+                }
+            }
+        }
+        book
+    }
+
+    /// Reconcile the working quotes of `symbol_id` with the wanted ladder
+    pub fn plan(&self, symbol_id: u32, ladder: QuoteLadder, min_change_bps: u32) -> QuotePlan {
+        let working = self.symbols.get(&symbol_id).cloned().unwrap_or_default();
+        let mut plan = QuotePlan::default();
+        for (mut current, wanted) in [(working.bids, ladder.bids), (working.asks, ladder.asks)] {
+            for wanted in wanted {
+                match current.remove(&wanted.level) {
+                    Some(current) if !is_stale(&current.quote, &wanted, min_change_bps) => {}
+                    Some(current) => {
+                        plan.cancel.push(current.order_id);
+                        plan.place.push(wanted);
+                    }
+                    None => plan.place.push(wanted),
+                }
+            }
+            // Levels the ladder no longer has
+            plan.cancel.extend(current.into_values().map(|working| working.order_id));
+        }
+        plan
+    }
+
+    fn find(&self, order_id: &str) -> Option<(u32, QuoteSide, usize)> {
+        self.symbols.iter().find_map(|(symbol_id, quotes)| {
+            quotes
+                .iter()
+                .find(|working| working.order_id == order_id)
+                .map(|working| (*symbol_id, working.quote.side.clone(), working.quote.level))
+        })
+    }
+}
+
+/// Whether a working quote should be replaced by `wanted`
+fn is_stale(current: &MarketMakerQuote, wanted: &MarketMakerQuote, min_change_bps: u32) -> bool {
+    moved_bps(current.fair_price_cents, wanted.fair_price_cents) >= min_change_bps as i64
+        || moved_bps(current.price_cents, wanted.price_cents) >= min_change_bps as i64
+        || current.quantity_bp != wanted.quantity_bp
+}
+
+fn moved_bps(from: i64, to: i64) -> i64 {
+    if from <= 0 {
+        return if from == to { 0 } else { i64::MAX };
+    }
+    (to - from).abs() * 10_000 / from
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(side: QuoteSide, fair_cents: i64) -> MarketMakerQuote {
+        MarketMakerQuote::new(7, side, fair_cents, 400, 10_000)
+    }
+
+    fn both(fair_cents: i64) -> QuoteLadder {
+        QuoteLadder {
+            bids: vec![quote(QuoteSide::Bid, fair_cents)],
+            asks: vec![quote(QuoteSide::Ask, fair_cents)],
+        }
+    }
+
+    fn at_level(mut quote: MarketMakerQuote, level: usize, price_cents: i64) -> MarketMakerQuote {
+        quote.level = level;
+        quote.price_cents = price_cents;
+        quote
+    }
+
+    fn working_at(fair_cents: i64) -> WorkingQuotes {
+        let mut working = WorkingQuotes::new();
+        working.insert("ord_1".to_string(), quote(QuoteSide::Bid, fair_cents));
+        working.insert("ord_2".to_string(), quote(QuoteSide::Ask, fair_cents));
+        working
+    }
+
+    #[test]
+    fn places_missing_sides() {
+        let plan = WorkingQuotes::new().plan(7, both(2000), 100);
+        assert!(plan.cancel.is_empty());
+        assert_eq!(plan.place.len(), 2);
+    }
+
+    #[test]
+    fn small_fair_price_moves_keep_working_quotes() {
+        // 2000 -> 2010 is 50 bps, under the 100 bps threshold
+        let plan = working_at(2000).plan(7, both(2010), 100);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn fair_price_move_past_threshold_cancels_and_replaces() {
+        let plan = working_at(2000).plan(7, both(2030), 100);
+        assert_eq!(plan.cancel, vec!["ord_1".to_string(), "ord_2".to_string()]);
+        let prices: Vec<_> = plan.place.iter().map(|q| q.price_cents).collect();
+        assert_eq!(prices, vec![1949, 2111]);
+    }
+
+    #[test]
+    fn sides_no_longer_wanted_are_cancelled() {
+        let ladder = QuoteLadder { bids: vec![], asks: vec![quote(QuoteSide::Ask, 2000)] };
+        let plan = working_at(2000).plan(7, ladder, 100);
+        assert_eq!(plan.cancel, vec!["ord_1".to_string()]);
+        assert!(plan.place.is_empty());
+    }
+
+    #[test]
+    fn fills_reduce_and_complete_working_quotes() {
+        let mut working = working_at(2000);
+
+        let partial = working.on_fill("ord_1", 4_000).unwrap();
+        assert!(!partial.completed);
+        assert_eq!(working.get(7).unwrap().bids[&0].remaining_bp(), 6_000);
+
+        let rest = working.on_fill("ord_1", 6_000).unwrap();
+        assert_eq!(
+            rest,
+            QuoteFill {
+                symbol_id: 7,
+                side: QuoteSide::Bid,
+                level: 0,
+                quantity_bp: 6_000,
+                completed: true
+            }
+        );
+        assert!(working.get(7).unwrap().bids.is_empty());
+        assert_eq!(working.len(), 1);
+
+        assert!(working.on_fill("ord_9", 1).is_none());
+        assert!(working.remove("ord_2").is_some());
+        assert!(working.is_empty());
+    }
+
+    #[test]
+    fn ladders_are_maintained_level_by_level() {
+        let bid = quote(QuoteSide::Bid, 2000);
+        let mut working = WorkingQuotes::new();
+        working.insert("ord_1".to_string(), at_level(bid.clone(), 0, 1920));
+        working.insert("ord_2".to_string(), at_level(bid.clone(), 1, 1880));
+        working.insert("ord_3".to_string(), at_level(bid.clone(), 2, 1840));
+        assert_eq!(working.len(), 3);
+
+        // Level 1 moves, level 2 goes away, level 3 is new; level 0 is untouched
+        let ladder = QuoteLadder {
+            bids: vec![
+                at_level(bid.clone(), 0, 1920),
+                at_level(bid.clone(), 1, 1800),
+                at_level(bid.clone(), 3, 1760),
+            ],
+            asks: vec![],
+        };
+        let plan = working.plan(7, ladder, 100);
+        assert_eq!(plan.cancel, vec!["ord_2".to_string(), "ord_3".to_string()]);
+        let placed: Vec<_> = plan.place.iter().map(|q| (q.level, q.price_cents)).collect();
+        assert_eq!(placed, vec![(1, 1800), (3, 1760)]);
+
+        let fill = working.on_fill("ord_2", 10_000).unwrap();
+        assert_eq!((fill.level, fill.completed), (1, true));
+        assert_eq!(working.get(7).unwrap().bids.keys().copied().collect::<Vec<_>>(), vec![0, 2]);
+    }
+
+    #[test]
+    fn own_quotes_are_excluded_from_the_book() {
+        let working = working_at(2000);
+        let mut book = OrderBookState {
+            symbol_id: 7,
+            buy_orders: HashMap::from([(1920, 10_000), (1900, 5_000)]),
+            sell_orders: HashMap::from([(2080, 25_000)]),
+            last_trade_price: None,
+            last_trade_quantity: None,
+            last_trade_timestamp: None,
+        };
+
+        let others = working.exclude_from(&book);
+        assert_eq!(others.buy_orders, HashMap::from([(1900, 5_000)]));
+        assert_eq!(others.sell_orders, HashMap::from([(2080, 15_000)]));
+
+        // A stale snapshot without our orders is left as is
+        book.buy_orders.clear();
+        assert!(working.exclude_from(&book).buy_orders.is_empty());
+    }
+}
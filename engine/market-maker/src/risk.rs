@@ -0,0 +1,578 @@
+//! Risk controls
+//!
+//! [`RiskManager`] accumulates the market maker's fills into per-symbol positions and a daily
+//! traded notional, and filters every quoting decision through:
+//!
+//! - the kill switch: no quotes anywhere while it is engaged
+//! - `daily_notional_limit_cents`: no quotes once the day's notional reaches the limit, and no
+//!   quote whose full fill would pass it
+//! - `max_position_per_player`: no quote on a side whose fill would pass the position limit
+//! - the circuit breakers of `dynamic_pricing_bots_design.md` §10: the house stops quoting a
+//!   symbol for a while when its share of the traded volume passes `max_house_share`, and
+//!   widens its quotes while the fair price has moved more than `max_fair_move_bps` within
+//!   the volatility window. The move is measured on prices rather than by counting updates,
+//!   so a fast but flat feed does not trip the breaker.
+//!
+//! Every state change is queued as a [`RiskAction`] for the audit trail.
+
+use crate::config::{CircuitBreakerConfig, MarketMakerConfig};
+use crate::ladder::QuoteLadder;
+use crate::models::{MarketMakerQuote, QuoteSide};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A risk state change to record in `admin_actions`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskAction {
+    pub action: &'static str,
+    pub symbol_id: Option<u32>,
+    pub details: serde_json::Value,
+}
+
+/// Pulls every market maker quote while engaged
+///
+/// Cloned handles control the same switch, so an operator-facing component can hold one.
+#[derive(Debug, Clone)]
+pub struct KillSwitch {
+    state: Arc<watch::Sender<Option<String>>>,
+}
+
+impl Default for KillSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KillSwitch {
+    pub fn new() -> Self {
+        Self { state: Arc::new(watch::channel(None).0) }
+    }
+
+    /// Stop quoting and pull all quotes
+    pub fn engage(&self, reason: impl Into<String>) {
+        self.state.send_replace(Some(reason.into()));
+    }
+
+    /// Resume quoting
+    pub fn release(&self) {
+        self.state.send_replace(None);
+    }
+
+    /// Reason the switch was engaged, if it is
+    pub fn reason(&self) -> Option<String> {
+        self.state.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Option<String>> {
+        self.state.subscribe()
+    }
+}
+
+/// Limits enforced by the [`RiskManager`]
+#[derive(Debug, Clone)]
+pub struct RiskLimits {
+    pub max_position_bp: i64,
+    pub daily_notional_limit_cents: i64,
+    pub breakers: CircuitBreakerConfig,
+}
+
+impl RiskLimits {
+    pub fn from_config(config: &MarketMakerConfig) -> Self {
+        Self {
+            max_position_bp: config.market_maker.max_position_per_player * 10_000,
+            daily_notional_limit_cents: config.market_maker.daily_notional_limit_cents,
+            breakers: config.circuit_breakers.clone(),
+        }
+    }
+}
+
+/// Positions, daily notional, kill switch and circuit breaker state
+#[derive(Debug)]
+pub struct RiskManager {
+    limits: RiskLimits,
+    positions: HashMap<u32, i64>,
+    position_breaches: HashSet<u32>,
+    notional_day: NaiveDate,
+    daily_notional_cents: i64,
+    notional_blocked: bool,
+    kill_reason: Option<String>,
+    house_disabled_until: HashMap<u32, DateTime<Utc>>,
+    /// Fair prices (time, cents) covering the volatility window, oldest first
+    fair_prices: HashMap<u32, VecDeque<(DateTime<Utc>, i64)>>,
+    volatile: HashSet<u32>,
+    actions: Vec<RiskAction>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits, now: DateTime<Utc>) -> Self {
+        Self {
+            limits,
+            positions: HashMap::new(),
+            position_breaches: HashSet::new(),
+            notional_day: now.date_naive(),
+            daily_notional_cents: 0,
+            notional_blocked: false,
+            kill_reason: None,
+            house_disabled_until: HashMap::new(),
+            fair_prices: HashMap::new(),
+            volatile: HashSet::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Position in basis points (negative = short)
+    pub fn position(&self, symbol_id: u32) -> i64 {
+        self.positions.get(&symbol_id).copied().unwrap_or(0)
+    }
+
+    /// Replace all positions with ones loaded from the database
+    pub fn set_positions(&mut self, positions: impl IntoIterator<Item = (u32, i64)>) {
+        self.positions = positions.into_iter().collect();
+    }
+
+    /// Notional traded today (cents)
+    pub fn daily_notional_cents(&self) -> i64 {
+        self.daily_notional_cents
+    }
+
+    /// Seed today's notional, e.g. from `house_fills` after a restart
+    pub fn set_daily_notional(&mut self, notional_cents: i64, now: DateTime<Utc>) {
+        self.notional_day = now.date_naive();
+        self.daily_notional_cents = notional_cents;
+        self.check_notional();
+    }
+
+    /// Apply one of our fills
+    pub fn on_fill(
+        &mut self,
+        symbol_id: u32,
+        signed_quantity_bp: i64,
+        price_cents: i64,
+        now: DateTime<Utc>,
+    ) {
+        self.roll_day(now);
+        self.daily_notional_cents += signed_quantity_bp.abs() * price_cents / 10_000;
+        self.check_notional();
+
+        let position = self.positions.entry(symbol_id).or_insert(0);
+        *position += signed_quantity_bp;
+        let position = *position;
+        if position.abs() > self.limits.max_position_bp {
+            if self.position_breaches.insert(symbol_id) {
+                self.actions.push(RiskAction {
+                    action: "market_maker_position_limit",
+                    symbol_id: Some(symbol_id),
+                    details: serde_json::json!({
+                        "position_bp": position,
+                        "max_position_bp": self.limits.max_position_bp,
+                    }),
+                });
+            }
+        } else {
+            self.position_breaches.remove(&symbol_id);
+        }
+    }
+
+    /// Follow the kill switch; returns whether its state changed
+    pub fn set_kill_switch(&mut self, reason: Option<String>) -> bool {
+        if self.kill_reason == reason {
+            return false;
+        }
+        let action = match &reason {
+            Some(_) => "market_maker_kill_switch_engaged",
+            None => "market_maker_kill_switch_released",
+        };
+        self.actions.push(RiskAction {
+            action,
+            symbol_id: None,
+            details: serde_json::json!({ "reason": reason.clone().or(self.kill_reason.clone()) }),
+        });
+        self.kill_reason = reason;
+        true
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.kill_reason.is_some()
+    }
+
+    /// Record a fair price for the volatility breaker
+    pub fn on_fair_price(&mut self, symbol_id: u32, fair_cents: i64, now: DateTime<Utc>) {
+        self.fair_prices.entry(symbol_id).or_default().push_back((now, fair_cents));
+        self.is_volatile(symbol_id, now);
+    }
+
+    /// Fair price move over the volatility window (basis points, highest over lowest)
+    ///
+    /// The last price from before the window is kept as the price the window started at,
+    /// so a single jump counts until it ages out.
+    fn fair_move_bps(&mut self, symbol_id: u32, now: DateTime<Utc>) -> i64 {
+        let window = Duration::seconds(self.limits.breakers.volatility_window_seconds as i64);
+        let Some(prices) = self.fair_prices.get_mut(&symbol_id) else {
+            return 0;
+        };
+        while prices.get(1).is_some_and(|(ts, _)| *ts <= now - window) {
+            prices.pop_front();
+        }
+
+        let low = prices.iter().map(|(_, cents)| *cents).min().unwrap_or(0);
+        let high = prices.iter().map(|(_, cents)| *cents).max().unwrap_or(0);
+        if low <= 0 {
+            return 0;
+        }
+        (high - low) * 10_000 / low
+    }
+
+    /// Whether the fair price has moved more within the window than the breaker allows
+    pub fn is_volatile(&mut self, symbol_id: u32, now: DateTime<Utc>) -> bool {
+        let move_bps = self.fair_move_bps(symbol_id, now);
+        let breakers = &self.limits.breakers;
+
+        let volatile = move_bps > breakers.max_fair_move_bps as i64;
+        if volatile && self.volatile.insert(symbol_id) {
+            self.actions.push(RiskAction {
+                action: "market_maker_volatility_breaker",
+                symbol_id: Some(symbol_id),
+                details: serde_json::json!({
+                    "fair_move_bps": move_bps,
+                    "window_seconds": breakers.volatility_window_seconds,
+                    "widen_bps": breakers.volatility_widen_bps,
+                }),
+            });
+        } else if !volatile && self.volatile.remove(&symbol_id) {
+            self.actions.push(RiskAction {
+                action: "market_maker_volatility_cleared",
+                symbol_id: Some(symbol_id),
+                details: serde_json::json!({ "fair_move_bps": move_bps }),
+            });
+        }
+        volatile
+    }
+
+    /// Check the house's share of a symbol's recent volume
+    pub fn on_house_share(
+        &mut self,
+        symbol_id: u32,
+        house_volume_bp: i64,
+        total_volume_bp: i64,
+        now: DateTime<Utc>,
+    ) {
+        let breakers = self.limits.breakers.clone();
+        if total_volume_bp < breakers.house_share_min_volume_bp || total_volume_bp <= 0 {
+            return;
+        }
+        let share = house_volume_bp as f64 / total_volume_bp as f64;
+        if share <= breakers.max_house_share || self.is_house_disabled(symbol_id, now) {
+            return;
+        }
+
+        let until = now + Duration::seconds(breakers.disable_seconds as i64);
+        self.house_disabled_until.insert(symbol_id, until);
+        self.actions.push(RiskAction {
+            action: "market_maker_house_share_breaker",
+            symbol_id: Some(symbol_id),
+            details: serde_json::json!({
+                "house_share": share,
+                "max_house_share": breakers.max_house_share,
+                "house_volume_bp": house_volume_bp,
+                "total_volume_bp": total_volume_bp,
+                "disabled_until": until,
+            }),
+        });
+    }
+
+    /// Whether the house share breaker has the symbol switched off
+    pub fn is_house_disabled(&mut self, symbol_id: u32, now: DateTime<Utc>) -> bool {
+        match self.house_disabled_until.get(&symbol_id) {
+            Some(until) if *until > now => true,
+            Some(_) => {
+                self.house_disabled_until.remove(&symbol_id);
+                self.actions.push(RiskAction {
+                    action: "market_maker_house_reenabled",
+                    symbol_id: Some(symbol_id),
+                    details: serde_json::json!({}),
+                });
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Whether no quotes at all are allowed for the symbol
+    pub fn blocks(&mut self, symbol_id: u32, now: DateTime<Utc>) -> bool {
+        self.roll_day(now);
+        self.is_killed() || self.notional_blocked || self.is_house_disabled(symbol_id, now)
+    }
+
+    /// Drop or widen quotes the limits and breakers do not allow
+    ///
+    /// Ladder levels are kept best first while a fill of the level and every level in front
+    /// of it stays within the position and daily notional limits.
+    pub fn filter(
+        &mut self,
+        symbol_id: u32,
+        ladder: QuoteLadder,
+        now: DateTime<Utc>,
+    ) -> QuoteLadder {
+        if self.blocks(symbol_id, now) {
+            return QuoteLadder::default();
+        }
+
+        let widen_bps = if self.is_volatile(symbol_id, now) {
+            self.limits.breakers.volatility_widen_bps
+        } else {
+            0
+        };
+        let (position, max) = (self.position(symbol_id), self.limits.max_position_bp);
+        QuoteLadder {
+            bids: self.within_limits(ladder.bids, |bought| position + bought <= max, widen_bps),
+            asks: self.within_limits(ladder.asks, |sold| position - sold >= -max, widen_bps),
+        }
+    }
+
+    /// State changes since the last call
+    pub fn take_actions(&mut self) -> Vec<RiskAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// Leading levels of one side whose combined fill stays within the limits
+    fn within_limits(
+        &self,
+        levels: Vec<MarketMakerQuote>,
+        position_allows: impl Fn(i64) -> bool,
+        widen_bps: u32,
+    ) -> Vec<MarketMakerQuote> {
+        let mut filled_bp = 0;
+        let mut notional_cents = self.daily_notional_cents;
+        levels
+            .into_iter()
+            .take_while(|quote| {
+                filled_bp += quote.quantity_bp;
+                notional_cents += quote.quantity_bp * quote.price_cents / 10_000;
+                position_allows(filled_bp)
+                    && notional_cents <= self.limits.daily_notional_limit_cents
+            })
+            .map(|quote| widen(quote, widen_bps))
+            .collect()
+    }
+
+    fn roll_day(&mut self, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        if today != self.notional_day {
+            self.notional_day = today;
+            self.daily_notional_cents = 0;
+            self.check_notional();
+        }
+    }
+
+    fn check_notional(&mut self) {
+        let blocked = self.daily_notional_cents >= self.limits.daily_notional_limit_cents;
+        if blocked != self.notional_blocked {
+            self.actions.push(RiskAction {
+                action: if blocked {
+                    "market_maker_daily_notional_limit"
+                } else {
+                    "market_maker_daily_notional_reset"
+                },
+                symbol_id: None,
+                details: serde_json::json!({
+                    "daily_notional_cents": self.daily_notional_cents,
+                    "limit_cents": self.limits.daily_notional_limit_cents,
+                }),
+            });
+            self.notional_blocked = blocked;
+        }
+    }
+}
+
+/// Move a quote `widen_bps` of the fair price further from it
+fn widen(mut quote: MarketMakerQuote, widen_bps: u32) -> MarketMakerQuote {
+    if widen_bps == 0 {
+        return quote;
+    }
+    let offset = quote.fair_price_cents * widen_bps as i64 / 10_000;
+    match quote.side {
+        QuoteSide::Bid => quote.price_cents = (quote.price_cents - offset).max(1),
+        QuoteSide::Ask => quote.price_cents += offset,
+    }
+    quote.spread_bps += widen_bps;
+    quote
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 12, 18, 0, 0).unwrap()
+    }
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            max_position_bp: 30_000,
+            daily_notional_limit_cents: 10_000,
+            breakers: CircuitBreakerConfig {
+                max_house_share: 0.6,
+                house_share_window_seconds: 600,
+                house_share_min_volume_bp: 50_000,
+                disable_seconds: 600,
+                max_fair_move_bps: 500,
+                volatility_window_seconds: 120,
+                volatility_widen_bps: 1000,
+            },
+        }
+    }
+
+    fn both(fair_cents: i64) -> QuoteLadder {
+        QuoteLadder {
+            bids: vec![MarketMakerQuote::new(1, QuoteSide::Bid, fair_cents, 400, 10_000)],
+            asks: vec![MarketMakerQuote::new(1, QuoteSide::Ask, fair_cents, 400, 10_000)],
+        }
+    }
+
+    /// Best bid and ask of a ladder
+    fn prices(ladder: &QuoteLadder) -> (Option<i64>, Option<i64>) {
+        (
+            ladder.bids.first().map(|bid| bid.price_cents),
+            ladder.asks.first().map(|ask| ask.price_cents),
+        )
+    }
+
+    #[test]
+    fn within_limits_quotes_pass_unchanged() {
+        let mut risk = RiskManager::new(limits(), start());
+        let decision = risk.filter(1, both(1000), start());
+        assert_eq!(prices(&decision), (Some(960), Some(1040)));
+        assert!(risk.take_actions().is_empty());
+    }
+
+    #[test]
+    fn fills_build_position_and_block_the_side_at_the_limit() {
+        let mut risk = RiskManager::new(limits(), start());
+        risk.on_fill(1, 20_000, 100, start());
+        assert_eq!(risk.position(1), 20_000);
+
+        // Another bid fill would make 30_000 (allowed); after it only the ask remains
+        assert_eq!(prices(&risk.filter(1, both(100), start())), (Some(96), Some(104)));
+        risk.on_fill(1, 20_000, 100, start());
+        assert_eq!(prices(&risk.filter(1, both(100), start())), (None, Some(104)));
+
+        let actions = risk.take_actions();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].action, "market_maker_position_limit");
+    }
+
+    #[test]
+    fn ladder_levels_are_kept_while_their_combined_fill_fits() {
+        let mut risk = RiskManager::new(limits(), start());
+        risk.on_fill(1, 10_000, 10, start());
+        let level = |side, level: usize| MarketMakerQuote {
+            level,
+            ..MarketMakerQuote::new(1, side, 10, 0, 10_000)
+        };
+        let ladder = QuoteLadder {
+            bids: (0..3).map(|i| level(QuoteSide::Bid, i)).collect(),
+            asks: (0..3).map(|i| level(QuoteSide::Ask, i)).collect(),
+        };
+
+        // Long one share against a three-share limit: two more bids fit, all three asks do
+        let filtered = risk.filter(1, ladder, start());
+        assert_eq!(filtered.bids.len(), 2);
+        assert_eq!(filtered.asks.len(), 3);
+    }
+
+    #[test]
+    fn daily_notional_limit_stops_quoting_until_the_next_day() {
+        let mut risk = RiskManager::new(limits(), start());
+        risk.on_fill(1, 10_000, 6_000, start());
+        // A 1-share quote at 4800 would take the day to 10_800 cents
+        assert_eq!(prices(&risk.filter(1, both(5_000), start())), (None, None));
+        assert_eq!(prices(&risk.filter(1, both(1_000), start())), (Some(960), Some(1040)));
+
+        risk.on_fill(2, -10_000, 4_000, start());
+        assert_eq!(risk.daily_notional_cents(), 10_000);
+        assert!(risk.filter(1, both(100), start()).is_empty());
+
+        let tomorrow = start() + Duration::days(1);
+        assert_eq!(prices(&risk.filter(1, both(100), tomorrow)), (Some(96), Some(104)));
+        let actions: Vec<_> = risk.take_actions().iter().map(|a| a.action).collect();
+        assert_eq!(
+            actions,
+            vec!["market_maker_daily_notional_limit", "market_maker_daily_notional_reset"]
+        );
+    }
+
+    #[test]
+    fn kill_switch_pulls_everything_until_released() {
+        let mut risk = RiskManager::new(limits(), start());
+        assert!(risk.set_kill_switch(Some("operator".to_string())));
+        assert!(!risk.set_kill_switch(Some("operator".to_string())));
+        assert!(risk.filter(1, both(100), start()).is_empty());
+
+        assert!(risk.set_kill_switch(None));
+        assert_eq!(prices(&risk.filter(1, both(100), start())), (Some(96), Some(104)));
+        assert_eq!(risk.take_actions().len(), 2);
+    }
+
+    #[test]
+    fn house_share_breaker_disables_the_symbol_for_a_while() {
+        let mut risk = RiskManager::new(limits(), start());
+
+        // Too little volume to judge, then a 50% share
+        risk.on_house_share(1, 40_000, 40_000, start());
+        risk.on_house_share(1, 50_000, 100_000, start());
+        assert_eq!(prices(&risk.filter(1, both(100), start())), (Some(96), Some(104)));
+
+        risk.on_house_share(1, 70_000, 100_000, start());
+        assert!(risk.filter(1, both(100), start()).is_empty());
+        // Other symbols keep quoting
+        assert!(!risk.filter(2, both(100), start()).is_empty());
+
+        let later = start() + Duration::seconds(601);
+        assert!(!risk.filter(1, both(100), later).is_empty());
+        let actions: Vec<_> = risk.take_actions().iter().map(|a| a.action).collect();
+        assert_eq!(
+            actions,
+            vec!["market_maker_house_share_breaker", "market_maker_house_reenabled"]
+        );
+    }
+
+    #[test]
+    fn flat_high_frequency_fair_prices_do_not_widen_quotes() {
+        let mut risk = RiskManager::new(limits(), start());
+        // Ten updates a second, wobbling a cent around $10
+        for i in 0..1200 {
+            let fair_cents = 1000 + i % 2;
+            risk.on_fair_price(1, fair_cents, start() + Duration::milliseconds(i * 100));
+        }
+        let now = start() + Duration::seconds(120);
+        assert!(!risk.is_volatile(1, now));
+        assert_eq!(prices(&risk.filter(1, both(1000), now)), (Some(960), Some(1040)));
+        assert!(risk.take_actions().is_empty());
+    }
+
+    #[test]
+    fn large_fair_price_jumps_widen_quotes() {
+        let mut risk = RiskManager::new(limits(), start());
+        risk.on_fair_price(1, 1000, start());
+        risk.on_fair_price(1, 1030, start() + Duration::seconds(10));
+        assert!(!risk.is_volatile(1, start() + Duration::seconds(10)));
+
+        // The window now starts at $10.30, and a jump to $11.20 is a move of 8.7%
+        risk.on_fair_price(1, 1120, start() + Duration::seconds(150));
+        let now = start() + Duration::seconds(160);
+        assert!(risk.is_volatile(1, now));
+        assert_eq!(prices(&risk.filter(1, both(1000), now)), (Some(860), Some(1140)));
+
+        // Once the jump ages out of the window the normal spread returns
+        let calm = start() + Duration::seconds(300);
+        assert_eq!(prices(&risk.filter(1, both(1000), calm)), (Some(960), Some(1040)));
+        let actions: Vec<_> = risk.take_actions().iter().map(|a| a.action).collect();
+        assert_eq!(
+            actions,
+            vec!["market_maker_volatility_breaker", "market_maker_volatility_cleared"]
+        );
+    }
+}
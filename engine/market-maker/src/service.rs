@@ -1,7 +1,13 @@
 use crate::config::MarketMakerConfig;
 use crate::models::*;
 use crate::cache::FairPriceCache;
+use crate::ladder::QuoteLadder;
+use crate::quotes::WorkingQuotes;
+use crate::risk::{KillSwitch, RiskLimits, RiskManager};
+use crate::session::{ExecutionReport, GatewaySession, SessionEvent};
+use crate::strategy::{self, QuoteContext, QuotingStrategy};
 use anyhow::{Context, Result};
+use fair_price_bus::{FairPriceBus, FairPriceUpdate, Subscription};
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -9,11 +15,33 @@ use persistence::snapshot::{SnapshotManager, Snapshot};
 use persistence::config::SnapshotConfig;
 use tracing::{info, warn, error, debug};
 use tokio::time::sleep;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use futures_util::{SinkExt, StreamExt};
-use url::Url;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio::sync::watch;
+
+/// What woke the market maker between cycles
+enum Wakeup {
+    FairPrice(Option<FairPriceUpdate>),
+    Session(SessionEvent),
+    KillSwitch,
+    RiskCheck,
+}
+
+/// Next pushed fair price; waits forever when not connected to the bus
+async fn next_fair_price(subscription: &mut Option<Subscription>) -> Option<FairPriceUpdate> {
+    match subscription {
+        Some(subscription) => subscription.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Next gateway session event; waits forever before the session is started
+async fn next_session_event(session: &mut Option<GatewaySession>) -> SessionEvent {
+    match session {
+        Some(session) => session.next_event().await,
+        None => std::future::pending().await,
+    }
+}
 
 /// Market Maker Service
 pub struct MarketMakerService {
@@ -21,9 +49,17 @@ pub struct MarketMakerService {
     db_pool: PgPool,
     cache: FairPriceCache,
     snapshot_manager: SnapshotManager,
-    websocket_url: String,
+    session: Option<GatewaySession>, // order gateway connection, opened by `start`
+    working: WorkingQuotes, // our resting quotes
     metrics: MarketMakerMetrics,
     symbol_to_player_mapping: HashMap<u32, i32>, // symbol_id -> sportsdataio_player_id
+    fair_prices: Option<Subscription>, // pushed fair price updates, if connected to the bus
+    strategy: Box<dyn QuotingStrategy>,
+    account_id: Option<i64>, // market maker account, resolved from its API key
+    risk: RiskManager, // positions, limits and circuit breakers
+    kill_switch: KillSwitch,
+    kill_signal: watch::Receiver<Option<String>>,
+    confidence: HashMap<u32, f64>, // symbol_id -> fair_prices.confidence, sizes ladders
 }
 
 impl MarketMakerService {
@@ -34,9 +70,11 @@ impl MarketMakerService {
             .context("Failed to connect to database")?;
         
         let cache = FairPriceCache::new(config.cache.clone());
-        
-        // Create HTTP client
-        let websocket_url = config.market_maker.websocket_gateway_url.clone();
+        let strategy = strategy::from_config(&config);
+        info!("Quoting with the {} strategy", strategy.name());
+        let risk = RiskManager::new(RiskLimits::from_config(&config), chrono::Utc::now());
+        let kill_switch = KillSwitch::new();
+        let kill_signal = kill_switch.subscribe();
         
         // Create snapshot manager
         let snapshot_config = SnapshotConfig {
@@ -54,17 +92,53 @@ impl MarketMakerService {
             db_pool,
             cache,
             snapshot_manager,
-            websocket_url,
+            session: None,
+            working: WorkingQuotes::new(),
             metrics: MarketMakerMetrics::default(),
             symbol_to_player_mapping: HashMap::new(),
+            fair_prices: None,
+            strategy,
+            account_id: None,
+            risk,
+            kill_switch,
+            kill_signal,
+            confidence: HashMap::new(),
         };
         
         // Load symbol to player mapping
         service.load_symbol_mapping().await?;
         
+        // Find our own account so quotes can account for inventory
+        service.resolve_account().await?;
+        service.load_daily_notional().await?;
+        
         Ok(service)
     }
     
+    /// Replace the quoting strategy
+    pub fn set_strategy(&mut self, strategy: Box<dyn QuotingStrategy>) {
+        info!("Quoting with the {} strategy", strategy.name());
+        self.strategy = strategy;
+    }
+    
+    /// Re-quote symbols as soon as their fair prices are published on `bus`
+    ///
+    /// The periodic database refresh keeps running as a fallback.
+    pub fn set_fair_price_bus(&mut self, bus: &FairPriceBus) {
+        self.fair_prices = Some(bus.subscribe());
+    }
+    
+    /// Handle to the kill switch; engaging it pulls every quote until it is released
+    pub fn kill_switch(&self) -> KillSwitch {
+        self.kill_switch.clone()
+    }
+    
+    /// Follow an externally owned kill switch instead of our own
+    pub fn set_kill_switch(&mut self, kill_switch: KillSwitch) {
+        self.kill_signal = kill_switch.subscribe();
+        self.kill_switch = kill_switch;
+    }
+    
     /// Start the market maker service
     pub async fn start(&mut self) -> Result<()> {
         info!("Starting Market Maker service");
@@ -74,6 +148,22 @@ impl MarketMakerService {
             return Ok(());
         }
         
+        // Seed the cache with anything published before we started
+        if let Some(subscription) = &self.fair_prices {
+            for update in subscription.snapshot() {
+                let fair_price = self.fair_price_from_update(update);
+                self.cache.store_fair_price(fair_price).await;
+            }
+        }
+        
+        // One gateway session carries every quote and reports our fills
+        let params = &self.config.market_maker;
+        self.session = Some(GatewaySession::connect(
+            params.websocket_gateway_url.clone(),
+            params.api_key.clone(),
+            params.api_secret.clone(),
+        ));
+        
         // Start the main market making loop
         self.run_market_making_loop().await
     }
@@ -81,6 +171,8 @@ impl MarketMakerService {
     /// Main market making loop
     async fn run_market_making_loop(&mut self) -> Result<()> {
         let update_frequency = self.config.update_frequency();
+        let mut risk_checks = tokio::time::interval(self.config.risk_check_interval());
+        risk_checks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         
         loop {
             match self.market_making_cycle().await {
@@ -93,7 +185,255 @@ impl MarketMakerService {
                 }
             }
             
-            sleep(update_frequency).await;
+            // Until the next cycle, react to pushed fair prices and our executions
+            let next_cycle = sleep(update_frequency);
+            tokio::pin!(next_cycle);
+            loop {
+                let wakeup = tokio::select! {
+                    _ = &mut next_cycle => break,
+                    update = next_fair_price(&mut self.fair_prices) => Wakeup::FairPrice(update),
+                    event = next_session_event(&mut self.session) => Wakeup::Session(event),
+                    Ok(()) = self.kill_signal.changed() => Wakeup::KillSwitch,
+                    _ = risk_checks.tick() => Wakeup::RiskCheck,
+                };
+                
+                match wakeup {
+                    Wakeup::FairPrice(Some(update)) => {
+                        if let Err(e) = self.on_fair_price_update(update).await {
+                            warn!("Failed to re-quote on fair price update: {}", e);
+                            self.metrics.errors += 1;
+                        }
+                    }
+                    Wakeup::FairPrice(None) => {
+                        warn!("Fair price bus closed, falling back to database polling");
+                        self.fair_prices = None;
+                    }
+                    Wakeup::Session(event) => self.on_session_event(event).await,
+                    Wakeup::KillSwitch => self.on_kill_switch().await,
+                    Wakeup::RiskCheck => {
+                        if let Err(e) = self.check_house_share().await {
+                            warn!("Risk check failed: {}", e);
+                            self.metrics.errors += 1;
+                        }
+                        self.pull_blocked_quotes().await;
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Track the gateway connection and our fills
+    async fn on_session_event(&mut self, event: SessionEvent) {
+        match event {
+            SessionEvent::Connected => info!("Order gateway session ready"),
+            SessionEvent::Disconnected => {
+                // Cancel-on-disconnect pulls everything that was working on the old connection
+                let dropped = self.working.clear();
+                warn!("Order gateway session lost, {} working quotes cancelled", dropped);
+            }
+            SessionEvent::Execution(report) => self.on_execution(report).await,
+        }
+    }
+    
+    /// Apply an execution report to our working quotes, positions and risk limits
+    async fn on_execution(&mut self, report: ExecutionReport) {
+        match report.exec_type.as_str() {
+            "TRADE" => {
+                let quantity = report.quantity.unwrap_or(0) as i64;
+                let price = report.price.unwrap_or(0) as i64;
+                self.risk.on_fill(report.symbol_id, report.signed_quantity_bp(), price, chrono::Utc::now());
+                self.metrics.fills_received += 1;
+                
+                if let Err(e) = self.record_house_fill(&report).await {
+                    warn!("Failed to record house fill for {}: {}", report.order_id, e);
+                }
+                self.record_risk_actions().await;
+                self.pull_blocked_quotes().await;
+                
+                match self.working.on_fill(&report.order_id, quantity) {
+                    Some(fill) => info!("{:?} quote {} on symbol {} filled {} bp at {:?} cents{}",
+                                        fill.side, report.order_id, fill.symbol_id, quantity, report.price,
+                                        if fill.completed { " (complete)" } else { "" }),
+                    None => debug!("Fill for {} which is not a working quote", report.order_id),
+                }
+            }
+            "CANCELLED" => {
+                if self.working.remove(&report.order_id).is_some() {
+                    info!("Quote {} cancelled by the exchange: {:?}", report.order_id, report.reason);
+                }
+            }
+            other => debug!("Ignoring {} execution report for {}", other, report.order_id),
+        }
+    }
+    
+    /// Pull every quote when the kill switch is engaged
+    async fn on_kill_switch(&mut self) {
+        let reason = self.kill_signal.borrow_and_update().clone();
+        if !self.risk.set_kill_switch(reason.clone()) {
+            return;
+        }
+        match reason {
+            Some(reason) => warn!("Kill switch engaged ({}), pulling {} quotes", reason, self.working.len()),
+            None => info!("Kill switch released, resuming quoting"),
+        }
+        self.record_risk_actions().await;
+        self.pull_blocked_quotes().await;
+    }
+    
+    /// Cancel the working quotes of every symbol the risk limits no longer allow us to quote
+    async fn pull_blocked_quotes(&mut self) {
+        let now = chrono::Utc::now();
+        for symbol_id in self.working.symbol_ids() {
+            if self.risk.blocks(symbol_id, now) {
+                if let Err(e) = self.apply_decision(symbol_id, QuoteLadder::default()).await {
+                    warn!("Failed to pull quotes for symbol {}: {}", symbol_id, e);
+                }
+            }
+        }
+        self.record_risk_actions().await;
+    }
+    
+    /// Feed the house share of each symbol's recent volume to the circuit breaker
+    async fn check_house_share(&mut self) -> Result<()> {
+        let Some(account_id) = self.account_id else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+        let window = chrono::Duration::seconds(self.config.circuit_breakers.house_share_window_seconds as i64);
+        
+        // Every trade has one row per side, so a symbol's volume is half its rows
+        let rows = sqlx::query!(
+            r#"
+            SELECT symbol_id,
+                   COALESCE(SUM(quantity) FILTER (WHERE account_id = $1), 0)::BIGINT AS "house_bp!",
+                   COALESCE(SUM(quantity) / 2, 0)::BIGINT AS "total_bp!"
+            FROM trades
+            WHERE timestamp >= $2
+            GROUP BY symbol_id
+            "#,
+            account_id,
+            (now - window).naive_utc()
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .context("Failed to load recent volume")?;
+        
+        for row in rows {
+            self.risk.on_house_share(row.symbol_id as u32, row.house_bp, row.total_bp, now);
+        }
+        self.record_risk_actions().await;
+        Ok(())
+    }
+    
+    /// Write risk state changes to `admin_actions`
+    async fn record_risk_actions(&mut self) {
+        for action in self.risk.take_actions() {
+            warn!("Market maker risk action {} (symbol {:?}): {}", action.action, action.symbol_id, action.details);
+            let Some(account_id) = self.account_id else {
+                continue;
+            };
+            let mut details = action.details;
+            details["symbol_id"] = serde_json::json!(action.symbol_id);
+            if let Err(e) = sqlx::query!(
+                "INSERT INTO admin_actions (account_id, action, details) VALUES ($1, $2, $3)",
+                account_id,
+                action.action,
+                details
+            )
+            .execute(&self.db_pool)
+            .await
+            {
+                warn!("Failed to record risk action {}: {}", action.action, e);
+            }
+        }
+    }
+    
+    /// Write one of our fills to the `house_fills` audit trail
+    ///
+    /// The gateway does not report the counterparty of a fill, so the row carries the
+    /// market maker's own account.
+    async fn record_house_fill(&mut self, report: &ExecutionReport) -> Result<()> {
+        let Some(account_id) = self.account_id else {
+            return Ok(());
+        };
+        let order_id: i64 = report
+            .order_id
+            .trim_start_matches("ord_")
+            .parse()
+            .with_context(|| format!("Unexpected order ID {}", report.order_id))?;
+        let price_cents = report.price.unwrap_or(0) as i64;
+        let fair_cents = self.cache.get_fair_price(report.symbol_id).await.map(|fp| fp.fair_cents);
+        let now = chrono::Utc::now();
+        let rule = serde_json::json!({
+            "strategy": self.strategy.name(),
+            "side": report.side,
+            "liquidity": report.liquidity,
+            "symbol_id": report.symbol_id,
+            "fair_known": fair_cents.is_some(),
+            "position_bp": self.risk.position(report.symbol_id),
+            "daily_notional_cents": self.risk.daily_notional_cents(),
+            "volatility_widened": self.risk.is_volatile(report.symbol_id, now),
+        });
+        
+        sqlx::query!(
+            r#"
+            INSERT INTO house_fills (order_id, account_id, player_id, ts, price_cents, qty_bp, ft_at_fill, rule_json)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            order_id,
+            account_id,
+            self.symbol_to_player_mapping.get(&report.symbol_id).copied().unwrap_or(report.symbol_id as i32),
+            now,
+            price_cents,
+            report.quantity.unwrap_or(0) as i64,
+            fair_cents.unwrap_or(price_cents),
+            rule
+        )
+        .execute(&self.db_pool)
+        .await
+        .context("Failed to insert house fill")?;
+        Ok(())
+    }
+    
+    /// Cache a pushed fair price (and any others already waiting) and re-quote those symbols
+    async fn on_fair_price_update(&mut self, update: FairPriceUpdate) -> Result<()> {
+        let mut updates = HashMap::new();
+        updates.insert(update.symbol_id, update);
+        if let Some(subscription) = self.fair_prices.as_mut() {
+            while let Some(update) = subscription.try_recv() {
+                updates.insert(update.symbol_id, update);
+            }
+        }
+        
+        self.refresh_inventory().await?;
+        let snapshot = self.snapshot_manager.load_latest_snapshot().await?;
+        for (symbol_id, update) in updates {
+            let fair_price = self.fair_price_from_update(update);
+            self.risk.on_fair_price(symbol_id, fair_price.fair_cents, fair_price.updated_at);
+            self.cache.store_fair_price(fair_price).await;
+            self.metrics.fair_price_updates += 1;
+            
+            if let Err(e) = self.process_symbol_with_snapshot(symbol_id, &snapshot).await {
+                warn!("Failed to process symbol {}: {}", symbol_id, e);
+                self.metrics.errors += 1;
+            }
+        }
+        self.record_risk_actions().await;
+        Ok(())
+    }
+    
+    fn fair_price_from_update(&self, update: FairPriceUpdate) -> FairPrice {
+        FairPrice {
+            player_id: self
+                .symbol_to_player_mapping
+                .get(&update.symbol_id)
+                .copied()
+                .unwrap_or_default(),
+            symbol_id: update.symbol_id,
+            fair_cents: update.fair_cents,
+            source: update.source,
+            confidence_score: Default::default(),
+            updated_at: update.ts,
         }
     }
     
@@ -104,16 +444,20 @@ impl MarketMakerService {
         // 1. Refresh fair prices if needed
         if self.cache.needs_refresh().await {
             self.refresh_fair_prices().await?;
+            self.refresh_confidence().await?;
             self.cache.mark_refreshed().await;
         }
         
-        // 2. Get symbols that have recent fair prices
+        // 2. Refresh our own positions
+        self.refresh_inventory().await?;
+        
+        // 3. Get symbols that have recent fair prices
         let symbols_with_prices = self.get_symbols_with_fair_prices().await?;
         
-        // 3. Load snapshot once for all symbols
+        // 4. Load snapshot once for all symbols
         let snapshot = self.snapshot_manager.load_latest_snapshot().await?;
         
-        // 4. Process each symbol with fair prices
+        // 5. Process each symbol with fair prices
         for symbol_id in symbols_with_prices {
             if let Err(e) = self.process_symbol_with_snapshot(symbol_id, &snapshot).await {
                 warn!("Failed to process symbol {}: {}", symbol_id, e);
@@ -121,7 +465,7 @@ impl MarketMakerService {
             }
         }
         
-        // 4. Clean up expired cache entries
+        // 6. Clean up expired cache entries
         self.cache.clear_expired().await;
         
         debug!("Completed market making cycle");
@@ -150,11 +494,12 @@ impl MarketMakerService {
             }
         };
         
-        // 3. Make market making decision
-        let decision = self.make_market_making_decision(&fair_price, &order_book);
+        // 3. Make market making decision, counting only other participants' liquidity
+        let order_book = self.working.exclude_from(&order_book);
+        let decision = self.decide(&fair_price, Some(&order_book));
         
-        // 4. Execute decision
-        self.execute_decision(decision).await?;
+        // 4. Bring our working quotes in line with it
+        self.apply_decision(symbol_id, decision).await?;
         
         Ok(())
     }
@@ -195,35 +540,13 @@ impl MarketMakerService {
             }
         };
         
-        // 3. Make market making decision
-        let decision = match order_book {
-            Some(ob) => self.make_market_making_decision(&fair_price, &ob),
-            None => {
-                // If no order book, post both quotes to activate the market
-                debug!("No order book for symbol {}, posting both quotes to activate market", symbol_id);
-                let spread_bps = self.config.market_maker.spread_bps;
-                let quantity_bp = self.config.market_maker.order_quantity_bp;
-                
-                let bid = MarketMakerQuote::new(
-                    symbol_id,
-                    QuoteSide::Bid,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                let ask = MarketMakerQuote::new(
-                    symbol_id,
-                    QuoteSide::Ask,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                MarketMakerDecision::PostBoth { bid, ask }
-            }
-        };
+        // 3. Make market making decision (no book: post both sides to activate the market),
+        //    counting only other participants' liquidity
+        let order_book = order_book.map(|book| self.working.exclude_from(&book));
+        let decision = self.decide(&fair_price, order_book.as_ref());
         
-        // 4. Execute decision
-        self.execute_decision(decision).await?;
+        // 4. Bring our working quotes in line with it
+        self.apply_decision(symbol_id, decision).await?;
         
         Ok(())
     }
@@ -324,210 +647,56 @@ impl MarketMakerService {
         }
     }
     
-    /// Make market making decision based on fair price and order book
-    fn make_market_making_decision(&self, fair_price: &FairPrice, order_book: &OrderBookState) -> MarketMakerDecision {
-        let spread_bps = self.config.market_maker.spread_bps;
-        let max_spread_bps = self.config.market_maker.max_spread_bps;
-        let quantity_bp = self.config.market_maker.order_quantity_bp;
-        
-        // Check if order book is empty
-        if order_book.is_empty() {
-            debug!("Order book empty for symbol {}, posting both quotes", fair_price.symbol_id);
-            let bid = MarketMakerQuote::new(
-                fair_price.symbol_id,
-                QuoteSide::Bid,
-                fair_price.fair_cents,
-                spread_bps,
-                quantity_bp,
-            );
-            let ask = MarketMakerQuote::new(
-                fair_price.symbol_id,
-                QuoteSide::Ask,
-                fair_price.fair_cents,
-                spread_bps,
-                quantity_bp,
-            );
-            return MarketMakerDecision::PostBoth { bid, ask };
-        }
-        
-        // Check if spread is too wide
-        if let Some(current_spread) = order_book.spread_bps() {
-            if current_spread > max_spread_bps {
-                debug!("Spread too wide for symbol {}: {} bps, posting both quotes", 
-                       fair_price.symbol_id, current_spread);
-                let bid = MarketMakerQuote::new(
-                    fair_price.symbol_id,
-                    QuoteSide::Bid,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                let ask = MarketMakerQuote::new(
-                    fair_price.symbol_id,
-                    QuoteSide::Ask,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                return MarketMakerDecision::PostBoth { bid, ask };
-            }
-        }
-        
-        // Check if one-sided
-        if order_book.is_one_sided() {
-            if order_book.buy_orders.is_empty() {
-                debug!("No bids for symbol {}, posting bid", fair_price.symbol_id);
-                let bid = MarketMakerQuote::new(
-                    fair_price.symbol_id,
-                    QuoteSide::Bid,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                return MarketMakerDecision::PostBid { bid };
-            } else if order_book.sell_orders.is_empty() {
-                debug!("No asks for symbol {}, posting ask", fair_price.symbol_id);
-                let ask = MarketMakerQuote::new(
-                    fair_price.symbol_id,
-                    QuoteSide::Ask,
-                    fair_price.fair_cents,
-                    spread_bps,
-                    quantity_bp,
-                );
-                return MarketMakerDecision::PostAsk { ask };
-            }
-        }
-        
-        // Do nothing - order book is healthy
-        MarketMakerDecision::DoNothing
+    /// Ask the strategy for quotes given the fair price, order book and our position, stack
+    /// the symbol's ladder behind them and apply the risk limits and circuit breakers
+    fn decide(&mut self, fair_price: &FairPrice, order_book: Option<&OrderBookState>) -> QuoteLadder {
+        let ctx = QuoteContext {
+            symbol_id: fair_price.symbol_id,
+            fair_cents: fair_price.fair_cents,
+            order_book,
+            position_bp: self.risk.position(fair_price.symbol_id),
+        };
+        let decision = self.strategy.quote(&ctx);
+        debug!("{} strategy for symbol {} at position {} bp: {:?}",
+               self.strategy.name(), ctx.symbol_id, ctx.position_bp, decision);
+        let confidence = self.confidence.get(&fair_price.symbol_id).copied().unwrap_or(1.0);
+        let ladder = self.config.ladder.profile(fair_price.symbol_id).build(decision, confidence);
+        self.risk.filter(fair_price.symbol_id, ladder, chrono::Utc::now())
     }
     
-    /// Execute market making decision
-    async fn execute_decision(&mut self, decision: MarketMakerDecision) -> Result<()> {
-        match decision {
-            MarketMakerDecision::PostBoth { bid, ask } => {
-                self.post_quote(bid).await?;
-                self.post_quote(ask).await?;
-            }
-            MarketMakerDecision::PostBid { bid } => {
-                self.post_quote(bid).await?;
-            }
-            MarketMakerDecision::PostAsk { ask } => {
-                self.post_quote(ask).await?;
-            }
-            MarketMakerDecision::DoNothing => {
-                // Nothing to do
-            }
+    /// Cancel, replace and place quotes so the working quotes match the ladder
+    ///
+    /// Working levels are only replaced once the fair price (or the level's price) has moved
+    /// by `min_fair_price_change_bps`.
+    async fn apply_decision(&mut self, symbol_id: u32, ladder: QuoteLadder) -> Result<()> {
+        let plan = self.working.plan(symbol_id, ladder, self.config.market_maker.min_fair_price_change_bps);
+        if plan.is_empty() {
+            return Ok(());
         }
+        let session = self.session.as_ref().context("Order gateway session not started")?;
         
-        Ok(())
-    }
-    
-    /// Post a market maker quote via WebSocket
-    async fn post_quote(&mut self, quote: MarketMakerQuote) -> Result<()> {
-        info!("Posting {} quote for symbol {}: {} cents, {} bp", 
-              match quote.side {
-                  QuoteSide::Bid => "BID",
-                  QuoteSide::Ask => "ASK",
-              },
-              quote.symbol_id,
-              quote.price_cents,
-              quote.quantity_bp);
-        
-        // Connect to WebSocket
-        let url = Url::parse(&self.websocket_url)
-            .context("Failed to parse WebSocket URL")?;
-        
-        let (ws_stream, _) = connect_async(url)
-            .await
-            .context("Failed to connect to WebSocket")?;
-        
-        let (mut write, mut read) = ws_stream.split();
-        
-        // First, authenticate
-        let auth_message = serde_json::json!({
-            "method": "auth.login",
-            "params": {
-                "api_key": self.config.market_maker.api_key,
-                "api_secret": self.config.market_maker.api_secret
-            }
-        });
-        
-        write.send(Message::Text(auth_message.to_string()))
-            .await
-            .context("Failed to send auth message")?;
-        
-        // Wait for auth response
-        if let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    let response: serde_json::Value = serde_json::from_str(&text)
-                        .context("Failed to parse auth response")?;
-                    
-                    if response.get("result").and_then(|r| r.get("authenticated")).and_then(|a| a.as_bool()).unwrap_or(false) {
-                        info!("✅ Market maker authenticated successfully");
-                    } else {
-                        error!("❌ Authentication failed: {}", text);
-                        self.metrics.errors += 1;
-                        return Ok(());
-                    }
-                }
-                Ok(_) => {
-                    error!("❌ Unexpected message type during authentication");
-                    self.metrics.errors += 1;
-                    return Ok(());
-                }
-                Err(e) => {
-                    error!("❌ WebSocket error during authentication: {}", e);
-                    self.metrics.errors += 1;
-                    return Ok(());
+        for order_id in plan.cancel {
+            match session.cancel(&order_id).await {
+                Ok(()) => {
+                    debug!("Cancelled quote {} on symbol {}", order_id, symbol_id);
+                    self.metrics.quotes_cancelled += 1;
                 }
+                // Usually already filled or cancelled; either way it is no longer ours to manage
+                Err(e) => warn!("Cancel of quote {} failed: {}", order_id, e),
             }
+            self.working.remove(&order_id);
         }
         
-        // Now submit the order
-        let order_message = serde_json::json!({
-            "method": "order.place",
-            "params": {
-                "symbol": quote.symbol_id.to_string(),
-                "side": match quote.side {
-                    QuoteSide::Bid => "BUY",
-                    QuoteSide::Ask => "SELL",
-                },
-                "type": "LIMIT",
-                "quantity": quote.quantity_bp as u32,
-                "price": quote.price_cents as u32,
-            }
-        });
-        
-        write.send(Message::Text(order_message.to_string()))
-            .await
-            .context("Failed to send order message")?;
-        
-        // Wait for order response
-        if let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    let response: serde_json::Value = serde_json::from_str(&text)
-                        .context("Failed to parse order response")?;
-                    
-                    if response.get("result").is_some() {
-                        info!("✅ Order submitted successfully: {}", text);
-                        self.metrics.quotes_posted += 1;
-                    } else if let Some(error) = response.get("error") {
-                        error!("❌ Order submission failed: {}", error);
-                        self.metrics.errors += 1;
-                    } else {
-                        error!("❌ Unexpected order response: {}", text);
-                        self.metrics.errors += 1;
-                    }
-                }
-                Ok(_) => {
-                    error!("❌ Unexpected message type for order response");
-                    self.metrics.errors += 1;
+        for quote in plan.place {
+            match session.place(&quote).await {
+                Ok(order_id) => {
+                    info!("Posted {:?} level {} quote {} for symbol {}: {} cents, {} bp",
+                          quote.side, quote.level, order_id, quote.symbol_id, quote.price_cents, quote.quantity_bp);
+                    self.metrics.quotes_posted += 1;
+                    self.working.insert(order_id, quote);
                 }
                 Err(e) => {
-                    error!("❌ WebSocket error for order response: {}", e);
+                    error!("Failed to post {:?} quote for symbol {}: {}", quote.side, quote.symbol_id, e);
                     self.metrics.errors += 1;
                 }
             }
@@ -577,6 +746,93 @@ impl MarketMakerService {
         Ok(())
     }
     
+    /// Load the latest fair price confidence of every symbol from `fair_prices`
+    async fn refresh_confidence(&mut self) -> Result<()> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT ON (symbol_id) symbol_id, confidence::FLOAT8 AS "confidence!"
+            FROM fair_prices
+            ORDER BY symbol_id, calculated_at DESC
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .context("Failed to load fair price confidence")?;
+        
+        self.confidence = rows
+            .into_iter()
+            .map(|row| (row.symbol_id as u32, row.confidence))
+            .collect();
+        Ok(())
+    }
+    
+    /// Resolve the market maker's account from its API key
+    async fn resolve_account(&mut self) -> Result<()> {
+        let row = sqlx::query!(
+            "SELECT account_id FROM api_keys WHERE key_id = $1 AND revoked_at IS NULL",
+            self.config.market_maker.api_key
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .context("Failed to resolve market maker account")?;
+        
+        self.account_id = row.map(|row| row.account_id);
+        match self.account_id {
+            Some(account_id) => info!("Market maker trades as account {}", account_id),
+            None => warn!("No active API key found for the market maker; quoting as if flat"),
+        }
+        Ok(())
+    }
+    
+    /// Reload our positions (in basis points) from the database
+    async fn refresh_inventory(&mut self) -> Result<()> {
+        let Some(account_id) = self.account_id else {
+            return Ok(());
+        };
+        
+        let rows = sqlx::query!(
+            "SELECT symbol_id, quantity FROM positions WHERE account_id = $1",
+            account_id
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .context("Failed to load market maker positions")?;
+        
+        self.risk.set_positions(rows.into_iter().map(|row| (row.symbol_id as u32, row.quantity)));
+        Ok(())
+    }
+    
+    /// Seed today's notional from the fills we recorded before a restart
+    async fn load_daily_notional(&mut self) -> Result<()> {
+        let Some(account_id) = self.account_id else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+        let midnight = now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        
+        let row = sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(qty_bp * price_cents / 10000), 0)::BIGINT AS "notional_cents!"
+            FROM house_fills
+            WHERE account_id = $1 AND ts >= $2
+            "#,
+            account_id,
+            midnight
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .context("Failed to load today's notional")?;
+        
+        self.risk.set_daily_notional(row.notional_cents, now);
+        self.record_risk_actions().await;
+        Ok(())
+    }
+    
+    /// Current position for a symbol in basis points
+    pub fn position_bp(&self, symbol_id: u32) -> i64 {
+        self.risk.position(symbol_id)
+    }
+    
     /// Load symbol to player mapping
     async fn load_symbol_mapping(&mut self) -> Result<()> {
         info!("Loading symbol to player mapping");
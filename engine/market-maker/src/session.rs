@@ -0,0 +1,407 @@
+//! Long-lived order gateway session
+//!
+//! Every order the market maker places or cancels goes over one authenticated WebSocket
+//! connection, and fills come back on its `execution` stream. When the connection drops it
+//! is re-established with exponential backoff. Cancel-on-disconnect is enabled on every
+//! connection, so the gateway pulls the working quotes of a session we can no longer manage.
+//!
+//! Every request carries an ID that the gateway echoes in its result or error, and responses
+//! are matched to requests by that ID.
+
+use crate::models::{MarketMakerQuote, QuoteSide};
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build an HMAC-signed `auth.login` request
+///
+/// The signing key is the secret and the payload is `api_key\ntimestamp\nnonce`,
+/// matching the order gateway's signed login mode.
+fn signed_login_message(api_key: &str, api_secret: &str) -> serde_json::Value {
+    let now = chrono::Utc::now();
+    let timestamp = now.timestamp();
+    let nonce = now.timestamp_nanos_opt().unwrap_or_default().to_string();
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(api_secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{api_key}\n{timestamp}\n{nonce}").as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    serde_json::json!({
+        "method": "auth.login",
+        "params": {
+            "api_key": api_key,
+            "timestamp": timestamp,
+            "nonce": nonce,
+            "signature": signature
+        }
+    })
+}
+
+/// Execution report from the gateway's `execution` stream
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionReport {
+    pub order_id: String,
+    pub symbol_id: u32,
+    /// TRADE or CANCELLED
+    pub exec_type: String,
+    #[serde(default)]
+    pub side: Option<String>,
+    #[serde(default)]
+    pub price: Option<u32>,
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    #[serde(default)]
+    pub liquidity: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl ExecutionReport {
+    /// Signed fill quantity in basis points (positive when we bought)
+    pub fn signed_quantity_bp(&self) -> i64 {
+        let quantity = self.quantity.unwrap_or(0) as i64;
+        match self.side.as_deref() {
+            Some("BUY") => quantity,
+            Some("SELL") => -quantity,
+            _ => 0,
+        }
+    }
+}
+
+/// Something the session wants the market maker to know about
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    /// Authenticated on a new connection; nothing is working yet
+    Connected,
+    /// The connection dropped; the gateway cancels every order placed on it
+    Disconnected,
+    /// A fill or cancel of one of our orders
+    Execution(ExecutionReport),
+}
+
+struct Request {
+    method: &'static str,
+    params: serde_json::Value,
+    reply: oneshot::Sender<Result<serde_json::Value>>,
+}
+
+enum Ended {
+    /// The session handle was dropped
+    Closed,
+    /// The connection was lost
+    Dropped(String),
+}
+
+/// Handle to the background gateway connection
+pub struct GatewaySession {
+    requests: mpsc::UnboundedSender<Request>,
+    events: mpsc::UnboundedReceiver<SessionEvent>,
+}
+
+impl GatewaySession {
+    /// Start connecting to the gateway in the background
+    pub fn connect(url: String, api_key: String, api_secret: String) -> Self {
+        let (requests, request_rx) = mpsc::unbounded_channel();
+        let (event_tx, events) = mpsc::unbounded_channel();
+        tokio::spawn(run(url, api_key, api_secret, request_rx, event_tx));
+        Self { requests, events }
+    }
+
+    /// Place a limit order for a quote, returning its gateway order ID
+    pub async fn place(&self, quote: &MarketMakerQuote) -> Result<String> {
+        let side = match quote.side {
+            QuoteSide::Bid => "BUY",
+            QuoteSide::Ask => "SELL",
+        };
+        let client_order_id = format!(
+            "mm-{}-{}-{}",
+            quote.symbol_id,
+            side,
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        );
+
+        let result = self
+            .request(
+                "order.place",
+                serde_json::json!({
+                    "symbol": quote.symbol_id.to_string(),
+                    "side": side,
+                    "type": "LIMIT",
+                    "quantity": quote.quantity_bp as u64,
+                    "price": quote.price_cents as u32,
+                    "client_order_id": client_order_id,
+                }),
+            )
+            .await?;
+
+        result
+            .get("order_id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Order response without order_id: {}", result))
+    }
+
+    /// Request cancellation of a working order
+    pub async fn cancel(&self, order_id: &str) -> Result<()> {
+        self.request("order.cancel", serde_json::json!({ "order_id": order_id })).await?;
+        Ok(())
+    }
+
+    /// Next session event; waits forever once the background task has stopped
+    pub async fn next_event(&mut self) -> SessionEvent {
+        match self.events.recv().await {
+            Some(event) => event,
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn request(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let (reply, response) = oneshot::channel();
+        self.requests
+            .send(Request { method, params, reply })
+            .map_err(|_| anyhow!("Order gateway session has stopped"))?;
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => bail!("Order gateway connection lost before {} completed", method),
+            Err(_) => bail!("Timed out waiting for {} response", method),
+        }
+    }
+}
+
+/// Connect, serve and reconnect until the session handle is dropped
+async fn run(
+    url: String,
+    api_key: String,
+    api_secret: String,
+    mut requests: mpsc::UnboundedReceiver<Request>,
+    events: mpsc::UnboundedSender<SessionEvent>,
+) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match open(&url, &api_key, &api_secret).await {
+            Ok(socket) => {
+                info!("Order gateway session established at {}", url);
+                backoff = MIN_BACKOFF;
+                if events.send(SessionEvent::Connected).is_err() {
+                    return;
+                }
+
+                let ended = serve(socket, &mut requests, &events).await;
+                if events.send(SessionEvent::Disconnected).is_err() {
+                    return;
+                }
+                match ended {
+                    Ended::Closed => return,
+                    Ended::Dropped(reason) => warn!("Order gateway session lost: {}", reason),
+                }
+            }
+            Err(e) => warn!("Failed to open order gateway session: {:#}", e),
+        }
+
+        // Fail requests while disconnected instead of holding them behind the backoff
+        let retry = tokio::time::sleep(backoff);
+        tokio::pin!(retry);
+        loop {
+            tokio::select! {
+                _ = &mut retry => break,
+                request = requests.recv() => match request {
+                    Some(request) => {
+                        let _ = request.reply.send(Err(anyhow!("Not connected to the order gateway")));
+                    }
+                    None => return,
+                },
+            }
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Connect, log in and enable cancel-on-disconnect
+async fn open(url: &str, api_key: &str, api_secret: &str) -> Result<Socket> {
+    let url = url::Url::parse(url).context("Failed to parse WebSocket URL")?;
+    let (mut socket, _) = connect_async(url).await.context("Failed to connect to WebSocket")?;
+
+    let login = call(&mut socket, signed_login_message(api_key, api_secret)).await?;
+    if !login.get("authenticated").and_then(|a| a.as_bool()).unwrap_or(false) {
+        bail!("Authentication failed: {}", login);
+    }
+
+    call(
+        &mut socket,
+        serde_json::json!({
+            "method": "session.cancel_on_disconnect",
+            "params": { "enabled": true }
+        }),
+    )
+    .await
+    .context("Failed to enable cancel-on-disconnect")?;
+
+    Ok(socket)
+}
+
+/// Send one request during setup and wait for its response
+async fn call(socket: &mut Socket, request: serde_json::Value) -> Result<serde_json::Value> {
+    socket.send(Message::Text(request.to_string())).await?;
+    while let Some(message) = socket.next().await {
+        let Message::Text(text) = message? else {
+            continue;
+        };
+        let message: serde_json::Value = serde_json::from_str(&text)?;
+        if message.get("stream").is_some_and(|stream| !stream.is_null()) {
+            debug!("Ignoring stream message during session setup: {}", text);
+            continue;
+        }
+        return response_result(message);
+    }
+    bail!("Connection closed during session setup")
+}
+
+/// Relay requests and responses until the connection drops or the handle is dropped
+async fn serve(
+    socket: Socket,
+    requests: &mut mpsc::UnboundedReceiver<Request>,
+    events: &mpsc::UnboundedSender<SessionEvent>,
+) -> Ended {
+    let (mut write, mut read) = socket.split();
+    let mut pending: HashMap<String, oneshot::Sender<Result<serde_json::Value>>> = HashMap::new();
+    let mut next_id: u64 = 0;
+
+    loop {
+        tokio::select! {
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Err(e) = dispatch(&text, &mut pending, events) {
+                        warn!("Ignoring unreadable gateway message: {}", e);
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    return Ended::Dropped("connection closed".to_string());
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Ended::Dropped(e.to_string()),
+            },
+            request = requests.recv() => {
+                let Some(request) = request else {
+                    let _ = write.send(Message::Close(None)).await;
+                    return Ended::Closed;
+                };
+                next_id += 1;
+                let id = next_id.to_string();
+                let message = serde_json::json!({
+                    "id": id,
+                    "method": request.method,
+                    "params": request.params,
+                });
+                if let Err(e) = write.send(Message::Text(message.to_string())).await {
+                    let _ = request.reply.send(Err(anyhow!("Failed to send {}: {}", request.method, e)));
+                    return Ended::Dropped(e.to_string());
+                }
+                // Forget requests whose caller timed out without an answer
+                pending.retain(|_, reply| !reply.is_closed());
+                pending.insert(id, request.reply);
+            }
+        }
+    }
+}
+
+/// Route one gateway message to a waiting request or the event channel
+fn dispatch(
+    text: &str,
+    pending: &mut HashMap<String, oneshot::Sender<Result<serde_json::Value>>>,
+    events: &mpsc::UnboundedSender<SessionEvent>,
+) -> Result<()> {
+    let message: serde_json::Value = serde_json::from_str(text)?;
+    match message.get("stream").and_then(|stream| stream.as_str()) {
+        Some("execution") => {
+            let data = message.get("data").cloned().unwrap_or_default();
+            let report: ExecutionReport = serde_json::from_value(data)?;
+            let _ = events.send(SessionEvent::Execution(report));
+        }
+        Some("session.mass_cancel") => {
+            info!("Gateway cancelled orders of an earlier session: {}", text);
+        }
+        Some(_) => {}
+        None => {
+            let reply =
+                message.get("id").and_then(|id| id.as_str()).and_then(|id| pending.remove(id));
+            match reply {
+                // The caller may have timed out already
+                Some(reply) => {
+                    let _ = reply.send(response_result(message));
+                }
+                None => warn!("Gateway response matches no pending request: {}", text),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `result` of a response, or its `error` as an error
+fn response_result(message: serde_json::Value) -> Result<serde_json::Value> {
+    if let Some(error) = message.get("error").filter(|error| !error.is_null()) {
+        let reason = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+        bail!("Gateway rejected request: {}", reason);
+    }
+    message
+        .get("result")
+        .cloned()
+        .ok_or_else(|| anyhow!("Unexpected gateway response: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_matches_responses_by_id() {
+        let (events, _event_rx) = mpsc::unbounded_channel();
+        let mut pending = HashMap::new();
+        let mut replies = Vec::new();
+        for id in ["1", "2", "3"] {
+            let (reply, response) = oneshot::channel();
+            pending.insert(id.to_string(), reply);
+            replies.push(response);
+        }
+
+        // Answers arrive out of order, including an error and one without an ID
+        dispatch(
+            r#"{"id":"2","error":{"code":50000,"message":"Insufficient funds"}}"#,
+            &mut pending,
+            &events,
+        )
+        .unwrap();
+        dispatch(
+            r#"{"id":null,"error":{"code":50000,"message":"bad json"}}"#,
+            &mut pending,
+            &events,
+        )
+        .unwrap();
+        dispatch(r#"{"id":"3","result":{"order_id":"ord_3"}}"#, &mut pending, &events).unwrap();
+
+        let error = replies[1].try_recv().unwrap().unwrap_err();
+        assert!(error.to_string().contains("Insufficient funds"));
+        assert_eq!(replies[2].try_recv().unwrap().unwrap()["order_id"], "ord_3");
+        assert!(replies[0].try_recv().is_err());
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec!["1"]);
+    }
+}
@@ -0,0 +1,417 @@
+//! Quoting strategies
+//!
+//! A [`QuotingStrategy`] turns a fair price, the current order book and the market maker's
+//! own position into a [`MarketMakerDecision`]. The service owns one strategy, chosen by
+//! `MARKET_MAKER_STRATEGY`:
+//!
+//! - `symmetric`: a fixed `fair ± spread_bps` quote, ignoring inventory (the original behavior)
+//! - `inventory_skew`: Avellaneda–Stoikov style quotes that skew away from the current position,
+//!   widen as it grows and drop a side at the position limit
+//!
+//! Both only add liquidity where the book needs it: an empty book, a missing side or a spread
+//! wider than `max_spread_bps`.
+
+use crate::config::{MarketMakerConfig, StrategyKind};
+use crate::models::{MarketMakerDecision, MarketMakerQuote, OrderBookState, QuoteSide};
+use serde::{Deserialize, Serialize};
+
+/// Everything a strategy sees when quoting one symbol
+#[derive(Debug, Clone)]
+pub struct QuoteContext<'a> {
+    pub symbol_id: u32,
+    pub fair_cents: i64,
+    /// Current book, if the symbol has one
+    pub order_book: Option<&'a OrderBookState>,
+    /// Market maker position in basis points (10000 = 1 share, negative = short)
+    pub position_bp: i64,
+}
+
+/// Decides which quotes to post for a symbol
+pub trait QuotingStrategy: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    /// Quotes to post for the given context
+    fn quote(&self, ctx: &QuoteContext<'_>) -> MarketMakerDecision;
+}
+
+/// Build the strategy selected in the config
+pub fn from_config(config: &MarketMakerConfig) -> Box<dyn QuotingStrategy> {
+    let params = &config.market_maker;
+    match config.strategy.kind {
+        StrategyKind::Symmetric => Box::new(SymmetricStrategy {
+            spread_bps: params.spread_bps,
+            max_spread_bps: params.max_spread_bps,
+            quantity_bp: params.order_quantity_bp,
+        }),
+        StrategyKind::InventorySkew => Box::new(InventorySkewStrategy {
+            params: config.strategy.inventory_skew.clone(),
+            max_spread_bps: params.max_spread_bps,
+            quantity_bp: params.order_quantity_bp,
+            max_position_bp: params.max_position_per_player * 10_000,
+        }),
+    }
+}
+
+/// Sides of the book that need liquidity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityNeed {
+    pub bid: bool,
+    pub ask: bool,
+}
+
+impl LiquidityNeed {
+    /// Empty book, missing side or a spread wider than `max_spread_bps`
+    pub fn of(order_book: Option<&OrderBookState>, max_spread_bps: u32) -> Self {
+        let book = match order_book {
+            Some(book) if !book.is_empty() => book,
+            _ => return Self { bid: true, ask: true },
+        };
+        if book.spread_bps().is_some_and(|spread| spread > max_spread_bps) {
+            return Self { bid: true, ask: true };
+        }
+        Self { bid: book.buy_orders.is_empty(), ask: book.sell_orders.is_empty() }
+    }
+}
+
+fn decision(bid: Option<MarketMakerQuote>, ask: Option<MarketMakerQuote>) -> MarketMakerDecision {
+    match (bid, ask) {
+        (Some(bid), Some(ask)) => MarketMakerDecision::PostBoth { bid, ask },
+        (Some(bid), None) => MarketMakerDecision::PostBid { bid },
+        (None, Some(ask)) => MarketMakerDecision::PostAsk { ask },
+        (None, None) => MarketMakerDecision::DoNothing,
+    }
+}
+
+/// Fixed-width quotes centred on the fair price
+#[derive(Debug, Clone)]
+pub struct SymmetricStrategy {
+    pub spread_bps: u32,
+    pub max_spread_bps: u32,
+    pub quantity_bp: i64,
+}
+
+impl QuotingStrategy for SymmetricStrategy {
+    fn name(&self) -> &'static str {
+        "symmetric"
+    }
+
+    fn quote(&self, ctx: &QuoteContext<'_>) -> MarketMakerDecision {
+        let need = LiquidityNeed::of(ctx.order_book, self.max_spread_bps);
+        let quote = |side| {
+            MarketMakerQuote::new(
+                ctx.symbol_id,
+                side,
+                ctx.fair_cents,
+                self.spread_bps,
+                self.quantity_bp,
+            )
+        };
+        decision(need.bid.then(|| quote(QuoteSide::Bid)), need.ask.then(|| quote(QuoteSide::Ask)))
+    }
+}
+
+/// Avellaneda–Stoikov parameters, in basis points of the fair price
+///
+/// Inventory `q` is the position as a fraction of the position limit (-1 to 1). Over one
+/// quoting horizon:
+///
+/// - reservation price `r = fair · (1 - q·γ·σ²)`
+/// - half spread `δ = max(min, (γ·σ² + (2/γ)·ln(1 + γ/κ)) / 2) · (1 + widening·|q|)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventorySkewParams {
+    /// Risk aversion γ (per bp)
+    pub risk_aversion: f64,
+    /// Fair price volatility σ over the quoting horizon (bps)
+    pub volatility_bps: f64,
+    /// Order arrival decay κ (per bp of distance from the fair price)
+    pub arrival_intensity: f64,
+    /// Extra half spread at the position limit, as a fraction of the base half spread
+    pub inventory_widening: f64,
+    /// Floor for the half spread (bps)
+    pub min_half_spread_bps: u32,
+}
+
+impl Default for InventorySkewParams {
+    fn default() -> Self {
+        Self {
+            risk_aversion: 0.005,
+            volatility_bps: 300.0,
+            arrival_intensity: 0.01,
+            inventory_widening: 0.5,
+            min_half_spread_bps: 100,
+        }
+    }
+}
+
+/// Inventory-aware quotes after Avellaneda & Stoikov (2008)
+#[derive(Debug, Clone)]
+pub struct InventorySkewStrategy {
+    pub params: InventorySkewParams,
+    pub max_spread_bps: u32,
+    pub quantity_bp: i64,
+    pub max_position_bp: i64,
+}
+
+impl InventorySkewStrategy {
+    /// Position as a fraction of the limit, clamped to [-1, 1]
+    fn inventory_ratio(&self, position_bp: i64) -> f64 {
+        if self.max_position_bp <= 0 {
+            return 0.0;
+        }
+        (position_bp as f64 / self.max_position_bp as f64).clamp(-1.0, 1.0)
+    }
+
+    /// Reservation price and half spread in cents
+    pub fn reservation_and_half_spread(&self, fair_cents: i64, position_bp: i64) -> (f64, f64) {
+        let p = &self.params;
+        let q = self.inventory_ratio(position_bp);
+        let risk_bps = p.risk_aversion * p.volatility_bps * p.volatility_bps;
+
+        let reservation_bps = -q * risk_bps;
+        let base_half_bps = (risk_bps
+            + (2.0 / p.risk_aversion) * (1.0 + p.risk_aversion / p.arrival_intensity).ln())
+            / 2.0;
+        let half_bps = base_half_bps.max(p.min_half_spread_bps as f64)
+            * (1.0 + p.inventory_widening * q.abs());
+
+        let fair = fair_cents as f64;
+        (fair * (1.0 + reservation_bps / 10_000.0), fair * half_bps / 10_000.0)
+    }
+}
+
+impl QuotingStrategy for InventorySkewStrategy {
+    fn name(&self) -> &'static str {
+        "inventory_skew"
+    }
+
+    fn quote(&self, ctx: &QuoteContext<'_>) -> MarketMakerDecision {
+        let need = LiquidityNeed::of(ctx.order_book, self.max_spread_bps);
+        let (reservation, half_spread) =
+            self.reservation_and_half_spread(ctx.fair_cents, ctx.position_bp);
+
+        // Never quote a side that would take the position past the limit
+        let can_buy = ctx.position_bp + self.quantity_bp <= self.max_position_bp;
+        let can_sell = ctx.position_bp - self.quantity_bp >= -self.max_position_bp;
+
+        let best_bid = ctx.order_book.and_then(|book| book.best_bid()).map(|p| p as i64);
+        let best_ask = ctx.order_book.and_then(|book| book.best_ask()).map(|p| p as i64);
+
+        // Skewed quotes stay passive: never cross the opposite side of the book
+        let mut bid_cents = (reservation - half_spread).floor() as i64;
+        if let Some(ask) = best_ask {
+            bid_cents = bid_cents.min(ask - 1);
+        }
+        let mut ask_cents = (reservation + half_spread).ceil() as i64;
+        if let Some(bid) = best_bid {
+            ask_cents = ask_cents.max(bid + 1);
+        }
+
+        let spread_bps = if ctx.fair_cents > 0 {
+            (half_spread * 10_000.0 / ctx.fair_cents as f64).round() as u32
+        } else {
+            0
+        };
+        let quote = |side, price_cents| {
+            MarketMakerQuote::at_price(
+                ctx.symbol_id,
+                side,
+                price_cents,
+                self.quantity_bp,
+                ctx.fair_cents,
+                spread_bps,
+            )
+        };
+
+        let bid = (need.bid && can_buy && bid_cents > 0).then(|| quote(QuoteSide::Bid, bid_cents));
+        let ask = (need.ask && can_sell).then(|| quote(QuoteSide::Ask, ask_cents));
+        decision(bid, ask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const SHARE: i64 = 10_000;
+
+    fn strategy() -> InventorySkewStrategy {
+        InventorySkewStrategy {
+            params: InventorySkewParams::default(),
+            max_spread_bps: 1200,
+            quantity_bp: SHARE,
+            max_position_bp: 10 * SHARE,
+        }
+    }
+
+    fn quotes(decision: &MarketMakerDecision) -> (Option<i64>, Option<i64>) {
+        match decision {
+            MarketMakerDecision::PostBoth { bid, ask } => {
+                (Some(bid.price_cents), Some(ask.price_cents))
+            }
+            MarketMakerDecision::PostBid { bid } => (Some(bid.price_cents), None),
+            MarketMakerDecision::PostAsk { ask } => (None, Some(ask.price_cents)),
+            MarketMakerDecision::DoNothing => (None, None),
+        }
+    }
+
+    fn ctx(book: Option<&OrderBookState>, position_bp: i64) -> QuoteContext<'_> {
+        QuoteContext { symbol_id: 1, fair_cents: 2000, order_book: book, position_bp }
+    }
+
+    /// A price-level book where our quotes rest and simulated takers trade against them
+    struct SimulatedBook {
+        book: OrderBookState,
+        position_bp: i64,
+    }
+
+    impl SimulatedBook {
+        fn new() -> Self {
+            Self {
+                book: OrderBookState {
+                    symbol_id: 1,
+                    buy_orders: HashMap::new(),
+                    sell_orders: HashMap::new(),
+                    last_trade_price: None,
+                    last_trade_quantity: None,
+                    last_trade_timestamp: None,
+                },
+                position_bp: 0,
+            }
+        }
+
+        fn rest(&mut self, decision: MarketMakerDecision) {
+            let mut add = |quote: MarketMakerQuote| {
+                let side = match quote.side {
+                    QuoteSide::Bid => &mut self.book.buy_orders,
+                    QuoteSide::Ask => &mut self.book.sell_orders,
+                };
+                *side.entry(quote.price_cents as u64).or_default() += quote.quantity_bp as u64;
+            };
+            match decision {
+                MarketMakerDecision::PostBoth { bid, ask } => {
+                    add(bid);
+                    add(ask);
+                }
+                MarketMakerDecision::PostBid { bid } => add(bid),
+                MarketMakerDecision::PostAsk { ask } => add(ask),
+                MarketMakerDecision::DoNothing => {}
+            }
+        }
+
+        /// A taker sells into our best bid (we buy)
+        fn hit_bid(&mut self) -> bool {
+            let Some(price) = self.book.best_bid() else { return false };
+            let qty = self.book.buy_orders.remove(&price).unwrap();
+            self.position_bp += qty as i64;
+            true
+        }
+
+        /// A taker buys our best ask (we sell)
+        fn lift_ask(&mut self) -> bool {
+            let Some(price) = self.book.best_ask() else { return false };
+            let qty = self.book.sell_orders.remove(&price).unwrap();
+            self.position_bp -= qty as i64;
+            true
+        }
+    }
+
+    #[test]
+    fn flat_inventory_quotes_symmetrically_around_fair() {
+        let (bid, ask) = quotes(&strategy().quote(&ctx(None, 0)));
+        let (bid, ask) = (bid.unwrap(), ask.unwrap());
+        assert!(bid < 2000 && ask > 2000);
+        assert!(((2000 - bid) - (ask - 2000)).abs() <= 1);
+    }
+
+    #[test]
+    fn long_inventory_skews_down_and_widens() {
+        let s = strategy();
+        let (flat_bid, flat_ask) = quotes(&s.quote(&ctx(None, 0)));
+        let (long_bid, long_ask) = quotes(&s.quote(&ctx(None, 5 * SHARE)));
+        let (short_bid, short_ask) = quotes(&s.quote(&ctx(None, -5 * SHARE)));
+
+        // Long: cheaper on both sides to shed inventory; short: dearer to rebuild it
+        assert!(long_bid.unwrap() < flat_bid.unwrap() && long_ask.unwrap() < flat_ask.unwrap());
+        assert!(short_bid.unwrap() > flat_bid.unwrap() && short_ask.unwrap() > flat_ask.unwrap());
+
+        let width = |(bid, ask): (Option<i64>, Option<i64>)| ask.unwrap() - bid.unwrap();
+        assert!(width((long_bid, long_ask)) > width((flat_bid, flat_ask)));
+        assert_eq!(width((long_bid, long_ask)), width((short_bid, short_ask)));
+    }
+
+    #[test]
+    fn stops_quoting_a_side_at_the_position_limit() {
+        let s = strategy();
+        assert_eq!(quotes(&s.quote(&ctx(None, 10 * SHARE))).0, None);
+        assert!(quotes(&s.quote(&ctx(None, 10 * SHARE))).1.is_some());
+        assert_eq!(quotes(&s.quote(&ctx(None, -10 * SHARE))).1, None);
+        // One more lot would breach the limit
+        assert_eq!(quotes(&s.quote(&ctx(None, 9 * SHARE + 1))).0, None);
+    }
+
+    #[test]
+    fn skewed_quotes_never_cross_the_book() {
+        let mut sim = SimulatedBook::new();
+        sim.book.buy_orders.insert(1990, SHARE as u64);
+        // A full long pulls the reservation price down; the ask would otherwise sit below 1990
+        let s = InventorySkewStrategy {
+            params: InventorySkewParams { risk_aversion: 0.05, ..Default::default() },
+            ..strategy()
+        };
+        let (bid, ask) = quotes(&s.quote(&ctx(Some(&sim.book), 10 * SHARE)));
+        assert_eq!(bid, None);
+        assert!(ask.unwrap() >= 1991);
+    }
+
+    #[test]
+    fn one_way_flow_is_capped_by_the_position_limit() {
+        let s = strategy();
+        let mut sim = SimulatedBook::new();
+        let mut bids_seen = Vec::new();
+
+        // Sellers keep hitting our bid until we stop offering one
+        for _ in 0..50 {
+            let decision = s.quote(&ctx(Some(&sim.book), sim.position_bp));
+            sim.rest(decision);
+            if let Some(bid) = sim.book.best_bid() {
+                bids_seen.push(bid);
+            }
+            if !sim.hit_bid() {
+                break;
+            }
+        }
+
+        assert_eq!(sim.position_bp, 10 * SHARE);
+        assert!(bids_seen.windows(2).all(|w| w[1] <= w[0]), "bids fall as inventory grows");
+        assert!(sim.book.buy_orders.is_empty());
+
+        // Buyers lifting our ask bring the position back down
+        for _ in 0..5 {
+            sim.book.sell_orders.clear();
+            sim.rest(s.quote(&ctx(Some(&sim.book), sim.position_bp)));
+            assert!(sim.lift_ask());
+        }
+        assert_eq!(sim.position_bp, 5 * SHARE);
+    }
+
+    #[test]
+    fn healthy_book_needs_no_quotes() {
+        let mut sim = SimulatedBook::new();
+        sim.book.buy_orders.insert(1990, SHARE as u64);
+        sim.book.sell_orders.insert(2010, SHARE as u64);
+        assert!(matches!(
+            strategy().quote(&ctx(Some(&sim.book), 0)),
+            MarketMakerDecision::DoNothing
+        ));
+
+        let symmetric =
+            SymmetricStrategy { spread_bps: 400, max_spread_bps: 1200, quantity_bp: SHARE };
+        assert!(matches!(
+            symmetric.quote(&ctx(Some(&sim.book), 0)),
+            MarketMakerDecision::DoNothing
+        ));
+        assert_eq!(quotes(&symmetric.quote(&ctx(None, 0))), (Some(1920), Some(2080)));
+    }
+}
@@ -1,15 +1,23 @@
 //! Authentication module for the OrderGateway
 
+use crate::config::AuthConfig;
 use crate::error::GatewayError;
 use crate::messages::{AuthRequest, AuthResponse, RateLimits};
-use account_service::AccountService;
+use account_service::{AccountService, ApiKey, ApiKeyRepository};
+use hmac::{Hmac, Mac};
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{info, warn};
 
+/// Capacity of the invalidated key broadcast
+const INVALIDATIONS_CAPACITY: usize = 64;
+
 /// JWT claims structure
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JwtClaims {
@@ -48,130 +56,330 @@ pub struct SupabaseJwtClaims {
     pub email: Option<String>,
 }
 
-/// Simple in-memory API key store
-/// In production, this would connect to a proper authentication service
-#[derive(Debug, Clone)]
-pub struct ApiKeyStore {
-    /// Map of API key to user information
-    keys: HashMap<String, UserInfo>,
+/// Header carrying the public API key identifier on signed REST requests
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the request timestamp (unix seconds) on signed REST requests
+pub const API_TIMESTAMP_HEADER: &str = "x-api-timestamp";
+
+/// Header carrying the single-use nonce on signed REST requests
+pub const API_NONCE_HEADER: &str = "x-api-nonce";
+
+/// Header carrying the hex HMAC-SHA256 signature on signed REST requests
+pub const API_SIGNATURE_HEADER: &str = "x-api-signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Build the string that is signed for an HMAC login or request
+///
+/// Parts are joined with `\n`. A WebSocket login signs `[api_key, timestamp, nonce]`;
+/// a REST request additionally signs `[method, path_and_query, body]`.
+pub fn signing_payload(parts: &[&str]) -> String {
+    parts.join("\n")
 }
 
-/// User information stored in the API key store
+/// Sign a payload with an API secret, as a client would
+///
+/// The HMAC key is the secret itself; the server re-derives it rather than storing it.
+pub fn sign_payload(api_secret: &str, payload: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(api_secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Verify a hex signature against a key's secret
+fn verify_signature(api_secret: &str, payload: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac =
+        HmacSha256::new_from_slice(api_secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Check a peer address against an allowlist of IPs and CIDR blocks
+///
+/// An empty allowlist permits every address. Unparseable entries never match.
+pub fn ip_allowed(ip: IpAddr, allowlist: &[String]) -> bool {
+    if allowlist.is_empty() {
+        return true;
+    }
+
+    allowlist.iter().any(|entry| {
+        let (addr, prefix) = match entry.split_once('/') {
+            Some((addr, prefix)) => (addr, prefix.parse::<u32>().ok()),
+            None => (entry.as_str(), None),
+        };
+        let Ok(network) = addr.trim().parse::<IpAddr>() else {
+            return false;
+        };
+
+        match (ip, network) {
+            (IpAddr::V4(ip), IpAddr::V4(network)) => {
+                let prefix = prefix.unwrap_or(32).min(32);
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+                u32::from(ip) & mask == u32::from(network) & mask
+            }
+            (IpAddr::V6(ip), IpAddr::V6(network)) => {
+                let prefix = prefix.unwrap_or(128).min(128);
+                let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+                u128::from(ip) & mask == u128::from(network) & mask
+            }
+            (IpAddr::V6(ip), IpAddr::V4(_)) => match ip.to_ipv4_mapped() {
+                Some(ip) => ip_allowed(IpAddr::V4(ip), std::slice::from_ref(entry)),
+                None => false,
+            },
+            _ => false,
+        }
+    })
+}
+
+/// Identity established by a successful API key authentication
 #[derive(Debug, Clone)]
-pub struct UserInfo {
-    /// User ID
-    pub user_id: String,
+pub struct ApiKeyPrincipal {
+    /// Public key identifier
+    pub key_id: String,
 
-    /// API secret (for validation)
-    pub api_secret: String,
+    /// Account the key belongs to
+    pub account_id: i64,
 
-    /// User permissions
+    /// Permissions granted to the key
     pub permissions: Vec<String>,
 
-    /// Rate limits
+    /// Symbols the key may trade (None = any)
+    pub allowed_symbols: Option<Vec<u32>>,
+
+    /// Rate limits attached to the key
     pub rate_limits: RateLimits,
 }
 
-impl Default for ApiKeyStore {
-    fn default() -> Self {
-        Self::new()
+impl ApiKeyPrincipal {
+    /// Check if the key grants a permission
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+
+    /// Convert into a gateway session
+    pub fn into_session(self) -> crate::messages::UserSession {
+        crate::messages::UserSession::new(
+            self.account_id.to_string(),
+            self.account_id,
+            self.permissions,
+            self.rate_limits,
+        )
+        .with_api_key(self.key_id)
+        .with_allowed_symbols(self.allowed_symbols)
     }
 }
 
+impl From<ApiKey> for ApiKeyPrincipal {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            key_id: key.key_id,
+            account_id: key.account_id,
+            permissions: key.permissions,
+            allowed_symbols: key
+                .allowed_symbols
+                .map(|symbols| symbols.into_iter().map(|s| s as u32).collect()),
+            rate_limits: RateLimits {
+                orders_per_second: key.orders_per_second.max(0) as u32,
+                market_data_per_second: key.market_data_per_second.max(0) as u32,
+                burst_limit: key.burst_limit.max(0) as u32,
+            },
+        }
+    }
+}
+
+/// Database-backed API key store
+///
+/// Keys live in the `api_keys` table with only a salt and a salted verifier of the
+/// secret. Clients either send the secret (compared by verifier) or, preferably, an
+/// HMAC-SHA256 signature over a timestamp and single-use nonce, checked against the
+/// secret re-derived from the master key.
+pub struct ApiKeyStore {
+    /// Persistent key storage
+    repository: ApiKeyRepository,
+
+    /// Authentication settings
+    config: AuthConfig,
+
+    /// Recently seen nonces per key, with the timestamp they were signed at
+    seen_nonces: Mutex<HashMap<(String, String), i64>>,
+
+    /// IDs of keys revoked or rotated, for connections logged on with them
+    invalidations: broadcast::Sender<String>,
+}
+
 impl ApiKeyStore {
     /// Create a new API key store
-    pub fn new() -> Self {
-        let mut keys = HashMap::new();
-
-        // Add some test API keys for development
-        keys.insert(
-            "ak_test_1234567890abcdef".to_string(),
-            UserInfo {
-                user_id: "user123".to_string(),
-                api_secret: "sk_test_abcdef1234567890".to_string(),
-                permissions: vec!["trade".to_string(), "market_data".to_string()],
-                rate_limits: RateLimits {
-                    orders_per_second: 100,
-                    market_data_per_second: 1000,
-                    burst_limit: 10,
-                },
-            },
-        );
+    pub fn new(repository: ApiKeyRepository, config: AuthConfig) -> Self {
+        Self {
+            repository,
+            config,
+            seen_nonces: Mutex::new(HashMap::new()),
+            invalidations: broadcast::channel(INVALIDATIONS_CAPACITY).0,
+        }
+    }
 
-        keys.insert(
-            "ak_admin_abcdef1234567890".to_string(),
-            UserInfo {
-                user_id: "admin".to_string(),
-                api_secret: "sk_admin_1234567890abcdef".to_string(),
-                permissions: vec![
-                    "trade".to_string(),
-                    "market_data".to_string(),
-                    "admin".to_string(),
-                ],
-                rate_limits: RateLimits {
-                    orders_per_second: 1000,
-                    market_data_per_second: 10000,
-                    burst_limit: 100,
-                },
-            },
-        );
+    /// Subscribe to the IDs of keys revoked or rotated; connections logged on with one
+    /// of them must log out
+    pub fn subscribe_invalidations(&self) -> broadcast::Receiver<String> {
+        self.invalidations.subscribe()
+    }
 
-        // Add API key for account 7 (used in EVS integration tests)
-        keys.insert(
-            "ak_test_7_abcdef1234567890".to_string(),
-            UserInfo {
-                user_id: "user7".to_string(),
-                api_secret: "sk_test_7_1234567890abcdef".to_string(),
-                permissions: vec!["trade".to_string(), "market_data".to_string()],
-                rate_limits: RateLimits {
-                    orders_per_second: 100,
-                    market_data_per_second: 1000,
-                    burst_limit: 10,
-                },
-            },
-        );
+    /// Tell connections logged on with a key that it was revoked or rotated
+    pub fn invalidate(&self, key_id: &str) {
+        let _ = self.invalidations.send(key_id.to_string());
+    }
 
-        // Add API key for market maker bot (house account)
-        keys.insert(
-            "ak_market_maker_1234567890abcdef".to_string(),
-            UserInfo {
-                user_id: "market_maker_bot".to_string(),
-                api_secret: "sk_market_maker_abcdef1234567890".to_string(),
-                permissions: vec!["trade".to_string(), "market_data".to_string()],
-                rate_limits: RateLimits {
-                    orders_per_second: 1000, // High rate limit for market making
-                    market_data_per_second: 10000,
-                    burst_limit: 100,
-                },
-            },
-        );
+    /// Underlying key repository (used by the admin endpoints)
+    pub fn repository(&self) -> &ApiKeyRepository {
+        &self.repository
+    }
+
+    /// Authenticate a WebSocket login, either by secret or by signature
+    pub async fn authenticate(
+        &self,
+        request: &AuthRequest,
+        peer_ip: IpAddr,
+    ) -> Result<ApiKeyPrincipal, GatewayError> {
+        let key = self.load_usable_key(&request.api_key, peer_ip).await?;
+
+        match (&request.signature, &request.api_secret) {
+            (Some(signature), _) => {
+                let timestamp = request.timestamp.ok_or_else(|| {
+                    GatewayError::Authentication("Signed login requires a timestamp".to_string())
+                })?;
+                let nonce = request.nonce.as_deref().ok_or_else(|| {
+                    GatewayError::Authentication("Signed login requires a nonce".to_string())
+                })?;
+                let timestamp_str = timestamp.to_string();
+                let payload = signing_payload(&[&request.api_key, &timestamp_str, nonce]);
+                self.check_signature(&key, timestamp, nonce, &payload, signature)?;
+            }
+            (None, Some(secret)) => {
+                if !self.config.allow_secret_login {
+                    return Err(GatewayError::Authentication(
+                        "Secret login is disabled; sign the login with HMAC-SHA256".to_string(),
+                    ));
+                }
+                let presented = account_service::api_key::hash_secret(secret, &key.secret_salt);
+                if !constant_time_eq(presented.as_bytes(), key.secret_hash.as_bytes()) {
+                    return Err(GatewayError::Authentication("Invalid API secret".to_string()));
+                }
+            }
+            (None, None) => {
+                return Err(GatewayError::Authentication(
+                    "Missing api_secret or signature".to_string(),
+                ));
+            }
+        }
 
-        Self { keys }
+        self.record_use(&key.key_id).await;
+        Ok(key.into())
     }
 
-    /// Authenticate a user with API key and secret
-    #[allow(clippy::result_large_err)]
-    pub fn authenticate(&self, request: &AuthRequest) -> Result<AuthResponse, GatewayError> {
-        let user_info = self
-            .keys
-            .get(&request.api_key)
+    /// Authenticate a signed REST request
+    #[allow(clippy::too_many_arguments)]
+    pub async fn authenticate_request(
+        &self,
+        key_id: &str,
+        timestamp: i64,
+        nonce: &str,
+        signature: &str,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+        peer_ip: IpAddr,
+    ) -> Result<ApiKeyPrincipal, GatewayError> {
+        let key = self.load_usable_key(key_id, peer_ip).await?;
+
+        let timestamp_str = timestamp.to_string();
+        let body = String::from_utf8_lossy(body);
+        let payload =
+            signing_payload(&[key_id, &timestamp_str, nonce, method, path_and_query, &body]);
+        self.check_signature(&key, timestamp, nonce, &payload, signature)?;
+
+        self.record_use(&key.key_id).await;
+        Ok(key.into())
+    }
+
+    /// Fetch a key and check it is active and reachable from this address
+    async fn load_usable_key(&self, key_id: &str, peer_ip: IpAddr) -> Result<ApiKey, GatewayError> {
+        let key = self
+            .repository
+            .get(key_id)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to load API key: {e}")))?
             .ok_or_else(|| GatewayError::Authentication("Invalid API key".to_string()))?;
 
-        if user_info.api_secret != request.api_secret {
-            return Err(GatewayError::Authentication("Invalid API secret".to_string()));
+        if key.is_revoked() {
+            return Err(GatewayError::Authentication("API key has been revoked".to_string()));
+        }
+        if key.is_expired() {
+            return Err(GatewayError::Authentication("API key has expired".to_string()));
+        }
+        if !ip_allowed(peer_ip, &key.allowed_ips) {
+            warn!("API key {} used from disallowed address {}", key.key_id, peer_ip);
+            return Err(GatewayError::Authentication(format!(
+                "API key is not allowed from {peer_ip}"
+            )));
         }
 
-        Ok(AuthResponse {
-            authenticated: true,
-            user_id: Some(user_info.user_id.clone()),
-            permissions: user_info.permissions.clone(),
-            rate_limits: user_info.rate_limits.clone(),
-        })
+        Ok(key)
     }
 
-    /// Get user information by API key
-    pub fn get_user_info(&self, api_key: &str) -> Option<&UserInfo> {
-        self.keys.get(api_key)
+    /// Check timestamp freshness, nonce uniqueness and the HMAC itself
+    #[allow(clippy::result_large_err)]
+    fn check_signature(
+        &self,
+        key: &ApiKey,
+        timestamp: i64,
+        nonce: &str,
+        payload: &str,
+        signature: &str,
+    ) -> Result<(), GatewayError> {
+        let now = chrono::Utc::now().timestamp();
+        let max_skew = self.config.signature_max_skew_seconds as i64;
+        if (now - timestamp).abs() > max_skew {
+            return Err(GatewayError::Authentication(format!(
+                "Signature timestamp outside the allowed {max_skew}s window"
+            )));
+        }
+        if nonce.is_empty() {
+            return Err(GatewayError::Authentication("Nonce must not be empty".to_string()));
+        }
+
+        let secret = self
+            .repository
+            .signing_key(key)
+            .map_err(|e| GatewayError::System(format!("Failed to derive signing key: {e}")))?;
+        if !verify_signature(&secret, payload, signature) {
+            return Err(GatewayError::Authentication("Invalid signature".to_string()));
+        }
+
+        // Only remember nonces of valid signatures, so garbage can't fill the cache
+        let mut seen = self.seen_nonces.lock();
+        seen.retain(|_, signed_at| (now - *signed_at).abs() <= max_skew);
+        if seen.insert((key.key_id.clone(), nonce.to_string()), timestamp).is_some() {
+            return Err(GatewayError::Authentication("Nonce has already been used".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Update last-used bookkeeping without failing the login
+    async fn record_use(&self, key_id: &str) {
+        if let Err(e) = self.repository.touch(key_id).await {
+            warn!("Failed to record use of API key {}: {}", key_id, e);
+        }
     }
 }
 
@@ -188,13 +396,17 @@ pub struct AuthManager {
 
     /// Cached Supabase JWKS decoding keys (fetched at startup)
     supabase_jwks: Arc<RwLock<Vec<(Algorithm, DecodingKey)>>>,
+
+    /// Legacy JWT secret from the config, if set there
+    jwt_secret: Option<String>,
 }
 
 impl AuthManager {
     /// Create a new authentication manager
-    pub fn new(account_service: Arc<AccountService>) -> Self {
+    pub fn new(account_service: Arc<AccountService>, config: AuthConfig) -> Self {
         let manager = Self {
-            store: Arc::new(ApiKeyStore::new()),
+            jwt_secret: config.jwt_secret.clone(),
+            store: Arc::new(ApiKeyStore::new(account_service.api_keys(), config)),
             account_service,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             supabase_jwks: Arc::new(RwLock::new(Vec::new())),
@@ -259,38 +471,35 @@ impl AuthManager {
         Ok(())
     }
 
-    /// Authenticate a user and create a session
-    pub async fn authenticate(&self, request: &AuthRequest) -> Result<AuthResponse, GatewayError> {
-        let response = self.store.authenticate(request)?;
-
-        if response.authenticated {
-            let user_id = response.user_id.clone().unwrap_or_default();
+    /// Authenticate an API key login and create a session
+    pub async fn authenticate(
+        &self,
+        request: &AuthRequest,
+        peer_ip: IpAddr,
+    ) -> Result<AuthResponse, GatewayError> {
+        let principal = self.store.authenticate(request, peer_ip).await?;
+        info!("API key {} authenticated for account {}", principal.key_id, principal.account_id);
 
-            // Look up account ID for this user
-            let account_id =
-                self.account_service.get_account_id_by_user_id(&user_id).await.map_err(|e| {
-                    GatewayError::Authentication(format!(
-                        "Failed to find account for user {}: {}",
-                        user_id, e
-                    ))
-                })?;
+        let session = principal.into_session();
+        let response = AuthResponse {
+            authenticated: true,
+            user_id: Some(session.user_id.clone()),
+            permissions: session.permissions.clone(),
+            rate_limits: session.rate_limits.clone(),
+        };
 
-            // Create session with account ID
-            let session = crate::messages::UserSession::new(
-                user_id,
-                account_id,
-                response.permissions.clone(),
-                response.rate_limits.clone(),
-            );
-
-            // Store session
-            let mut sessions = self.sessions.write().await;
-            sessions.insert(request.api_key.clone(), session);
-        }
+        // Store session
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(request.api_key.clone(), session);
 
         Ok(response)
     }
 
+    /// Get the API key store
+    pub fn api_key_store(&self) -> Arc<ApiKeyStore> {
+        self.store.clone()
+    }
+
     /// Get user session by API key
     pub async fn get_session(
         &self,
@@ -327,6 +536,14 @@ impl AuthManager {
         sessions.remove(api_key);
     }
 
+    /// End everything opened with a revoked or rotated key: its session is removed and
+    /// WebSocket and FIX connections logged on with it are told to log out
+    pub async fn end_api_key_sessions(&self, key_id: &str) {
+        self.remove_session(key_id).await;
+        self.store.invalidate(key_id);
+        info!("Ended sessions of API key {}", key_id);
+    }
+
     /// Clean up expired sessions
     pub async fn cleanup_expired_sessions(&self, max_age_seconds: u64) {
         let mut sessions = self.sessions.write().await;
@@ -346,8 +563,12 @@ impl AuthManager {
         }
 
         // Legacy JWT validation
-        let jwt_secret = std::env::var("JWT_SECRET")
-            .map_err(|_| GatewayError::Authentication("JWT_SECRET not configured".to_string()))?;
+        let jwt_secret = match &self.jwt_secret {
+            Some(secret) => secret.clone(),
+            None => std::env::var("JWT_SECRET").map_err(|_| {
+                GatewayError::Authentication("JWT_SECRET not configured".to_string())
+            })?,
+        };
 
         let mut validation = Validation::new(Algorithm::HS256);
         validation.set_audience(&["waiver-exchange-api"]);
@@ -519,3 +740,117 @@ impl AuthManager {
         Ok(session.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_service::api_key::{derive_secret, hash_secret};
+
+    const MASTER_KEY: &[u8] = b"test-master-key";
+
+    fn store() -> ApiKeyStore {
+        let pool = sqlx::PgPool::connect_lazy("postgresql://localhost/unused").unwrap();
+        ApiKeyStore::new(ApiKeyRepository::with_master_key(pool, MASTER_KEY), AuthConfig::default())
+    }
+
+    fn key() -> ApiKey {
+        let now = chrono::Utc::now();
+        ApiKey {
+            id: 1,
+            key_id: "ak_test".to_string(),
+            account_id: 7,
+            label: String::new(),
+            secret_hash: String::new(),
+            secret_salt: "salt".to_string(),
+            permissions: vec!["trade".to_string()],
+            allowed_ips: vec![],
+            allowed_symbols: None,
+            orders_per_second: 100,
+            market_data_per_second: 1000,
+            burst_limit: 10,
+            created_at: now,
+            rotated_at: None,
+            last_used_at: None,
+            expires_at: None,
+            revoked_at: None,
+        }
+    }
+
+    /// Sign a login as a client holding the key's secret would
+    fn signed_login(key: &ApiKey, timestamp: i64, nonce: &str) -> (String, String) {
+        let secret = derive_secret(MASTER_KEY, &key.secret_salt, &key.key_id);
+        let payload = signing_payload(&[&key.key_id, &timestamp.to_string(), nonce]);
+        let signature = sign_payload(&secret, &payload);
+        (payload, signature)
+    }
+
+    #[test]
+    fn test_ip_allowlist() {
+        let v4 = |s: &str| s.parse::<IpAddr>().unwrap();
+        let allow = |entries: &[&str]| entries.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+
+        assert!(ip_allowed(v4("203.0.113.9"), &[]));
+        assert!(ip_allowed(v4("10.1.2.3"), &allow(&["10.0.0.0/8"])));
+        assert!(!ip_allowed(v4("11.1.2.3"), &allow(&["10.0.0.0/8"])));
+        assert!(ip_allowed(v4("192.168.1.5"), &allow(&["10.0.0.0/8", "192.168.1.5"])));
+        assert!(!ip_allowed(v4("192.168.1.6"), &allow(&["192.168.1.5"])));
+        assert!(ip_allowed(v4("2001:db8::1"), &allow(&["2001:db8::/32"])));
+        assert!(!ip_allowed(v4("2001:db9::1"), &allow(&["2001:db8::/32"])));
+        // IPv4 peers seen through a dual-stack socket
+        assert!(ip_allowed(v4("::ffff:10.0.0.1"), &allow(&["10.0.0.0/8"])));
+        // Garbage never matches, and never opens the list up
+        assert!(!ip_allowed(v4("10.0.0.1"), &allow(&["not-an-ip", "10.0.0.0/abc/8"])));
+    }
+
+    #[tokio::test]
+    async fn test_invalidated_keys_reach_subscribers() {
+        let store = store();
+        let mut invalidations = store.subscribe_invalidations();
+
+        store.invalidate("ak_test");
+
+        assert_eq!(invalidations.try_recv().unwrap(), "ak_test");
+        assert!(invalidations.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signature_accepted_once_per_nonce() {
+        let store = store();
+        let key = key();
+        let now = chrono::Utc::now().timestamp();
+
+        let (payload, signature) = signed_login(&key, now, "n-1");
+        assert!(store.check_signature(&key, now, "n-1", &payload, &signature).is_ok());
+        // Replaying the same signed login is rejected
+        assert!(store.check_signature(&key, now, "n-1", &payload, &signature).is_err());
+
+        let (payload, signature) = signed_login(&key, now, "n-2");
+        assert!(store.check_signature(&key, now, "n-2", &payload, &signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_signature_rejects_skewed_timestamp_and_wrong_key() {
+        let store = store();
+        let key = key();
+        let max_skew = AuthConfig::default().signature_max_skew_seconds as i64;
+        let now = chrono::Utc::now().timestamp();
+
+        for timestamp in [now - max_skew - 5, now + max_skew + 5] {
+            let (payload, signature) = signed_login(&key, timestamp, "n");
+            assert!(store.check_signature(&key, timestamp, "n", &payload, &signature).is_err());
+        }
+
+        // A signature keyed with what the table stores does not verify
+        let payload = signing_payload(&[&key.key_id, &now.to_string(), "n"]);
+        let forged = sign_payload(&hash_of_stored(&key), &payload);
+        assert!(store.check_signature(&key, now, "n", &payload, &forged).is_err());
+        // An empty nonce is rejected even with a valid signature
+        let (payload, signature) = signed_login(&key, now, "");
+        assert!(store.check_signature(&key, now, "", &payload, &signature).is_err());
+    }
+
+    fn hash_of_stored(key: &ApiKey) -> String {
+        let secret = derive_secret(MASTER_KEY, &key.secret_salt, &key.key_id);
+        hash_secret(&secret, &key.secret_salt)
+    }
+}
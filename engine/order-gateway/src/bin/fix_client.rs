@@ -0,0 +1,276 @@
+//! FIX 4.4 client harness for testing the OrderGateway's FIX acceptor locally
+//!
+//! Logs on with an API key, prints every message it receives and reads commands from
+//! stdin:
+//!
+//! ```text
+//! buy <symbol> <qty> <price|market> [clordid]
+//! sell <symbol> <qty> <price|market> [clordid]
+//! cancel <orig_clordid>
+//! replace <orig_clordid> <qty> <price>
+//! test                      send a TestRequest
+//! resend <begin> [end]      ask the acceptor to resend messages
+//! skip <n>                  skip n outbound sequence numbers (forces a gap)
+//! logout
+//! ```
+//!
+//! Configuration comes from the environment: `FIX_ADDR` (default `127.0.0.1:9878`),
+//! `FIX_API_KEY`, `FIX_API_SECRET`, `FIX_SENDER_COMP_ID` (default `CLIENT`),
+//! `FIX_TARGET_COMP_ID` (default `WAIVEREX`), `FIX_HEARTBEAT` (default 30),
+//! `FIX_DROP_COPY=1` for a drop copy session, `FIX_SIGNED=1` to sign the Logon instead
+//! of sending the secret, and `FIX_RESET=1` to reset sequence numbers on Logon.
+
+use order_gateway::auth::{sign_payload, signing_payload};
+use order_gateway::fix::codec::{self, msg_type, tags, FixMessage};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+
+/// Outbound side of the harness session
+struct Client {
+    writer: OwnedWriteHalf,
+    sender_comp_id: String,
+    target_comp_id: String,
+    next_seq: u64,
+    /// ClOrdID -> (symbol, side) for cancels and replaces
+    orders: HashMap<String, (String, String)>,
+    next_cl_ord_id: u64,
+}
+
+impl Client {
+    async fn send(&mut self, mut message: FixMessage) -> std::io::Result<()> {
+        message.set(tags::MSG_SEQ_NUM, self.next_seq);
+        message.set(tags::SENDER_COMP_ID, &self.sender_comp_id);
+        message.set(tags::TARGET_COMP_ID, &self.target_comp_id);
+        if message.get(tags::SENDING_TIME).is_none() {
+            message.set(tags::SENDING_TIME, codec::utc_timestamp(chrono::Utc::now()));
+        }
+        self.next_seq += 1;
+
+        println!(">> {}", display(&message));
+        self.writer.write_all(&message.encode()).await
+    }
+
+    fn cl_ord_id(&mut self, requested: Option<&str>) -> String {
+        match requested {
+            Some(id) => id.to_string(),
+            None => {
+                self.next_cl_ord_id += 1;
+                format!("fix-{}-{}", chrono::Utc::now().timestamp(), self.next_cl_ord_id)
+            }
+        }
+    }
+
+    /// Handle one command line; returns false to quit
+    async fn command(&mut self, line: &str) -> std::io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [side @ ("buy" | "sell"), symbol, qty, price, rest @ ..] => {
+                let cl_ord_id = self.cl_ord_id(rest.first().copied());
+                let side = if *side == "buy" { "1" } else { "2" };
+                let mut order = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+                    .with(tags::CL_ORD_ID, &cl_ord_id)
+                    .with(tags::SYMBOL, symbol)
+                    .with(tags::SIDE, side)
+                    .with(tags::ORDER_QTY, qty)
+                    .with(tags::TRANSACT_TIME, codec::utc_timestamp(chrono::Utc::now()));
+                if *price == "market" {
+                    order.set(tags::ORD_TYPE, "1");
+                } else {
+                    order.set(tags::ORD_TYPE, "2");
+                    order.set(tags::PRICE, price);
+                }
+                self.orders.insert(cl_ord_id, (symbol.to_string(), side.to_string()));
+                self.send(order).await?;
+            }
+            ["cancel", orig_cl_ord_id] => {
+                let cl_ord_id = self.cl_ord_id(None);
+                let (symbol, side) = self.orders.get(*orig_cl_ord_id).cloned().unwrap_or_default();
+                let cancel = FixMessage::new(msg_type::ORDER_CANCEL_REQUEST)
+                    .with(tags::CL_ORD_ID, cl_ord_id)
+                    .with(tags::ORIG_CL_ORD_ID, orig_cl_ord_id)
+                    .with(tags::SYMBOL, symbol)
+                    .with(tags::SIDE, side)
+                    .with(tags::TRANSACT_TIME, codec::utc_timestamp(chrono::Utc::now()));
+                self.send(cancel).await?;
+            }
+            ["replace", orig_cl_ord_id, qty, price] => {
+                let cl_ord_id = self.cl_ord_id(None);
+                let (symbol, side) = self.orders.get(*orig_cl_ord_id).cloned().unwrap_or_default();
+                let replace = FixMessage::new(msg_type::ORDER_CANCEL_REPLACE_REQUEST)
+                    .with(tags::CL_ORD_ID, &cl_ord_id)
+                    .with(tags::ORIG_CL_ORD_ID, orig_cl_ord_id)
+                    .with(tags::SYMBOL, &symbol)
+                    .with(tags::SIDE, &side)
+                    .with(tags::ORD_TYPE, "2")
+                    .with(tags::ORDER_QTY, qty)
+                    .with(tags::PRICE, price)
+                    .with(tags::TRANSACT_TIME, codec::utc_timestamp(chrono::Utc::now()));
+                self.orders.insert(cl_ord_id, (symbol, side));
+                self.send(replace).await?;
+            }
+            ["test"] => {
+                let test_req_id = chrono::Utc::now().timestamp_millis();
+                self.send(
+                    FixMessage::new(msg_type::TEST_REQUEST).with(tags::TEST_REQ_ID, test_req_id),
+                )
+                .await?;
+            }
+            ["resend", begin, rest @ ..] => {
+                let request = FixMessage::new(msg_type::RESEND_REQUEST)
+                    .with(tags::BEGIN_SEQ_NO, begin)
+                    .with(tags::END_SEQ_NO, rest.first().copied().unwrap_or("0"));
+                self.send(request).await?;
+            }
+            ["skip", n] => {
+                self.next_seq += n.parse::<u64>().unwrap_or(1);
+                println!("-- next outbound MsgSeqNum is {}", self.next_seq);
+            }
+            ["logout"] => {
+                self.send(FixMessage::new(msg_type::LOGOUT)).await?;
+                return Ok(false);
+            }
+            [] => {}
+            _ => println!("-- unknown command: {line}"),
+        }
+        Ok(true)
+    }
+
+    /// Answer session-level messages from the acceptor
+    async fn respond(&mut self, message: &FixMessage) -> std::io::Result<()> {
+        match message.msg_type() {
+            msg_type::TEST_REQUEST => {
+                let mut heartbeat = FixMessage::new(msg_type::HEARTBEAT);
+                if let Some(test_req_id) = message.get(tags::TEST_REQ_ID) {
+                    heartbeat.set(tags::TEST_REQ_ID, test_req_id);
+                }
+                self.send(heartbeat).await
+            }
+            msg_type::RESEND_REQUEST => {
+                // The harness keeps no message store, so everything is gap-filled
+                let begin = message.get_u64(tags::BEGIN_SEQ_NO).unwrap_or(1);
+                let mut gap_fill = FixMessage::new(msg_type::SEQUENCE_RESET)
+                    .with(tags::POSS_DUP_FLAG, "Y")
+                    .with(tags::GAP_FILL_FLAG, "Y")
+                    .with(tags::NEW_SEQ_NO, self.next_seq);
+                gap_fill.set(tags::MSG_SEQ_NUM, begin);
+                gap_fill.set(tags::SENDER_COMP_ID, &self.sender_comp_id);
+                gap_fill.set(tags::TARGET_COMP_ID, &self.target_comp_id);
+                gap_fill.set(tags::SENDING_TIME, codec::utc_timestamp(chrono::Utc::now()));
+                println!(">> {}", display(&gap_fill));
+                self.writer.write_all(&gap_fill.encode()).await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Human-readable form of a message
+fn display(message: &FixMessage) -> String {
+    let fields: Vec<String> = message.fields().iter().map(|(t, v)| format!("{t}={v}")).collect();
+    fields.join("|")
+}
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+
+    let addr = env_or("FIX_ADDR", "127.0.0.1:9878");
+    let api_key = std::env::var("FIX_API_KEY").map_err(|_| "FIX_API_KEY must be set")?;
+    let api_secret = std::env::var("FIX_API_SECRET").map_err(|_| "FIX_API_SECRET must be set")?;
+    let heartbeat: u64 = env_or("FIX_HEARTBEAT", "30").parse()?;
+
+    let stream = TcpStream::connect(&addr).await?;
+    stream.set_nodelay(true)?;
+    let (mut reader, writer) = stream.into_split();
+    println!("-- connected to {addr}");
+
+    let mut client = Client {
+        writer,
+        sender_comp_id: env_or("FIX_SENDER_COMP_ID", "CLIENT"),
+        target_comp_id: env_or("FIX_TARGET_COMP_ID", "WAIVEREX"),
+        next_seq: 1,
+        orders: HashMap::new(),
+        next_cl_ord_id: 0,
+    };
+
+    let logon_time = chrono::Utc::now();
+    let sending_time = codec::utc_timestamp(logon_time);
+    let mut logon = FixMessage::new(msg_type::LOGON)
+        .with(tags::SENDING_TIME, &sending_time)
+        .with(tags::ENCRYPT_METHOD, 0)
+        .with(tags::HEART_BT_INT, heartbeat)
+        .with(tags::USERNAME, &api_key);
+    if env_flag("FIX_SIGNED") {
+        // The acceptor takes the signed timestamp from SendingTime
+        let timestamp = logon_time.timestamp().to_string();
+        let payload = signing_payload(&[&api_key, &timestamp, &sending_time]);
+        logon.set(tags::RAW_DATA, sign_payload(&api_secret, &payload));
+    } else {
+        logon.set(tags::PASSWORD, &api_secret);
+    }
+    if env_flag("FIX_DROP_COPY") {
+        logon.set(tags::TARGET_SUB_ID, "DROPCOPY");
+    }
+    if env_flag("FIX_RESET") {
+        logon.set(tags::RESET_SEQ_NUM_FLAG, "Y");
+    }
+    client.send(logon).await?;
+
+    let mut stdin = BufReader::new(tokio::io::stdin()).lines();
+    let mut heartbeat_timer = tokio::time::interval(Duration::from_secs(heartbeat));
+    heartbeat_timer.tick().await;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        tokio::select! {
+            read = reader.read(&mut chunk) => {
+                let n = read?;
+                if n == 0 {
+                    println!("-- connection closed by acceptor");
+                    return Ok(());
+                }
+                buffer.extend_from_slice(&chunk[..n]);
+                while let Some(frame) = codec::take_frame(&mut buffer)? {
+                    match FixMessage::decode(&frame) {
+                        Ok(message) => {
+                            println!("<< {}", display(&message));
+                            client.respond(&message).await?;
+                        }
+                        Err(e) => println!("-- bad message from acceptor: {e}"),
+                    }
+                }
+            }
+            line = stdin.next_line() => match line? {
+                Some(line) => {
+                    if !client.command(line.trim()).await? {
+                        // Wait briefly for the Logout acknowledgement
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                        return Ok(());
+                    }
+                }
+                None => {
+                    // stdin closed (e.g. piped commands); keep the session open for reports
+                    tokio::time::sleep(Duration::from_secs(heartbeat)).await;
+                    client.send(FixMessage::new(msg_type::LOGOUT)).await?;
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    return Ok(());
+                }
+            },
+            _ = heartbeat_timer.tick() => {
+                client.send(FixMessage::new(msg_type::HEARTBEAT)).await?;
+            }
+        }
+    }
+}
@@ -2,7 +2,10 @@
 
 use chrono::{Duration, Timelike, Utc};
 use num_traits::FromPrimitive;
+use account_service::ApiKeyRepository;
+use order_gateway::auth::ApiKeyStore;
 use order_gateway::cache::create_cache_manager;
+use order_gateway::config::AuthConfig;
 use order_gateway::rest_api;
 use persistence::config::SnapshotConfig;
 use persistence::snapshot::SnapshotManager;
@@ -62,11 +65,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cache = Arc::new(cache);
     info!("Created cache manager");
 
+    // Create API key store for signed requests and key administration
+    let api_keys = Arc::new(ApiKeyStore::new(
+        ApiKeyRepository::new((*db_pool).clone()),
+        AuthConfig::default(),
+    ));
+
     // Start daily equity snapshot scheduler
     start_daily_equity_scheduler(db_pool.clone());
 
     // Create routes
-    let routes = rest_api::create_routes(registry, db_pool, snapshot_manager, cache);
+    let routes = rest_api::create_routes(registry, db_pool, snapshot_manager, cache, api_keys);
 
     // Start server
     let port = 8083;
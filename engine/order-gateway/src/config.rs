@@ -17,6 +17,14 @@ pub struct GatewayConfig {
 
     /// Market data configuration
     pub market_data: MarketDataConfig,
+
+    /// Order entry configuration
+    #[serde(default)]
+    pub orders: OrderEntryConfig,
+
+    /// FIX acceptor configuration
+    #[serde(default)]
+    pub fix: FixConfig,
 }
 
 /// Server configuration
@@ -36,6 +44,14 @@ pub struct ServerConfig {
 
     /// Connection timeout in seconds
     pub connection_timeout: u64,
+
+    /// Port for the REST order entry endpoints (None disables them)
+    #[serde(default = "default_rest_port")]
+    pub rest_port: Option<u16>,
+}
+
+fn default_rest_port() -> Option<u16> {
+    Some(8084)
 }
 
 /// Rate limiting configuration
@@ -65,6 +81,55 @@ pub struct AuthConfig {
 
     /// Default permissions for authenticated users
     pub default_permissions: Vec<String>,
+
+    /// Accept logins that send the raw API secret (signed logins are always accepted)
+    #[serde(default = "default_allow_secret_login")]
+    pub allow_secret_login: bool,
+
+    /// Maximum clock skew for signed requests in seconds (also the nonce retention window)
+    #[serde(default = "default_signature_max_skew_seconds")]
+    pub signature_max_skew_seconds: u64,
+
+    /// Secret for legacy JWTs; the `JWT_SECRET` environment variable when unset
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+}
+
+fn default_allow_secret_login() -> bool {
+    true
+}
+
+fn default_signature_max_skew_seconds() -> u64 {
+    30
+}
+
+/// Order entry configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderEntryConfig {
+    /// How long a `client_order_id` is remembered for duplicate detection, in seconds
+    pub client_order_id_window_seconds: u64,
+}
+
+/// FIX 4.4 acceptor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixConfig {
+    /// Port for the FIX acceptor (None disables it)
+    pub port: Option<u16>,
+
+    /// CompID of the acceptor (the clients' TargetCompID)
+    pub comp_id: String,
+
+    /// TargetSubID that selects a read-only drop copy session
+    pub drop_copy_sub_id: String,
+
+    /// Seconds a new connection has to send its Logon
+    pub logon_timeout_seconds: u64,
+
+    /// Largest HeartBtInt a client may request, in seconds
+    pub max_heartbeat_interval_seconds: u64,
+
+    /// Sent application messages kept per session for ResendRequests
+    pub resend_buffer_size: usize,
 }
 
 /// Market data configuration
@@ -78,6 +143,14 @@ pub struct MarketDataConfig {
 
     /// Market data compression
     pub compression: bool,
+
+    /// Minimum milliseconds between `equity` stream updates for one account
+    #[serde(default = "default_equity_update_interval_ms")]
+    pub equity_update_interval_ms: u64,
+}
+
+fn default_equity_update_interval_ms() -> u64 {
+    250
 }
 
 impl Default for ServerConfig {
@@ -88,6 +161,7 @@ impl Default for ServerConfig {
             max_connections: 10000,
             heartbeat_interval: 30,
             connection_timeout: 60,
+            rest_port: default_rest_port(),
         }
     }
 }
@@ -109,13 +183,40 @@ impl Default for AuthConfig {
             api_key_validation: true,
             validation_endpoint: None,
             default_permissions: vec!["trade".to_string(), "market_data".to_string()],
+            allow_secret_login: default_allow_secret_login(),
+            signature_max_skew_seconds: default_signature_max_skew_seconds(),
+            jwt_secret: None,
+        }
+    }
+}
+
+impl Default for OrderEntryConfig {
+    fn default() -> Self {
+        Self { client_order_id_window_seconds: 86400 }
+    }
+}
+
+impl Default for FixConfig {
+    fn default() -> Self {
+        Self {
+            port: Some(9878),
+            comp_id: "WAIVEREX".to_string(),
+            drop_copy_sub_id: "DROPCOPY".to_string(),
+            logon_timeout_seconds: 10,
+            max_heartbeat_interval_seconds: 300,
+            resend_buffer_size: 10_000,
         }
     }
 }
 
 impl Default for MarketDataConfig {
     fn default() -> Self {
-        Self { enabled: true, max_updates_per_second: 1000, compression: false }
+        Self {
+            enabled: true,
+            max_updates_per_second: 1000,
+            compression: false,
+            equity_update_interval_ms: default_equity_update_interval_ms(),
+        }
     }
 }
 
@@ -0,0 +1,276 @@
+//! Cancel-on-disconnect bookkeeping for the OrderGateway
+//!
+//! When a session with cancel-on-disconnect enabled drops, its handler routes cancels
+//! for the session's resting orders and leaves a [`MassCancelReport`] here. The report
+//! is handed to the account's next authenticated connection. Reports are kept in memory,
+//! at most [`MAX_REPORTS_PER_ACCOUNT`] per account for up to [`REPORT_TTL`]; the gateway's
+//! cleanup task prunes expired ones for accounts that never log in again.
+//!
+//! [`RestingOrders`] tracks which of a session's orders can still be on the book, so the
+//! mass cancel only targets those.
+
+use crate::messages::MassCancelReport;
+use execution_manager::TradeEvent;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Reports kept per account; the oldest are dropped beyond this
+pub const MAX_REPORTS_PER_ACCOUNT: usize = 32;
+
+/// How long a report waits for the account's next login
+pub const REPORT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Pending mass-cancel reports, keyed by account ID
+#[derive(Debug)]
+pub struct DisconnectReports {
+    reports: RwLock<HashMap<i64, VecDeque<(Instant, MassCancelReport)>>>,
+    max_per_account: usize,
+    ttl: Duration,
+}
+
+impl Default for DisconnectReports {
+    fn default() -> Self {
+        Self::with_limits(MAX_REPORTS_PER_ACCOUNT, REPORT_TTL)
+    }
+}
+
+impl DisconnectReports {
+    /// Create an empty report store with the default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty report store keeping up to `max_per_account` reports per account
+    /// for `ttl` each
+    pub fn with_limits(max_per_account: usize, ttl: Duration) -> Self {
+        Self { reports: RwLock::new(HashMap::new()), max_per_account, ttl }
+    }
+
+    /// Record the outcome of a mass cancel, dropping the account's oldest report if it
+    /// already has `max_per_account`
+    pub async fn record(&self, report: MassCancelReport) {
+        let mut reports = self.reports.write().await;
+        let pending = reports.entry(report.account_id).or_default();
+        pending.push_back((Instant::now(), report));
+        while pending.len() > self.max_per_account {
+            pending.pop_front();
+        }
+    }
+
+    /// Take all unexpired pending reports for an account
+    pub async fn take(&self, account_id: i64) -> Vec<MassCancelReport> {
+        let mut reports = self.reports.write().await;
+        reports
+            .remove(&account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(recorded_at, _)| recorded_at.elapsed() < self.ttl)
+            .map(|(_, report)| report)
+            .collect()
+    }
+
+    /// Drop expired reports; returns how many were dropped
+    pub async fn prune(&self) -> usize {
+        let mut reports = self.reports.write().await;
+        let mut dropped = 0;
+        reports.retain(|_, pending| {
+            let before = pending.len();
+            pending.retain(|(recorded_at, _)| recorded_at.elapsed() < self.ttl);
+            dropped += before - pending.len();
+            !pending.is_empty()
+        });
+        dropped
+    }
+}
+
+/// An order placed on a connection that can still be on the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RestingOrder {
+    symbol_id: u32,
+    open_quantity: u64,
+}
+
+/// Orders placed on one connection that can still rest on the book
+///
+/// Fills reduce an order's open quantity and remove it once it is fully filled;
+/// engine-confirmed cancels remove it outright.
+#[derive(Debug, Default)]
+pub struct RestingOrders {
+    orders: HashMap<u64, RestingOrder>,
+}
+
+impl RestingOrders {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a newly routed order
+    pub fn insert(&mut self, order_id: u64, symbol_id: u32, quantity: u64) {
+        self.orders.insert(order_id, RestingOrder { symbol_id, open_quantity: quantity });
+    }
+
+    /// Apply a trade to the sides of it that belong to `account_id`
+    pub fn on_trade(&mut self, trade: &TradeEvent, account_id: i64) {
+        let sides = [
+            (trade.maker_order_id, trade.maker_account_id),
+            (trade.taker_order_id, trade.taker_account_id),
+        ];
+        for (order_id, owner) in sides {
+            if owner != account_id {
+                continue;
+            }
+            if let Entry::Occupied(mut entry) = self.orders.entry(order_id) {
+                let order = entry.get_mut();
+                order.open_quantity = order.open_quantity.saturating_sub(trade.quantity);
+                if order.open_quantity == 0 {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
+    /// Stop tracking a cancelled order; returns whether it was tracked
+    pub fn remove(&mut self, order_id: u64) -> bool {
+        self.orders.remove(&order_id).is_some()
+    }
+
+    /// Number of tracked orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Whether no orders are tracked
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Take every tracked order as `(order ID, symbol ID)` pairs
+    pub fn drain(&mut self) -> Vec<(u64, u32)> {
+        self.orders.drain().map(|(order_id, order)| (order_id, order.symbol_id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    const ACCOUNT: i64 = 7;
+    const OTHER: i64 = 9;
+
+    fn trade(maker: (u64, i64), taker: (u64, i64), quantity: u64) -> TradeEvent {
+        TradeEvent {
+            symbol: 1,
+            price: 100,
+            quantity,
+            aggressor_side: whistle::Side::Buy,
+            logical_timestamp: 1,
+            wall_clock_timestamp: Instant::now(),
+            execution_id: 1,
+            maker_order_id: maker.0,
+            taker_order_id: taker.0,
+            maker_account_id: maker.1,
+            taker_account_id: taker.1,
+        }
+    }
+
+    #[test]
+    fn test_fills_prune_only_fully_filled_orders() {
+        let mut resting = RestingOrders::new();
+        resting.insert(1, 10, 5);
+        resting.insert(2, 10, 5);
+
+        resting.on_trade(&trade((1, ACCOUNT), (50, OTHER), 5), ACCOUNT);
+        resting.on_trade(&trade((2, ACCOUNT), (51, OTHER), 3), ACCOUNT);
+
+        assert_eq!(resting.drain(), vec![(2, 10)]);
+    }
+
+    #[test]
+    fn test_trades_of_other_accounts_are_ignored() {
+        let mut resting = RestingOrders::new();
+        resting.insert(1, 10, 5);
+
+        // Same order ID reported for another account's side
+        resting.on_trade(&trade((1, OTHER), (50, OTHER), 5), ACCOUNT);
+
+        assert_eq!(resting.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_on_disconnect_targets_only_open_orders() {
+        let mut resting = RestingOrders::new();
+        resting.insert(1, 10, 5); // fills completely
+        resting.insert(2, 10, 5); // cancelled by the client
+        resting.insert(3, 11, 5); // partly filled, still resting
+        resting.insert(4, 12, 5); // untouched
+
+        resting.on_trade(&trade((60, OTHER), (1, ACCOUNT), 5), ACCOUNT);
+        assert!(resting.remove(2));
+        resting.on_trade(&trade((3, ACCOUNT), (61, OTHER), 2), ACCOUNT);
+
+        let mut targeted = resting.drain();
+        targeted.sort_unstable();
+        assert_eq!(targeted, vec![(3, 11), (4, 12)]);
+        assert!(resting.is_empty());
+
+        let reports = DisconnectReports::new();
+        reports
+            .record(MassCancelReport {
+                account_id: ACCOUNT,
+                reason: "connection closed".to_string(),
+                orders_targeted: targeted.len(),
+                cancels_routed: targeted.len(),
+                failed_order_ids: vec![],
+                disconnected_at: chrono::Utc::now().to_rfc3339(),
+            })
+            .await;
+
+        let delivered = reports.take(ACCOUNT).await;
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].orders_targeted, 2);
+        assert!(reports.take(ACCOUNT).await.is_empty());
+    }
+
+    fn report(account_id: i64, orders_targeted: usize) -> MassCancelReport {
+        MassCancelReport {
+            account_id,
+            reason: "connection closed".to_string(),
+            orders_targeted,
+            cancels_routed: orders_targeted,
+            failed_order_ids: vec![],
+            disconnected_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_capped_per_account() {
+        let reports = DisconnectReports::with_limits(2, REPORT_TTL);
+        for orders_targeted in 1..=3 {
+            reports.record(report(ACCOUNT, orders_targeted)).await;
+        }
+        reports.record(report(OTHER, 9)).await;
+
+        let delivered: Vec<usize> =
+            reports.take(ACCOUNT).await.iter().map(|r| r.orders_targeted).collect();
+        assert_eq!(delivered, vec![2, 3]);
+        assert_eq!(reports.take(OTHER).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_reports_are_pruned() {
+        let reports = DisconnectReports::with_limits(MAX_REPORTS_PER_ACCOUNT, Duration::ZERO);
+        reports.record(report(ACCOUNT, 1)).await;
+        reports.record(report(OTHER, 1)).await;
+
+        assert_eq!(reports.prune().await, 2);
+        assert!(reports.reports.read().await.is_empty());
+
+        // Expired reports are not delivered either
+        reports.record(report(ACCOUNT, 1)).await;
+        assert!(reports.take(ACCOUNT).await.is_empty());
+    }
+}
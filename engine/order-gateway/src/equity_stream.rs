@@ -0,0 +1,190 @@
+//! Per-account throttling for the `equity` stream
+//!
+//! EquityValuationService publishes an update for every account it revalues at the end of a
+//! tick. An account is sent at most one update per interval; updates arriving sooner replace
+//! each other and the latest is sent once the interval has passed, so clients always end up
+//! with the account's current equity.
+//!
+//! Only accounts with subscribers are throttled; their state is dropped once the last
+//! subscriber unsubscribes or disconnects (see [`EquityThrottle::retain`]).
+
+use crate::messages::EquityUpdate;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+impl From<equity_service::EquityUpdate> for EquityUpdate {
+    fn from(update: equity_service::EquityUpdate) -> Self {
+        Self {
+            account_id: update.account_id,
+            total_equity: update.total_equity,
+            cash_balance: update.cash_balance,
+            position_value: update.position_value,
+            unrealized_pnl: update.unrealized_pnl,
+            realized_pnl: update.realized_pnl,
+            day_change: update.day_change,
+            day_change_percent: update.day_change_percent,
+            tick: update.tick,
+            timestamp: update.timestamp.max(0) as u64,
+        }
+    }
+}
+
+/// Holds back equity updates sent sooner than the interval after an account's last one
+#[derive(Debug)]
+pub struct EquityThrottle {
+    interval: Duration,
+    last_sent: HashMap<i64, Instant>,
+    pending: HashMap<i64, EquityUpdate>,
+}
+
+impl EquityThrottle {
+    /// Create a throttle sending each account at most once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last_sent: HashMap::new(), pending: HashMap::new() }
+    }
+
+    /// Offer an update; returns it if it may be sent now, else keeps it as the account's
+    /// pending update
+    pub fn offer(&mut self, update: EquityUpdate, now: Instant) -> Option<EquityUpdate> {
+        let account_id = update.account_id;
+        if self.is_due(account_id, now) {
+            self.pending.remove(&account_id);
+            self.last_sent.insert(account_id, now);
+            Some(update)
+        } else {
+            self.pending.insert(account_id, update);
+            None
+        }
+    }
+
+    /// Take the pending updates whose accounts may be sent again
+    pub fn take_due(&mut self, now: Instant) -> Vec<EquityUpdate> {
+        let due: Vec<i64> = self
+            .pending
+            .keys()
+            .copied()
+            .filter(|account_id| self.is_due(*account_id, now))
+            .collect();
+
+        due.into_iter()
+            .filter_map(|account_id| {
+                self.last_sent.insert(account_id, now);
+                self.pending.remove(&account_id)
+            })
+            .collect()
+    }
+
+    /// Forget every account `keep` rejects, including its pending update
+    pub fn retain(&mut self, keep: impl Fn(i64) -> bool) {
+        self.last_sent.retain(|account_id, _| keep(*account_id));
+        self.pending.retain(|account_id, _| keep(*account_id));
+    }
+
+    /// Number of accounts with throttling state
+    pub fn len(&self) -> usize {
+        self.last_sent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last_sent.is_empty()
+    }
+
+    fn is_due(&self, account_id: i64, now: Instant) -> bool {
+        self.last_sent
+            .get(&account_id)
+            .is_none_or(|sent| now.saturating_duration_since(*sent) >= self.interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    fn update(account_id: i64, total_equity: i64) -> EquityUpdate {
+        EquityUpdate {
+            account_id,
+            total_equity,
+            cash_balance: total_equity,
+            position_value: 0,
+            unrealized_pnl: 0,
+            realized_pnl: 0,
+            day_change: 0,
+            day_change_percent: 0.0,
+            tick: 0,
+            timestamp: 0,
+        }
+    }
+
+    fn equities(updates: &[EquityUpdate]) -> Vec<(i64, i64)> {
+        let mut equities: Vec<_> = updates.iter().map(|u| (u.account_id, u.total_equity)).collect();
+        equities.sort_unstable();
+        equities
+    }
+
+    #[test]
+    fn test_updates_within_the_interval_are_coalesced() {
+        let start = Instant::now();
+        let mut throttle = EquityThrottle::new(INTERVAL);
+
+        assert_eq!(throttle.offer(update(1, 100), start).map(|u| u.total_equity), Some(100));
+        for (ms, equity) in [(100, 101), (200, 102), (300, 103)] {
+            assert!(throttle.offer(update(1, equity), start + Duration::from_millis(ms)).is_none());
+        }
+
+        // Only the latest is sent, once
+        let due = throttle.take_due(start + INTERVAL);
+        assert_eq!(equities(&due), vec![(1, 103)]);
+        assert!(throttle.take_due(start + INTERVAL * 3).is_empty());
+    }
+
+    #[test]
+    fn test_pending_update_is_held_until_the_interval_has_passed() {
+        let start = Instant::now();
+        let mut throttle = EquityThrottle::new(INTERVAL);
+        throttle.offer(update(1, 100), start);
+        throttle.offer(update(1, 101), start + Duration::from_millis(10));
+
+        assert!(throttle.take_due(start + INTERVAL - Duration::from_millis(1)).is_empty());
+        let sent_at = start + INTERVAL;
+        assert_eq!(equities(&throttle.take_due(sent_at)), vec![(1, 101)]);
+
+        // Sending the pending update starts a new interval
+        assert!(throttle.offer(update(1, 102), sent_at + Duration::from_millis(10)).is_none());
+        assert!(throttle.offer(update(1, 103), sent_at + INTERVAL).is_some());
+    }
+
+    #[test]
+    fn test_accounts_are_throttled_independently() {
+        let start = Instant::now();
+        let mut throttle = EquityThrottle::new(INTERVAL);
+
+        assert!(throttle.offer(update(1, 100), start).is_some());
+        assert!(throttle.offer(update(2, 200), start + Duration::from_millis(100)).is_some());
+        assert!(throttle.offer(update(1, 101), start + Duration::from_millis(200)).is_none());
+        assert!(throttle.offer(update(2, 201), start + Duration::from_millis(300)).is_none());
+
+        // Account 1's interval ends first
+        let due = throttle.take_due(start + INTERVAL);
+        assert_eq!(equities(&due), vec![(1, 101)]);
+        let due = throttle.take_due(start + INTERVAL + Duration::from_millis(100));
+        assert_eq!(equities(&due), vec![(2, 201)]);
+    }
+
+    #[test]
+    fn test_retain_drops_unsubscribed_accounts() {
+        let start = Instant::now();
+        let mut throttle = EquityThrottle::new(INTERVAL);
+        throttle.offer(update(1, 100), start);
+        throttle.offer(update(2, 200), start);
+        throttle.offer(update(2, 201), start + Duration::from_millis(10));
+
+        throttle.retain(|account_id| account_id == 1);
+        assert_eq!(throttle.len(), 1);
+        assert!(throttle.take_due(start + INTERVAL).is_empty());
+
+        // A returning subscriber is not held back by its old interval
+        assert!(throttle.offer(update(2, 202), start + Duration::from_millis(20)).is_some());
+    }
+}
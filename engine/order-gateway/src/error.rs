@@ -26,6 +26,9 @@ pub enum GatewayError {
     #[error("Symbol not found: {0}")]
     SymbolNotFound(String),
 
+    #[error("Order not found: {0}")]
+    OrderNotFound(String),
+
     #[error("System error: {0}")]
     System(String),
 
@@ -40,6 +43,9 @@ pub enum GatewayError {
 
     #[error("Execution manager error: {0}")]
     ExecutionManager(String),
+
+    #[error("FIX protocol error: {0}")]
+    Fix(String),
 }
 
 /// Auth errors for warp rejections
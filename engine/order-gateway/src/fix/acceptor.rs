@@ -0,0 +1,1041 @@
+//! FIX 4.4 acceptor
+//!
+//! Accepts TCP connections, authenticates the Logon against the API key store and
+//! then runs one of two session kinds:
+//!
+//! - order entry: NewOrderSingle, OrderCancelRequest and OrderCancelReplaceRequest go
+//!   through [`OrderEntry`] exactly like WebSocket and REST orders, and ExecutionReports
+//!   for the session's orders are driven by ExecutionManager events
+//! - drop copy (`TargetSubID=DROPCOPY`): read-only ExecutionReports for every order of
+//!   the account, whichever channel placed it
+
+use super::codec::{self, msg_type, tags, FixMessage};
+use super::execution::{self, exec_type, ord_status, TrackedOrder};
+use super::session::{
+    InboundSeq, SessionHeader, SessionKey, SessionKind, SessionState, SessionStore,
+};
+use crate::auth::ApiKeyStore;
+use crate::config::FixConfig;
+use crate::error::{GatewayError, GatewayResult};
+use crate::messages::{AuthRequest, UserSession};
+use crate::order_entry::{parse_order_id, OrderEntry};
+use crate::websocket_handler::next_event;
+
+use execution_manager::{DispatchEvent, ExecutionManager, OrderCancelled, TradeEvent};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{error, info, warn};
+
+/// SessionRejectReason (373) values
+const REJECT_REASON_REQUIRED_TAG_MISSING: u32 = 1;
+const REJECT_REASON_VALUE_INCORRECT: u32 = 5;
+const REJECT_REASON_COMP_ID_PROBLEM: u32 = 9;
+
+/// BusinessRejectReason (380) values
+const BUSINESS_REJECT_UNSUPPORTED_MSG_TYPE: u32 = 3;
+const BUSINESS_REJECT_NOT_AUTHORIZED: u32 = 6;
+
+/// FIX 4.4 acceptor for order entry and drop copy sessions
+pub struct FixAcceptor {
+    /// Acceptor configuration
+    config: FixConfig,
+
+    /// API key store used to authenticate Logons
+    api_key_store: Arc<ApiKeyStore>,
+
+    /// Order entry shared with the WebSocket and REST APIs
+    order_entry: Arc<OrderEntry>,
+
+    /// Source of execution events (without it only acknowledgements are reported)
+    execution_manager: Option<Arc<ExecutionManager>>,
+
+    /// Session state that survives reconnects
+    sessions: SessionStore,
+}
+
+impl FixAcceptor {
+    /// Create a new acceptor
+    pub fn new(
+        config: FixConfig,
+        api_key_store: Arc<ApiKeyStore>,
+        order_entry: Arc<OrderEntry>,
+        execution_manager: Option<Arc<ExecutionManager>>,
+    ) -> Self {
+        Self {
+            config,
+            api_key_store,
+            order_entry,
+            execution_manager,
+            sessions: SessionStore::new(),
+        }
+    }
+
+    /// Accept FIX connections until the listener fails
+    pub async fn run(self: Arc<Self>, addr: SocketAddr) -> GatewayResult<()> {
+        let listener = TcpListener::bind(addr).await?;
+        info!("FIX acceptor listening on {} as {}", addr, self.config.comp_id);
+
+        loop {
+            let (stream, peer_addr) = listener.accept().await?;
+            let acceptor = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = acceptor.handle_connection(stream, peer_addr).await {
+                    warn!("FIX connection from {} ended with error: {}", peer_addr, e);
+                }
+            });
+        }
+    }
+
+    /// Run one connection: Logon, then the session until Logout or disconnect
+    async fn handle_connection(
+        self: Arc<Self>,
+        stream: TcpStream,
+        peer_addr: SocketAddr,
+    ) -> GatewayResult<()> {
+        info!("New FIX connection from {}", peer_addr);
+        stream.set_nodelay(true)?;
+        let (mut reader, mut writer) = stream.into_split();
+        let mut buffer = Vec::new();
+
+        // The first message must be a Logon
+        let logon_timeout = Duration::from_secs(self.config.logon_timeout_seconds);
+        let logon =
+            match tokio::time::timeout(logon_timeout, read_message(&mut reader, &mut buffer)).await
+            {
+                Ok(Ok(Some(message))) if message.msg_type() == msg_type::LOGON => message,
+                Ok(Ok(Some(message))) => {
+                    return Err(GatewayError::Fix(format!(
+                        "Expected Logon, received MsgType {}",
+                        message.msg_type()
+                    )));
+                }
+                Ok(Ok(None)) => return Ok(()),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => return Err(GatewayError::Fix("No Logon received in time".to_string())),
+            };
+
+        let header = SessionHeader {
+            sender_comp_id: self.config.comp_id.clone(),
+            target_comp_id: logon.get(tags::SENDER_COMP_ID).unwrap_or_default().to_string(),
+        };
+
+        let (key, session, heartbeat_interval) =
+            match self.authenticate_logon(&logon, peer_addr).await {
+                Ok(logon) => logon,
+                Err(e) => {
+                    warn!("FIX Logon from {} rejected: {}", peer_addr, e);
+                    reject_logon(&mut writer, &header, &e.to_string()).await;
+                    return Ok(());
+                }
+            };
+
+        if !self.sessions.activate(&key) {
+            reject_logon(&mut writer, &header, "Session is already logged on").await;
+            return Ok(());
+        }
+
+        let state = self.sessions.state(&key, self.config.resend_buffer_size).await;
+        let mut connection = FixConnection {
+            acceptor: self.clone(),
+            peer_addr,
+            writer,
+            header,
+            state,
+            key: key.clone(),
+            session,
+            heartbeat_interval,
+            last_received: Instant::now(),
+            last_sent: Instant::now(),
+            test_request_sent: None,
+        };
+
+        let result = connection.run(logon, &mut reader, &mut buffer).await;
+        self.sessions.deactivate(&key);
+        info!(
+            "FIX session {} ({:?}) for account {} disconnected",
+            key.sender_comp_id, key.kind, key.account_id
+        );
+        result
+    }
+
+    /// Validate the Logon header and credentials
+    ///
+    /// `Username` (553) is the API key ID. The key is proven either with `Password` (554)
+    /// set to the API secret, or with `RawData` (96) set to the hex HMAC-SHA256 of
+    /// `Username\n<SendingTime as unix seconds>\n<SendingTime>`, the same signature as a
+    /// WebSocket signed login with SendingTime as the nonce.
+    async fn authenticate_logon(
+        &self,
+        logon: &FixMessage,
+        peer_addr: SocketAddr,
+    ) -> GatewayResult<(SessionKey, UserSession, Duration)> {
+        let sender_comp_id = logon
+            .get(tags::SENDER_COMP_ID)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| GatewayError::Authentication("Missing SenderCompID".to_string()))?;
+        if logon.get(tags::TARGET_COMP_ID) != Some(self.config.comp_id.as_str()) {
+            return Err(GatewayError::Authentication(format!(
+                "TargetCompID must be {}",
+                self.config.comp_id
+            )));
+        }
+        if logon.get(tags::ENCRYPT_METHOD).is_some_and(|method| method != "0") {
+            return Err(GatewayError::Authentication("EncryptMethod must be 0".to_string()));
+        }
+
+        let heartbeat_seconds = logon
+            .get_u64(tags::HEART_BT_INT)
+            .filter(|interval| (1..=self.config.max_heartbeat_interval_seconds).contains(interval))
+            .ok_or_else(|| {
+                GatewayError::Authentication(format!(
+                    "HeartBtInt must be between 1 and {}",
+                    self.config.max_heartbeat_interval_seconds
+                ))
+            })?;
+
+        let api_key = logon
+            .get(tags::USERNAME)
+            .ok_or_else(|| GatewayError::Authentication("Missing Username".to_string()))?;
+        let request = match logon.get(tags::RAW_DATA) {
+            Some(signature) => {
+                let sending_time = logon.get(tags::SENDING_TIME).unwrap_or_default();
+                let timestamp = codec::parse_utc_timestamp(sending_time)
+                    .ok_or_else(|| GatewayError::Authentication("Invalid SendingTime".to_string()))?
+                    .timestamp();
+                AuthRequest {
+                    api_key: api_key.to_string(),
+                    api_secret: None,
+                    timestamp: Some(timestamp),
+                    nonce: Some(sending_time.to_string()),
+                    signature: Some(signature.to_string()),
+                }
+            }
+            None => AuthRequest {
+                api_key: api_key.to_string(),
+                api_secret: logon.get(tags::PASSWORD).map(str::to_string),
+                timestamp: None,
+                nonce: None,
+                signature: None,
+            },
+        };
+
+        let principal = self.api_key_store.authenticate(&request, peer_addr.ip()).await?;
+
+        let kind = if logon.get(tags::TARGET_SUB_ID) == Some(self.config.drop_copy_sub_id.as_str())
+        {
+            SessionKind::DropCopy
+        } else {
+            if !principal.has_permission("trade") {
+                return Err(GatewayError::Authentication(
+                    "API key lacks the trade permission; use a drop copy session".to_string(),
+                ));
+            }
+            SessionKind::OrderEntry
+        };
+
+        let key = SessionKey {
+            account_id: principal.account_id,
+            sender_comp_id: sender_comp_id.to_string(),
+            kind,
+        };
+        Ok((key, principal.into_session(), Duration::from_secs(heartbeat_seconds)))
+    }
+}
+
+/// Outcome of handling one inbound message
+enum Flow {
+    Continue,
+    Disconnect,
+}
+
+/// A logged-on FIX connection
+struct FixConnection {
+    acceptor: Arc<FixAcceptor>,
+    peer_addr: SocketAddr,
+    writer: OwnedWriteHalf,
+    header: SessionHeader,
+    state: Arc<Mutex<SessionState>>,
+    key: SessionKey,
+    session: UserSession,
+    heartbeat_interval: Duration,
+    last_received: Instant,
+    last_sent: Instant,
+    test_request_sent: Option<Instant>,
+}
+
+impl FixConnection {
+    /// Complete the Logon handshake and run the session loop
+    async fn run(
+        &mut self,
+        logon: FixMessage,
+        reader: &mut OwnedReadHalf,
+        buffer: &mut Vec<u8>,
+    ) -> GatewayResult<()> {
+        // Subscribed before the Logon is answered so no revocation slips through
+        let mut invalidations = Some(self.acceptor.api_key_store.subscribe_invalidations());
+        let reset = logon.get_bool(tags::RESET_SEQ_NUM_FLAG);
+        let logon_seq = logon.seq_num().unwrap_or(0);
+        let seq_check = {
+            let mut state = self.state.lock().await;
+            if reset {
+                state.reset();
+            }
+            state.check_inbound(logon_seq, logon.is_poss_dup())
+        };
+
+        if let InboundSeq::TooLow { expected } = seq_check {
+            self.logout(&format!(
+                "MsgSeqNum too low, expecting {expected} but received {logon_seq}"
+            ))
+            .await?;
+            return Ok(());
+        }
+
+        let mut response = FixMessage::new(msg_type::LOGON)
+            .with(tags::ENCRYPT_METHOD, 0)
+            .with(tags::HEART_BT_INT, self.heartbeat_interval.as_secs());
+        if reset {
+            response.set(tags::RESET_SEQ_NUM_FLAG, "Y");
+        }
+        self.send(response).await?;
+        info!(
+            "FIX session {} ({:?}) logged on for account {} from {}",
+            self.key.sender_comp_id, self.key.kind, self.key.account_id, self.peer_addr
+        );
+
+        if let InboundSeq::Gap { expected } = seq_check {
+            self.send_resend_request(expected).await?;
+        }
+
+        self.reconcile_orders().await?;
+
+        let mut events = match &self.acceptor.execution_manager {
+            Some(execution_manager) => execution_manager.subscribe_events(),
+            None => {
+                warn!("FIX acceptor has no execution event source; fills will not be reported");
+                broadcast::channel(1).1
+            }
+        };
+
+        let mut timer = tokio::time::interval(Duration::from_secs(1));
+        let mut chunk = [0u8; 4096];
+
+        loop {
+            tokio::select! {
+                read = reader.read(&mut chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        return Ok(());
+                    }
+                    buffer.extend_from_slice(&chunk[..n]);
+                    self.last_received = Instant::now();
+                    self.test_request_sent = None;
+
+                    while let Some(frame) = codec::take_frame(buffer)? {
+                        let message = match FixMessage::decode(&frame) {
+                            Ok(message) => message,
+                            Err(e) => {
+                                // Garbled messages are ignored without consuming a sequence number
+                                warn!("Discarding garbled FIX message from {}: {}", self.peer_addr, e);
+                                continue;
+                            }
+                        };
+                        if let Flow::Disconnect = self.handle_message(message).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+                event = events.recv() => match event {
+                    Ok(event) => self.handle_event(event).await?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "FIX session {} lagged behind execution events, {} skipped",
+                            self.key.sender_comp_id, skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        events = broadcast::channel(1).1;
+                    }
+                },
+                key_id = next_event(&mut invalidations) => match key_id {
+                    Ok(key_id) if self.session.api_key_id.as_deref() == Some(key_id.as_str()) => {
+                        self.logout("API key revoked").await?;
+                        return Ok(());
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "FIX session {} lagged behind API key revocations, {} skipped",
+                            self.key.sender_comp_id, skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => invalidations = None,
+                },
+                _ = timer.tick() => {
+                    if let Flow::Disconnect = self.check_heartbeats().await? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Session-level checks and dispatch of one inbound message
+    async fn handle_message(&mut self, message: FixMessage) -> GatewayResult<Flow> {
+        let seq_num = message.seq_num();
+        let Some(seq_num) = seq_num else {
+            self.logout("MsgSeqNum (34) missing").await?;
+            return Ok(Flow::Disconnect);
+        };
+
+        if message.get(tags::SENDER_COMP_ID) != Some(self.key.sender_comp_id.as_str())
+            || message.get(tags::TARGET_COMP_ID) != Some(self.header.sender_comp_id.as_str())
+        {
+            self.send_reject(&message, REJECT_REASON_COMP_ID_PROBLEM, None, "CompID problem")
+                .await?;
+            self.logout("Incorrect SenderCompID or TargetCompID").await?;
+            return Ok(Flow::Disconnect);
+        }
+
+        // SequenceReset-Reset ignores MsgSeqNum entirely
+        if message.msg_type() == msg_type::SEQUENCE_RESET && !message.get_bool(tags::GAP_FILL_FLAG)
+        {
+            return self.handle_sequence_reset(&message, false).await;
+        }
+
+        let seq_check = self.state.lock().await.check_inbound(seq_num, message.is_poss_dup());
+        match seq_check {
+            InboundSeq::InOrder => {}
+            InboundSeq::Duplicate => return Ok(Flow::Continue),
+            InboundSeq::TooLow { expected } => {
+                self.logout(&format!(
+                    "MsgSeqNum too low, expecting {expected} but received {seq_num}"
+                ))
+                .await?;
+                return Ok(Flow::Disconnect);
+            }
+            InboundSeq::Gap { expected } => {
+                self.send_resend_request(expected).await?;
+                // A counterparty's ResendRequest is honoured even while we are behind
+                if message.msg_type() != msg_type::RESEND_REQUEST {
+                    return Ok(Flow::Continue);
+                }
+            }
+            InboundSeq::GapPending => {
+                if message.msg_type() != msg_type::RESEND_REQUEST {
+                    return Ok(Flow::Continue);
+                }
+            }
+        }
+
+        match message.msg_type() {
+            msg_type::HEARTBEAT | msg_type::REJECT => Ok(Flow::Continue),
+            msg_type::TEST_REQUEST => {
+                let mut heartbeat = FixMessage::new(msg_type::HEARTBEAT);
+                if let Some(test_req_id) = message.get(tags::TEST_REQ_ID) {
+                    heartbeat.set(tags::TEST_REQ_ID, test_req_id);
+                }
+                self.send(heartbeat).await?;
+                Ok(Flow::Continue)
+            }
+            msg_type::RESEND_REQUEST => {
+                self.handle_resend_request(&message).await?;
+                Ok(Flow::Continue)
+            }
+            msg_type::SEQUENCE_RESET => self.handle_sequence_reset(&message, true).await,
+            msg_type::LOGOUT => {
+                self.send(FixMessage::new(msg_type::LOGOUT)).await?;
+                Ok(Flow::Disconnect)
+            }
+            msg_type::LOGON => {
+                self.logout("Unexpected Logon on an established session").await?;
+                Ok(Flow::Disconnect)
+            }
+            msg_type::NEW_ORDER_SINGLE
+            | msg_type::ORDER_CANCEL_REQUEST
+            | msg_type::ORDER_CANCEL_REPLACE_REQUEST
+                if self.key.kind == SessionKind::DropCopy =>
+            {
+                self.send_business_reject(
+                    &message,
+                    BUSINESS_REJECT_NOT_AUTHORIZED,
+                    "Drop copy sessions are read-only",
+                )
+                .await?;
+                Ok(Flow::Continue)
+            }
+            msg_type::NEW_ORDER_SINGLE => {
+                self.handle_new_order(&message).await?;
+                Ok(Flow::Continue)
+            }
+            msg_type::ORDER_CANCEL_REQUEST => {
+                self.handle_cancel(&message).await?;
+                Ok(Flow::Continue)
+            }
+            msg_type::ORDER_CANCEL_REPLACE_REQUEST => {
+                self.handle_replace(&message).await?;
+                Ok(Flow::Continue)
+            }
+            _ => {
+                self.send_business_reject(
+                    &message,
+                    BUSINESS_REJECT_UNSUPPORTED_MSG_TYPE,
+                    "Unsupported MsgType",
+                )
+                .await?;
+                Ok(Flow::Continue)
+            }
+        }
+    }
+
+    /// Replay (or gap-fill) our messages in the requested range
+    async fn handle_resend_request(&mut self, message: &FixMessage) -> GatewayResult<()> {
+        let (Some(begin), Some(end)) =
+            (message.get_u64(tags::BEGIN_SEQ_NO), message.get_u64(tags::END_SEQ_NO))
+        else {
+            return self
+                .send_reject(
+                    message,
+                    REJECT_REASON_REQUIRED_TAG_MISSING,
+                    Some(tags::BEGIN_SEQ_NO),
+                    "BeginSeqNo and EndSeqNo are required",
+                )
+                .await;
+        };
+
+        let replay = self.state.lock().await.resend(begin, end, &self.header);
+        info!(
+            "FIX session {} resending {}..{} ({} messages)",
+            self.key.sender_comp_id,
+            begin,
+            end,
+            replay.len()
+        );
+        for message in replay {
+            self.write(&message).await?;
+        }
+        Ok(())
+    }
+
+    /// Apply a SequenceReset in gap-fill or reset mode
+    async fn handle_sequence_reset(
+        &mut self,
+        message: &FixMessage,
+        gap_fill: bool,
+    ) -> GatewayResult<Flow> {
+        let Some(new_seq_no) = message.get_u64(tags::NEW_SEQ_NO) else {
+            self.send_reject(
+                message,
+                REJECT_REASON_REQUIRED_TAG_MISSING,
+                Some(tags::NEW_SEQ_NO),
+                "NewSeqNo is required",
+            )
+            .await?;
+            return Ok(Flow::Continue);
+        };
+
+        if !self.state.lock().await.apply_sequence_reset(new_seq_no, gap_fill) {
+            self.send_reject(
+                message,
+                REJECT_REASON_VALUE_INCORRECT,
+                Some(tags::NEW_SEQ_NO),
+                "NewSeqNo may not move the sequence backwards",
+            )
+            .await?;
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// NewOrderSingle
+    async fn handle_new_order(&mut self, message: &FixMessage) -> GatewayResult<()> {
+        let request = match execution::order_request_from_fix(message) {
+            Ok(request) => request,
+            Err(text) => {
+                return self
+                    .send(execution::order_reject(message, self.key.account_id, &text))
+                    .await
+            }
+        };
+
+        let acceptor = self.acceptor.clone();
+        let placed = match acceptor.order_entry.place_order(&self.session, &request).await {
+            Ok(placed) => placed,
+            Err(e) => {
+                let reject = execution::order_reject(message, self.key.account_id, &e.to_string());
+                return self.send(reject).await;
+            }
+        };
+
+        let order_id = parse_order_id(&placed.response.order_id)?;
+        let report = {
+            let mut state = self.state.lock().await;
+            match state.orders.get(&order_id) {
+                // A resent ClOrdID for an order this session already reported
+                Some(order) => execution::execution_report(
+                    order,
+                    exec_type::NEW,
+                    &execution::new_exec_id(),
+                    self.key.account_id,
+                ),
+                None => {
+                    let order = TrackedOrder::from_request(order_id, &request);
+                    let report = execution::execution_report(
+                        &order,
+                        exec_type::NEW,
+                        &execution::new_exec_id(),
+                        self.key.account_id,
+                    );
+                    state.orders.insert(order_id, order);
+                    report
+                }
+            }
+        };
+        self.send(report).await
+    }
+
+    /// OrderCancelRequest
+    async fn handle_cancel(&mut self, message: &FixMessage) -> GatewayResult<()> {
+        let original = match self.resolve_original(message).await {
+            Ok(order) => order,
+            Err(e) => {
+                let reject = execution::cancel_reject(
+                    message,
+                    None,
+                    execution::RESPONSE_TO_CANCEL,
+                    &e.to_string(),
+                );
+                return self.send(reject).await;
+            }
+        };
+
+        let acceptor = self.acceptor.clone();
+        if let Err(e) = acceptor.order_entry.cancel_order(&self.session, original.order_id).await {
+            let reject = execution::cancel_reject(
+                message,
+                Some(&original),
+                execution::RESPONSE_TO_CANCEL,
+                &e.to_string(),
+            );
+            return self.send(reject).await;
+        }
+
+        // The cancel is confirmed with ExecType=Canceled when the engine reports it
+        let report = {
+            let mut state = self.state.lock().await;
+            let order = state.orders.entry(original.order_id).or_insert(original);
+            order.orig_cl_ord_id = order.cl_ord_id.take();
+            order.cl_ord_id = message.get(tags::CL_ORD_ID).map(str::to_string);
+            order.status = ord_status::PENDING_CANCEL;
+            execution::execution_report(
+                order,
+                exec_type::PENDING_CANCEL,
+                &execution::new_exec_id(),
+                self.key.account_id,
+            )
+        };
+        self.send(report).await
+    }
+
+    /// OrderCancelReplaceRequest
+    ///
+    /// The engine has no in-place modify, so a replace cancels the original order and
+    /// places a new one for the remaining quantity. The two steps are not atomic: fills
+    /// on the original that race the cancel are still reported against it.
+    async fn handle_replace(&mut self, message: &FixMessage) -> GatewayResult<()> {
+        let reject = |order: Option<&TrackedOrder>, text: &str| {
+            execution::cancel_reject(message, order, execution::RESPONSE_TO_REPLACE, text)
+        };
+
+        let original = match self.resolve_original(message).await {
+            Ok(order) => order,
+            Err(e) => return self.send(reject(None, &e.to_string())).await,
+        };
+        let mut request = match execution::order_request_from_fix(message) {
+            Ok(request) => request,
+            Err(text) => return self.send(reject(Some(&original), &text)).await,
+        };
+
+        if message.get(tags::SIDE) != Some(original.side.as_str()) {
+            return self.send(reject(Some(&original), "Side cannot be changed")).await;
+        }
+        let total_quantity = request.quantity as i64;
+        let remaining = total_quantity - original.cum_qty;
+        if remaining <= 0 {
+            return self
+                .send(reject(Some(&original), "OrderQty must exceed the filled quantity"))
+                .await;
+        }
+        request.symbol = original.symbol.clone();
+        request.quantity = remaining as u64;
+
+        let acceptor = self.acceptor.clone();
+        if let Err(e) = acceptor.order_entry.cancel_order(&self.session, original.order_id).await {
+            return self.send(reject(Some(&original), &e.to_string())).await;
+        }
+
+        let placed = match acceptor.order_entry.place_order(&self.session, &request).await {
+            Ok(placed) => placed,
+            Err(e) => {
+                // The original is already being cancelled; its Canceled report follows
+                let text = format!("Replacement rejected ({e}); original order is being cancelled");
+                return self.send(reject(Some(&original), &text)).await;
+            }
+        };
+        let new_order_id = parse_order_id(&placed.response.order_id)?;
+
+        let report = {
+            let mut state = self.state.lock().await;
+            let replaced = state.orders.entry(original.order_id).or_insert(original.clone());
+            replaced.replaced = true;
+
+            let mut order = TrackedOrder::from_request(new_order_id, &request);
+            order.orig_cl_ord_id = original.cl_ord_id.clone();
+            order.quantity = total_quantity;
+            order.cum_qty = original.cum_qty;
+            order.notional = original.notional;
+            if order.cum_qty > 0 {
+                order.status = ord_status::PARTIALLY_FILLED;
+            }
+            let report = execution::execution_report(
+                &order,
+                exec_type::REPLACED,
+                &execution::new_exec_id(),
+                self.key.account_id,
+            );
+            state.orders.insert(new_order_id, order);
+            report
+        };
+        self.send(report).await
+    }
+
+    /// Find the order a cancel or replace refers to, by OrigClOrdID or OrderID
+    async fn resolve_original(&self, message: &FixMessage) -> GatewayResult<TrackedOrder> {
+        let orig_cl_ord_id = message.get(tags::ORIG_CL_ORD_ID);
+        let order_id = message.get(tags::ORDER_ID).map(parse_order_id).transpose()?;
+
+        {
+            let state = self.state.lock().await;
+            let tracked = state.orders.values().find(|order| {
+                !order.replaced
+                    && (order_id == Some(order.order_id)
+                        || (orig_cl_ord_id.is_some()
+                            && order.cl_ord_id.as_deref() == orig_cl_ord_id))
+            });
+            if let Some(order) = tracked {
+                return Ok(order.clone());
+            }
+        }
+
+        let order_entry = &self.acceptor.order_entry;
+        let record = match (order_id, orig_cl_ord_id) {
+            (Some(order_id), _) => order_entry.get_order(self.key.account_id, order_id).await?,
+            (None, Some(orig_cl_ord_id)) => {
+                order_entry
+                    .get_order_by_client_order_id(self.key.account_id, orig_cl_ord_id)
+                    .await?
+            }
+            (None, None) => {
+                return Err(GatewayError::InvalidOrder(
+                    "OrigClOrdID (41) or OrderID (37) is required".to_string(),
+                ))
+            }
+        };
+        Ok(TrackedOrder::from_record(&record))
+    }
+
+    /// Report anything that happened to this session's open orders while it was offline
+    async fn reconcile_orders(&mut self) -> GatewayResult<()> {
+        let open: Vec<TrackedOrder> = {
+            let state = self.state.lock().await;
+            state.orders.values().filter(|order| !order.is_terminal()).cloned().collect()
+        };
+
+        for tracked in open {
+            let Ok(record) =
+                self.acceptor.order_entry.get_order(self.key.account_id, tracked.order_id).await
+            else {
+                continue;
+            };
+
+            let mut reports = Vec::new();
+            {
+                let mut state = self.state.lock().await;
+                let Some(order) = state.orders.get_mut(&tracked.order_id) else {
+                    continue;
+                };
+
+                // Fill prices are not on the record, so catch-up fills use the order price
+                let missed = record.filled_quantity - order.cum_qty;
+                if missed > 0 {
+                    order.apply_fill(missed, order.price);
+                    reports.push(execution::fill_report(
+                        order,
+                        &execution::new_exec_id(),
+                        missed,
+                        order.price,
+                        self.key.account_id,
+                    ));
+                }
+                if record.status == "CANCELLED" && !order.is_terminal() {
+                    order.status = ord_status::CANCELED;
+                    if !order.replaced {
+                        reports.push(execution::execution_report(
+                            order,
+                            exec_type::CANCELED,
+                            &execution::new_exec_id(),
+                            self.key.account_id,
+                        ));
+                    }
+                }
+                if order.is_terminal() {
+                    state.orders.remove(&tracked.order_id);
+                }
+            }
+
+            for report in reports {
+                self.send(report).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Turn an execution event into ExecutionReports for this session
+    async fn handle_event(&mut self, event: DispatchEvent) -> GatewayResult<()> {
+        match event {
+            DispatchEvent::TradeEvent(trade) => self.handle_trade(&trade).await,
+            DispatchEvent::OrderCancelled(cancelled) => self.handle_cancelled(&cancelled).await,
+            DispatchEvent::OrderSubmitted(submitted) => {
+                // Drop copy sessions also see orders entered through other channels
+                if self.key.kind == SessionKind::DropCopy
+                    && i64::from(submitted.account_id) == self.key.account_id
+                {
+                    if let Some(order) = self.drop_copy_order(submitted.order_id, 0).await {
+                        let report = execution::execution_report(
+                            &order,
+                            exec_type::NEW,
+                            &execution::new_exec_id(),
+                            self.key.account_id,
+                        );
+                        self.send(report).await?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn handle_trade(&mut self, trade: &TradeEvent) -> GatewayResult<()> {
+        let sides = [
+            (trade.maker_order_id, trade.maker_account_id),
+            (trade.taker_order_id, trade.taker_account_id),
+        ];
+
+        for (order_id, account_id) in sides {
+            if account_id != self.key.account_id {
+                continue;
+            }
+            if self.key.kind == SessionKind::DropCopy {
+                self.drop_copy_order(order_id, trade.quantity as i64).await;
+            }
+
+            let report = {
+                let mut state = self.state.lock().await;
+                let Some(order) = state.orders.get_mut(&order_id) else {
+                    continue;
+                };
+                order.apply_fill(trade.quantity as i64, trade.price as i64);
+                let report = execution::fill_report(
+                    order,
+                    &execution::trade_exec_id(trade.execution_id, order_id),
+                    trade.quantity as i64,
+                    trade.price as i64,
+                    self.key.account_id,
+                );
+                if order.is_terminal() {
+                    state.orders.remove(&order_id);
+                }
+                report
+            };
+            self.send(report).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_cancelled(&mut self, cancelled: &OrderCancelled) -> GatewayResult<()> {
+        if self.key.kind == SessionKind::DropCopy {
+            self.drop_copy_order(cancelled.order_id, 0).await;
+        }
+
+        let report = {
+            let mut state = self.state.lock().await;
+            let Some(mut order) = state.orders.remove(&cancelled.order_id) else {
+                return Ok(());
+            };
+            // The cancel half of a replace was already reported as Replaced
+            if order.replaced {
+                return Ok(());
+            }
+            order.status = ord_status::CANCELED;
+            let mut report = execution::execution_report(
+                &order,
+                exec_type::CANCELED,
+                &execution::new_exec_id(),
+                self.key.account_id,
+            );
+            if let Some(reason) = &cancelled.reason {
+                report.set(tags::TEXT, reason);
+            }
+            report
+        };
+        self.send(report).await
+    }
+
+    /// Make sure a drop copy session tracks an order of its account
+    ///
+    /// Execution events arrive after the ExecutionManager has updated the order record,
+    /// so `pending_fill` is backed out of the loaded fill quantity before it is applied.
+    async fn drop_copy_order(&mut self, order_id: u64, pending_fill: i64) -> Option<TrackedOrder> {
+        if let Some(order) = self.state.lock().await.orders.get(&order_id) {
+            return Some(order.clone());
+        }
+
+        let record =
+            self.acceptor.order_entry.get_order(self.key.account_id, order_id).await.ok()?;
+        let mut order = TrackedOrder::from_record(&record);
+        order.cum_qty = (order.cum_qty - pending_fill).max(0);
+        order.notional = order.cum_qty * order.price;
+        order.status = match order.cum_qty {
+            0 => ord_status::NEW,
+            _ => ord_status::PARTIALLY_FILLED,
+        };
+
+        self.state.lock().await.orders.insert(order_id, order.clone());
+        Some(order)
+    }
+
+    /// Heartbeats, TestRequests and the heartbeat timeout
+    async fn check_heartbeats(&mut self) -> GatewayResult<Flow> {
+        if let Some(sent_at) = self.test_request_sent {
+            if sent_at.elapsed() >= self.heartbeat_interval {
+                warn!(
+                    "FIX session {} missed its TestRequest, disconnecting",
+                    self.key.sender_comp_id
+                );
+                self.logout("Heartbeat timeout").await?;
+                return Ok(Flow::Disconnect);
+            }
+        } else if self.last_received.elapsed() >= self.heartbeat_interval + self.heartbeat_grace() {
+            let test_req_id = codec::utc_timestamp(chrono::Utc::now());
+            self.send(FixMessage::new(msg_type::TEST_REQUEST).with(tags::TEST_REQ_ID, test_req_id))
+                .await?;
+            self.test_request_sent = Some(Instant::now());
+        }
+
+        if self.last_sent.elapsed() >= self.heartbeat_interval {
+            self.send(FixMessage::new(msg_type::HEARTBEAT)).await?;
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Allowance for transmission delay before a silent client is probed
+    fn heartbeat_grace(&self) -> Duration {
+        (self.heartbeat_interval / 5).max(Duration::from_secs(1))
+    }
+
+    async fn send_resend_request(&mut self, begin: u64) -> GatewayResult<()> {
+        info!(
+            "FIX session {} gap detected, requesting resend from {}",
+            self.key.sender_comp_id, begin
+        );
+        let request = FixMessage::new(msg_type::RESEND_REQUEST)
+            .with(tags::BEGIN_SEQ_NO, begin)
+            .with(tags::END_SEQ_NO, 0);
+        self.send(request).await
+    }
+
+    async fn send_reject(
+        &mut self,
+        rejected: &FixMessage,
+        reason: u32,
+        ref_tag: Option<u32>,
+        text: &str,
+    ) -> GatewayResult<()> {
+        let mut reject = FixMessage::new(msg_type::REJECT)
+            .with(tags::REF_SEQ_NUM, rejected.seq_num().unwrap_or(0))
+            .with(tags::REF_MSG_TYPE, rejected.msg_type())
+            .with(tags::SESSION_REJECT_REASON, reason)
+            .with(tags::TEXT, text);
+        if let Some(ref_tag) = ref_tag {
+            reject.set(tags::REF_TAG_ID, ref_tag);
+        }
+        self.send(reject).await
+    }
+
+    async fn send_business_reject(
+        &mut self,
+        rejected: &FixMessage,
+        reason: u32,
+        text: &str,
+    ) -> GatewayResult<()> {
+        let reject = FixMessage::new(msg_type::BUSINESS_MESSAGE_REJECT)
+            .with(tags::REF_SEQ_NUM, rejected.seq_num().unwrap_or(0))
+            .with(tags::REF_MSG_TYPE, rejected.msg_type())
+            .with(tags::BUSINESS_REJECT_REASON, reason)
+            .with(tags::TEXT, text);
+        self.send(reject).await
+    }
+
+    async fn logout(&mut self, text: &str) -> GatewayResult<()> {
+        info!("Logging out FIX session {}: {}", self.key.sender_comp_id, text);
+        self.send(FixMessage::new(msg_type::LOGOUT).with(tags::TEXT, text)).await
+    }
+
+    /// Stamp the next sequence number and send
+    async fn send(&mut self, message: FixMessage) -> GatewayResult<()> {
+        let message = self.state.lock().await.stamp(message, &self.header);
+        self.write(&message).await
+    }
+
+    /// Write an already stamped message
+    async fn write(&mut self, message: &FixMessage) -> GatewayResult<()> {
+        if let Err(e) = self.writer.write_all(&message.encode()).await {
+            error!("Failed to write FIX message to {}: {}", self.peer_addr, e);
+            return Err(e.into());
+        }
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+}
+
+/// Read the next complete message, or None if the peer closed the connection
+async fn read_message(
+    reader: &mut OwnedReadHalf,
+    buffer: &mut Vec<u8>,
+) -> GatewayResult<Option<FixMessage>> {
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(frame) = codec::take_frame(buffer)? {
+            return FixMessage::decode(&frame).map(Some);
+        }
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// Answer a failed Logon with a Logout outside of any session's sequence numbers
+async fn reject_logon(writer: &mut OwnedWriteHalf, header: &SessionHeader, text: &str) {
+    let logout = SessionState::new(0)
+        .stamp(FixMessage::new(msg_type::LOGOUT).with(tags::TEXT, text), header);
+    if let Err(e) = writer.write_all(&logout.encode()).await {
+        warn!("Failed to send FIX Logout: {}", e);
+    }
+}
@@ -0,0 +1,443 @@
+//! FIX 4.4 tag=value encoding
+//!
+//! Messages are kept as an ordered list of body fields; `BeginString`, `BodyLength`
+//! and `CheckSum` are added on encode and verified on decode.
+
+use crate::error::{GatewayError, GatewayResult};
+
+/// Field delimiter
+pub const SOH: u8 = 0x01;
+
+/// The only protocol version the acceptor speaks
+pub const BEGIN_STRING: &str = "FIX.4.4";
+
+/// Largest body accepted from a client, to bound buffering of a bad frame
+pub const MAX_BODY_LENGTH: usize = 64 * 1024;
+
+/// Longest `8=...<SOH>9=...<SOH>` prefix accepted before the BodyLength is known
+pub const MAX_HEADER_LENGTH: usize = 32;
+
+/// Tag numbers used by the acceptor
+pub mod tags {
+    pub const ACCOUNT: u32 = 1;
+    pub const AVG_PX: u32 = 6;
+    pub const BEGIN_SEQ_NO: u32 = 7;
+    pub const BEGIN_STRING: u32 = 8;
+    pub const BODY_LENGTH: u32 = 9;
+    pub const CHECKSUM: u32 = 10;
+    pub const CL_ORD_ID: u32 = 11;
+    pub const CUM_QTY: u32 = 14;
+    pub const END_SEQ_NO: u32 = 16;
+    pub const EXEC_ID: u32 = 17;
+    pub const EXEC_INST: u32 = 18;
+    pub const LAST_PX: u32 = 31;
+    pub const LAST_QTY: u32 = 32;
+    pub const MSG_SEQ_NUM: u32 = 34;
+    pub const MSG_TYPE: u32 = 35;
+    pub const NEW_SEQ_NO: u32 = 36;
+    pub const ORDER_ID: u32 = 37;
+    pub const ORDER_QTY: u32 = 38;
+    pub const ORD_STATUS: u32 = 39;
+    pub const ORD_TYPE: u32 = 40;
+    pub const ORIG_CL_ORD_ID: u32 = 41;
+    pub const POSS_DUP_FLAG: u32 = 43;
+    pub const PRICE: u32 = 44;
+    pub const REF_SEQ_NUM: u32 = 45;
+    pub const SENDER_COMP_ID: u32 = 49;
+    pub const SENDING_TIME: u32 = 52;
+    pub const SIDE: u32 = 54;
+    pub const SYMBOL: u32 = 55;
+    pub const TARGET_COMP_ID: u32 = 56;
+    pub const TARGET_SUB_ID: u32 = 57;
+    pub const TEXT: u32 = 58;
+    pub const TIME_IN_FORCE: u32 = 59;
+    pub const TRANSACT_TIME: u32 = 60;
+    pub const RAW_DATA_LENGTH: u32 = 95;
+    pub const RAW_DATA: u32 = 96;
+    pub const ENCRYPT_METHOD: u32 = 98;
+    pub const CXL_REJ_REASON: u32 = 102;
+    pub const ORD_REJ_REASON: u32 = 103;
+    pub const HEART_BT_INT: u32 = 108;
+    pub const TEST_REQ_ID: u32 = 112;
+    pub const ORIG_SENDING_TIME: u32 = 122;
+    pub const GAP_FILL_FLAG: u32 = 123;
+    pub const RESET_SEQ_NUM_FLAG: u32 = 141;
+    pub const EXEC_TYPE: u32 = 150;
+    pub const LEAVES_QTY: u32 = 151;
+    pub const REF_TAG_ID: u32 = 371;
+    pub const REF_MSG_TYPE: u32 = 372;
+    pub const SESSION_REJECT_REASON: u32 = 373;
+    pub const BUSINESS_REJECT_REASON: u32 = 380;
+    pub const CXL_REJ_RESPONSE_TO: u32 = 434;
+    pub const USERNAME: u32 = 553;
+    pub const PASSWORD: u32 = 554;
+}
+
+/// MsgType (35) values used by the acceptor
+pub mod msg_type {
+    pub const HEARTBEAT: &str = "0";
+    pub const TEST_REQUEST: &str = "1";
+    pub const RESEND_REQUEST: &str = "2";
+    pub const REJECT: &str = "3";
+    pub const SEQUENCE_RESET: &str = "4";
+    pub const LOGOUT: &str = "5";
+    pub const EXECUTION_REPORT: &str = "8";
+    pub const ORDER_CANCEL_REJECT: &str = "9";
+    pub const LOGON: &str = "A";
+    pub const NEW_ORDER_SINGLE: &str = "D";
+    pub const ORDER_CANCEL_REQUEST: &str = "F";
+    pub const ORDER_CANCEL_REPLACE_REQUEST: &str = "G";
+    pub const BUSINESS_MESSAGE_REJECT: &str = "j";
+
+    /// Session-level (admin) messages are never resent, only gap-filled
+    pub fn is_admin(msg_type: &str) -> bool {
+        matches!(
+            msg_type,
+            HEARTBEAT | TEST_REQUEST | RESEND_REQUEST | REJECT | SEQUENCE_RESET | LOGOUT | LOGON
+        )
+    }
+}
+
+/// A FIX message body (everything between `BodyLength` and `CheckSum`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    /// Create a message of the given MsgType
+    pub fn new(msg_type: &str) -> Self {
+        Self { fields: vec![(tags::MSG_TYPE, msg_type.to_string())] }
+    }
+
+    /// MsgType (35)
+    pub fn msg_type(&self) -> &str {
+        self.get(tags::MSG_TYPE).unwrap_or_default()
+    }
+
+    /// MsgSeqNum (34), if present and numeric
+    pub fn seq_num(&self) -> Option<u64> {
+        self.get_u64(tags::MSG_SEQ_NUM)
+    }
+
+    /// Whether PossDupFlag (43) is set
+    pub fn is_poss_dup(&self) -> bool {
+        self.get_bool(tags::POSS_DUP_FLAG)
+    }
+
+    /// First value of a tag
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| v.as_str())
+    }
+
+    /// First value of a tag parsed as an unsigned integer
+    pub fn get_u64(&self, tag: u32) -> Option<u64> {
+        self.get(tag).and_then(|v| v.parse().ok())
+    }
+
+    /// First value of a tag parsed as a FIX boolean (`Y`/`N`)
+    pub fn get_bool(&self, tag: u32) -> bool {
+        self.get(tag) == Some("Y")
+    }
+
+    /// Set a tag, replacing an existing value or appending a new field
+    pub fn set(&mut self, tag: u32, value: impl ToString) -> &mut Self {
+        let value = value.to_string();
+        match self.fields.iter_mut().find(|(t, _)| *t == tag) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((tag, value)),
+        }
+        self
+    }
+
+    /// Builder-style [`FixMessage::set`]
+    pub fn with(mut self, tag: u32, value: impl ToString) -> Self {
+        self.set(tag, value);
+        self
+    }
+
+    /// Remove every occurrence of a tag
+    pub fn remove(&mut self, tag: u32) {
+        self.fields.retain(|(t, _)| *t != tag);
+    }
+
+    /// Body fields in wire order
+    pub fn fields(&self) -> &[(u32, String)] {
+        &self.fields
+    }
+
+    /// Encode with header length and trailing checksum
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        // MsgType first, then the rest of the standard header, then the body
+        for (tag, value) in self.fields.iter().filter(|(t, _)| *t == tags::MSG_TYPE) {
+            push_field(&mut body, *tag, value);
+        }
+        for (tag, value) in self.fields.iter().filter(|(t, _)| HEADER_TAGS.contains(t)) {
+            push_field(&mut body, *tag, value);
+        }
+        for (tag, value) in self.fields.iter().filter(|(t, _)| !is_header_or_type(*t)) {
+            push_field(&mut body, *tag, value);
+        }
+
+        let mut frame = Vec::with_capacity(body.len() + 32);
+        push_field(&mut frame, tags::BEGIN_STRING, BEGIN_STRING);
+        push_field(&mut frame, tags::BODY_LENGTH, &body.len().to_string());
+        frame.extend_from_slice(&body);
+        let checksum = checksum(&frame);
+        push_field(&mut frame, tags::CHECKSUM, &format!("{checksum:03}"));
+        frame
+    }
+
+    /// Decode a complete frame, verifying BeginString, BodyLength and CheckSum
+    #[allow(clippy::result_large_err)]
+    pub fn decode(frame: &[u8]) -> GatewayResult<Self> {
+        let text = std::str::from_utf8(frame)
+            .map_err(|_| GatewayError::Fix("Message is not valid UTF-8".to_string()))?;
+        let mut raw = Vec::new();
+        for field in text.split('\x01').filter(|f| !f.is_empty()) {
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| GatewayError::Fix(format!("Malformed field: {field}")))?;
+            let tag = tag
+                .parse::<u32>()
+                .map_err(|_| GatewayError::Fix(format!("Invalid tag number: {tag}")))?;
+            raw.push((tag, value.to_string()));
+        }
+
+        match raw.first() {
+            Some((tags::BEGIN_STRING, version)) if version == BEGIN_STRING => {}
+            _ => return Err(GatewayError::Fix(format!("BeginString must be {BEGIN_STRING}"))),
+        }
+        if raw.get(1).map(|(t, _)| *t) != Some(tags::BODY_LENGTH) {
+            return Err(GatewayError::Fix("BodyLength must be the second field".to_string()));
+        }
+        if raw.get(2).map(|(t, _)| *t) != Some(tags::MSG_TYPE) {
+            return Err(GatewayError::Fix("MsgType must be the third field".to_string()));
+        }
+
+        let Some((tags::CHECKSUM, expected)) = raw.last() else {
+            return Err(GatewayError::Fix("CheckSum must be the last field".to_string()));
+        };
+        let checksum_start = frame.len() - (expected.len() + 4);
+
+        // BodyLength counts from after its own field up to the CheckSum field
+        let body_start = raw[..2].iter().map(|(tag, value)| field_len(*tag, value)).sum::<usize>();
+        let body_length = checksum_start.checked_sub(body_start);
+        if raw[1].1.parse::<usize>().ok() != body_length {
+            return Err(GatewayError::Fix(format!(
+                "BodyLength mismatch: declared {}, actual {}",
+                raw[1].1,
+                body_length.unwrap_or_default()
+            )));
+        }
+
+        let actual = checksum(&frame[..checksum_start]);
+        if expected.parse::<u8>().ok() != Some(actual) {
+            return Err(GatewayError::Fix(format!(
+                "CheckSum mismatch: expected {expected}, calculated {actual:03}"
+            )));
+        }
+
+        let fields = raw[2..raw.len() - 1].to_vec();
+        Ok(Self { fields })
+    }
+}
+
+/// Standard header fields (after MsgType) that the acceptor sets
+const HEADER_TAGS: [u32; 7] = [
+    tags::MSG_SEQ_NUM,
+    tags::POSS_DUP_FLAG,
+    tags::SENDER_COMP_ID,
+    tags::TARGET_COMP_ID,
+    tags::TARGET_SUB_ID,
+    tags::SENDING_TIME,
+    tags::ORIG_SENDING_TIME,
+];
+
+fn is_header_or_type(tag: u32) -> bool {
+    tag == tags::MSG_TYPE || HEADER_TAGS.contains(&tag)
+}
+
+/// Encoded length of `tag=value<SOH>`
+fn field_len(tag: u32, value: &str) -> usize {
+    tag.to_string().len() + value.len() + 2
+}
+
+fn push_field(buf: &mut Vec<u8>, tag: u32, value: &str) {
+    buf.extend_from_slice(tag.to_string().as_bytes());
+    buf.push(b'=');
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(SOH);
+}
+
+/// FIX checksum: byte sum modulo 256
+pub fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))
+}
+
+/// Split the next complete frame off the front of a read buffer
+///
+/// Returns `Ok(None)` when more bytes are needed. Garbage before a `8=` prefix is
+/// discarded; a frame whose header cannot be parsed, or does not end within
+/// [`MAX_HEADER_LENGTH`] bytes, is an error (the session is unrecoverable at that
+/// point). The buffer therefore never holds more than one maximum-size frame.
+#[allow(clippy::result_large_err)]
+pub fn take_frame(buf: &mut Vec<u8>) -> GatewayResult<Option<Vec<u8>>> {
+    let prefix = b"8=";
+    match buf.windows(prefix.len()).position(|w| w == prefix) {
+        Some(0) => {}
+        Some(start) => {
+            buf.drain(..start);
+        }
+        None => {
+            // Keep a trailing '8' in case the prefix is split across reads
+            let keep = usize::from(buf.last() == Some(&b'8'));
+            buf.drain(..buf.len() - keep);
+            return Ok(None);
+        }
+    }
+
+    // 8=FIX.4.4<SOH>9=<len><SOH>
+    let header = &buf[..buf.len().min(MAX_HEADER_LENGTH)];
+    let header_end = header.iter().position(|b| *b == SOH).and_then(|begin_end| {
+        let length_len = header[begin_end + 1..].iter().position(|b| *b == SOH)?;
+        Some((begin_end, length_len))
+    });
+    let Some((begin_end, length_len)) = header_end else {
+        if buf.len() >= MAX_HEADER_LENGTH {
+            return Err(GatewayError::Fix(format!(
+                "No BodyLength within the first {MAX_HEADER_LENGTH} bytes"
+            )));
+        }
+        return Ok(None);
+    };
+    let length_field = &buf[begin_end + 1..begin_end + 1 + length_len];
+    let body_length = std::str::from_utf8(length_field)
+        .ok()
+        .and_then(|f| f.strip_prefix("9="))
+        .and_then(|len| len.parse::<usize>().ok())
+        .ok_or_else(|| GatewayError::Fix("Missing or invalid BodyLength".to_string()))?;
+    if body_length > MAX_BODY_LENGTH {
+        return Err(GatewayError::Fix(format!("BodyLength {body_length} exceeds limit")));
+    }
+
+    // Body, then "10=nnn<SOH>"
+    let body_start = begin_end + 1 + length_len + 1;
+    let frame_len = body_start + body_length + 7;
+    if buf.len() < frame_len {
+        return Ok(None);
+    }
+
+    Ok(Some(buf.drain(..frame_len).collect()))
+}
+
+/// UTCTimestamp with milliseconds, as used in SendingTime/TransactTime
+pub fn utc_timestamp(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+/// Parse a UTCTimestamp with or without fractional seconds
+pub fn parse_utc_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    ["%Y%m%d-%H:%M:%S%.f", "%Y%m%d-%H:%M:%S"].iter().find_map(|format| {
+        chrono::NaiveDateTime::parse_from_str(value, format).ok().map(|t| t.and_utc())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(seq: u64) -> FixMessage {
+        FixMessage::new(msg_type::HEARTBEAT).with(tags::MSG_SEQ_NUM, seq)
+    }
+
+    /// Replace `|` with SOH
+    fn wire(text: &str) -> Vec<u8> {
+        text.replace('|', "\x01").into_bytes()
+    }
+
+    #[test]
+    fn test_encode_sets_body_length_and_checksum() {
+        let frame = heartbeat(1).encode();
+        assert_eq!(&frame[..frame.len() - 7], wire("8=FIX.4.4|9=10|35=0|34=1|").as_slice());
+
+        let sum = frame[..frame.len() - 7].iter().map(|b| u32::from(*b)).sum::<u32>() % 256;
+        assert_eq!(checksum(&frame[..frame.len() - 7]), sum as u8);
+        assert_eq!(&frame[frame.len() - 7..], format!("10={sum:03}\x01").as_bytes());
+    }
+
+    #[test]
+    fn test_decode_round_trips_and_verifies_trailer() {
+        let message = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+            .with(tags::MSG_SEQ_NUM, 7)
+            .with(tags::CL_ORD_ID, "abc")
+            .with(tags::PRICE, 101);
+        let frame = message.encode();
+        assert_eq!(FixMessage::decode(&frame).unwrap(), message);
+
+        // Corrupt the checksum
+        let mut bad_checksum = frame.clone();
+        let last_digit = bad_checksum.len() - 2;
+        bad_checksum[last_digit] = if bad_checksum[last_digit] == b'0' { b'1' } else { b'0' };
+        assert!(FixMessage::decode(&bad_checksum).is_err());
+
+        // A BodyLength that disagrees with the body, with a checksum that matches it
+        let mut body = wire("8=FIX.4.4|9=11|35=0|34=1|");
+        let sum = checksum(&body);
+        body.extend_from_slice(format!("10={sum:03}\x01").as_bytes());
+        assert!(FixMessage::decode(&body).is_err());
+
+        let mut wrong_version = frame;
+        wrong_version[6] = b'2';
+        assert!(FixMessage::decode(&wrong_version).is_err());
+    }
+
+    #[test]
+    fn test_take_frame_waits_for_partial_frames() {
+        let frame = heartbeat(1).encode();
+        let mut buf = Vec::new();
+
+        for split in [1, 5, 12, frame.len() - 1] {
+            buf.clear();
+            buf.extend_from_slice(&frame[..split]);
+            assert_eq!(take_frame(&mut buf).unwrap(), None, "split at {split}");
+            buf.extend_from_slice(&frame[split..]);
+            assert_eq!(take_frame(&mut buf).unwrap(), Some(frame.clone()));
+            assert!(buf.is_empty());
+        }
+
+        // Two frames in one read come out one at a time
+        let second = heartbeat(2).encode();
+        buf = [frame.clone(), second.clone()].concat();
+        assert_eq!(take_frame(&mut buf).unwrap(), Some(frame));
+        assert_eq!(take_frame(&mut buf).unwrap(), Some(second));
+        assert_eq!(take_frame(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_take_frame_discards_garbage() {
+        let frame = heartbeat(1).encode();
+
+        let mut buf = [b"noise".as_slice(), &frame].concat();
+        assert_eq!(take_frame(&mut buf).unwrap(), Some(frame));
+
+        // Garbage without a prefix is dropped, except a trailing '8'
+        let mut buf = b"garbage without a frame 8".to_vec();
+        assert_eq!(take_frame(&mut buf).unwrap(), None);
+        assert_eq!(buf, b"8");
+    }
+
+    #[test]
+    fn test_take_frame_bounds_the_buffer() {
+        // A prefix that never delivers SOH framing
+        let mut buf = [b"8=FIX.4.4".as_slice(), &[b'x'; MAX_HEADER_LENGTH]].concat();
+        assert!(take_frame(&mut buf).is_err());
+
+        let mut buf = wire(&format!("8=FIX.4.4|9={}|35=0|", MAX_BODY_LENGTH + 1));
+        assert!(take_frame(&mut buf).is_err());
+
+        let mut buf = wire("8=FIX.4.4|9=ten|35=0|");
+        assert!(take_frame(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! Order translation and ExecutionReport building
+//!
+//! Prices and quantities are carried in the same integer units as the rest of the API
+//! (prices in cents), so `Price=3500` is $35.00.
+
+use super::codec::{msg_type, tags, utc_timestamp, FixMessage};
+use crate::messages::OrderPlaceRequest;
+use account_service::OrderRecord;
+
+/// ExecType (150) values
+pub mod exec_type {
+    pub const NEW: &str = "0";
+    pub const CANCELED: &str = "4";
+    pub const REPLACED: &str = "5";
+    pub const PENDING_CANCEL: &str = "6";
+    pub const REJECTED: &str = "8";
+    pub const TRADE: &str = "F";
+}
+
+/// OrdStatus (39) values
+pub mod ord_status {
+    pub const NEW: &str = "0";
+    pub const PARTIALLY_FILLED: &str = "1";
+    pub const FILLED: &str = "2";
+    pub const CANCELED: &str = "4";
+    pub const PENDING_CANCEL: &str = "6";
+    pub const REJECTED: &str = "8";
+}
+
+/// OrdRejReason (103) / CxlRejReason (102) "Other"
+const REJECT_REASON_OTHER: u32 = 99;
+
+/// CxlRejResponseTo (434) values
+pub const RESPONSE_TO_CANCEL: &str = "1";
+pub const RESPONSE_TO_REPLACE: &str = "2";
+
+/// An order whose executions are reported on a session
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    /// Engine order ID (OrderID, 37)
+    pub order_id: u64,
+
+    /// Client order ID (ClOrdID, 11)
+    pub cl_ord_id: Option<String>,
+
+    /// Client order ID of the order this one replaced (OrigClOrdID, 41)
+    pub orig_cl_ord_id: Option<String>,
+
+    /// Symbol as the client sent it
+    pub symbol: String,
+
+    /// Side (54)
+    pub side: String,
+
+    /// OrdType (40)
+    pub ord_type: String,
+
+    /// Limit price in cents (0 for market orders)
+    pub price: i64,
+
+    /// Order quantity
+    pub quantity: i64,
+
+    /// Quantity filled so far
+    pub cum_qty: i64,
+
+    /// Sum of fill price times quantity, for AvgPx
+    pub notional: i64,
+
+    /// Current OrdStatus (39)
+    pub status: &'static str,
+
+    /// Cancelled as the first half of a cancel/replace (its cancel is not reported)
+    pub replaced: bool,
+}
+
+impl TrackedOrder {
+    /// Track a newly placed order
+    pub fn from_request(order_id: u64, request: &OrderPlaceRequest) -> Self {
+        Self {
+            order_id,
+            cl_ord_id: request.client_order_id.clone(),
+            orig_cl_ord_id: None,
+            symbol: request.symbol.clone(),
+            side: fix_side(&request.side).to_string(),
+            ord_type: fix_ord_type(&request.r#type).to_string(),
+            price: request.price as i64,
+            quantity: request.quantity as i64,
+            cum_qty: 0,
+            notional: 0,
+            status: ord_status::NEW,
+            replaced: false,
+        }
+    }
+
+    /// Track an order placed through another channel (drop copy)
+    pub fn from_record(record: &OrderRecord) -> Self {
+        let status = match record.status.as_str() {
+            "PARTIALLY_FILLED" => ord_status::PARTIALLY_FILLED,
+            "FILLED" => ord_status::FILLED,
+            "CANCELLED" => ord_status::CANCELED,
+            "REJECTED" => ord_status::REJECTED,
+            _ => ord_status::NEW,
+        };
+
+        Self {
+            order_id: record.order_id as u64,
+            cl_ord_id: record.client_order_id.clone(),
+            orig_cl_ord_id: None,
+            symbol: record.symbol_id.to_string(),
+            side: fix_side(&record.side).to_string(),
+            ord_type: fix_ord_type(&record.order_type).to_string(),
+            price: record.price,
+            quantity: record.quantity,
+            cum_qty: record.filled_quantity,
+            // Earlier fill prices are not stored on the record
+            notional: record.filled_quantity * record.price,
+            status,
+            replaced: false,
+        }
+    }
+
+    /// Whether no further executions can happen
+    pub fn is_terminal(&self) -> bool {
+        matches!(self.status, ord_status::FILLED | ord_status::CANCELED | ord_status::REJECTED)
+    }
+
+    /// Quantity still open on the book
+    pub fn leaves_qty(&self) -> i64 {
+        if self.is_terminal() {
+            0
+        } else {
+            (self.quantity - self.cum_qty).max(0)
+        }
+    }
+
+    /// Apply a fill and update the status
+    pub fn apply_fill(&mut self, quantity: i64, price: i64) {
+        self.cum_qty += quantity;
+        self.notional += quantity * price;
+        self.status = if self.cum_qty >= self.quantity {
+            ord_status::FILLED
+        } else {
+            ord_status::PARTIALLY_FILLED
+        };
+    }
+
+    /// Average fill price in cents
+    pub fn avg_px(&self) -> String {
+        if self.cum_qty == 0 {
+            "0".to_string()
+        } else {
+            format!("{:.2}", self.notional as f64 / self.cum_qty as f64)
+        }
+    }
+}
+
+/// ExecutionReport for a tracked order
+pub fn execution_report(
+    order: &TrackedOrder,
+    exec_type: &str,
+    exec_id: &str,
+    account_id: i64,
+) -> FixMessage {
+    let mut report = FixMessage::new(msg_type::EXECUTION_REPORT)
+        .with(tags::ORDER_ID, order.order_id)
+        .with(tags::EXEC_ID, exec_id)
+        .with(tags::EXEC_TYPE, exec_type)
+        .with(tags::ORD_STATUS, order.status)
+        .with(tags::ACCOUNT, account_id)
+        .with(tags::SYMBOL, &order.symbol)
+        .with(tags::SIDE, &order.side)
+        .with(tags::ORD_TYPE, &order.ord_type)
+        .with(tags::ORDER_QTY, order.quantity)
+        .with(tags::LEAVES_QTY, order.leaves_qty())
+        .with(tags::CUM_QTY, order.cum_qty)
+        .with(tags::AVG_PX, order.avg_px())
+        .with(tags::TRANSACT_TIME, utc_timestamp(chrono::Utc::now()));
+
+    if order.price > 0 {
+        report.set(tags::PRICE, order.price);
+    }
+    if let Some(cl_ord_id) = &order.cl_ord_id {
+        report.set(tags::CL_ORD_ID, cl_ord_id);
+    }
+    if let Some(orig_cl_ord_id) = &order.orig_cl_ord_id {
+        report.set(tags::ORIG_CL_ORD_ID, orig_cl_ord_id);
+    }
+    report
+}
+
+/// ExecutionReport (ExecType=Trade) for a fill
+pub fn fill_report(
+    order: &TrackedOrder,
+    exec_id: &str,
+    last_qty: i64,
+    last_px: i64,
+    account_id: i64,
+) -> FixMessage {
+    execution_report(order, exec_type::TRADE, exec_id, account_id)
+        .with(tags::LAST_QTY, last_qty)
+        .with(tags::LAST_PX, last_px)
+}
+
+/// ExecutionReport rejecting a NewOrderSingle that never reached the book
+pub fn order_reject(request: &FixMessage, account_id: i64, text: &str) -> FixMessage {
+    let mut report = FixMessage::new(msg_type::EXECUTION_REPORT)
+        .with(tags::ORDER_ID, "NONE")
+        .with(tags::EXEC_ID, new_exec_id())
+        .with(tags::EXEC_TYPE, exec_type::REJECTED)
+        .with(tags::ORD_STATUS, ord_status::REJECTED)
+        .with(tags::ORD_REJ_REASON, REJECT_REASON_OTHER)
+        .with(tags::ACCOUNT, account_id)
+        .with(tags::LEAVES_QTY, 0)
+        .with(tags::CUM_QTY, 0)
+        .with(tags::AVG_PX, 0)
+        .with(tags::TEXT, text)
+        .with(tags::TRANSACT_TIME, utc_timestamp(chrono::Utc::now()));
+
+    for tag in [tags::CL_ORD_ID, tags::SYMBOL, tags::SIDE, tags::ORD_TYPE, tags::ORDER_QTY] {
+        if let Some(value) = request.get(tag) {
+            report.set(tag, value);
+        }
+    }
+    report
+}
+
+/// OrderCancelReject for a cancel or cancel/replace request
+pub fn cancel_reject(
+    request: &FixMessage,
+    order: Option<&TrackedOrder>,
+    response_to: &str,
+    text: &str,
+) -> FixMessage {
+    let order_id = order.map_or_else(|| "NONE".to_string(), |o| o.order_id.to_string());
+    let status = order.map_or(ord_status::REJECTED, |o| o.status);
+
+    FixMessage::new(msg_type::ORDER_CANCEL_REJECT)
+        .with(tags::ORDER_ID, order_id)
+        .with(tags::CL_ORD_ID, request.get(tags::CL_ORD_ID).unwrap_or("NONE"))
+        .with(tags::ORIG_CL_ORD_ID, request.get(tags::ORIG_CL_ORD_ID).unwrap_or("NONE"))
+        .with(tags::ORD_STATUS, status)
+        .with(tags::CXL_REJ_RESPONSE_TO, response_to)
+        .with(tags::CXL_REJ_REASON, REJECT_REASON_OTHER)
+        .with(tags::TEXT, text)
+}
+
+/// Translate a NewOrderSingle (or the new terms of a cancel/replace) into a gateway order
+pub fn order_request_from_fix(message: &FixMessage) -> Result<OrderPlaceRequest, String> {
+    let required = |tag: u32, name: &str| {
+        message.get(tag).map(str::to_string).ok_or_else(|| format!("Missing {name} ({tag})"))
+    };
+
+    let client_order_id = required(tags::CL_ORD_ID, "ClOrdID")?;
+    let symbol = required(tags::SYMBOL, "Symbol")?;
+
+    let side = match message.get(tags::SIDE) {
+        Some("1") => "BUY",
+        Some("2") => "SELL",
+        other => return Err(format!("Unsupported Side: {}", other.unwrap_or("missing"))),
+    };
+
+    let quantity = message
+        .get_u64(tags::ORDER_QTY)
+        .filter(|q| *q > 0)
+        .ok_or_else(|| "OrderQty (38) must be a positive integer".to_string())?;
+
+    let post_only = message.get(tags::EXEC_INST).is_some_and(|inst| inst.contains('6'));
+    let ioc = message.get(tags::TIME_IN_FORCE) == Some("3");
+    let day = message.get(tags::TIME_IN_FORCE) == Some("0");
+
+    let (order_type, price) = match message.get(tags::ORD_TYPE) {
+        Some("1") => ("MARKET", 0),
+        Some("2") => {
+            let price = message
+                .get(tags::PRICE)
+                .and_then(parse_price)
+                .ok_or_else(|| "Limit orders need an integer Price (44) in cents".to_string())?;
+            let order_type = match (post_only, ioc) {
+                (true, _) => "POST_ONLY",
+                (false, true) => "IOC",
+                (false, false) => "LIMIT",
+            };
+            (order_type, price)
+        }
+        other => return Err(format!("Unsupported OrdType: {}", other.unwrap_or("missing"))),
+    };
+
+    Ok(OrderPlaceRequest {
+        symbol,
+        side: side.to_string(),
+        r#type: order_type.to_string(),
+        price,
+        quantity,
+        client_order_id: Some(client_order_id),
+        time_in_force: day.then(|| "DAY".to_string()),
+    })
+}
+
+/// Parse a price in cents, tolerating a zero fractional part (`3500.00`)
+fn parse_price(value: &str) -> Option<u32> {
+    let whole = match value.split_once('.') {
+        Some((whole, fraction)) if fraction.bytes().all(|b| b == b'0') => whole,
+        Some(_) => return None,
+        None => value,
+    };
+    whole.parse().ok().filter(|price| *price > 0)
+}
+
+fn fix_side(side: &str) -> &'static str {
+    if side.eq_ignore_ascii_case("SELL") {
+        "2"
+    } else {
+        "1"
+    }
+}
+
+fn fix_ord_type(order_type: &str) -> &'static str {
+    if order_type.eq_ignore_ascii_case("MARKET") {
+        "1"
+    } else {
+        "2"
+    }
+}
+
+/// Unique ExecID for reports that don't come from an engine execution
+pub fn new_exec_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// ExecID for one side of an engine execution
+pub fn trade_exec_id(execution_id: u64, order_id: u64) -> String {
+    format!("{execution_id}-{order_id}")
+}
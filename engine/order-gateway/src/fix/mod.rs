@@ -0,0 +1,12 @@
+//! FIX 4.4 order entry and drop copy
+//!
+//! The acceptor is served by the OrderGateway next to the WebSocket and REST APIs and
+//! places orders through the same [`OrderEntry`](crate::order_entry::OrderEntry).
+
+pub mod acceptor;
+pub mod codec;
+pub mod execution;
+pub mod session;
+
+pub use acceptor::FixAcceptor;
+pub use codec::FixMessage;
@@ -0,0 +1,404 @@
+//! FIX session state: sequence numbers, the resend store and tracked orders
+//!
+//! State is keyed by [`SessionKey`] and outlives the TCP connection, so a client that
+//! reconnects without `ResetSeqNumFlag` continues its sequence numbers and can ask for
+//! anything it missed with a ResendRequest.
+
+use super::codec::{msg_type, tags, utc_timestamp, FixMessage};
+use super::execution::TrackedOrder;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Kind of FIX session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionKind {
+    /// Order entry with execution reports for the session's own orders
+    OrderEntry,
+    /// Read-only copy of execution reports for every order of the account
+    DropCopy,
+}
+
+/// Identifies a FIX session across reconnects
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    /// Account the logon authenticated as
+    pub account_id: i64,
+
+    /// Client's SenderCompID
+    pub sender_comp_id: String,
+
+    /// Order entry or drop copy
+    pub kind: SessionKind,
+}
+
+/// Result of checking an inbound MsgSeqNum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InboundSeq {
+    /// The expected sequence number; the message should be processed
+    InOrder,
+    /// A gap was detected; request a resend from `expected`
+    Gap { expected: u64 },
+    /// A gap is already being resent; drop the message, it will be replayed
+    GapPending,
+    /// A possible duplicate of an already processed message; ignore it
+    Duplicate,
+    /// Lower than expected without PossDupFlag; the session must be terminated
+    TooLow { expected: u64 },
+}
+
+/// Per-session sequence and order state
+#[derive(Debug)]
+pub struct SessionState {
+    /// Next MsgSeqNum the acceptor will send
+    next_sender_seq: u64,
+
+    /// Next MsgSeqNum expected from the client
+    next_target_seq: u64,
+
+    /// Highest MsgSeqNum seen while a gap is outstanding
+    resend_up_to: Option<u64>,
+
+    /// Recently sent application messages, oldest first, for resends
+    sent: VecDeque<(u64, FixMessage)>,
+
+    /// Maximum number of messages kept in `sent`
+    resend_buffer_size: usize,
+
+    /// Orders reported on this session, by engine order ID
+    pub orders: HashMap<u64, TrackedOrder>,
+}
+
+impl SessionState {
+    /// Create a session starting at sequence number 1
+    pub fn new(resend_buffer_size: usize) -> Self {
+        Self {
+            next_sender_seq: 1,
+            next_target_seq: 1,
+            resend_up_to: None,
+            sent: VecDeque::new(),
+            resend_buffer_size,
+            orders: HashMap::new(),
+        }
+    }
+
+    /// Reset both sequence numbers to 1 (`ResetSeqNumFlag=Y`)
+    pub fn reset(&mut self) {
+        self.next_sender_seq = 1;
+        self.next_target_seq = 1;
+        self.resend_up_to = None;
+        self.sent.clear();
+    }
+
+    /// Next MsgSeqNum the acceptor will send
+    pub fn next_sender_seq(&self) -> u64 {
+        self.next_sender_seq
+    }
+
+    /// Next MsgSeqNum expected from the client
+    pub fn next_target_seq(&self) -> u64 {
+        self.next_target_seq
+    }
+
+    /// Fill in the standard header, assign the next sequence number and remember
+    /// application messages for resends
+    pub fn stamp(&mut self, mut message: FixMessage, header: &SessionHeader) -> FixMessage {
+        message.set(tags::MSG_SEQ_NUM, self.next_sender_seq);
+        header.apply(&mut message, chrono::Utc::now());
+
+        if !msg_type::is_admin(message.msg_type()) {
+            self.sent.push_back((self.next_sender_seq, message.clone()));
+            while self.sent.len() > self.resend_buffer_size {
+                self.sent.pop_front();
+            }
+        }
+
+        self.next_sender_seq += 1;
+        message
+    }
+
+    /// Check an inbound MsgSeqNum, advancing the expected number when in order
+    pub fn check_inbound(&mut self, seq_num: u64, poss_dup: bool) -> InboundSeq {
+        if seq_num == self.next_target_seq {
+            self.next_target_seq += 1;
+            if self.resend_up_to.is_some_and(|up_to| self.next_target_seq > up_to) {
+                self.resend_up_to = None;
+            }
+            return InboundSeq::InOrder;
+        }
+
+        if seq_num < self.next_target_seq {
+            return if poss_dup {
+                InboundSeq::Duplicate
+            } else {
+                InboundSeq::TooLow { expected: self.next_target_seq }
+            };
+        }
+
+        match self.resend_up_to {
+            Some(up_to) => {
+                self.resend_up_to = Some(up_to.max(seq_num));
+                InboundSeq::GapPending
+            }
+            None => {
+                self.resend_up_to = Some(seq_num);
+                InboundSeq::Gap { expected: self.next_target_seq }
+            }
+        }
+    }
+
+    /// Apply a SequenceReset; gap fills may only move the expected number forward
+    pub fn apply_sequence_reset(&mut self, new_seq_no: u64, gap_fill: bool) -> bool {
+        if gap_fill && new_seq_no < self.next_target_seq {
+            return false;
+        }
+        self.next_target_seq = new_seq_no;
+        if self.resend_up_to.is_some_and(|up_to| self.next_target_seq > up_to) {
+            self.resend_up_to = None;
+        }
+        true
+    }
+
+    /// Build the replay for a ResendRequest
+    ///
+    /// Stored application messages are resent with `PossDupFlag=Y`; admin messages and
+    /// anything no longer in the buffer are covered by SequenceReset-GapFill messages.
+    pub fn resend(&self, begin: u64, end: u64, header: &SessionHeader) -> Vec<FixMessage> {
+        let last_sent = self.next_sender_seq.saturating_sub(1);
+        let end = if end == 0 || end > last_sent { last_sent } else { end };
+        let now = chrono::Utc::now();
+
+        let mut replay = Vec::new();
+        let mut gap_start: Option<u64> = None;
+        let mut seq = begin.max(1);
+
+        while seq <= end {
+            let stored = self.sent.iter().find(|(s, _)| *s == seq).map(|(_, m)| m);
+            match stored {
+                Some(original) => {
+                    if let Some(start) = gap_start.take() {
+                        replay.push(gap_fill(start, seq, header, now));
+                    }
+                    let mut message = original.clone();
+                    if let Some(sent_at) = original.get(tags::SENDING_TIME) {
+                        message.set(tags::ORIG_SENDING_TIME, sent_at.to_string());
+                    }
+                    message.set(tags::POSS_DUP_FLAG, "Y");
+                    message.set(tags::SENDING_TIME, utc_timestamp(now));
+                    replay.push(message);
+                }
+                None => {
+                    gap_start.get_or_insert(seq);
+                }
+            }
+            seq += 1;
+        }
+
+        if let Some(start) = gap_start {
+            replay.push(gap_fill(start, end + 1, header, now));
+        }
+
+        replay
+    }
+}
+
+/// SequenceReset-GapFill covering `[start, new_seq_no)`
+fn gap_fill(
+    start: u64,
+    new_seq_no: u64,
+    header: &SessionHeader,
+    now: chrono::DateTime<chrono::Utc>,
+) -> FixMessage {
+    let mut message = FixMessage::new(msg_type::SEQUENCE_RESET)
+        .with(tags::MSG_SEQ_NUM, start)
+        .with(tags::POSS_DUP_FLAG, "Y")
+        .with(tags::GAP_FILL_FLAG, "Y")
+        .with(tags::NEW_SEQ_NO, new_seq_no);
+    header.apply(&mut message, now);
+    message.set(tags::ORIG_SENDING_TIME, utc_timestamp(now));
+    message
+}
+
+/// CompIDs stamped on every outbound message of a session
+#[derive(Debug, Clone)]
+pub struct SessionHeader {
+    /// Acceptor's CompID (the client's TargetCompID)
+    pub sender_comp_id: String,
+
+    /// Client's CompID
+    pub target_comp_id: String,
+}
+
+impl SessionHeader {
+    fn apply(&self, message: &mut FixMessage, now: chrono::DateTime<chrono::Utc>) {
+        message.set(tags::SENDER_COMP_ID, &self.sender_comp_id);
+        message.set(tags::TARGET_COMP_ID, &self.target_comp_id);
+        message.set(tags::SENDING_TIME, utc_timestamp(now));
+    }
+}
+
+/// Session state shared across reconnects, plus the set of sessions logged on right now
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    sessions: Mutex<HashMap<SessionKey, Arc<Mutex<SessionState>>>>,
+    active: parking_lot::Mutex<HashSet<SessionKey>>,
+}
+
+impl SessionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get (or create) the state for a session
+    pub async fn state(
+        &self,
+        key: &SessionKey,
+        resend_buffer_size: usize,
+    ) -> Arc<Mutex<SessionState>> {
+        let mut sessions = self.sessions.lock().await;
+        sessions
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(SessionState::new(resend_buffer_size))))
+            .clone()
+    }
+
+    /// Mark a session logged on; false if it already is
+    pub fn activate(&self, key: &SessionKey) -> bool {
+        self.active.lock().insert(key.clone())
+    }
+
+    /// Mark a session logged off
+    pub fn deactivate(&self, key: &SessionKey) {
+        self.active.lock().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> SessionHeader {
+        SessionHeader { sender_comp_id: "EXCH".to_string(), target_comp_id: "CLIENT".to_string() }
+    }
+
+    /// (MsgType, MsgSeqNum, NewSeqNo) of each replayed message
+    fn summarize(replay: &[FixMessage]) -> Vec<(String, u64, Option<u64>)> {
+        replay
+            .iter()
+            .map(|m| {
+                (
+                    m.msg_type().to_string(),
+                    m.get_u64(tags::MSG_SEQ_NUM).unwrap(),
+                    m.get_u64(tags::NEW_SEQ_NO),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_inbound_detects_gaps_and_duplicates() {
+        let mut state = SessionState::new(10);
+        assert_eq!(state.check_inbound(1, false), InboundSeq::InOrder);
+        assert_eq!(state.check_inbound(2, false), InboundSeq::InOrder);
+
+        // 3 and 4 are missing
+        assert_eq!(state.check_inbound(5, false), InboundSeq::Gap { expected: 3 });
+        assert_eq!(state.check_inbound(6, false), InboundSeq::GapPending);
+        assert_eq!(state.next_target_seq(), 3);
+
+        // Replays of already processed messages
+        assert_eq!(state.check_inbound(2, true), InboundSeq::Duplicate);
+        assert_eq!(state.check_inbound(1, false), InboundSeq::TooLow { expected: 3 });
+
+        // The resend closes the gap once it passes the highest number seen
+        for seq in 3..=5 {
+            assert_eq!(state.check_inbound(seq, true), InboundSeq::InOrder);
+        }
+        assert_eq!(state.check_inbound(8, false), InboundSeq::GapPending);
+        assert_eq!(state.check_inbound(6, false), InboundSeq::InOrder);
+        assert_eq!(state.check_inbound(7, false), InboundSeq::InOrder);
+        assert_eq!(state.check_inbound(8, false), InboundSeq::InOrder);
+        assert_eq!(state.check_inbound(10, false), InboundSeq::Gap { expected: 9 });
+    }
+
+    #[test]
+    fn test_apply_sequence_reset() {
+        let mut state = SessionState::new(10);
+        for seq in 1..=4 {
+            state.check_inbound(seq, false);
+        }
+
+        // A gap fill may not move backwards
+        assert!(!state.apply_sequence_reset(3, true));
+        assert_eq!(state.next_target_seq(), 5);
+
+        // A gap fill over an outstanding gap clears it
+        assert_eq!(state.check_inbound(8, false), InboundSeq::Gap { expected: 5 });
+        assert!(state.apply_sequence_reset(9, true));
+        assert_eq!(state.next_target_seq(), 9);
+        assert_eq!(state.check_inbound(11, false), InboundSeq::Gap { expected: 9 });
+
+        // Reset mode may move either way
+        assert!(state.apply_sequence_reset(2, false));
+        assert_eq!(state.next_target_seq(), 2);
+        assert_eq!(state.check_inbound(2, false), InboundSeq::InOrder);
+    }
+
+    #[test]
+    fn test_resend_replays_application_messages_and_gap_fills_the_rest() {
+        let header = header();
+        let mut state = SessionState::new(10);
+        // 1 Logon, 2-3 ExecutionReports, 4-5 Heartbeats, 6 ExecutionReport
+        state.stamp(FixMessage::new(msg_type::LOGON), &header);
+        state.stamp(FixMessage::new(msg_type::EXECUTION_REPORT), &header);
+        state.stamp(FixMessage::new(msg_type::EXECUTION_REPORT), &header);
+        state.stamp(FixMessage::new(msg_type::HEARTBEAT), &header);
+        state.stamp(FixMessage::new(msg_type::HEARTBEAT), &header);
+        state.stamp(FixMessage::new(msg_type::EXECUTION_REPORT), &header);
+
+        let replay = state.resend(1, 0, &header);
+        assert_eq!(
+            summarize(&replay),
+            vec![
+                ("4".to_string(), 1, Some(2)),
+                ("8".to_string(), 2, None),
+                ("8".to_string(), 3, None),
+                ("4".to_string(), 4, Some(6)),
+                ("8".to_string(), 6, None),
+            ]
+        );
+        assert!(replay.iter().all(|m| m.is_poss_dup()));
+        assert!(replay.iter().all(|m| m.get(tags::ORIG_SENDING_TIME).is_some()));
+        assert!(replay
+            .iter()
+            .filter(|m| m.msg_type() == msg_type::SEQUENCE_RESET)
+            .all(|m| m.get_bool(tags::GAP_FILL_FLAG)));
+
+        // A range ending on admin messages is closed by a trailing gap fill, and
+        // an end past the last sent message is clamped
+        assert_eq!(
+            summarize(&state.resend(3, 5, &header)),
+            vec![("8".to_string(), 3, None), ("4".to_string(), 4, Some(6))]
+        );
+        assert_eq!(summarize(&state.resend(6, 100, &header)), vec![("8".to_string(), 6, None)]);
+    }
+
+    #[test]
+    fn test_resend_gap_fills_evicted_messages() {
+        let header = header();
+        let mut state = SessionState::new(2);
+        for _ in 0..4 {
+            state.stamp(FixMessage::new(msg_type::EXECUTION_REPORT), &header);
+        }
+
+        assert_eq!(
+            summarize(&state.resend(1, 0, &header)),
+            vec![
+                ("4".to_string(), 1, Some(3)),
+                ("8".to_string(), 3, None),
+                ("8".to_string(), 4, None),
+            ]
+        );
+    }
+}
@@ -2,21 +2,30 @@
 
 use crate::auth::AuthManager;
 use crate::config::GatewayConfig;
+use crate::disconnect::DisconnectReports;
+use crate::equity_stream::EquityThrottle;
 use crate::error::{GatewayError, GatewayResult};
+use crate::fix::FixAcceptor;
 use crate::market_data_broadcaster::MarketDataBroadcaster;
+use crate::order_entry::OrderEntry;
 use crate::rate_limiter::RateLimiter;
+use crate::rest_api;
 use crate::websocket_handler::WebSocketHandler;
 
-use std::collections::HashMap;
+use equity_service::{ContestService, EquityValuationService};
+use fair_price_bus::FairPriceBus;
+use trading_calendar::{SessionEvent, SessionScheduler};
+
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
-use warp::Filter;
 
 // OrderRouter integration
 use account_service::AccountService;
+use execution_manager::ExecutionManager;
 use order_router::{
     CoordError as OrderRouterCoordError, OrderRouter, ReadyAtTick as OrderRouterReadyAtTick,
     RouterConfig, SymbolCoordinatorApi as OrderRouterApi,
@@ -69,18 +78,30 @@ pub struct OrderGateway {
     /// Market data broadcaster
     market_data_broadcaster: Arc<MarketDataBroadcaster>,
 
-    /// Order router for routing orders to trading engines
-    order_router: Arc<RwLock<OrderRouter>>,
-
-    /// Symbol coordinator for managing trading engines
-    symbol_coordinator: Arc<SymbolCoordinator>,
-
-    /// Player registry for mapping player names to symbol IDs
-    player_registry: Arc<RwLock<PlayerRegistry>>,
+    /// Order entry (validation, reservations and routing) shared by WebSocket and REST
+    order_entry: Arc<OrderEntry>,
 
     /// Account service for balance and position validation
     account_service: Arc<AccountService>,
 
+    /// Mass-cancel reports awaiting the next login of each account
+    disconnect_reports: Arc<DisconnectReports>,
+
+    /// Execution event source for FIX ExecutionReports
+    execution_manager: Option<Arc<ExecutionManager>>,
+
+    /// Source of pushed fair prices for the `fair_price` stream
+    fair_price_bus: Option<FairPriceBus>,
+
+    /// Trading calendar driving DAY-order expiry and the session admin endpoints
+    session_scheduler: Option<SessionScheduler>,
+
+    /// Source of per-account updates for the `equity` stream
+    equity_service: Option<Arc<EquityValuationService>>,
+
+    /// Contests behind the contest endpoints and the `leaderboard` stream
+    contest_service: Option<Arc<ContestService>>,
+
     /// Connection count
     connection_count: Arc<RwLock<usize>>,
 
@@ -96,12 +117,13 @@ impl OrderGateway {
         player_registry: PlayerRegistry,
         account_service: Arc<AccountService>,
     ) -> Self {
-        let auth_manager = Arc::new(AuthManager::new(account_service.clone()));
+        let auth_manager = Arc::new(AuthManager::new(account_service.clone(), config.auth.clone()));
         let rate_limiter = Arc::new(RateLimiter::new(config.rate_limits.clone()));
         let market_data_broadcaster = Arc::new(MarketDataBroadcaster::new());
 
         // Create OrderRouter with default configuration
         let router_config = RouterConfig::default();
+        let prewarm_top_k = router_config.prewarm_top_k as usize;
         let mut order_router = OrderRouter::new(router_config);
 
         // Set the SymbolCoordinator in the OrderRouter using the adapter
@@ -109,20 +131,67 @@ impl OrderGateway {
         let coordinator_box: Box<dyn OrderRouterApi> = Box::new(adapter);
         order_router.set_coordinator(coordinator_box);
 
+        // Boot the engines of the most-traded players before their first order
+        let top_players: Vec<u32> = player_registry
+            .get_top_players(prewarm_top_k)
+            .iter()
+            .map(|player| player.symbol_id)
+            .collect();
+        order_router.prewarm(&top_players);
+
+        let order_entry = Arc::new(OrderEntry::new(
+            Arc::new(order_router),
+            symbol_coordinator,
+            Arc::new(RwLock::new(player_registry)),
+            account_service.clone(),
+            rate_limiter.clone(),
+            config.orders.clone(),
+        ));
+
         Self {
             config,
             auth_manager,
             rate_limiter,
             market_data_broadcaster,
-            order_router: Arc::new(RwLock::new(order_router)),
-            symbol_coordinator,
-            player_registry: Arc::new(RwLock::new(player_registry)),
+            order_entry,
             account_service,
+            disconnect_reports: Arc::new(DisconnectReports::new()),
+            execution_manager: None,
+            fair_price_bus: None,
+            session_scheduler: None,
+            equity_service: None,
+            contest_service: None,
             connection_count: Arc::new(RwLock::new(0)),
             is_running: Arc::new(RwLock::new(false)),
         }
     }
 
+    /// Provide the ExecutionManager whose events drive FIX ExecutionReports and the
+    /// WebSocket `execution` stream
+    pub fn set_execution_manager(&mut self, execution_manager: Arc<ExecutionManager>) {
+        self.execution_manager = Some(execution_manager);
+    }
+
+    /// Provide the fair price bus whose updates drive the `fair_price` stream
+    pub fn set_fair_price_bus(&mut self, bus: FairPriceBus) {
+        self.fair_price_bus = Some(bus);
+    }
+
+    /// Provide the session scheduler whose closes expire DAY orders
+    pub fn set_session_scheduler(&mut self, scheduler: SessionScheduler) {
+        self.session_scheduler = Some(scheduler);
+    }
+
+    /// Provide the equity valuation service whose updates drive the `equity` stream
+    pub fn set_equity_service(&mut self, equity_service: Arc<EquityValuationService>) {
+        self.equity_service = Some(equity_service);
+    }
+
+    /// Provide the contests served on the contest endpoints and the `leaderboard` stream
+    pub fn set_contest_service(&mut self, contest_service: Arc<ContestService>) {
+        self.contest_service = Some(contest_service);
+    }
+
     /// Start the OrderGateway server
     pub async fn start(&self) -> GatewayResult<()> {
         let addr = self
@@ -142,7 +211,14 @@ impl OrderGateway {
 
         // Start background tasks
         let _cleanup_task = self.start_cleanup_task();
+        let _api_key_task = self.start_api_key_invalidation_task();
         let _market_data_task = self.start_market_data_task();
+        let _order_rest_task = self.start_order_rest_server();
+        let _fix_task = self.start_fix_acceptor();
+        let _fair_price_task = self.start_fair_price_task();
+        let _session_task = self.start_session_task();
+        let _equity_task = self.start_equity_task();
+        let _leaderboard_task = self.start_leaderboard_task();
 
         // Create TCP listener
         let listener = TcpListener::bind(addr).await?;
@@ -191,11 +267,17 @@ impl OrderGateway {
             self.auth_manager.clone(),
             self.rate_limiter.clone(),
             self.market_data_broadcaster.clone(),
-            self.order_router.clone(),
-            self.symbol_coordinator.clone(),
-            self.player_registry.clone(),
+            self.order_entry.clone(),
             self.account_service.clone(),
+            self.config.clone(),
+            self.disconnect_reports.clone(),
         );
+        if let Some(execution_manager) = &self.execution_manager {
+            handler.set_execution_manager(execution_manager.clone());
+        }
+        if let Some(contest_service) = &self.contest_service {
+            handler.set_contest_service(contest_service.clone());
+        }
 
         // Handle the connection
         let connection_count = self.connection_count.clone();
@@ -215,6 +297,7 @@ impl OrderGateway {
     /// Start the cleanup task for expired sessions
     fn start_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
         let auth_manager = self.auth_manager.clone();
+        let disconnect_reports = self.disconnect_reports.clone();
         let is_running = self.is_running.clone();
 
         tokio::spawn(async move {
@@ -233,10 +316,94 @@ impl OrderGateway {
 
                 // Clean up expired sessions (1 hour timeout)
                 auth_manager.cleanup_expired_sessions(3600).await;
+
+                // Drop mass-cancel reports nobody logged in to collect
+                let dropped = disconnect_reports.prune().await;
+                if dropped > 0 {
+                    info!("Dropped {} expired disconnect reports", dropped);
+                }
+            }
+        })
+    }
+
+    /// End the sessions of API keys revoked or rotated, here or by the admin REST server
+    fn start_api_key_invalidation_task(&self) -> tokio::task::JoinHandle<()> {
+        let auth_manager = self.auth_manager.clone();
+        let repository = self.account_service.api_keys();
+
+        tokio::spawn(async move {
+            let mut listener = match repository.listen_invalidated().await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to listen for revoked API keys: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        auth_manager.end_api_key_sessions(notification.payload()).await;
+                    }
+                    Err(e) => {
+                        // The listener reconnects on the next receive
+                        warn!("Lost the API key revocation listener: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
             }
         })
     }
 
+    /// Serve the REST order entry endpoints, if a port is configured
+    fn start_order_rest_server(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let port = self.config.server.rest_port?;
+        let addr: SocketAddr = match format!("{}:{}", self.config.server.host, port).parse() {
+            Ok(addr) => addr,
+            Err(e) => {
+                error!("Invalid REST order entry address: {}", e);
+                return None;
+            }
+        };
+
+        let routes = rest_api::create_order_routes(
+            self.order_entry.clone(),
+            self.auth_manager.clone(),
+            self.session_scheduler.clone(),
+            self.contest_service.clone(),
+            self.equity_service.clone(),
+            Arc::new(self.account_service.db_pool()),
+        );
+        info!("Starting REST order entry on {}", addr);
+
+        Some(tokio::spawn(warp::serve(routes).run(addr)))
+    }
+
+    /// Serve the FIX acceptor, if a port is configured
+    fn start_fix_acceptor(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let port = self.config.fix.port?;
+        let addr: SocketAddr = match format!("{}:{}", self.config.server.host, port).parse() {
+            Ok(addr) => addr,
+            Err(e) => {
+                error!("Invalid FIX acceptor address: {}", e);
+                return None;
+            }
+        };
+
+        let acceptor = Arc::new(FixAcceptor::new(
+            self.config.fix.clone(),
+            self.auth_manager.api_key_store(),
+            self.order_entry.clone(),
+            self.execution_manager.clone(),
+        ));
+
+        Some(tokio::spawn(async move {
+            if let Err(e) = acceptor.run(addr).await {
+                error!("FIX acceptor failed: {}", e);
+            }
+        }))
+    }
+
     /// Start the market data broadcasting task
     fn start_market_data_task(&self) -> tokio::task::JoinHandle<()> {
         let broadcaster = self.market_data_broadcaster.clone();
@@ -264,6 +431,120 @@ impl OrderGateway {
         })
     }
 
+    /// Forward fair price bus updates to clients, if a bus is configured
+    fn start_fair_price_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let bus = self.fair_price_bus.as_ref()?;
+        let mut subscription = bus.subscribe();
+        let initial = bus.snapshot();
+        let broadcaster = self.market_data_broadcaster.clone();
+
+        Some(tokio::spawn(async move {
+            for update in initial {
+                if let Err(e) = broadcaster.send_fair_price_update(update).await {
+                    error!("Fair price broadcast error: {}", e);
+                }
+            }
+            while let Some(update) = subscription.recv().await {
+                if let Err(e) = broadcaster.send_fair_price_update(update).await {
+                    error!("Fair price broadcast error: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Forward equity updates to subscribed clients, throttled per account, if an equity
+    /// service is configured
+    ///
+    /// Updates for accounts nobody follows skip the throttle and only refresh the
+    /// broadcaster's cache; throttle state is pruned to subscribed accounts on every flush.
+    fn start_equity_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let mut updates = self.equity_service.as_ref()?.subscribe_updates();
+        let broadcaster = self.market_data_broadcaster.clone();
+        let interval = Duration::from_millis(self.config.market_data.equity_update_interval_ms);
+
+        Some(tokio::spawn(async move {
+            let mut throttle = EquityThrottle::new(interval);
+            let mut flush = tokio::time::interval(interval.max(Duration::from_millis(1)));
+            flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                let ready = tokio::select! {
+                    received = updates.recv() => match received {
+                        Ok(update) if broadcaster.has_equity_subscribers(update.account_id).await => {
+                            throttle.offer(update.into(), Instant::now()).into_iter().collect()
+                        }
+                        Ok(update) => vec![update.into()],
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Equity task lagged, skipped {} updates", skipped);
+                            Vec::new()
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = flush.tick() => {
+                        let subscribed = broadcaster.equity_subscribed_accounts().await;
+                        throttle.retain(|account_id| subscribed.contains(&account_id));
+                        throttle.take_due(Instant::now())
+                    }
+                };
+
+                for update in ready {
+                    if let Err(e) = broadcaster.send_equity_update(update.account_id, update).await
+                    {
+                        error!("Equity broadcast error: {}", e);
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Forward contest leaderboards to subscribed clients, if contests are configured
+    fn start_leaderboard_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let mut leaderboards = self.contest_service.as_ref()?.subscribe();
+        let broadcaster = self.market_data_broadcaster.clone();
+
+        Some(tokio::spawn(async move {
+            loop {
+                match leaderboards.recv().await {
+                    Ok(leaderboard) => {
+                        if let Err(e) = broadcaster.send_leaderboard_update(leaderboard).await {
+                            error!("Leaderboard broadcast error: {}", e);
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Leaderboard task lagged, skipped {} leaderboards", skipped);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+
+    /// Expire DAY orders when the session closes, if a scheduler is configured
+    fn start_session_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let mut events = self.session_scheduler.as_ref()?.subscribe();
+        let order_entry = self.order_entry.clone();
+
+        Some(tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(SessionEvent::ExpireDayOrders { trading_day, .. }) => {
+                        match order_entry.expire_day_orders().await {
+                            Ok(count) => {
+                                info!("Expired {} DAY orders for {}", count, trading_day)
+                            }
+                            Err(e) => error!("DAY order expiry for {} failed: {}", trading_day, e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Session task lagged, skipped {} events", skipped);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+
     /// Stop the OrderGateway
     pub async fn stop(&self) -> GatewayResult<()> {
         info!("Stopping OrderGateway...");
@@ -7,11 +7,15 @@
 pub mod auth;
 pub mod cache;
 pub mod config;
+pub mod disconnect;
+pub mod equity_stream;
 pub mod error;
+pub mod fix;
 pub mod gateway;
 pub mod market_data_broadcaster;
 pub mod messages;
 pub mod oauth;
+pub mod order_entry;
 pub mod rate_limiter;
 pub mod rest_api;
 pub mod websocket_handler;
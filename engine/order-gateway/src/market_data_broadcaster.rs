@@ -1,8 +1,10 @@
 //! Market data broadcasting for the OrderGateway
 
 use crate::error::GatewayError;
-use crate::messages::MarketDataUpdate;
-use std::collections::HashMap;
+use crate::messages::{EquityUpdate, MarketDataUpdate};
+use equity_service::Leaderboard;
+use fair_price_bus::FairPriceUpdate;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message;
@@ -14,6 +16,21 @@ pub struct MarketDataBroadcaster {
 
     /// Market data cache (symbol -> latest data)
     market_data_cache: Arc<RwLock<HashMap<String, MarketDataUpdate>>>,
+
+    /// Latest fair price per symbol, as pushed by the RPE
+    fair_price_cache: Arc<RwLock<HashMap<u32, FairPriceUpdate>>>,
+
+    /// Clients subscribed to fair prices (user_id -> symbols, `None` for all symbols)
+    fair_price_subscribers: Arc<RwLock<HashMap<String, Option<HashSet<u32>>>>>,
+
+    /// Clients subscribed to each account's equity (account_id -> user_ids)
+    equity_subscribers: Arc<RwLock<HashMap<i64, HashSet<String>>>>,
+
+    /// Latest equity update sent per account
+    equity_cache: Arc<RwLock<HashMap<i64, EquityUpdate>>>,
+
+    /// Clients subscribed to each contest's leaderboard (contest_id -> user_ids)
+    leaderboard_subscribers: Arc<RwLock<HashMap<i64, HashSet<String>>>>,
 }
 
 impl Default for MarketDataBroadcaster {
@@ -28,6 +45,11 @@ impl MarketDataBroadcaster {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
             market_data_cache: Arc::new(RwLock::new(HashMap::new())),
+            fair_price_cache: Arc::new(RwLock::new(HashMap::new())),
+            fair_price_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            equity_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            equity_cache: Arc::new(RwLock::new(HashMap::new())),
+            leaderboard_subscribers: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -45,6 +67,11 @@ impl MarketDataBroadcaster {
     pub async fn remove_client(&self, user_id: &str) {
         let mut clients = self.clients.write().await;
         clients.remove(user_id);
+        drop(clients);
+
+        self.unsubscribe_fair_prices(user_id).await;
+        self.unsubscribe_equity(user_id).await;
+        self.unsubscribe_leaderboards(user_id).await;
     }
 
     /// Update market data for a symbol
@@ -107,40 +134,253 @@ impl MarketDataBroadcaster {
         clients.len()
     }
 
-    /// Send equity update to a specific account
-    pub async fn send_equity_update(&self, account_id: i64, equity_update: crate::messages::EquityUpdate) -> Result<(), GatewayError> {
-        let clients = self.clients.read().await;
-        
-        // Find the client for this account (we need to map account_id to user_id)
-        // For now, we'll send to all clients - in production you'd want proper account-to-user mapping
-        if clients.is_empty() {
+    /// Subscribe a client to fair prices for some symbols (all symbols if `None`)
+    ///
+    /// Replaces the client's previous fair price subscription.
+    pub async fn subscribe_fair_prices(&self, user_id: String, symbols: Option<&[u32]>) {
+        let symbols = symbols.map(|symbols| symbols.iter().copied().collect());
+        self.fair_price_subscribers.write().await.insert(user_id, symbols);
+    }
+
+    /// Drop a client's fair price subscription
+    pub async fn unsubscribe_fair_prices(&self, user_id: &str) {
+        self.fair_price_subscribers.write().await.remove(user_id);
+    }
+
+    /// Send a fair price update to the symbol's subscribers on the `fair_price` stream
+    pub async fn send_fair_price_update(
+        &self,
+        update: FairPriceUpdate,
+    ) -> Result<(), GatewayError> {
+        {
+            let mut cache = self.fair_price_cache.write().await;
+            cache.insert(update.symbol_id, update.clone());
+        }
+
+        let user_ids: Vec<String> = self
+            .fair_price_subscribers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, symbols)| {
+                symbols.as_ref().is_none_or(|symbols| symbols.contains(&update.symbol_id))
+            })
+            .map(|(user_id, _)| user_id.clone())
+            .collect();
+        if user_ids.is_empty() {
             return Ok(());
         }
 
-        // Create equity update message
+        let fair_price_msg = serde_json::json!({
+            "stream": "fair_price",
+            "data": update
+        });
+
+        let message = Message::Text(serde_json::to_string(&fair_price_msg)?);
+
+        let clients = self.clients.read().await;
+        let mut failed_clients = Vec::new();
+        for user_id in user_ids {
+            match clients.get(&user_id) {
+                Some(sender) if sender.send(message.clone()).is_ok() => {}
+                _ => failed_clients.push(user_id),
+            }
+        }
+        drop(clients);
+
+        // Remove failed clients
+        for user_id in failed_clients {
+            self.remove_client(&user_id).await;
+        }
+
+        Ok(())
+    }
+
+    /// Latest fair prices, optionally limited to some symbols, ordered by symbol
+    pub async fn get_fair_prices(&self, symbols: Option<&[u32]>) -> Vec<FairPriceUpdate> {
+        let cache = self.fair_price_cache.read().await;
+        let mut prices: Vec<_> = match symbols {
+            Some(symbols) => symbols.iter().filter_map(|s| cache.get(s).cloned()).collect(),
+            None => cache.values().cloned().collect(),
+        };
+        prices.sort_by_key(|p| p.symbol_id);
+        prices
+    }
+
+    /// Subscribe a client to an account's equity; returns the latest update sent for it
+    pub async fn subscribe_equity(&self, account_id: i64, user_id: String) -> Option<EquityUpdate> {
+        let mut subscribers = self.equity_subscribers.write().await;
+        subscribers.entry(account_id).or_default().insert(user_id);
+        drop(subscribers);
+
+        self.equity_cache.read().await.get(&account_id).cloned()
+    }
+
+    /// Whether any client is subscribed to an account's equity
+    pub async fn has_equity_subscribers(&self, account_id: i64) -> bool {
+        self.equity_subscribers.read().await.contains_key(&account_id)
+    }
+
+    /// Accounts with at least one equity subscriber
+    pub async fn equity_subscribed_accounts(&self) -> HashSet<i64> {
+        self.equity_subscribers.read().await.keys().copied().collect()
+    }
+
+    /// Drop a client's equity subscriptions
+    async fn unsubscribe_equity(&self, user_id: &str) {
+        let mut subscribers = self.equity_subscribers.write().await;
+        subscribers.retain(|_, user_ids| {
+            user_ids.remove(user_id);
+            !user_ids.is_empty()
+        });
+    }
+
+    /// Send an equity update to the account's subscribers on the `equity` stream
+    pub async fn send_equity_update(
+        &self,
+        account_id: i64,
+        equity_update: EquityUpdate,
+    ) -> Result<(), GatewayError> {
+        {
+            let mut cache = self.equity_cache.write().await;
+            cache.insert(account_id, equity_update.clone());
+        }
+
+        let user_ids: Vec<String> = match self.equity_subscribers.read().await.get(&account_id) {
+            Some(user_ids) => user_ids.iter().cloned().collect(),
+            None => return Ok(()),
+        };
+
         let equity_msg = serde_json::json!({
-            "stream": "equity_update",
+            "stream": "equity",
             "data": equity_update
         });
 
         let message = Message::Text(serde_json::to_string(&equity_msg)?);
 
-        // Send to all clients (in production, filter by account_id)
+        let clients = self.clients.read().await;
         let mut failed_clients = Vec::new();
-        for (user_id, sender) in clients.iter() {
-            if sender.send(message.clone()).is_err() {
-                failed_clients.push(user_id.clone());
+        for user_id in user_ids {
+            match clients.get(&user_id) {
+                Some(sender) if sender.send(message.clone()).is_ok() => {}
+                _ => failed_clients.push(user_id),
             }
         }
+        drop(clients);
 
         // Remove failed clients
-        if !failed_clients.is_empty() {
-            let mut clients = self.clients.write().await;
-            for user_id in failed_clients {
-                clients.remove(&user_id);
+        for user_id in failed_clients {
+            self.remove_client(&user_id).await;
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe a client to a contest's leaderboard
+    pub async fn subscribe_leaderboard(&self, contest_id: i64, user_id: String) {
+        let mut subscribers = self.leaderboard_subscribers.write().await;
+        subscribers.entry(contest_id).or_default().insert(user_id);
+    }
+
+    /// Drop a client's leaderboard subscriptions
+    async fn unsubscribe_leaderboards(&self, user_id: &str) {
+        let mut subscribers = self.leaderboard_subscribers.write().await;
+        subscribers.retain(|_, user_ids| {
+            user_ids.remove(user_id);
+            !user_ids.is_empty()
+        });
+    }
+
+    /// Send a leaderboard to the contest's subscribers on the `leaderboard` stream
+    pub async fn send_leaderboard_update(
+        &self,
+        leaderboard: Leaderboard,
+    ) -> Result<(), GatewayError> {
+        let user_ids: Vec<String> =
+            match self.leaderboard_subscribers.read().await.get(&leaderboard.contest.id) {
+                Some(user_ids) => user_ids.iter().cloned().collect(),
+                None => return Ok(()),
+            };
+
+        let leaderboard_msg = serde_json::json!({
+            "stream": "leaderboard",
+            "data": leaderboard
+        });
+
+        let message = Message::Text(serde_json::to_string(&leaderboard_msg)?);
+
+        let clients = self.clients.read().await;
+        let mut failed_clients = Vec::new();
+        for user_id in user_ids {
+            match clients.get(&user_id) {
+                Some(sender) if sender.send(message.clone()).is_ok() => {}
+                _ => failed_clients.push(user_id),
             }
         }
+        drop(clients);
+
+        // Remove failed clients
+        for user_id in failed_clients {
+            self.remove_client(&user_id).await;
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn fair_price(symbol_id: u32) -> FairPriceUpdate {
+        FairPriceUpdate {
+            symbol_id,
+            fair_cents: 2450,
+            delta_cents: 35,
+            source: "rpe_live".to_string(),
+            ts: chrono::Utc::now(),
+        }
+    }
+
+    /// Symbol IDs of the `fair_price` messages a client has received
+    fn received(receiver: &mut mpsc::UnboundedReceiver<Message>) -> Vec<u64> {
+        let mut symbols = Vec::new();
+        while let Ok(Message::Text(text)) = receiver.try_recv() {
+            let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(message["stream"], "fair_price");
+            symbols.push(message["data"]["symbol_id"].as_u64().unwrap());
+        }
+        symbols
+    }
+
+    #[tokio::test]
+    async fn test_fair_prices_go_to_subscribers_only() {
+        let broadcaster = MarketDataBroadcaster::new();
+        let mut receivers = Vec::new();
+        for user_id in ["all", "some", "none"] {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            broadcaster.add_client(user_id.to_string(), sender).await;
+            receivers.push(receiver);
+        }
+        broadcaster.subscribe_fair_prices("all".to_string(), None).await;
+        broadcaster.subscribe_fair_prices("some".to_string(), Some(&[764])).await;
+
+        broadcaster.send_fair_price_update(fair_price(764)).await.unwrap();
+        broadcaster.send_fair_price_update(fair_price(765)).await.unwrap();
+        assert_eq!(received(&mut receivers[0]), vec![764, 765]);
+        assert_eq!(received(&mut receivers[1]), vec![764]);
+        assert!(received(&mut receivers[2]).is_empty());
+
+        // Unsubscribed clients stop receiving, but prices are still cached
+        broadcaster.unsubscribe_fair_prices("all").await;
+        broadcaster.send_fair_price_update(fair_price(766)).await.unwrap();
+        assert!(received(&mut receivers[0]).is_empty());
+        assert_eq!(broadcaster.get_fair_prices(None).await.len(), 3);
+
+        // Disconnected clients are dropped from the subscriber set
+        drop(receivers.remove(1));
+        broadcaster.send_fair_price_update(fair_price(764)).await.unwrap();
+        assert!(broadcaster.fair_price_subscribers.read().await.is_empty());
+    }
+}
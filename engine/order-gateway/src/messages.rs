@@ -57,6 +57,17 @@ pub struct OrderPlaceRequest {
 
     /// Client order ID (optional)
     pub client_order_id: Option<String>,
+
+    /// Time in force (GTC/DAY, default GTC); DAY orders are cancelled at the session close
+    #[serde(default)]
+    pub time_in_force: Option<String>,
+}
+
+/// Order cancel request (`order.cancel`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderCancelRequest {
+    /// Order ID as returned by `order.place` (`ord_123`)
+    pub order_id: String,
 }
 
 /// Order placement response
@@ -76,13 +87,30 @@ pub struct OrderPlaceResponse {
 }
 
 /// Authentication request
+///
+/// Either `api_secret` or the `timestamp`/`nonce`/`signature` triple must be set.
+/// The signature is a hex HMAC-SHA256 over `api_key\ntimestamp\nnonce`, keyed
+/// with the SHA-256 digest of the secret.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthRequest {
     /// API key
     pub api_key: String,
 
-    /// API secret
-    pub api_secret: String,
+    /// API secret (plain login)
+    #[serde(default)]
+    pub api_secret: Option<String>,
+
+    /// Unix timestamp in seconds (signed login)
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+
+    /// Single-use nonce (signed login)
+    #[serde(default)]
+    pub nonce: Option<String>,
+
+    /// Hex HMAC-SHA256 signature (signed login)
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
 /// JWT authentication request
@@ -92,6 +120,50 @@ pub struct JwtAuthRequest {
     pub token: String,
 }
 
+/// Session options request (`session.cancel_on_disconnect`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelOnDisconnectRequest {
+    /// Whether resting orders should be cancelled when the connection drops
+    pub enabled: bool,
+}
+
+/// Fair price stream request (`fair_price.subscribe`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FairPriceSubscribeRequest {
+    /// Symbols to stream and include in the initial snapshot (all symbols if omitted)
+    #[serde(default)]
+    pub symbols: Option<Vec<u32>>,
+}
+
+/// Leaderboard stream request (`leaderboard.subscribe`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardSubscribeRequest {
+    /// Contest to follow
+    pub contest_id: i64,
+}
+
+/// Outcome of a cancel-on-disconnect mass cancel, reported on the next login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MassCancelReport {
+    /// Account whose orders were cancelled
+    pub account_id: i64,
+
+    /// Why the connection was considered dead
+    pub reason: String,
+
+    /// Number of resting orders the session had placed
+    pub orders_targeted: usize,
+
+    /// Cancels accepted by the order router
+    pub cancels_routed: usize,
+
+    /// Order IDs whose cancel could not be routed
+    pub failed_order_ids: Vec<String>,
+
+    /// When the disconnect was detected (RFC 3339)
+    pub disconnected_at: String,
+}
+
 /// Authentication response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
@@ -178,6 +250,43 @@ pub struct OrderStatusUpdate {
     pub client_order_id: Option<String>,
 }
 
+/// Execution report pushed on the `execution` stream
+///
+/// Sent for every fill of the account's orders and for engine-confirmed cancels of orders
+/// placed on the same connection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionUpdate {
+    /// Order ID (`ord_123`)
+    pub order_id: String,
+
+    /// Symbol ID
+    pub symbol_id: u32,
+
+    /// TRADE or CANCELLED
+    pub exec_type: String,
+
+    /// Order side (BUY/SELL), for trades
+    pub side: Option<String>,
+
+    /// Fill price in cents, for trades
+    pub price: Option<u32>,
+
+    /// Fill quantity, for trades
+    pub quantity: Option<u64>,
+
+    /// MAKER or TAKER, for trades
+    pub liquidity: Option<String>,
+
+    /// Engine execution ID, for trades
+    pub execution_id: Option<u64>,
+
+    /// Cancel reason, if the engine gave one
+    pub reason: Option<String>,
+
+    /// Timestamp (milliseconds)
+    pub timestamp: u64,
+}
+
 /// User session information
 #[derive(Debug, Clone)]
 pub struct UserSession {
@@ -193,6 +302,15 @@ pub struct UserSession {
     /// Rate limits
     pub rate_limits: RateLimits,
 
+    /// API key used to open the session (None for JWT sessions)
+    pub api_key_id: Option<String>,
+
+    /// Symbols this session may trade (None = any)
+    pub allowed_symbols: Option<Vec<u32>>,
+
+    /// Cancel this session's resting orders when the connection drops
+    pub cancel_on_disconnect: bool,
+
     /// Session start time
     pub start_time: std::time::Instant,
 
@@ -209,7 +327,29 @@ impl UserSession {
         rate_limits: RateLimits,
     ) -> Self {
         let now = std::time::Instant::now();
-        Self { user_id, account_id, permissions, rate_limits, start_time: now, last_activity: now }
+        Self {
+            user_id,
+            account_id,
+            permissions,
+            rate_limits,
+            api_key_id: None,
+            allowed_symbols: None,
+            cancel_on_disconnect: false,
+            start_time: now,
+            last_activity: now,
+        }
+    }
+
+    /// Record the API key the session was opened with
+    pub fn with_api_key(mut self, key_id: String) -> Self {
+        self.api_key_id = Some(key_id);
+        self
+    }
+
+    /// Restrict the session to a set of symbols
+    pub fn with_allowed_symbols(mut self, allowed_symbols: Option<Vec<u32>>) -> Self {
+        self.allowed_symbols = allowed_symbols;
+        self
     }
 
     /// Update last activity time
@@ -221,6 +361,11 @@ impl UserSession {
     pub fn has_permission(&self, permission: &str) -> bool {
         self.permissions.contains(&permission.to_string())
     }
+
+    /// Check if the session may trade a symbol
+    pub fn can_trade_symbol(&self, symbol_id: u32) -> bool {
+        self.allowed_symbols.as_ref().is_none_or(|symbols| symbols.contains(&symbol_id))
+    }
 }
 
 /// Equity update message
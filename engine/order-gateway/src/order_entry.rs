@@ -0,0 +1,643 @@
+//! Order entry shared by the WebSocket and REST APIs
+//!
+//! Validation, client_order_id deduplication, order records, reservations and routing
+//! all live here so every entry point places and cancels orders the same way.
+
+use crate::config::OrderEntryConfig;
+use crate::error::{GatewayError, GatewayResult};
+use crate::messages::{OrderPlaceRequest, OrderPlaceResponse, UserSession};
+use crate::rate_limiter::RateLimiter;
+
+use account_service::{
+    AccountService, AccountServiceError, Balance, NewOrderRecord, OrderInsert, OrderRecord,
+    OrderStatus,
+};
+use order_router::{InboundMsgWithSymbol, OrderRouter, RouterError};
+use player_registry::PlayerRegistry;
+use std::sync::Arc;
+use symbol_coordinator::SymbolCoordinator;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use whistle::{AccountId, InboundMsg, OrderId, OrderType, Side, TickId};
+
+/// Result of an order placement
+#[derive(Debug, Clone)]
+pub struct PlacedOrder {
+    /// Response to hand back to the client
+    pub response: OrderPlaceResponse,
+
+    /// Engine order ID and symbol of a newly routed order that can rest on the book
+    pub resting: Option<(u64, u32)>,
+}
+
+/// Places, cancels and looks up orders on behalf of authenticated sessions
+pub struct OrderEntry {
+    /// Order router for routing orders
+    order_router: Arc<OrderRouter>,
+
+    /// Symbol coordinator for managing trading engines
+    symbol_coordinator: Arc<SymbolCoordinator>,
+
+    /// Player registry for mapping player names to symbol IDs
+    player_registry: Arc<RwLock<PlayerRegistry>>,
+
+    /// Account service for balance and position validation
+    account_service: Arc<AccountService>,
+
+    /// Rate limiter
+    rate_limiter: Arc<RateLimiter>,
+
+    /// Order entry configuration
+    config: OrderEntryConfig,
+}
+
+/// Parse an order ID as returned to clients (`ord_123`) or as a bare number
+#[allow(clippy::result_large_err)]
+pub fn parse_order_id(order_id: &str) -> GatewayResult<u64> {
+    order_id
+        .strip_prefix("ord_")
+        .unwrap_or(order_id)
+        .parse::<u64>()
+        .map_err(|_| GatewayError::InvalidOrder(format!("Invalid order ID: {order_id}")))
+}
+
+/// Hand back the order already holding a retried client_order_id
+fn duplicate_order(account_id: i64, existing: OrderRecord) -> PlacedOrder {
+    info!(
+        "Duplicate client_order_id {:?} for account {}, returning order {}",
+        existing.client_order_id, account_id, existing.order_id
+    );
+
+    let response = OrderPlaceResponse {
+        order_id: format!("ord_{}", existing.order_id),
+        status: existing.status,
+        timestamp: existing.created_at.timestamp_millis() as u64,
+        client_order_id: existing.client_order_id,
+    };
+    PlacedOrder { response, resting: None }
+}
+
+/// Time in force of an order request; orders without one are GTC
+#[allow(clippy::result_large_err)]
+fn time_in_force(request: &OrderPlaceRequest) -> GatewayResult<&'static str> {
+    match request.time_in_force.as_deref().map(str::to_uppercase).as_deref() {
+        None | Some("GTC") => Ok("GTC"),
+        Some("DAY") => Ok("DAY"),
+        Some(other) => Err(GatewayError::InvalidOrder(format!("Invalid time in force: {other}"))),
+    }
+}
+
+impl OrderEntry {
+    /// Create a new order entry service
+    pub fn new(
+        order_router: Arc<OrderRouter>,
+        symbol_coordinator: Arc<SymbolCoordinator>,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        account_service: Arc<AccountService>,
+        rate_limiter: Arc<RateLimiter>,
+        config: OrderEntryConfig,
+    ) -> Self {
+        Self {
+            order_router,
+            symbol_coordinator,
+            player_registry,
+            account_service,
+            rate_limiter,
+            config,
+        }
+    }
+
+    /// Validate, record, reserve and route an order for a session
+    pub async fn place_order(
+        &self,
+        session: &UserSession,
+        order_request: &OrderPlaceRequest,
+    ) -> GatewayResult<PlacedOrder> {
+        if !session.has_permission("trade") {
+            return Err(GatewayError::Authentication("Insufficient permissions".to_string()));
+        }
+
+        // Check rate limits
+        self.rate_limiter.check_order_rate_limit(&session.user_id).await?;
+
+        // Enforce API key symbol scope
+        let symbol_id = self.parse_symbol_id(&order_request.symbol).await?;
+        if !session.can_trade_symbol(symbol_id) {
+            return Err(GatewayError::Authentication(format!(
+                "Not permitted to trade symbol {}",
+                order_request.symbol
+            )));
+        }
+
+        // A retried client_order_id returns the original order instead of routing again.
+        // This catches plain retries before validation, which the original order's
+        // reservation could fail; concurrent submissions are settled when recording.
+        if let Some(client_order_id) = &order_request.client_order_id {
+            if let Some(existing) =
+                self.find_duplicate_order(session.account_id, client_order_id).await?
+            {
+                return Ok(duplicate_order(session.account_id, existing));
+            }
+        }
+
+        // Validate order against account balance and position
+        self.validate_order(order_request, session.account_id).await?;
+
+        // Generate a single order ID to use for both reservation and Whistle (mask high bit to prevent negative numbers)
+        let order_id = uuid::Uuid::new_v4().as_u128() as u64 & 0x7FFFFFFFFFFFFFFF;
+
+        // Persist the order (and its client_order_id mapping) before anything is routed
+        let record = NewOrderRecord {
+            order_id: order_id as i64,
+            account_id: session.account_id,
+            symbol_id: symbol_id as i32,
+            client_order_id: order_request.client_order_id.clone(),
+            side: order_request.side.to_uppercase(),
+            order_type: order_request.r#type.to_uppercase(),
+            price: order_request.price as i64,
+            quantity: order_request.quantity as i64,
+            time_in_force: time_in_force(order_request)?.to_string(),
+        };
+        let recorded = self
+            .account_service
+            .orders()
+            .insert(&record, self.dedupe_until())
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to record order: {e}")))?;
+        if let OrderInsert::Duplicate(existing) = recorded {
+            return Ok(duplicate_order(session.account_id, existing));
+        }
+
+        // Create reservation for limit orders
+        let reservation = self
+            .create_reservation_with_order_id(order_request, session.account_id, order_id as i64)
+            .await;
+        if let Err(e) = reservation {
+            self.update_order_status(order_id, OrderStatus::Rejected).await;
+            return Err(e);
+        }
+
+        // Convert order request to Whistle InboundMsg
+        let order_msg = match self.convert_order_request_to_inbound_msg_with_id(
+            order_request,
+            session.account_id,
+            order_id,
+        ) {
+            Ok(msg) => msg,
+            Err(e) => {
+                self.update_order_status(order_id, OrderStatus::Rejected).await;
+                return Err(e);
+            }
+        };
+
+        // Create message with symbol ID for routing
+        let msg_with_symbol = InboundMsgWithSymbol { symbol_id, msg: order_msg };
+
+        // Route order through OrderRouter
+        let current_tick = self.get_current_tick().await?;
+        let route_result = self.order_router.route(current_tick, msg_with_symbol);
+
+        let status =
+            if route_result.is_ok() { OrderStatus::Accepted } else { OrderStatus::Rejected };
+        self.update_order_status(order_id, status).await;
+
+        match route_result {
+            Ok(()) => {
+                info!("Order successfully routed to symbol {}", symbol_id);
+
+                // Create success response using the same order ID that was sent to Whistle
+                let response = OrderPlaceResponse {
+                    order_id: format!("ord_{}", order_id),
+                    status: "ACCEPTED".to_string(),
+                    timestamp: chrono::Utc::now().timestamp_millis() as u64,
+                    client_order_id: order_request.client_order_id.clone(),
+                };
+
+                // Only limit-style orders can rest on the book
+                let resting =
+                    matches!(order_request.r#type.to_uppercase().as_str(), "LIMIT" | "POST_ONLY")
+                        .then_some((order_id, symbol_id));
+
+                Ok(PlacedOrder { response, resting })
+            }
+            Err(RouterError::Backpressure) => {
+                error!("Order rejected due to backpressure");
+                Err(GatewayError::OrderRouter(
+                    "Order rejected due to system backpressure".to_string(),
+                ))
+            }
+            Err(RouterError::SymbolInactive) => {
+                error!("Order rejected - symbol {} is inactive", symbol_id);
+                Err(GatewayError::OrderRouter(format!(
+                    "Symbol {} is currently inactive",
+                    order_request.symbol
+                )))
+            }
+            Err(RouterError::SymbolCapacity) => {
+                error!("Order rejected - symbol {} at capacity", symbol_id);
+                Err(GatewayError::OrderRouter(format!(
+                    "Symbol {} is at capacity",
+                    order_request.symbol
+                )))
+            }
+            Err(e) => {
+                error!("Order routing failed: {:?}", e);
+                Err(GatewayError::OrderRouter(format!("Order routing failed: {e:?}")))
+            }
+        }
+    }
+
+    /// Request cancellation of one of the session's open orders
+    ///
+    /// The record moves to `CANCELLED` once the engine confirms the cancel.
+    pub async fn cancel_order(
+        &self,
+        session: &UserSession,
+        order_id: u64,
+    ) -> GatewayResult<OrderRecord> {
+        if !session.has_permission("trade") {
+            return Err(GatewayError::Authentication("Insufficient permissions".to_string()));
+        }
+
+        self.rate_limiter.check_order_rate_limit(&session.user_id).await?;
+
+        let order = self.get_order(session.account_id, order_id).await?;
+        if !order.is_open() {
+            return Err(GatewayError::InvalidOrder(format!(
+                "Order ord_{} is {} and cannot be cancelled",
+                order_id, order.status
+            )));
+        }
+
+        let failed = self.cancel_orders(&[(order_id, order.symbol_id as u32)]).await;
+        if !failed.is_empty() {
+            return Err(GatewayError::OrderRouter(format!(
+                "Failed to route cancel for ord_{order_id}"
+            )));
+        }
+
+        Ok(order)
+    }
+
+    /// Route cancels for a batch of orders, returning the IDs whose cancel failed to route
+    pub async fn cancel_orders(&self, orders: &[(u64, u32)]) -> Vec<u64> {
+        let mut failed = Vec::new();
+        if orders.is_empty() {
+            return failed;
+        }
+
+        let current_tick = self.get_current_tick().await.unwrap_or(1000);
+        let ts_norm = chrono::Utc::now().timestamp_millis() as u64;
+
+        for &(order_id, symbol_id) in orders {
+            let msg = InboundMsg::cancel(OrderId::from(order_id), ts_norm, 0);
+            if let Err(e) =
+                self.order_router.route(current_tick, InboundMsgWithSymbol { symbol_id, msg })
+            {
+                warn!(
+                    "Failed to route cancel for order {} on symbol {}: {:?}",
+                    order_id, symbol_id, e
+                );
+                failed.push(order_id);
+            }
+        }
+
+        failed
+    }
+
+    /// Route cancels for every open DAY order, at the close of a trading session
+    ///
+    /// Returns the number of cancels routed. As with any cancel, records move to
+    /// `CANCELLED` once the engine confirms.
+    pub async fn expire_day_orders(&self) -> GatewayResult<usize> {
+        let orders: Vec<_> = self
+            .account_service
+            .orders()
+            .list_open_day_orders()
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to list DAY orders: {e}")))?
+            .into_iter()
+            .map(|order| (order.order_id as u64, order.symbol_id as u32))
+            .collect();
+
+        let failed = self.cancel_orders(&orders).await;
+        if !failed.is_empty() {
+            warn!("Failed to route {} of {} DAY order expiries", failed.len(), orders.len());
+        }
+        Ok(orders.len() - failed.len())
+    }
+
+    /// Look up one of an account's orders
+    pub async fn get_order(&self, account_id: i64, order_id: u64) -> GatewayResult<OrderRecord> {
+        let order = self
+            .account_service
+            .orders()
+            .get(order_id as i64)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to load order: {e}")))?;
+
+        // Other accounts' orders are reported as missing rather than forbidden
+        order
+            .filter(|order| order.account_id == account_id)
+            .ok_or_else(|| GatewayError::OrderNotFound(format!("ord_{order_id}")))
+    }
+
+    /// Look up one of an account's orders by the client order ID it was placed with
+    ///
+    /// Unlike duplicate detection this is not limited to the dedupe window, so long-lived
+    /// GTC orders can still be cancelled or replaced by their client order ID.
+    pub async fn get_order_by_client_order_id(
+        &self,
+        account_id: i64,
+        client_order_id: &str,
+    ) -> GatewayResult<OrderRecord> {
+        self.account_service
+            .orders()
+            .find_by_client_order_id(account_id, client_order_id)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to look up client_order_id: {e}")))?
+            .ok_or_else(|| GatewayError::OrderNotFound(client_order_id.to_string()))
+    }
+
+    /// List an account's orders, newest first
+    pub async fn list_orders(
+        &self,
+        account_id: i64,
+        open_only: bool,
+        limit: i64,
+    ) -> GatewayResult<Vec<OrderRecord>> {
+        self.account_service
+            .orders()
+            .list(account_id, open_only, limit)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to list orders: {e}")))
+    }
+
+    /// Find an earlier order with the same client_order_id inside the dedupe window
+    async fn find_duplicate_order(
+        &self,
+        account_id: i64,
+        client_order_id: &str,
+    ) -> GatewayResult<Option<OrderRecord>> {
+        self.account_service
+            .orders()
+            .find_duplicate(account_id, client_order_id)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to look up client_order_id: {e}")))
+    }
+
+    /// When a client_order_id recorded now may be reused
+    fn dedupe_until(&self) -> chrono::DateTime<chrono::Utc> {
+        let window = chrono::Duration::seconds(self.config.client_order_id_window_seconds as i64);
+        chrono::Utc::now() + window
+    }
+
+    /// Update a recorded order's status, logging (not failing) on database errors
+    async fn update_order_status(&self, order_id: u64, status: OrderStatus) {
+        if let Err(e) = self.account_service.orders().set_status(order_id as i64, status).await {
+            warn!("Failed to mark order {} as {}: {}", order_id, status.as_str(), e);
+        }
+    }
+
+    /// Convert OrderPlaceRequest to Whistle InboundMsg with a specific order ID
+    #[allow(clippy::result_large_err)]
+    fn convert_order_request_to_inbound_msg_with_id(
+        &self,
+        request: &OrderPlaceRequest,
+        account_id: i64,
+        order_id: u64,
+    ) -> GatewayResult<InboundMsg> {
+        let order_id = OrderId::from(order_id);
+        let account_id = AccountId::from(account_id as u32);
+
+        let side = match request.side.to_uppercase().as_str() {
+            "BUY" => Side::Buy,
+            "SELL" => Side::Sell,
+            _ => return Err(GatewayError::InvalidOrder(format!("Invalid side: {}", request.side))),
+        };
+
+        let order_type = match request.r#type.to_uppercase().as_str() {
+            "LIMIT" => OrderType::Limit,
+            "MARKET" => OrderType::Market,
+            "IOC" => OrderType::Ioc,
+            "POST_ONLY" => OrderType::PostOnly,
+            _ => {
+                return Err(GatewayError::InvalidOrder(format!(
+                    "Invalid order type: {}",
+                    request.r#type
+                )))
+            }
+        };
+
+        let price = match order_type {
+            OrderType::Limit => Some(request.price),
+            _ => None,
+        };
+
+        let quantity = request.quantity;
+        let ts_norm = chrono::Utc::now().timestamp_millis() as u64;
+        let meta = 0u64; // No metadata for now
+        let enq_seq = 0; // Will be stamped by OrderRouter
+
+        Ok(InboundMsg::submit(
+            order_id, account_id, side, order_type, price, quantity, ts_norm, meta, enq_seq,
+        ))
+    }
+
+    /// Parse symbol string to symbol ID
+    async fn parse_symbol_id(&self, symbol: &str) -> GatewayResult<u32> {
+        // First try to parse as a direct symbol ID (for backward compatibility)
+        if let Ok(symbol_id) = symbol.parse::<u32>() {
+            return Ok(symbol_id);
+        }
+
+        // Try to look up player by name in the registry
+        let registry = self.player_registry.read().await;
+        match registry.get_by_name(symbol) {
+            Ok(player_symbol) => {
+                info!("Found player '{}' with symbol ID {}", symbol, player_symbol.symbol_id);
+                Ok(player_symbol.symbol_id)
+            }
+            Err(_) => {
+                // Try partial name search
+                let search_results = registry.search_players(symbol);
+                if search_results.len() == 1 {
+                    let player_symbol = search_results[0];
+                    info!(
+                        "Found player '{}' via search with symbol ID {}",
+                        player_symbol.name, player_symbol.symbol_id
+                    );
+                    Ok(player_symbol.symbol_id)
+                } else if search_results.len() > 1 {
+                    // Multiple matches - return error with suggestions
+                    let suggestions: Vec<String> =
+                        search_results.iter().map(|p| p.name.clone()).collect();
+                    Err(GatewayError::InvalidOrder(format!(
+                        "Multiple players found for '{}': {}. Please be more specific.",
+                        symbol,
+                        suggestions.join(", ")
+                    )))
+                } else {
+                    Err(GatewayError::SymbolNotFound(format!("Player not found: {symbol}")))
+                }
+            }
+        }
+    }
+
+    /// Get current tick from SymbolCoordinator
+    async fn get_current_tick(&self) -> GatewayResult<TickId> {
+        // Get the actual current tick from SymbolCoordinator
+        // This ensures orders are routed to the correct tick for processing
+        match self.symbol_coordinator.get_current_tick() {
+            Ok(tick) => {
+                info!("OrderGateway got current tick {} from SymbolCoordinator", tick);
+                Ok(tick)
+            }
+            Err(_) => {
+                // Fallback to a reasonable tick if SymbolCoordinator is not available
+                warn!("Failed to get current tick from SymbolCoordinator, using fallback");
+                Ok(1000)
+            }
+        }
+    }
+
+    /// Validate order against account balance and position
+    async fn validate_order(
+        &self,
+        order_request: &OrderPlaceRequest,
+        account_id: i64,
+    ) -> GatewayResult<()> {
+        let symbol_id = self.parse_symbol_id(&order_request.symbol).await? as i64;
+        let side = match order_request.side.to_uppercase().as_str() {
+            "BUY" => whistle::Side::Buy,
+            "SELL" => whistle::Side::Sell,
+            _ => {
+                return Err(GatewayError::InvalidOrder(format!(
+                    "Invalid side: {}",
+                    order_request.side
+                )))
+            }
+        };
+
+        let quantity = Balance::from_basis_points(order_request.quantity as i64);
+        let _price = match order_request.r#type.to_uppercase().as_str() {
+            "MARKET" => {
+                // For market orders, we need to estimate the worst-case price
+                // For now, we'll use a conservative estimate of $1000 per share
+                Balance::from_cents(100000) // $1000
+            }
+            "LIMIT" | "IOC" | "POST_ONLY" => Balance::from_cents(order_request.price as i64),
+            _ => {
+                return Err(GatewayError::InvalidOrder(format!(
+                    "Invalid order type: {}",
+                    order_request.r#type
+                )))
+            }
+        };
+
+        match side {
+            whistle::Side::Buy => {
+                // Validate sufficient balance for buy orders: quantity (shares) × price (cents)
+                let required_amount =
+                    (order_request.quantity as i64) * (order_request.price as i64);
+                let account =
+                    self.account_service.get_account(account_id).await.map_err(|e| {
+                        GatewayError::System(format!("Failed to get account: {}", e))
+                    })?;
+
+                let available_balance = account.currency_balance.unwrap_or(0);
+                if available_balance < required_amount {
+                    return Err(GatewayError::InvalidOrder(format!(
+                        "Insufficient balance: required {required_amount} cents, available \
+                         {available_balance} cents"
+                    )));
+                }
+
+                info!("Buy order validated: account {} has sufficient balance", account_id);
+            }
+            whistle::Side::Sell => {
+                // Validate sufficient position for sell orders
+                let position =
+                    self.account_service.get_position(account_id, symbol_id).await.map_err(
+                        |e| GatewayError::System(format!("Failed to get position: {}", e)),
+                    )?;
+
+                match position {
+                    Some(pos) => {
+                        if pos.quantity < quantity {
+                            return Err(GatewayError::InvalidOrder(format!(
+                                "Insufficient position: required {} shares, available {} shares",
+                                quantity.to_decimal(),
+                                pos.quantity.to_decimal()
+                            )));
+                        }
+                    }
+                    None => {
+                        return Err(GatewayError::InvalidOrder(format!(
+                            "No position found for account {account_id} and symbol {symbol_id}"
+                        )));
+                    }
+                }
+
+                info!("Sell order validated: account {} has sufficient position", account_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a reservation for limit orders with a specific order ID
+    async fn create_reservation_with_order_id(
+        &self,
+        order_request: &OrderPlaceRequest,
+        account_id: i64,
+        order_id: i64,
+    ) -> GatewayResult<Option<u64>> {
+        // Only create reservations for limit orders
+        if order_request.r#type.to_uppercase() != "LIMIT" {
+            return Ok(None);
+        }
+
+        let side = match order_request.side.to_uppercase().as_str() {
+            "BUY" => whistle::Side::Buy,
+            "SELL" => whistle::Side::Sell,
+            _ => {
+                return Err(GatewayError::InvalidOrder(format!(
+                    "Invalid side: {}",
+                    order_request.side
+                )))
+            }
+        };
+
+        let _quantity = Balance::from_basis_points(order_request.quantity as i64);
+        let _price = Balance::from_cents(order_request.price as i64);
+
+        match side {
+            whistle::Side::Buy => {
+                // Reserve balance for buy orders: quantity (shares) × price (cents)
+                let required_amount =
+                    (order_request.quantity as i64) * (order_request.price as i64);
+
+                let reservation_id = self
+                    .account_service
+                    .check_and_reserve_balance(account_id, required_amount, order_id)
+                    .await
+                    .map_err(|e| match e {
+                        AccountServiceError::InsufficientBalance { .. } => {
+                            GatewayError::InvalidOrder(e.to_string())
+                        }
+                        _ => GatewayError::System(format!("Failed to create reservation: {e}")),
+                    })?;
+
+                info!("Created balance reservation {} for buy order", reservation_id.0);
+                Ok(Some(reservation_id.0))
+            }
+            whistle::Side::Sell => {
+                // For sell orders, we don't need to reserve balance, but we could reserve the position
+                // For now, we'll just return None since the position validation already ensures sufficient shares
+                info!("Sell order - no reservation needed");
+                Ok(None)
+            }
+        }
+    }
+}
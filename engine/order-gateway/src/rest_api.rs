@@ -3,8 +3,18 @@
 //! This module provides REST API endpoints for symbol information, price history,
 //! account data, and order placement.
 
+use crate::auth::AuthManager;
+use crate::auth::{
+    ApiKeyPrincipal, ApiKeyStore, API_KEY_HEADER, API_NONCE_HEADER, API_SIGNATURE_HEADER,
+    API_TIMESTAMP_HEADER,
+};
 use crate::cache::CacheManager;
+use crate::error::GatewayError;
+use crate::messages::{OrderPlaceRequest, UserSession};
+use crate::order_entry::{parse_order_id, OrderEntry};
+use account_service::{AccountServiceError, NewApiKey, OrderRecord};
 use chrono::Timelike;
+use equity_service::{ContestService, EquityServiceError, EquityValuationService, NewContest};
 use num_traits::cast::ToPrimitive;
 use num_traits::FromPrimitive;
 use persistence::snapshot::SnapshotManager;
@@ -12,7 +22,12 @@ use player_registry::PlayerRegistry;
 use serde::{Deserialize, Serialize};
 use sqlx::types::BigDecimal;
 use sqlx::PgPool;
+use trading_calendar::{SessionPhase, SessionScheduler};
+use std::net::SocketAddr;
 use std::sync::Arc;
+use warp::http::{HeaderMap, Method, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::path::FullPath;
 use warp::Filter;
 
 /// Custom error for not found responses
@@ -21,6 +36,54 @@ struct NotFoundError(ErrorResponse);
 
 impl warp::reject::Reject for NotFoundError {}
 
+/// Custom error for unauthenticated requests
+#[derive(Debug)]
+struct UnauthorizedError(ErrorResponse);
+
+impl warp::reject::Reject for UnauthorizedError {}
+
+/// Custom error for authenticated requests lacking permission
+#[derive(Debug)]
+struct ForbiddenError(ErrorResponse);
+
+impl warp::reject::Reject for ForbiddenError {}
+
+/// Custom error for malformed requests
+#[derive(Debug)]
+struct BadRequestError(ErrorResponse);
+
+impl warp::reject::Reject for BadRequestError {}
+
+/// Custom error for rate-limited requests
+#[derive(Debug)]
+struct TooManyRequestsError(ErrorResponse);
+
+impl warp::reject::Reject for TooManyRequestsError {}
+
+/// Custom error for unexpected server-side failures
+#[derive(Debug)]
+struct InternalError(ErrorResponse);
+
+impl warp::reject::Reject for InternalError {}
+
+/// Custom error for requests the exchange cannot take right now (retryable)
+#[derive(Debug)]
+struct ServiceUnavailableError(ErrorResponse);
+
+impl warp::reject::Reject for ServiceUnavailableError {}
+
+/// Build an error response body
+fn error_response(
+    code: &str,
+    message: String,
+    details: Option<serde_json::Value>,
+) -> ErrorResponse {
+    ErrorResponse {
+        error: ErrorDetail { code: code.to_string(), message, details },
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
 /// Symbol information response
 #[derive(Serialize, Deserialize)]
 pub struct SymbolInfoResponse {
@@ -94,6 +157,7 @@ pub struct EquitySnapshot {
     pub realized_pnl: i64,       // In cents
     pub day_change: i64,         // In cents
     pub day_change_percent: f64, // Percentage
+    pub mark_sources: serde_json::Value, // Mark source per symbol
 }
 
 /// Price history query parameters
@@ -607,7 +671,7 @@ pub async fn get_equity_history(
         .unwrap_or_else(|_| chrono::Utc::now().date_naive());
 
     let rows = sqlx::query!(
-        "SELECT created_at, total_equity, cash_balance, position_value, unrealized_pnl, realized_pnl, day_change, day_change_percent
+        "SELECT created_at, total_equity, cash_balance, position_value, unrealized_pnl, realized_pnl, day_change, day_change_percent, mark_sources
          FROM equity_timeseries 
          WHERE account_id = $1 AND DATE(created_at) BETWEEN $2 AND $3
          ORDER BY created_at ASC",
@@ -644,6 +708,7 @@ pub async fn get_equity_history(
             realized_pnl: row.realized_pnl,
             day_change: row.day_change,
             day_change_percent: row.day_change_percent.to_f64().unwrap_or(0.0),
+            mark_sources: row.mark_sources,
         })
         .collect();
 
@@ -1009,6 +1074,29 @@ pub async fn get_account_positions(
     Ok(warp::reply::json(&response))
 }
 
+/// Get the session account's portfolio risk
+pub async fn get_account_risk(
+    session: UserSession,
+    equity_service: Arc<EquityValuationService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+    let risk = equity_service.account_risk(account_id).await.map_err(|e| match e {
+        EquityServiceError::AccountNotFound(_) => warp::reject::custom(NotFoundError(
+            error_response("ACCOUNT_NOT_FOUND", format!("Account {account_id} not found"), None),
+        )),
+        other => {
+            tracing::error!("Failed to assess risk for account {}: {}", account_id, other);
+            warp::reject::custom(InternalError(error_response(
+                "DATABASE_ERROR",
+                "Failed to assess account risk".to_string(),
+                Some(serde_json::Value::String(other.to_string())),
+            )))
+        }
+    })?;
+
+    Ok(warp::reply::json(&risk))
+}
+
 /// Get account trade history
 pub async fn get_account_trades(
     account_id: i64,
@@ -1067,10 +1155,987 @@ pub async fn get_account_trades(
     Ok(warp::reply::json(&response))
 }
 
+/// Permissions an API key may be granted
+const KNOWN_PERMISSIONS: [&str; 3] = ["trade", "market_data", "admin"];
+
+/// Caller of an admin endpoint
+#[derive(Debug, Clone)]
+pub struct AdminCaller {
+    /// Account behind the admin API key (None for the bootstrap admin token)
+    pub account_id: Option<i64>,
+
+    /// Human-readable identity for logs
+    pub identity: String,
+}
+
+/// API key list response
+#[derive(Debug, Serialize)]
+pub struct ApiKeysListResponse {
+    pub keys: Vec<account_service::ApiKey>,
+    pub total_keys: usize,
+}
+
+/// API key creation/rotation response (the secret is only ever shown here)
+#[derive(Debug, Serialize)]
+pub struct ApiKeySecretResponse {
+    pub key_id: String,
+    pub api_secret: String,
+    pub key: Option<account_service::ApiKey>,
+}
+
+/// Verify the signature headers of a REST request against the API key store
+async fn authenticate_signed_request(
+    store: &ApiKeyStore,
+    headers: &HeaderMap,
+    method: &Method,
+    path: &FullPath,
+    query: &str,
+    remote: Option<SocketAddr>,
+    body: &Bytes,
+) -> Result<ApiKeyPrincipal, warp::Rejection> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let unauthorized = |message: String| {
+        warp::reject::custom(UnauthorizedError(error_response("UNAUTHORIZED", message, None)))
+    };
+
+    let (Some(key_id), Some(timestamp), Some(nonce), Some(signature)) = (
+        header(API_KEY_HEADER),
+        header(API_TIMESTAMP_HEADER),
+        header(API_NONCE_HEADER),
+        header(API_SIGNATURE_HEADER),
+    ) else {
+        return Err(unauthorized("Missing API key signature headers".to_string()));
+    };
+    let timestamp = timestamp
+        .parse::<i64>()
+        .map_err(|_| unauthorized("Invalid API timestamp header".to_string()))?;
+    let peer_ip = remote
+        .map(|addr| addr.ip())
+        .ok_or_else(|| unauthorized("Unable to determine client address".to_string()))?;
+
+    let path_and_query = if query.is_empty() {
+        path.as_str().to_string()
+    } else {
+        format!("{}?{}", path.as_str(), query)
+    };
+
+    store
+        .authenticate_request(
+            key_id,
+            timestamp,
+            nonce,
+            signature,
+            method.as_str(),
+            &path_and_query,
+            body,
+            peer_ip,
+        )
+        .await
+        .map_err(|e| unauthorized(e.to_string()))
+}
+
+/// Filter that authenticates an admin caller and hands back the raw request body
+///
+/// Accepts either `Authorization: Bearer $ADMIN_API_TOKEN` (for bootstrapping the
+/// first key) or a request signed by an API key holding the `admin` permission.
+fn with_admin(
+    store: Arc<ApiKeyStore>,
+) -> impl Filter<Extract = (AdminCaller, Bytes), Error = warp::Rejection> + Clone {
+    warp::header::headers_cloned()
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::addr::remote())
+        .and(warp::body::bytes())
+        .and(warp::any().map(move || store.clone()))
+        .and_then(
+            |headers: HeaderMap,
+             method: Method,
+             path: FullPath,
+             query: String,
+             remote: Option<SocketAddr>,
+             body: Bytes,
+             store: Arc<ApiKeyStore>| async move {
+                let bearer = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "));
+
+                if let Some(token) = bearer {
+                    let expected = std::env::var("ADMIN_API_TOKEN").unwrap_or_default();
+                    if !expected.is_empty()
+                        && crate::auth::constant_time_eq(token.as_bytes(), expected.as_bytes())
+                    {
+                        let caller =
+                            AdminCaller { account_id: None, identity: "admin-token".to_string() };
+                        return Ok::<_, warp::Rejection>((caller, body));
+                    }
+                    return Err(warp::reject::custom(UnauthorizedError(error_response(
+                        "UNAUTHORIZED",
+                        "Invalid admin token".to_string(),
+                        None,
+                    ))));
+                }
+
+                let principal = authenticate_signed_request(
+                    &store, &headers, &method, &path, &query, remote, &body,
+                )
+                .await?;
+                if !principal.has_permission("admin") {
+                    return Err(warp::reject::custom(ForbiddenError(error_response(
+                        "FORBIDDEN",
+                        "Admin permission required".to_string(),
+                        None,
+                    ))));
+                }
+
+                let caller = AdminCaller {
+                    account_id: Some(principal.account_id),
+                    identity: principal.key_id,
+                };
+                Ok((caller, body))
+            },
+        )
+        .untuple_one()
+}
+
+/// Record an admin action in the audit trail (only possible for account-backed callers)
+async fn record_admin_action(
+    db_pool: &PgPool,
+    caller: &AdminCaller,
+    action: &str,
+    details: serde_json::Value,
+) {
+    let Some(account_id) = caller.account_id else {
+        tracing::info!("Admin action {} by {}: {}", action, caller.identity, details);
+        return;
+    };
+
+    if let Err(e) = sqlx::query!(
+        "INSERT INTO admin_actions (account_id, action, details) VALUES ($1, $2, $3)",
+        account_id,
+        action,
+        details
+    )
+    .execute(db_pool)
+    .await
+    {
+        tracing::warn!("Failed to record admin action {}: {}", action, e);
+    }
+}
+
+/// Reject a key definition with unknown permissions or malformed IP entries
+fn validate_new_api_key(new_key: &NewApiKey) -> Result<(), ErrorResponse> {
+    if let Some(unknown) =
+        new_key.permissions.iter().find(|p| !KNOWN_PERMISSIONS.contains(&p.as_str()))
+    {
+        return Err(error_response(
+            "INVALID_PERMISSION",
+            format!("Unknown permission '{unknown}'"),
+            Some(serde_json::json!({ "allowed": KNOWN_PERMISSIONS })),
+        ));
+    }
+
+    for entry in &new_key.allowed_ips {
+        let addr = entry.split_once('/').map_or(entry.as_str(), |(addr, _)| addr);
+        if addr.trim().parse::<std::net::IpAddr>().is_err() {
+            return Err(error_response(
+                "INVALID_IP",
+                format!("Invalid IP allowlist entry '{entry}'"),
+                None,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 500 for a failed key repository call; the cause is logged, not returned
+fn api_key_failure(action: &str, key_id: Option<&str>, e: AccountServiceError) -> warp::Rejection {
+    match key_id {
+        Some(key_id) => tracing::error!("Failed to {} API key {}: {}", action, key_id, e),
+        None => tracing::error!("Failed to {} API keys: {}", action, e),
+    }
+    warp::reject::custom(InternalError(error_response(
+        "INTERNAL_ERROR",
+        format!("Failed to {action} API key"),
+        None,
+    )))
+}
+
+/// 404 for a key the repository has no active record of
+fn api_key_not_found(key_id: &str) -> warp::Rejection {
+    warp::reject::custom(NotFoundError(error_response(
+        "API_KEY_NOT_FOUND",
+        format!("Active API key '{key_id}' not found"),
+        None,
+    )))
+}
+
+/// Create an API key (admin)
+pub async fn create_api_key(
+    caller: AdminCaller,
+    body: Bytes,
+    store: Arc<ApiKeyStore>,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let new_key: NewApiKey = serde_json::from_slice(&body).map_err(|e| {
+        warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            format!("Invalid API key definition: {e}"),
+            None,
+        )))
+    })?;
+    validate_new_api_key(&new_key).map_err(|e| warp::reject::custom(BadRequestError(e)))?;
+
+    let (key, credentials) = store.repository().create(&new_key).await.map_err(|e| match e {
+        AccountServiceError::AccountNotFound { account_id } => {
+            warp::reject::custom(BadRequestError(error_response(
+                "ACCOUNT_NOT_FOUND",
+                format!("Account {account_id} not found"),
+                None,
+            )))
+        }
+        e => api_key_failure("create", None, e),
+    })?;
+
+    record_admin_action(
+        &db_pool,
+        &caller,
+        "create_api_key",
+        serde_json::json!({
+            "key_id": key.key_id,
+            "account_id": key.account_id,
+            "permissions": key.permissions,
+        }),
+    )
+    .await;
+
+    let response = ApiKeySecretResponse {
+        key_id: credentials.key_id,
+        api_secret: credentials.api_secret,
+        key: Some(key),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::CREATED))
+}
+
+/// List API keys, optionally for one account (admin)
+pub async fn list_api_keys(
+    params: std::collections::HashMap<String, String>,
+    store: Arc<ApiKeyStore>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = params.get("account_id").and_then(|s| s.parse::<i64>().ok());
+
+    let keys =
+        store.repository().list(account_id).await.map_err(|e| api_key_failure("list", None, e))?;
+
+    let total_keys = keys.len();
+    Ok(warp::reply::json(&ApiKeysListResponse { keys, total_keys }))
+}
+
+/// Revoke an API key (admin)
+pub async fn revoke_api_key(
+    key_id: String,
+    caller: AdminCaller,
+    store: Arc<ApiKeyStore>,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let revoked = store
+        .repository()
+        .revoke(&key_id)
+        .await
+        .map_err(|e| api_key_failure("revoke", Some(&key_id), e))?;
+    if !revoked {
+        return Err(api_key_not_found(&key_id));
+    }
+
+    record_admin_action(&db_pool, &caller, "revoke_api_key", serde_json::json!({ "key_id": key_id }))
+        .await;
+
+    Ok(warp::reply::json(&serde_json::json!({
+        "key_id": key_id,
+        "revoked": true,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// Rotate the secret of an API key (admin)
+pub async fn rotate_api_key(
+    key_id: String,
+    caller: AdminCaller,
+    store: Arc<ApiKeyStore>,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let credentials = store
+        .repository()
+        .rotate(&key_id)
+        .await
+        .map_err(|e| api_key_failure("rotate", Some(&key_id), e))?
+        .ok_or_else(|| api_key_not_found(&key_id))?;
+
+    record_admin_action(&db_pool, &caller, "rotate_api_key", serde_json::json!({ "key_id": key_id }))
+        .await;
+
+    let response = ApiKeySecretResponse {
+        key_id: credentials.key_id,
+        api_secret: credentials.api_secret,
+        key: None,
+    };
+    Ok(warp::reply::json(&response))
+}
+
+/// Market maker performance query parameters
+#[derive(Debug, Deserialize)]
+pub struct MarketMakerPerformanceParams {
+    /// House account (defaults to the account behind MARKET_MAKER_API_KEY)
+    pub account_id: Option<i64>,
+    pub symbol_id: Option<u32>,
+    /// Defaults to 24 hours before `until`
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Defaults to now
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// P&L attribution and quoting performance of the house account (admin)
+pub async fn get_market_maker_performance(
+    params: MarketMakerPerformanceParams,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let until = params.until.unwrap_or_else(chrono::Utc::now);
+    let since = params.since.unwrap_or(until - chrono::Duration::hours(24));
+    if since >= until {
+        return Err(warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            "since must be before until".to_string(),
+            None,
+        ))));
+    }
+
+    let account_id = match params.account_id {
+        Some(account_id) => account_id,
+        None => {
+            let api_key = std::env::var("MARKET_MAKER_API_KEY").unwrap_or_default();
+            market_maker::performance::house_account_id(&db_pool, &api_key)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to resolve the market maker account: {}", e);
+                    warp::reject::custom(InternalError(error_response(
+                        "DATABASE_ERROR",
+                        "Failed to resolve the market maker account".to_string(),
+                        Some(serde_json::Value::String(e.to_string())),
+                    )))
+                })?
+                .ok_or_else(|| {
+                    warp::reject::custom(BadRequestError(error_response(
+                        "INVALID_REQUEST",
+                        "No active market maker API key; pass account_id".to_string(),
+                        None,
+                    )))
+                })?
+        }
+    };
+
+    let report =
+        market_maker::performance::report(&db_pool, account_id, params.symbol_id, since, until)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to build market maker performance report: {}", e);
+                warp::reject::custom(InternalError(error_response(
+                    "DATABASE_ERROR",
+                    "Failed to build market maker performance report".to_string(),
+                    Some(serde_json::Value::String(e.to_string())),
+                )))
+            })?;
+
+    Ok(warp::reply::json(&report))
+}
+
+/// Order as returned by the REST order endpoints
+#[derive(Debug, Serialize)]
+pub struct OrderResponse {
+    pub order_id: String,
+    pub client_order_id: Option<String>,
+    pub symbol_id: i32,
+    pub side: String,
+    pub order_type: String,
+    pub price: i64,
+    pub quantity: i64,
+    pub filled_quantity: i64,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<OrderRecord> for OrderResponse {
+    fn from(order: OrderRecord) -> Self {
+        Self {
+            order_id: format!("ord_{}", order.order_id),
+            client_order_id: order.client_order_id,
+            symbol_id: order.symbol_id,
+            side: order.side,
+            order_type: order.order_type,
+            price: order.price,
+            quantity: order.quantity,
+            filled_quantity: order.filled_quantity,
+            status: order.status,
+            created_at: order.created_at.to_rfc3339(),
+            updated_at: order.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Order list response
+#[derive(Debug, Serialize)]
+pub struct OrdersListResponse {
+    pub orders: Vec<OrderResponse>,
+    pub total_orders: usize,
+}
+
+/// Query parameters for `GET /api/orders`
+#[derive(Debug, Deserialize)]
+pub struct OrdersQuery {
+    /// `open` for resting orders only, anything else (or omitted) for all orders
+    pub status: Option<String>,
+
+    /// Maximum number of orders to return
+    pub limit: Option<i64>,
+}
+
+/// Convert an order entry error into a REST rejection
+fn order_rejection(err: GatewayError) -> warp::Rejection {
+    let message = err.to_string();
+    match err {
+        GatewayError::Authentication(_) => {
+            warp::reject::custom(ForbiddenError(error_response("FORBIDDEN", message, None)))
+        }
+        GatewayError::RateLimit(_) => warp::reject::custom(TooManyRequestsError(error_response(
+            "RATE_LIMITED",
+            message,
+            None,
+        ))),
+        GatewayError::OrderNotFound(_) => {
+            warp::reject::custom(NotFoundError(error_response("ORDER_NOT_FOUND", message, None)))
+        }
+        GatewayError::InvalidOrder(_)
+        | GatewayError::SymbolNotFound(_)
+        | GatewayError::Serialization(_) => {
+            warp::reject::custom(BadRequestError(error_response("ORDER_REJECTED", message, None)))
+        }
+        // Backpressure, capacity or an inactive symbol: the same request may succeed later
+        GatewayError::OrderRouter(_) => warp::reject::custom(ServiceUnavailableError(
+            error_response("SERVICE_UNAVAILABLE", message, None),
+        )),
+        _ => {
+            tracing::error!("Order request failed: {}", message);
+            warp::reject::custom(InternalError(error_response(
+                "INTERNAL_ERROR",
+                "Order request failed".to_string(),
+                None,
+            )))
+        }
+    }
+}
+
+/// Filter that authenticates a trading session and hands back the raw request body
+///
+/// Accepts `Authorization: Bearer <jwt>` (the same tokens as `auth.jwt` on the WebSocket)
+/// or a request signed with an API key.
+fn with_session(
+    auth_manager: Arc<AuthManager>,
+) -> impl Filter<Extract = (UserSession, Bytes), Error = warp::Rejection> + Clone {
+    warp::header::headers_cloned()
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::addr::remote())
+        .and(warp::body::bytes())
+        .and(warp::any().map(move || auth_manager.clone()))
+        .and_then(
+            |headers: HeaderMap,
+             method: Method,
+             path: FullPath,
+             query: String,
+             remote: Option<SocketAddr>,
+             body: Bytes,
+             auth_manager: Arc<AuthManager>| async move {
+                let bearer = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "));
+
+                if let Some(token) = bearer {
+                    let unauthorized = |e: GatewayError| {
+                        warp::reject::custom(UnauthorizedError(error_response(
+                            "UNAUTHORIZED",
+                            e.to_string(),
+                            None,
+                        )))
+                    };
+                    auth_manager.authenticate_jwt(token).await.map_err(unauthorized)?;
+                    let session =
+                        auth_manager.get_session_by_jwt(token).await.map_err(unauthorized)?;
+                    return Ok::<_, warp::Rejection>((session, body));
+                }
+
+                let store = auth_manager.api_key_store();
+                let principal = authenticate_signed_request(
+                    &store, &headers, &method, &path, &query, remote, &body,
+                )
+                .await?;
+                Ok((principal.into_session(), body))
+            },
+        )
+        .untuple_one()
+}
+
+/// Place an order (`POST /api/orders`)
+pub async fn place_order(
+    session: UserSession,
+    body: Bytes,
+    order_entry: Arc<OrderEntry>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let order_request: OrderPlaceRequest = serde_json::from_slice(&body).map_err(|e| {
+        warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            format!("Invalid order parameters: {e}"),
+            None,
+        )))
+    })?;
+
+    let placed =
+        order_entry.place_order(&session, &order_request).await.map_err(order_rejection)?;
+    Ok(warp::reply::json(&placed.response))
+}
+
+/// Request cancellation of an open order (`DELETE /api/orders/{id}`)
+pub async fn cancel_order(
+    order_id: String,
+    session: UserSession,
+    order_entry: Arc<OrderEntry>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let order_id = parse_order_id(&order_id).map_err(order_rejection)?;
+    let order = order_entry.cancel_order(&session, order_id).await.map_err(order_rejection)?;
+
+    let response = serde_json::json!({
+        "order_id": format!("ord_{}", order.order_id),
+        "client_order_id": order.client_order_id,
+        "status": "CANCEL_REQUESTED",
+        "timestamp": chrono::Utc::now().timestamp_millis(),
+    });
+    Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::ACCEPTED))
+}
+
+/// Get one of the caller's orders (`GET /api/orders/{id}`)
+pub async fn get_order(
+    order_id: String,
+    session: UserSession,
+    order_entry: Arc<OrderEntry>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let order_id = parse_order_id(&order_id).map_err(order_rejection)?;
+    let order =
+        order_entry.get_order(session.account_id, order_id).await.map_err(order_rejection)?;
+    Ok(warp::reply::json(&OrderResponse::from(order)))
+}
+
+/// List the caller's orders (`GET /api/orders?status=open`)
+pub async fn list_orders(
+    query: OrdersQuery,
+    session: UserSession,
+    order_entry: Arc<OrderEntry>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let open_only = query.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("open"));
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+
+    let orders = order_entry
+        .list_orders(session.account_id, open_only, limit)
+        .await
+        .map_err(order_rejection)?;
+
+    let orders: Vec<OrderResponse> = orders.into_iter().map(OrderResponse::from).collect();
+    let total_orders = orders.len();
+    Ok(warp::reply::json(&OrdersListResponse { orders, total_orders }))
+}
+
+/// Body of `POST /api/admin/session/phase`
+#[derive(Debug, Deserialize)]
+pub struct PhaseOverrideRequest {
+    pub phase: SessionPhase,
+    /// The calendar takes over again at this time; omit to hold until cleared
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Body of `POST /api/admin/session/halts/{symbol_id}`
+#[derive(Debug, Deserialize)]
+pub struct SymbolHaltRequest {
+    /// `true` halts the symbol, `false` keeps it trading through its kickoff
+    pub halted: bool,
+}
+
+/// Current session phase, halts and overrides (admin)
+pub async fn get_session_status(
+    _caller: AdminCaller,
+    scheduler: SessionScheduler,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&scheduler.status()))
+}
+
+/// Override the calendar's phase (admin)
+pub async fn override_session_phase(
+    caller: AdminCaller,
+    body: Bytes,
+    scheduler: SessionScheduler,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let request: PhaseOverrideRequest = serde_json::from_slice(&body).map_err(|e| {
+        warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            format!("Invalid phase override: {e}"),
+            None,
+        )))
+    })?;
+
+    scheduler.override_phase(request.phase, request.until);
+    record_admin_action(
+        &db_pool,
+        &caller,
+        "override_session_phase",
+        serde_json::json!({ "phase": request.phase, "until": request.until }),
+    )
+    .await;
+    Ok(warp::reply::json(&scheduler.status()))
+}
+
+/// Hand the phase back to the calendar (admin)
+pub async fn clear_session_phase(
+    caller: AdminCaller,
+    scheduler: SessionScheduler,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    scheduler.clear_phase_override();
+    record_admin_action(&db_pool, &caller, "clear_session_phase", serde_json::json!({})).await;
+    Ok(warp::reply::json(&scheduler.status()))
+}
+
+/// Halt a symbol, or keep it trading through its kickoff (admin)
+pub async fn override_symbol_halt(
+    symbol_id: u32,
+    caller: AdminCaller,
+    body: Bytes,
+    scheduler: SessionScheduler,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let request: SymbolHaltRequest = serde_json::from_slice(&body).map_err(|e| {
+        warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            format!("Invalid halt override: {e}"),
+            None,
+        )))
+    })?;
+
+    scheduler.override_symbol(symbol_id, request.halted);
+    record_admin_action(
+        &db_pool,
+        &caller,
+        "override_symbol_halt",
+        serde_json::json!({ "symbol_id": symbol_id, "halted": request.halted }),
+    )
+    .await;
+    Ok(warp::reply::json(&scheduler.status()))
+}
+
+/// Hand a symbol's halts back to the calendar (admin)
+pub async fn clear_symbol_halt(
+    symbol_id: u32,
+    caller: AdminCaller,
+    scheduler: SessionScheduler,
+    db_pool: Arc<PgPool>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !scheduler.clear_symbol_override(symbol_id) {
+        return Err(warp::reject::custom(NotFoundError(error_response(
+            "OVERRIDE_NOT_FOUND",
+            format!("No halt override for symbol {symbol_id}"),
+            None,
+        ))));
+    }
+
+    record_admin_action(
+        &db_pool,
+        &caller,
+        "clear_symbol_halt",
+        serde_json::json!({ "symbol_id": symbol_id }),
+    )
+    .await;
+    Ok(warp::reply::json(&scheduler.status()))
+}
+
+/// Map a contest error to a rejection
+fn contest_rejection(err: EquityServiceError) -> warp::Rejection {
+    match err {
+        EquityServiceError::ContestNotFound(contest_id) => warp::reject::custom(NotFoundError(
+            error_response("CONTEST_NOT_FOUND", format!("Contest {contest_id} not found"), None),
+        )),
+        EquityServiceError::InvalidContest(message) => {
+            warp::reject::custom(BadRequestError(error_response("INVALID_CONTEST", message, None)))
+        }
+        other => {
+            tracing::error!("Contest request failed: {}", other);
+            warp::reject::custom(InternalError(error_response(
+                "INTERNAL_ERROR",
+                "Contest request failed".to_string(),
+                None,
+            )))
+        }
+    }
+}
+
+/// List contests (`GET /api/contests`)
+pub async fn list_contests(
+    contests: Arc<ContestService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let contests = contests.contests().await.map_err(contest_rejection)?;
+    Ok(warp::reply::json(&serde_json::json!({ "contests": contests })))
+}
+
+/// Live standings, or final results, of a contest (`GET /api/contests/{id}/leaderboard`)
+pub async fn get_contest_leaderboard(
+    contest_id: i64,
+    contests: Arc<ContestService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let leaderboard = contests.leaderboard(contest_id).await.map_err(contest_rejection)?;
+    Ok(warp::reply::json(&leaderboard))
+}
+
+/// Enter the caller's account into a contest (`POST /api/contests/{id}/entrants`)
+pub async fn enter_contest(
+    contest_id: i64,
+    session: UserSession,
+    contests: Arc<ContestService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let leaderboard =
+        contests.add_entrant(contest_id, session.account_id).await.map_err(contest_rejection)?;
+    Ok(warp::reply::json(&leaderboard))
+}
+
+/// Create a contest (admin)
+pub async fn create_contest(
+    caller: AdminCaller,
+    body: Bytes,
+    contests: Arc<ContestService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let request: NewContest = serde_json::from_slice(&body).map_err(|e| {
+        warp::reject::custom(BadRequestError(error_response(
+            "INVALID_REQUEST",
+            format!("Invalid contest: {e}"),
+            None,
+        )))
+    })?;
+
+    let contest = contests.create_contest(request).await.map_err(contest_rejection)?;
+    tracing::info!(
+        "Admin action create_contest by {}: contest {} '{}'",
+        caller.identity,
+        contest.id,
+        contest.name
+    );
+    Ok(warp::reply::with_status(warp::reply::json(&contest), StatusCode::CREATED))
+}
+
+/// Create the REST order entry routes
+///
+/// These are served by the OrderGateway process, which owns the order router. With a
+/// session scheduler they also carry the `/api/admin/session` calendar overrides, with a
+/// contest service the `/api/contests` endpoints, and with an equity service
+/// `/api/account/risk`.
+pub fn create_order_routes(
+    order_entry: Arc<OrderEntry>,
+    auth_manager: Arc<AuthManager>,
+    session_scheduler: Option<SessionScheduler>,
+    contest_service: Option<Arc<ContestService>>,
+    equity_service: Option<Arc<EquityValuationService>>,
+    db_pool: Arc<PgPool>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    let admin_filter = with_admin(auth_manager.api_key_store());
+    let db_pool_filter = warp::any().map(move || db_pool.clone());
+    let admin_only = admin_filter.clone().map(|caller: AdminCaller, _body: Bytes| caller);
+    let session_filter = with_session(auth_manager);
+    let session_only = session_filter.clone().map(|session: UserSession, _body: Bytes| session);
+    let order_entry_filter = warp::any().map(move || order_entry.clone());
+
+    let place = warp::path!("api" / "orders")
+        .and(warp::post())
+        .and(session_filter.clone())
+        .and(order_entry_filter.clone())
+        .and_then(place_order);
+
+    let list = warp::path!("api" / "orders")
+        .and(warp::get())
+        .and(warp::query::<OrdersQuery>())
+        .and(session_only.clone())
+        .and(order_entry_filter.clone())
+        .and_then(list_orders);
+
+    let get = warp::path!("api" / "orders" / String)
+        .and(warp::get())
+        .and(session_only.clone())
+        .and(order_entry_filter.clone())
+        .and_then(get_order);
+
+    let cancel = warp::path!("api" / "orders" / String)
+        .and(warp::delete())
+        .and(session_only.clone())
+        .and(order_entry_filter)
+        .and_then(cancel_order);
+
+    // Without a calendar the session routes answer 404
+    let scheduler_filter = warp::any().and_then(move || {
+        let scheduler = session_scheduler.clone();
+        async move {
+            scheduler.ok_or_else(|| {
+                warp::reject::custom(NotFoundError(error_response(
+                    "NO_TRADING_CALENDAR",
+                    "No trading calendar is configured".to_string(),
+                    None,
+                )))
+            })
+        }
+    });
+
+    let session_status = warp::path!("api" / "admin" / "session")
+        .and(warp::get())
+        .and(admin_only.clone())
+        .and(scheduler_filter.clone())
+        .and_then(get_session_status);
+
+    let override_phase = warp::path!("api" / "admin" / "session" / "phase")
+        .and(warp::post())
+        .and(admin_filter.clone())
+        .and(scheduler_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(override_session_phase);
+
+    let clear_phase = warp::path!("api" / "admin" / "session" / "phase")
+        .and(warp::delete())
+        .and(admin_only.clone())
+        .and(scheduler_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(clear_session_phase);
+
+    let override_halt = warp::path!("api" / "admin" / "session" / "halts" / u32)
+        .and(warp::post())
+        .and(admin_filter.clone())
+        .and(scheduler_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(override_symbol_halt);
+
+    let clear_halt = warp::path!("api" / "admin" / "session" / "halts" / u32)
+        .and(warp::delete())
+        .and(admin_only)
+        .and(scheduler_filter)
+        .and(db_pool_filter)
+        .and_then(clear_symbol_halt);
+
+    // Without a contest service the contest routes answer 404
+    let contests_filter = warp::any().and_then(move || {
+        let contests = contest_service.clone();
+        async move {
+            contests.ok_or_else(|| {
+                warp::reject::custom(NotFoundError(error_response(
+                    "NO_CONTESTS",
+                    "Contests are not available".to_string(),
+                    None,
+                )))
+            })
+        }
+    });
+
+    let contests = warp::path!("api" / "contests")
+        .and(warp::get())
+        .and(contests_filter.clone())
+        .and_then(list_contests);
+
+    let leaderboard = warp::path!("api" / "contests" / i64 / "leaderboard")
+        .and(warp::get())
+        .and(contests_filter.clone())
+        .and_then(get_contest_leaderboard);
+
+    let enter = warp::path!("api" / "contests" / i64 / "entrants")
+        .and(warp::post())
+        .and(session_only.clone())
+        .and(contests_filter.clone())
+        .and_then(enter_contest);
+
+    let create_contest_route = warp::path!("api" / "admin" / "contests")
+        .and(warp::post())
+        .and(admin_filter)
+        .and(contests_filter)
+        .and_then(create_contest);
+
+    // Without an equity service the risk route answers 404
+    let equity_filter = warp::any().and_then(move || {
+        let equity = equity_service.clone();
+        async move {
+            equity.ok_or_else(|| {
+                warp::reject::custom(NotFoundError(error_response(
+                    "NO_EQUITY_SERVICE",
+                    "Account risk is not available".to_string(),
+                    None,
+                )))
+            })
+        }
+    });
+
+    let account_risk = warp::path!("api" / "account" / "risk")
+        .and(warp::get())
+        .and(session_only)
+        .and(equity_filter)
+        .and_then(get_account_risk);
+
+    place
+        .or(list)
+        .or(get)
+        .or(cancel)
+        .or(session_status)
+        .or(override_phase)
+        .or(clear_phase)
+        .or(override_halt)
+        .or(clear_halt)
+        .or(contests)
+        .or(leaderboard)
+        .or(enter)
+        .or(create_contest_route)
+        .or(account_risk)
+        .with(
+            warp::cors()
+                .allow_any_origin()
+                .allow_headers(vec![
+                    "content-type",
+                    "authorization",
+                    API_KEY_HEADER,
+                    API_TIMESTAMP_HEADER,
+                    API_NONCE_HEADER,
+                    API_SIGNATURE_HEADER,
+                ])
+                .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS"]),
+        )
+        .recover(handle_rejection)
+}
+
 /// Create REST API routes
 async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, std::convert::Infallible> {
     let (code, message) = if let Some(e) = err.find::<NotFoundError>() {
         (warp::http::StatusCode::NOT_FOUND, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<UnauthorizedError>() {
+        (warp::http::StatusCode::UNAUTHORIZED, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<ForbiddenError>() {
+        (warp::http::StatusCode::FORBIDDEN, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<BadRequestError>() {
+        (warp::http::StatusCode::BAD_REQUEST, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<TooManyRequestsError>() {
+        (warp::http::StatusCode::TOO_MANY_REQUESTS, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<InternalError>() {
+        (warp::http::StatusCode::INTERNAL_SERVER_ERROR, e.0.error.message.clone())
+    } else if let Some(e) = err.find::<ServiceUnavailableError>() {
+        (warp::http::StatusCode::SERVICE_UNAVAILABLE, e.0.error.message.clone())
     } else if err.is_not_found() {
         (warp::http::StatusCode::NOT_FOUND, "Not found".to_string())
     } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
@@ -1096,7 +2161,10 @@ pub fn create_routes(
     db_pool: Arc<PgPool>,
     snapshot_manager: Arc<SnapshotManager>,
     cache: Arc<CacheManager>,
+    api_keys: Arc<ApiKeyStore>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    let admin_filter = with_admin(api_keys.clone());
+    let api_keys_filter = warp::any().map(move || api_keys.clone());
     let registry_filter = warp::any().map(move || registry.clone());
     let db_pool_filter = warp::any().map(move || db_pool.clone());
     let snapshot_filter = warp::any().map(move || snapshot_manager.clone());
@@ -1198,6 +2266,71 @@ pub fn create_routes(
         .and(db_pool_filter.clone())
         .and_then(test_scheduler_logic);
 
+    // API key management endpoints (admin)
+    let create_key = warp::path!("api" / "admin" / "api-keys")
+        .and(warp::post())
+        .and(admin_filter.clone())
+        .and(api_keys_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(create_api_key);
+
+    let list_keys = warp::path!("api" / "admin" / "api-keys")
+        .and(warp::get())
+        .and(warp::query::<std::collections::HashMap<String, String>>())
+        .and(admin_filter.clone())
+        .and(api_keys_filter.clone())
+        .and_then(
+            |params: std::collections::HashMap<String, String>,
+             _caller: AdminCaller,
+             _body: Bytes,
+             store: Arc<ApiKeyStore>| async move { list_api_keys(params, store).await },
+        );
+
+    let revoke_key = warp::path!("api" / "admin" / "api-keys" / String)
+        .and(warp::delete())
+        .and(admin_filter.clone())
+        .and(api_keys_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(
+            |key_id: String,
+             caller: AdminCaller,
+             _body: Bytes,
+             store: Arc<ApiKeyStore>,
+             db_pool: Arc<PgPool>| async move {
+                revoke_api_key(key_id, caller, store, db_pool).await
+            },
+        );
+
+    let rotate_key = warp::path!("api" / "admin" / "api-keys" / String / "rotate")
+        .and(warp::post())
+        .and(admin_filter.clone())
+        .and(api_keys_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(
+            |key_id: String,
+             caller: AdminCaller,
+             _body: Bytes,
+             store: Arc<ApiKeyStore>,
+             db_pool: Arc<PgPool>| async move {
+                rotate_api_key(key_id, caller, store, db_pool).await
+            },
+        );
+
+    // Market maker performance endpoint (admin)
+    let market_maker_performance = warp::path!("api" / "admin" / "market-maker" / "performance")
+        .and(warp::get())
+        .and(warp::query::<MarketMakerPerformanceParams>())
+        .and(admin_filter.clone())
+        .and(db_pool_filter.clone())
+        .and_then(
+            |params: MarketMakerPerformanceParams,
+             _caller: AdminCaller,
+             _body: Bytes,
+             db_pool: Arc<PgPool>| async move {
+                get_market_maker_performance(params, db_pool).await
+            },
+        );
+
     // Account positions endpoint
     let account_positions = warp::path("api")
         .and(warp::path("account"))
@@ -1264,13 +2397,337 @@ pub fn create_routes(
         .or(equity_history)
         .or(create_snapshots)
         .or(test_scheduler)
+        .or(create_key)
+        .or(list_keys)
+        .or(revoke_key)
+        .or(rotate_key)
+        .or(market_maker_performance)
         .or(snapshot)
         .or(health)
         .with(
             warp::cors()
                 .allow_any_origin()
-                .allow_headers(vec!["content-type"])
-                .allow_methods(vec!["GET", "POST", "OPTIONS"]),
+                .allow_headers(vec![
+                    "content-type",
+                    "authorization",
+                    API_KEY_HEADER,
+                    API_TIMESTAMP_HEADER,
+                    API_NONCE_HEADER,
+                    API_SIGNATURE_HEADER,
+                ])
+                .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS"]),
         )
         .recover(handle_rejection)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::JwtClaims;
+    use crate::config::{AuthConfig, OrderEntryConfig, RateLimitConfig};
+    use crate::rate_limiter::RateLimiter;
+    use account_service::{AccountService, AccountServiceConfig, NewOrderRecord};
+    use execution_manager::{ExecManagerConfig, ExecutionManager};
+    use order_router::{OrderRouter, RouterConfig};
+    use symbol_coordinator::{CoordinatorConfig, SymbolCoordinator};
+    use tokio::sync::RwLock;
+
+    const JWT_SECRET: &str = "rest-order-routes-test-secret";
+
+    /// Order entry over the test database, with two accounts that each hold a JWT
+    struct Harness {
+        order_entry: Arc<OrderEntry>,
+        auth_manager: Arc<AuthManager>,
+        account_service: Arc<AccountService>,
+        pool: PgPool,
+        accounts: [i64; 2],
+    }
+
+    impl Harness {
+        /// Needs a database with the migrations applied
+        async fn new() -> Self {
+            let url = std::env::var("DATABASE_URL").expect("DATABASE_URL");
+
+            let pool = PgPool::connect(&url).await.unwrap();
+            let mut config = AccountServiceConfig::default();
+            config.database.url = url;
+            config.oauth.redirect_url = "http://localhost/callback".to_string();
+            let account_service = Arc::new(AccountService::new(config).await.unwrap());
+
+            let mut accounts = [0; 2];
+            for account in &mut accounts {
+                *account = sqlx::query_scalar(
+                    "INSERT INTO accounts (display_name) VALUES ('rest-order-test') RETURNING id",
+                )
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            }
+
+            let execution_manager = Arc::new(ExecutionManager::new(
+                ExecManagerConfig::default(),
+                account_service.clone(),
+            ));
+            let symbol_coordinator =
+                Arc::new(SymbolCoordinator::new(CoordinatorConfig::default(), execution_manager));
+            // Without a coordinator the router routes to placeholder queues
+            let order_entry = Arc::new(OrderEntry::new(
+                Arc::new(OrderRouter::new(RouterConfig::default())),
+                symbol_coordinator,
+                Arc::new(RwLock::new(PlayerRegistry::new())),
+                account_service.clone(),
+                Arc::new(RateLimiter::new(RateLimitConfig::default())),
+                OrderEntryConfig::default(),
+            ));
+            let auth_config =
+                AuthConfig { jwt_secret: Some(JWT_SECRET.to_string()), ..AuthConfig::default() };
+            let auth_manager = Arc::new(AuthManager::new(account_service.clone(), auth_config));
+
+            Self { order_entry, auth_manager, account_service, pool, accounts }
+        }
+
+        fn routes(
+            &self,
+        ) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone
+        {
+            create_order_routes(
+                self.order_entry.clone(),
+                self.auth_manager.clone(),
+                None,
+                None,
+                None,
+                Arc::new(self.pool.clone()),
+            )
+        }
+
+        /// Bearer header value for one of the accounts
+        fn bearer(&self, account_id: i64) -> String {
+            let now = chrono::Utc::now().timestamp() as u64;
+            let claims = JwtClaims {
+                sub: account_id.to_string(),
+                iss: "waiver-exchange".to_string(),
+                aud: "waiver-exchange-api".to_string(),
+                exp: now + 600,
+                iat: now,
+                user_id: format!("user-{account_id}"),
+                email: String::new(),
+                name: String::new(),
+            };
+            let token = jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claims,
+                &jsonwebtoken::EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+            )
+            .unwrap();
+            format!("Bearer {token}")
+        }
+
+        /// Record an open order for an account, as placing it would
+        async fn record_order(&self, account_id: i64) -> i64 {
+            let order_id = account_id * 1_000 + 1;
+            self.account_service
+                .orders()
+                .insert(
+                    &NewOrderRecord {
+                        order_id,
+                        account_id,
+                        symbol_id: 1,
+                        client_order_id: None,
+                        side: "BUY".to_string(),
+                        order_type: "LIMIT".to_string(),
+                        price: 100,
+                        quantity: 1,
+                        time_in_force: "GTC".to_string(),
+                    },
+                    chrono::Utc::now() + chrono::Duration::hours(1),
+                )
+                .await
+                .unwrap();
+            order_id
+        }
+
+        async fn cleanup(self) {
+            for account_id in self.accounts {
+                sqlx::query("DELETE FROM orders WHERE account_id = $1")
+                    .bind(account_id)
+                    .execute(&self.pool)
+                    .await
+                    .unwrap();
+                sqlx::query("DELETE FROM accounts WHERE id = $1")
+                    .bind(account_id)
+                    .execute(&self.pool)
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn test_order_routes_require_a_valid_session() {
+        let harness = Harness::new().await;
+        let routes = harness.routes();
+
+        let missing = warp::test::request()
+            .method("POST")
+            .path("/api/orders")
+            .body(r#"{"symbol":"1","side":"BUY","type":"LIMIT","price":100,"quantity":1}"#)
+            .reply(&routes)
+            .await;
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let invalid = warp::test::request()
+            .method("GET")
+            .path("/api/orders")
+            .header("authorization", "Bearer not-a-jwt")
+            .reply(&routes)
+            .await;
+        assert_eq!(invalid.status(), StatusCode::UNAUTHORIZED);
+
+        harness.cleanup().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn test_cannot_cancel_or_read_another_accounts_order() {
+        let harness = Harness::new().await;
+        let routes = harness.routes();
+        let [owner, other] = harness.accounts;
+        let order_id = harness.record_order(owner).await;
+
+        for method in ["DELETE", "GET"] {
+            let reply = warp::test::request()
+                .method(method)
+                .path(&format!("/api/orders/ord_{order_id}"))
+                .header("authorization", harness.bearer(other))
+                .reply(&routes)
+                .await;
+            assert_eq!(reply.status(), StatusCode::NOT_FOUND, "{method}");
+        }
+
+        let order = harness.order_entry.get_order(owner, order_id as u64).await.unwrap();
+        assert!(order.is_open());
+
+        harness.cleanup().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn test_owner_can_read_list_and_cancel_order() {
+        let harness = Harness::new().await;
+        let routes = harness.routes();
+        let owner = harness.accounts[0];
+        let order_id = harness.record_order(owner).await;
+        let bearer = harness.bearer(owner);
+
+        let get = warp::test::request()
+            .method("GET")
+            .path(&format!("/api/orders/ord_{order_id}"))
+            .header("authorization", &bearer)
+            .reply(&routes)
+            .await;
+        assert_eq!(get.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(get.body()).unwrap();
+        assert_eq!(body["order_id"], format!("ord_{order_id}"));
+
+        let list = warp::test::request()
+            .method("GET")
+            .path("/api/orders?status=open")
+            .header("authorization", &bearer)
+            .reply(&routes)
+            .await;
+        assert_eq!(list.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(list.body()).unwrap();
+        assert_eq!(body["total_orders"], 1);
+
+        let cancel = warp::test::request()
+            .method("DELETE")
+            .path(&format!("/api/orders/ord_{order_id}"))
+            .header("authorization", &bearer)
+            .reply(&routes)
+            .await;
+        assert_eq!(cancel.status(), StatusCode::ACCEPTED);
+        let body: serde_json::Value = serde_json::from_slice(cancel.body()).unwrap();
+        assert_eq!(body["status"], "CANCEL_REQUESTED");
+
+        harness.cleanup().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn test_account_risk_requires_a_session() {
+        let harness = Harness::new().await;
+        let routes = harness.routes();
+        let [owner, other] = harness.accounts;
+
+        // The account comes from the session; a query can't name another one
+        let anonymous = warp::test::request()
+            .method("GET")
+            .path(&format!("/api/account/risk?account_id={other}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+
+        // Without an equity service there are no marks to assess risk at
+        let unavailable = warp::test::request()
+            .method("GET")
+            .path("/api/account/risk")
+            .header("authorization", harness.bearer(owner))
+            .reply(&routes)
+            .await;
+        assert_eq!(unavailable.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = serde_json::from_slice(unavailable.body()).unwrap();
+        assert_eq!(body["error"]["message"], "Account risk is not available");
+
+        harness.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn test_order_rejection_statuses() {
+        let cases = [
+            (GatewayError::InvalidOrder("Invalid side: UP".to_string()), StatusCode::BAD_REQUEST),
+            (GatewayError::SymbolNotFound("Player not found".to_string()), StatusCode::BAD_REQUEST),
+            (
+                GatewayError::OrderRouter("backpressure".to_string()),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                GatewayError::System("Failed to record order: pool timed out".to_string()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+        for (err, status) in cases {
+            let reply =
+                warp::Reply::into_response(handle_rejection(order_rejection(err)).await.unwrap());
+            assert_eq!(reply.status(), status);
+        }
+
+        // Server-side failures don't leak their cause
+        let rejection = order_rejection(GatewayError::System(
+            "Failed to record order: pool timed out".to_string(),
+        ));
+        let reply = warp::Reply::into_response(handle_rejection(rejection).await.unwrap());
+        let body = warp::hyper::body::to_bytes(reply.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["message"], "Order request failed");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_rejection_statuses() {
+        let failure = api_key_failure(
+            "revoke",
+            Some("ak_1"),
+            AccountServiceError::Internal { message: "pool timed out".to_string() },
+        );
+        let reply = warp::Reply::into_response(handle_rejection(failure).await.unwrap());
+        assert_eq!(reply.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = warp::hyper::body::to_bytes(reply.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["message"], "Failed to revoke API key");
+        assert!(body["error"]["details"].is_null());
+
+        let reply =
+            warp::Reply::into_response(handle_rejection(api_key_not_found("ak_1")).await.unwrap());
+        assert_eq!(reply.status(), StatusCode::NOT_FOUND);
+    }
+}
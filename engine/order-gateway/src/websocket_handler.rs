@@ -1,28 +1,32 @@
 //! WebSocket connection handler for the OrderGateway
 
 use crate::auth::AuthManager;
+use crate::config::GatewayConfig;
+use crate::disconnect::{DisconnectReports, RestingOrders};
 use crate::error::{GatewayError, GatewayResult};
 use crate::market_data_broadcaster::MarketDataBroadcaster;
 use crate::messages::{
-    AuthRequest, JwtAuthRequest, Message as ApiMessage, OrderPlaceRequest, OrderPlaceResponse,
+    AuthRequest, CancelOnDisconnectRequest, ExecutionUpdate, FairPriceSubscribeRequest,
+    JwtAuthRequest, LeaderboardSubscribeRequest, MassCancelReport, Message as ApiMessage,
+    OrderCancelRequest, OrderPlaceRequest,
 };
+use crate::order_entry::{parse_order_id, OrderEntry};
 use crate::rate_limiter::RateLimiter;
 
 use futures_util::{SinkExt, StreamExt};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::MissedTickBehavior;
 use tokio_tungstenite::accept_async;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info, warn};
 
-// OrderRouter integration
-use account_service::{AccountService, Balance};
-use order_router::{InboundMsgWithSymbol, OrderRouter, RouterError};
-use player_registry::PlayerRegistry;
-use symbol_coordinator::SymbolCoordinator;
-use whistle::{AccountId, InboundMsg, OrderId, OrderType, Side, TickId};
+use account_service::AccountService;
+use equity_service::ContestService;
+use execution_manager::{DispatchEvent, ExecutionManager, OrderCancelled, TradeEvent};
 
 /// WebSocket connection handler
 pub struct WebSocketHandler {
@@ -38,18 +42,27 @@ pub struct WebSocketHandler {
     /// Market data broadcaster
     market_data_broadcaster: Arc<MarketDataBroadcaster>,
 
-    /// Order router for routing orders
-    order_router: Arc<RwLock<OrderRouter>>,
-
-    /// Symbol coordinator for managing trading engines
-    symbol_coordinator: Arc<SymbolCoordinator>,
-
-    /// Player registry for mapping player names to symbol IDs
-    player_registry: Arc<RwLock<PlayerRegistry>>,
+    /// Order entry shared with the REST API
+    order_entry: Arc<OrderEntry>,
 
     /// Account service for balance and position validation
     account_service: Arc<AccountService>,
 
+    /// Gateway configuration (heartbeats, connection timeout, order entry)
+    config: GatewayConfig,
+
+    /// Mass-cancel reports left by dropped cancel-on-disconnect sessions
+    disconnect_reports: Arc<DisconnectReports>,
+
+    /// Orders placed on this connection that can still rest on the book
+    resting_orders: RestingOrders,
+
+    /// Source of the execution events reported on the `execution` stream
+    execution_manager: Option<Arc<ExecutionManager>>,
+
+    /// Contests behind `leaderboard.subscribe`
+    contest_service: Option<Arc<ContestService>>,
+
     /// WebSocket sender channel
     sender: Option<mpsc::UnboundedSender<WsMessage>>,
 
@@ -64,25 +77,38 @@ impl WebSocketHandler {
         auth_manager: Arc<AuthManager>,
         rate_limiter: Arc<RateLimiter>,
         market_data_broadcaster: Arc<MarketDataBroadcaster>,
-        order_router: Arc<RwLock<OrderRouter>>,
-        symbol_coordinator: Arc<SymbolCoordinator>,
-        player_registry: Arc<RwLock<PlayerRegistry>>,
+        order_entry: Arc<OrderEntry>,
         account_service: Arc<AccountService>,
+        config: GatewayConfig,
+        disconnect_reports: Arc<DisconnectReports>,
     ) -> Self {
         Self {
             peer_addr,
             auth_manager,
             rate_limiter,
             market_data_broadcaster,
-            order_router,
-            symbol_coordinator,
-            player_registry,
+            order_entry,
             account_service,
+            config,
+            disconnect_reports,
+            resting_orders: RestingOrders::new(),
+            execution_manager: None,
+            contest_service: None,
             sender: None,
             user_session: None,
         }
     }
 
+    /// Provide the ExecutionManager whose events drive the `execution` stream
+    pub fn set_execution_manager(&mut self, execution_manager: Arc<ExecutionManager>) {
+        self.execution_manager = Some(execution_manager);
+    }
+
+    /// Provide the contests whose leaderboards `leaderboard.subscribe` streams
+    pub fn set_contest_service(&mut self, contest_service: Arc<ContestService>) {
+        self.contest_service = Some(contest_service);
+    }
+
     /// Handle the WebSocket connection
     pub async fn handle(&mut self, stream: TcpStream) -> GatewayResult<()> {
         info!("Handling WebSocket connection from {}", self.peer_addr);
@@ -107,25 +133,92 @@ impl WebSocketHandler {
             }
         });
 
+        // Ping on every heartbeat tick; drop the connection if nothing (including a pong)
+        // has been received within the connection timeout
+        let connection_timeout = Duration::from_secs(self.config.server.connection_timeout);
+        let mut heartbeat = tokio::time::interval(Duration::from_secs(
+            self.config.server.heartbeat_interval.max(1),
+        ));
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        heartbeat.tick().await;
+        let mut last_seen = Instant::now();
+        let mut events = self.execution_manager.as_ref().map(|em| em.subscribe_events());
+        let mut invalidations = Some(self.auth_manager.api_key_store().subscribe_invalidations());
+
         // Main message handling loop
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(message) => {
-                    if let Err(e) = self.handle_message(message).await {
-                        error!("Failed to handle message: {}", e);
-                        self.send_error("message_handling_error", &e.to_string()).await;
+        let disconnect_reason = loop {
+            tokio::select! {
+                msg = ws_receiver.next() => match msg {
+                    Some(Ok(WsMessage::Close(_))) => {
+                        info!("Received close message from {}", self.peer_addr);
+                        break "client closed the connection".to_string();
+                    }
+                    Some(Ok(message)) => {
+                        last_seen = Instant::now();
+                        if let Err(e) = self.handle_message(message).await {
+                            error!("Failed to handle message: {}", e);
+                            self.send_error(None, "message_handling_error", &e.to_string()).await;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        error!("WebSocket error: {}", e);
+                        break format!("websocket error: {e}");
+                    }
+                    None => break "connection closed".to_string(),
+                },
+                event = next_event(&mut events) => match event {
+                    Ok(event) => {
+                        if let Err(e) = self.handle_execution_event(event).await {
+                            error!("Failed to report execution to {}: {}", self.peer_addr, e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Connection {} lagged behind execution events, {} skipped",
+                            self.peer_addr, skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => events = None,
+                },
+                key_id = next_event(&mut invalidations) => match key_id {
+                    Ok(key_id) if self.session_api_key() == Some(key_id.as_str()) => {
+                        self.send_error(None, "api_key_revoked", "API key revoked").await;
+                        break "API key revoked".to_string();
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Connection {} lagged behind API key revocations, {} skipped",
+                            self.peer_addr, skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => invalidations = None,
+                },
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() >= connection_timeout {
+                        warn!(
+                            "No heartbeat from {} in {}s, disconnecting",
+                            self.peer_addr, self.config.server.connection_timeout
+                        );
+                        break "heartbeat timeout".to_string();
+                    }
+                    let ping = chrono::Utc::now().timestamp_millis().to_be_bytes().to_vec();
+                    if self.send_message(WsMessage::Ping(ping)).await.is_err() {
+                        break "send channel closed".to_string();
                     }
-                }
-                Err(e) => {
-                    error!("WebSocket error: {}", e);
-                    break;
                 }
             }
-        }
+        };
 
         // Clean up
         if let Some(session) = &self.user_session {
             self.market_data_broadcaster.remove_client(&session.user_id).await;
+
+            if session.cancel_on_disconnect {
+                let report =
+                    self.cancel_resting_orders(session.account_id, disconnect_reason).await;
+                self.disconnect_reports.record(report).await;
+            }
         }
 
         // Cancel sender task
@@ -135,6 +228,11 @@ impl WebSocketHandler {
         Ok(())
     }
 
+    /// API key the connection logged in with, if any
+    fn session_api_key(&self) -> Option<&str> {
+        self.user_session.as_ref().and_then(|session| session.api_key_id.as_deref())
+    }
+
     /// Handle incoming WebSocket messages
     async fn handle_message(&mut self, message: WsMessage) -> GatewayResult<()> {
         match message {
@@ -161,10 +259,20 @@ impl WebSocketHandler {
         Ok(())
     }
 
-    /// Handle text messages
+    /// Handle text messages; errors are answered with the request's ID
     async fn handle_text_message(&mut self, text: String) -> GatewayResult<()> {
         let message: ApiMessage = serde_json::from_str(&text)?;
+        let id = message.id.clone();
+
+        if let Err(e) = self.handle_request(message).await {
+            error!("Failed to handle request {:?}: {}", id, e);
+            self.send_error(id, "message_handling_error", &e.to_string()).await;
+        }
+        Ok(())
+    }
 
+    /// Dispatch a request to its method handler
+    async fn handle_request(&mut self, message: ApiMessage) -> GatewayResult<()> {
         match message.method.as_deref() {
             Some("auth.login") => {
                 self.handle_auth(message).await?;
@@ -175,9 +283,24 @@ impl WebSocketHandler {
             Some("order.place") | Some("order.submit") => {
                 self.handle_order_place(message).await?;
             }
+            Some("order.cancel") => {
+                self.handle_order_cancel(message).await?;
+            }
             Some("market_data.subscribe") => {
                 self.handle_market_data_subscribe(message).await?;
             }
+            Some("fair_price.subscribe") => {
+                self.handle_fair_price_subscribe(message).await?;
+            }
+            Some("fair_price.unsubscribe") => {
+                self.handle_fair_price_unsubscribe(message).await?;
+            }
+            Some("equity.subscribe") => {
+                self.handle_equity_subscribe(message).await?;
+            }
+            Some("leaderboard.subscribe") => {
+                self.handle_leaderboard_subscribe(message).await?;
+            }
             Some("account.info") => {
                 self.handle_account_info(message).await?;
             }
@@ -193,6 +316,12 @@ impl WebSocketHandler {
             Some("account.select_league") => {
                 self.handle_select_league(message).await?;
             }
+            Some("session.cancel_on_disconnect") => {
+                self.handle_cancel_on_disconnect(message).await?;
+            }
+            Some("session.heartbeat") => {
+                self.handle_heartbeat(message).await?;
+            }
             _ => {
                 return Err(GatewayError::System(format!("Unknown method: {:?}", message.method)));
             }
@@ -209,7 +338,8 @@ impl WebSocketHandler {
                 .ok_or_else(|| GatewayError::System("Missing auth parameters".to_string()))?,
         )?;
 
-        let auth_response = self.auth_manager.authenticate(&auth_request).await?;
+        let auth_response =
+            self.auth_manager.authenticate(&auth_request, self.peer_addr.ip()).await?;
 
         if auth_response.authenticated {
             // Get the session from AuthManager (which includes the account_id)
@@ -236,6 +366,10 @@ impl WebSocketHandler {
         };
 
         self.send_json_message(response).await?;
+
+        if let Some(session) = &self.user_session {
+            self.send_disconnect_reports(session.account_id).await?;
+        }
         Ok(())
     }
 
@@ -274,6 +408,10 @@ impl WebSocketHandler {
         };
 
         self.send_json_message(response).await?;
+
+        if let Some(session) = &self.user_session {
+            self.send_disconnect_reports(session.account_id).await?;
+        }
         Ok(())
     }
 
@@ -287,13 +425,6 @@ impl WebSocketHandler {
             .as_ref()
             .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
 
-        if !session.has_permission("trade") {
-            return Err(GatewayError::Authentication("Insufficient permissions".to_string()));
-        }
-
-        // Check rate limits
-        self.rate_limiter.check_order_rate_limit(&session.user_id).await?;
-
         // Parse order request
         let params = message
             .params
@@ -311,80 +442,64 @@ impl WebSocketHandler {
             }
         };
 
-        // Validate order against account balance and position
-        self.validate_order(&order_request, session.account_id).await?;
-
-        // Generate a single order ID to use for both reservation and Whistle (mask high bit to prevent negative numbers)
-        let order_id = uuid::Uuid::new_v4().as_u128() as u64 & 0x7FFFFFFFFFFFFFFF;
+        let placed = self.order_entry.place_order(session, &order_request).await?;
 
-        // Create reservation for limit orders
-        // TEMPORARILY COMMENTED OUT TO TEST SNAPSHOT ISSUE
-        let _reservation_id = self
-            .create_reservation_with_order_id(&order_request, session.account_id, order_id as i64)
-            .await?;
-
-        // Convert order request to Whistle InboundMsg
-        let order_msg = self.convert_order_request_to_inbound_msg_with_id(
-            &order_request,
-            session.account_id,
-            order_id,
-        )?;
+        // Remember orders that can rest on the book for cancel-on-disconnect
+        if let Some((order_id, symbol_id)) = placed.resting {
+            self.resting_orders.insert(order_id, symbol_id, order_request.quantity);
+        }
 
-        // Create message with symbol ID for routing
-        let symbol_id = self.parse_symbol_id(&order_request.symbol).await?;
-        let msg_with_symbol = InboundMsgWithSymbol { symbol_id, msg: order_msg };
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::to_value(placed.response)?),
+            error: None,
+        };
 
-        // Route order through OrderRouter
-        let current_tick = self.get_current_tick().await?;
-        let mut router = self.order_router.write().await;
+        info!("Sending order response: {:?}", response);
+        self.send_json_message(response).await?;
+        info!("Order response sent successfully");
+        Ok(())
+    }
 
-        match router.route(current_tick, msg_with_symbol) {
-            Ok(()) => {
-                info!("Order successfully routed to symbol {}", symbol_id);
+    /// Handle `order.cancel`
+    ///
+    /// The reply only confirms the cancel was routed; the `execution` stream reports
+    /// CANCELLED once the engine has removed the order.
+    async fn handle_order_cancel(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let session = self
+            .user_session
+            .as_ref()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
 
-                // Create success response using the same order ID that was sent to Whistle
-                let order_response = OrderPlaceResponse {
-                    order_id: format!("ord_{}", order_id),
-                    status: "ACCEPTED".to_string(),
-                    timestamp: chrono::Utc::now().timestamp_millis() as u64,
-                    client_order_id: order_request.client_order_id,
-                };
+        let request: OrderCancelRequest = serde_json::from_value(
+            message
+                .params
+                .ok_or_else(|| GatewayError::System("Missing cancel parameters".to_string()))?,
+        )?;
+        let order_id = parse_order_id(&request.order_id)?;
+        let order = self.order_entry.cancel_order(session, order_id).await?;
 
-                let response = ApiMessage {
-                    id: message.id,
-                    method: None,
-                    stream: None,
-                    params: None,
-                    data: None,
-                    result: Some(serde_json::to_value(order_response)?),
-                    error: None,
-                };
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({
+                "order_id": format!("ord_{}", order.order_id),
+                "client_order_id": order.client_order_id,
+                "status": "CANCEL_REQUESTED",
+                "timestamp": chrono::Utc::now().timestamp_millis(),
+            })),
+            error: None,
+        };
 
-                info!("Sending order response: {:?}", response);
-                self.send_json_message(response).await?;
-                info!("Order response sent successfully");
-                Ok(())
-            }
-            Err(RouterError::Backpressure) => {
-                error!("Order rejected due to backpressure");
-                Err(GatewayError::System("Order rejected due to system backpressure".to_string()))
-            }
-            Err(RouterError::SymbolInactive) => {
-                error!("Order rejected - symbol {} is inactive", symbol_id);
-                Err(GatewayError::System(format!(
-                    "Symbol {} is currently inactive",
-                    order_request.symbol
-                )))
-            }
-            Err(RouterError::SymbolCapacity) => {
-                error!("Order rejected - symbol {} at capacity", symbol_id);
-                Err(GatewayError::System(format!("Symbol {} is at capacity", order_request.symbol)))
-            }
-            Err(e) => {
-                error!("Order routing failed: {:?}", e);
-                Err(GatewayError::System(format!("Order routing failed: {e:?}")))
-            }
-        }
+        self.send_json_message(response).await?;
+        Ok(())
     }
 
     /// Handle market data subscription
@@ -418,371 +533,325 @@ impl WebSocketHandler {
         Ok(())
     }
 
-    /// Send a JSON message
-    async fn send_json_message(&self, message: ApiMessage) -> GatewayResult<()> {
-        let json = serde_json::to_string(&message)?;
-        self.send_message(WsMessage::Text(json)).await?;
-        Ok(())
-    }
+    /// Handle `fair_price.subscribe`: stream fair prices for some or all symbols
+    ///
+    /// Prices arrive on the `fair_price` stream as they are published; the reply carries the
+    /// latest price of each requested symbol as a starting point.
+    async fn handle_fair_price_subscribe(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let session = self
+            .user_session
+            .as_ref()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
 
-    /// Send a message
-    async fn send_message(&self, message: WsMessage) -> GatewayResult<()> {
-        if let Some(sender) = &self.sender {
-            sender
-                .send(message)
-                .map_err(|_| GatewayError::Connection("Failed to send message".to_string()))?;
+        if !session.has_permission("market_data") {
+            return Err(GatewayError::Authentication("Insufficient permissions".to_string()));
         }
-        Ok(())
-    }
 
-    /// Send an error message
-    async fn send_error(&self, code: &str, message: &str) {
-        let error_msg = ApiMessage {
-            id: None,
+        self.rate_limiter.check_market_data_rate_limit(&session.user_id).await?;
+
+        let request: FairPriceSubscribeRequest = match message.params {
+            Some(params) => serde_json::from_value(params)?,
+            None => FairPriceSubscribeRequest::default(),
+        };
+        self.market_data_broadcaster
+            .subscribe_fair_prices(session.user_id.clone(), request.symbols.as_deref())
+            .await;
+        let prices = self.market_data_broadcaster.get_fair_prices(request.symbols.as_deref()).await;
+
+        let response = ApiMessage {
+            id: message.id,
             method: None,
             stream: None,
             params: None,
             data: None,
-            result: None,
-            error: Some(crate::messages::ErrorMessage {
-                code: 50000,
-                message: message.to_string(),
-                details: Some(serde_json::json!({"code": code})),
-            }),
+            result: Some(serde_json::json!({"subscribed": true, "fair_prices": prices})),
+            error: None,
         };
 
-        if let Err(e) = self.send_json_message(error_msg).await {
-            error!("Failed to send error message: {}", e);
-        }
+        self.send_json_message(response).await?;
+        Ok(())
     }
 
-    /// Close the connection
-    pub async fn close(&self) -> GatewayResult<()> {
-        if let Some(sender) = &self.sender {
-            sender
-                .send(WsMessage::Close(None))
-                .map_err(|_| GatewayError::Connection("Failed to close connection".to_string()))?;
-        }
+    /// Handle `fair_price.unsubscribe`: stop the `fair_price` stream
+    async fn handle_fair_price_unsubscribe(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let session = self
+            .user_session
+            .as_ref()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
+
+        self.market_data_broadcaster.unsubscribe_fair_prices(&session.user_id).await;
+
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({"subscribed": false})),
+            error: None,
+        };
+
+        self.send_json_message(response).await?;
         Ok(())
     }
 
-    /// Convert OrderPlaceRequest to Whistle InboundMsg with a specific order ID
-    #[allow(clippy::result_large_err)]
-    fn convert_order_request_to_inbound_msg_with_id(
-        &self,
-        request: &OrderPlaceRequest,
-        account_id: i64,
-        order_id: u64,
-    ) -> GatewayResult<InboundMsg> {
-        let order_id = OrderId::from(order_id);
-        let account_id = AccountId::from(account_id as u32);
-
-        let side = match request.side.to_uppercase().as_str() {
-            "BUY" => Side::Buy,
-            "SELL" => Side::Sell,
-            _ => return Err(GatewayError::System(format!("Invalid side: {}", request.side))),
-        };
+    /// Handle `equity.subscribe`: stream the session account's equity
+    ///
+    /// Updates arrive on the `equity` stream after each tick the account traded or was
+    /// repriced in; the reply carries the latest one, if any was sent yet.
+    async fn handle_equity_subscribe(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let session = self
+            .user_session
+            .as_ref()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
 
-        let order_type = match request.r#type.to_uppercase().as_str() {
-            "LIMIT" => OrderType::Limit,
-            "MARKET" => OrderType::Market,
-            "IOC" => OrderType::Ioc,
-            "POST_ONLY" => OrderType::PostOnly,
-            _ => {
-                return Err(GatewayError::System(format!("Invalid order type: {}", request.r#type)))
-            }
-        };
+        let equity = self
+            .market_data_broadcaster
+            .subscribe_equity(session.account_id, session.user_id.clone())
+            .await;
 
-        let price = match order_type {
-            OrderType::Limit => Some(request.price),
-            _ => None,
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({"subscribed": true, "equity": equity})),
+            error: None,
         };
 
-        let quantity = request.quantity;
-        let ts_norm = chrono::Utc::now().timestamp_millis() as u64;
-        let meta = 0u64; // No metadata for now
-        let enq_seq = 0; // Will be stamped by OrderRouter
-
-        Ok(InboundMsg::submit(
-            order_id, account_id, side, order_type, price, quantity, ts_norm, meta, enq_seq,
-        ))
+        self.send_json_message(response).await?;
+        Ok(())
     }
 
-    /// Convert OrderPlaceRequest to Whistle InboundMsg
-    #[allow(clippy::result_large_err)]
-    fn convert_order_request_to_inbound_msg(
-        &self,
-        request: &OrderPlaceRequest,
-        account_id: i64,
-    ) -> GatewayResult<(InboundMsg, OrderId)> {
-        let order_id = OrderId::from(uuid::Uuid::new_v4().as_u128() as u64);
-
-        let account_id = AccountId::from(account_id as u32);
-
-        let side = match request.side.to_uppercase().as_str() {
-            "BUY" => Side::Buy,
-            "SELL" => Side::Sell,
-            _ => return Err(GatewayError::System(format!("Invalid side: {}", request.side))),
-        };
+    /// Handle `leaderboard.subscribe`: stream a contest's standings
+    ///
+    /// Leaderboards arrive on the `leaderboard` stream whenever the standings change and once
+    /// more when the contest ends; the reply carries the current one.
+    async fn handle_leaderboard_subscribe(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let session = self
+            .user_session
+            .as_ref()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
 
-        let order_type = match request.r#type.to_uppercase().as_str() {
-            "LIMIT" => OrderType::Limit,
-            "MARKET" => OrderType::Market,
-            "IOC" => OrderType::Ioc,
-            "POST_ONLY" => OrderType::PostOnly,
-            _ => {
-                return Err(GatewayError::System(format!("Invalid order type: {}", request.r#type)))
-            }
-        };
+        let contest_service = self
+            .contest_service
+            .as_ref()
+            .ok_or_else(|| GatewayError::System("Contests are not available".to_string()))?;
 
-        let price = match order_type {
-            OrderType::Limit => Some(request.price),
-            _ => None,
-        };
+        let request: LeaderboardSubscribeRequest = serde_json::from_value(
+            message.params.ok_or_else(|| GatewayError::System("Missing contest_id".to_string()))?,
+        )?;
 
-        let quantity = request.quantity;
-        let ts_norm = chrono::Utc::now().timestamp_millis() as u64;
-        let meta = 0u64; // No metadata for now
-        let enq_seq = 0; // Will be stamped by OrderRouter
+        let leaderboard = contest_service
+            .leaderboard(request.contest_id)
+            .await
+            .map_err(|e| GatewayError::System(format!("Failed to get leaderboard: {e}")))?;
+        self.market_data_broadcaster
+            .subscribe_leaderboard(request.contest_id, session.user_id.clone())
+            .await;
 
-        let msg = InboundMsg::submit(
-            order_id, account_id, side, order_type, price, quantity, ts_norm, meta, enq_seq,
-        );
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({"subscribed": true, "leaderboard": leaderboard})),
+            error: None,
+        };
 
-        Ok((msg, order_id))
+        self.send_json_message(response).await?;
+        Ok(())
     }
 
-    /// Parse symbol string to symbol ID
-    async fn parse_symbol_id(&self, symbol: &str) -> GatewayResult<u32> {
-        // First try to parse as a direct symbol ID (for backward compatibility)
-        if let Ok(symbol_id) = symbol.parse::<u32>() {
-            return Ok(symbol_id);
-        }
+    /// Handle `session.cancel_on_disconnect`
+    async fn handle_cancel_on_disconnect(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let request: CancelOnDisconnectRequest = serde_json::from_value(
+            message
+                .params
+                .ok_or_else(|| GatewayError::System("Missing session parameters".to_string()))?,
+        )?;
 
-        // Try to look up player by name in the registry
-        let registry = self.player_registry.read().await;
-        match registry.get_by_name(symbol) {
-            Ok(player_symbol) => {
-                info!("Found player '{}' with symbol ID {}", symbol, player_symbol.symbol_id);
-                Ok(player_symbol.symbol_id)
-            }
-            Err(_) => {
-                // Try partial name search
-                let search_results = registry.search_players(symbol);
-                if search_results.len() == 1 {
-                    let player_symbol = search_results[0];
-                    info!(
-                        "Found player '{}' via search with symbol ID {}",
-                        player_symbol.name, player_symbol.symbol_id
-                    );
-                    Ok(player_symbol.symbol_id)
-                } else if search_results.len() > 1 {
-                    // Multiple matches - return error with suggestions
-                    let suggestions: Vec<String> =
-                        search_results.iter().map(|p| p.name.clone()).collect();
-                    Err(GatewayError::System(format!(
-                        "Multiple players found for '{}': {}. Please be more specific.",
-                        symbol,
-                        suggestions.join(", ")
-                    )))
-                } else {
-                    Err(GatewayError::System(format!("Player not found: {symbol}")))
-                }
-            }
-        }
-    }
+        let session = self
+            .user_session
+            .as_mut()
+            .ok_or_else(|| GatewayError::Authentication("Not authenticated".to_string()))?;
+        session.cancel_on_disconnect = request.enabled;
 
-    /// Get current tick from SymbolCoordinator
-    async fn get_current_tick(&self) -> GatewayResult<TickId> {
-        // Get the actual current tick from SymbolCoordinator
-        // This ensures orders are routed to the correct tick for processing
-        match self.symbol_coordinator.get_current_tick() {
-            Ok(tick) => {
-                info!("OrderGateway got current tick {} from SymbolCoordinator", tick);
-                Ok(tick)
-            }
-            Err(_) => {
-                // Fallback to a reasonable tick if SymbolCoordinator is not available
-                warn!("Failed to get current tick from SymbolCoordinator, using fallback");
-                Ok(1000)
-            }
-        }
-    }
+        info!(
+            "Cancel-on-disconnect {} for account {}",
+            if request.enabled { "enabled" } else { "disabled" },
+            session.account_id
+        );
 
-    /// Validate order against account balance and position
-    async fn validate_order(
-        &self,
-        order_request: &OrderPlaceRequest,
-        account_id: i64,
-    ) -> GatewayResult<()> {
-        let symbol_id = self.parse_symbol_id(&order_request.symbol).await? as i64;
-        let side = match order_request.side.to_uppercase().as_str() {
-            "BUY" => whistle::Side::Buy,
-            "SELL" => whistle::Side::Sell,
-            _ => return Err(GatewayError::System(format!("Invalid side: {}", order_request.side))),
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({"cancel_on_disconnect": request.enabled})),
+            error: None,
         };
 
-        let quantity = Balance::from_basis_points(order_request.quantity as i64);
-        let _price = match order_request.r#type.to_uppercase().as_str() {
-            "MARKET" => {
-                // For market orders, we need to estimate the worst-case price
-                // For now, we'll use a conservative estimate of $1000 per share
-                Balance::from_cents(100000) // $1000
-            }
-            "LIMIT" | "IOC" | "POST_ONLY" => Balance::from_cents(order_request.price as i64),
-            _ => {
-                return Err(GatewayError::System(format!(
-                    "Invalid order type: {}",
-                    order_request.r#type
-                )))
-            }
+        self.send_json_message(response).await?;
+        Ok(())
+    }
+
+    /// Handle `session.heartbeat` (application-level keepalive for clients without ping frames)
+    async fn handle_heartbeat(&mut self, message: ApiMessage) -> GatewayResult<()> {
+        let response = ApiMessage {
+            id: message.id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: Some(serde_json::json!({"timestamp": chrono::Utc::now().timestamp_millis()})),
+            error: None,
         };
 
-        match side {
-            whistle::Side::Buy => {
-                // Validate sufficient balance for buy orders: quantity (shares) × price (cents)
-                let required_amount =
-                    (order_request.quantity as i64) * (order_request.price as i64);
-                let account =
-                    self.account_service.get_account(account_id).await.map_err(|e| {
-                        GatewayError::System(format!("Failed to get account: {}", e))
-                    })?;
+        self.send_json_message(response).await?;
+        Ok(())
+    }
 
-                let available_balance = account.currency_balance.unwrap_or(0);
-                if available_balance < required_amount {
-                    return Err(GatewayError::System(format!(
-                        "Insufficient balance: required {} cents, available {} cents",
-                        required_amount, available_balance
-                    )));
-                }
+    /// Route cancels for every resting order placed on this connection
+    ///
+    /// Fully filled and confirmed-cancelled orders are no longer tracked. A fill or cancel
+    /// still in flight is rejected by the engine, so `cancels_routed` counts cancel
+    /// requests, not orders actually removed.
+    async fn cancel_resting_orders(&mut self, account_id: i64, reason: String) -> MassCancelReport {
+        let orders = self.resting_orders.drain();
+        let failed_order_ids: Vec<String> = self
+            .order_entry
+            .cancel_orders(&orders)
+            .await
+            .into_iter()
+            .map(|order_id| format!("ord_{order_id}"))
+            .collect();
 
-                info!("Buy order validated: account {} has sufficient balance", account_id);
-            }
-            whistle::Side::Sell => {
-                // Validate sufficient position for sell orders
-                let position =
-                    self.account_service.get_position(account_id, symbol_id).await.map_err(
-                        |e| GatewayError::System(format!("Failed to get position: {}", e)),
-                    )?;
-
-                match position {
-                    Some(pos) => {
-                        if pos.quantity < quantity {
-                            return Err(GatewayError::System(format!(
-                                "Insufficient position: required {} shares, available {} shares",
-                                quantity.to_decimal(),
-                                pos.quantity.to_decimal()
-                            )));
-                        }
-                    }
-                    None => {
-                        return Err(GatewayError::System(format!(
-                            "No position found for account {} and symbol {}",
-                            account_id, symbol_id
-                        )));
-                    }
-                }
+        let report = MassCancelReport {
+            account_id,
+            reason,
+            orders_targeted: orders.len(),
+            cancels_routed: orders.len() - failed_order_ids.len(),
+            failed_order_ids,
+            disconnected_at: chrono::Utc::now().to_rfc3339(),
+        };
 
-                info!("Sell order validated: account {} has sufficient position", account_id);
-            }
-        }
+        info!(
+            "Cancel-on-disconnect for account {} ({}): {} orders targeted, {} cancels routed, {} failed",
+            account_id,
+            report.reason,
+            report.orders_targeted,
+            report.cancels_routed,
+            report.failed_order_ids.len()
+        );
 
-        Ok(())
+        report
     }
 
-    /// Create a reservation for limit orders with a specific order ID
-    async fn create_reservation_with_order_id(
-        &self,
-        order_request: &OrderPlaceRequest,
-        account_id: i64,
-        order_id: i64,
-    ) -> GatewayResult<Option<u64>> {
-        // Only create reservations for limit orders
-        if order_request.r#type.to_uppercase() != "LIMIT" {
-            return Ok(None);
+    /// Deliver mass-cancel reports from earlier dropped sessions
+    async fn send_disconnect_reports(&self, account_id: i64) -> GatewayResult<()> {
+        for report in self.disconnect_reports.take(account_id).await {
+            let message = ApiMessage {
+                id: None,
+                method: None,
+                stream: Some("session.mass_cancel".to_string()),
+                params: None,
+                data: Some(serde_json::to_value(report)?),
+                result: None,
+                error: None,
+            };
+            self.send_json_message(message).await?;
         }
+        Ok(())
+    }
 
-        let side = match order_request.side.to_uppercase().as_str() {
-            "BUY" => whistle::Side::Buy,
-            "SELL" => whistle::Side::Sell,
-            _ => return Err(GatewayError::System(format!("Invalid side: {}", order_request.side))),
+    /// Report an execution event on the `execution` stream if it concerns this session
+    async fn handle_execution_event(&mut self, event: DispatchEvent) -> GatewayResult<()> {
+        let Some(account_id) = self.user_session.as_ref().map(|session| session.account_id)
+        else {
+            return Ok(());
         };
 
-        let _quantity = Balance::from_basis_points(order_request.quantity as i64);
-        let _price = Balance::from_cents(order_request.price as i64);
-
-        match side {
-            whistle::Side::Buy => {
-                // Reserve balance for buy orders: quantity (shares) × price (cents)
-                let required_amount =
-                    (order_request.quantity as i64) * (order_request.price as i64);
-
-                let reservation_id = self
-                    .account_service
-                    .check_and_reserve_balance(account_id, required_amount, order_id)
-                    .await
-                    .map_err(|e| {
-                        GatewayError::System(format!("Failed to create reservation: {}", e))
-                    })?;
-
-                info!("Created balance reservation {} for buy order", reservation_id.0);
-                Ok(Some(reservation_id.0))
+        let updates = match &event {
+            DispatchEvent::TradeEvent(trade) => {
+                self.resting_orders.on_trade(trade, account_id);
+                trade_updates(trade, account_id)
             }
-            whistle::Side::Sell => {
-                // For sell orders, we don't need to reserve balance, but we could reserve the position
-                // For now, we'll just return None since the position validation already ensures sufficient shares
-                info!("Sell order - no reservation needed");
-                Ok(None)
+            DispatchEvent::OrderCancelled(cancelled) => {
+                // Cancels carry no account, so only orders placed here are reported
+                if self.resting_orders.remove(cancelled.order_id) {
+                    vec![cancel_update(cancelled)]
+                } else {
+                    Vec::new()
+                }
             }
+            _ => Vec::new(),
+        };
+
+        for update in updates {
+            let message = ApiMessage {
+                id: None,
+                method: None,
+                stream: Some("execution".to_string()),
+                params: None,
+                data: Some(serde_json::to_value(update)?),
+                result: None,
+                error: None,
+            };
+            self.send_json_message(message).await?;
         }
+        Ok(())
+    }
+
+    /// Send a JSON message
+    async fn send_json_message(&self, message: ApiMessage) -> GatewayResult<()> {
+        let json = serde_json::to_string(&message)?;
+        self.send_message(WsMessage::Text(json)).await?;
+        Ok(())
     }
 
-    /// Create a reservation for limit orders
-    async fn create_reservation(
-        &self,
-        order_request: &OrderPlaceRequest,
-        account_id: i64,
-    ) -> GatewayResult<Option<u64>> {
-        // Only create reservations for limit orders
-        if order_request.r#type.to_uppercase() != "LIMIT" {
-            return Ok(None);
+    /// Send a message
+    async fn send_message(&self, message: WsMessage) -> GatewayResult<()> {
+        if let Some(sender) = &self.sender {
+            sender
+                .send(message)
+                .map_err(|_| GatewayError::Connection("Failed to send message".to_string()))?;
         }
+        Ok(())
+    }
 
-        let side = match order_request.side.to_uppercase().as_str() {
-            "BUY" => whistle::Side::Buy,
-            "SELL" => whistle::Side::Sell,
-            _ => return Err(GatewayError::System(format!("Invalid side: {}", order_request.side))),
+    /// Send an error message, echoing the failed request's ID if it had one
+    async fn send_error(&self, id: Option<String>, code: &str, message: &str) {
+        let error_msg = ApiMessage {
+            id,
+            method: None,
+            stream: None,
+            params: None,
+            data: None,
+            result: None,
+            error: Some(crate::messages::ErrorMessage {
+                code: 50000,
+                message: message.to_string(),
+                details: Some(serde_json::json!({"code": code})),
+            }),
         };
 
-        let _quantity = Balance::from_basis_points(order_request.quantity as i64);
-        let _price = Balance::from_cents(order_request.price as i64);
-
-        match side {
-            whistle::Side::Buy => {
-                // Reserve balance for buy orders: quantity (shares) × price (cents)
-                let required_amount =
-                    (order_request.quantity as i64) * (order_request.price as i64);
-                // Generate a unique order ID for the reservation (not tied to client_order_id)
-                let order_id = uuid::Uuid::new_v4().as_u128() as i64;
-
-                let reservation_id = self
-                    .account_service
-                    .check_and_reserve_balance(account_id, required_amount, order_id)
-                    .await
-                    .map_err(|e| {
-                        GatewayError::System(format!("Failed to create reservation: {}", e))
-                    })?;
+        if let Err(e) = self.send_json_message(error_msg).await {
+            error!("Failed to send error message: {}", e);
+        }
+    }
 
-                info!("Created balance reservation {} for buy order", reservation_id.0);
-                Ok(Some(reservation_id.0))
-            }
-            whistle::Side::Sell => {
-                // For sell orders, we don't need to reserve balance, but we could reserve the position
-                // For now, we'll just return None since the position validation already ensures sufficient shares
-                info!("Sell order - no reservation needed");
-                Ok(None)
-            }
+    /// Close the connection
+    pub async fn close(&self) -> GatewayResult<()> {
+        if let Some(sender) = &self.sender {
+            sender
+                .send(WsMessage::Close(None))
+                .map_err(|_| GatewayError::Connection("Failed to close connection".to_string()))?;
         }
+        Ok(())
     }
 
     /// Handle account info request
@@ -1062,3 +1131,58 @@ impl WebSocketHandler {
         Ok(())
     }
 }
+
+/// Next message of a broadcast, or never if there is no source
+pub(crate) async fn next_event<T: Clone>(
+    events: &mut Option<broadcast::Receiver<T>>,
+) -> Result<T, broadcast::error::RecvError> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Fill reports for the sides of a trade that belong to `account_id`
+fn trade_updates(trade: &TradeEvent, account_id: i64) -> Vec<ExecutionUpdate> {
+    let (taker_side, maker_side) = match trade.aggressor_side {
+        whistle::Side::Buy => ("BUY", "SELL"),
+        whistle::Side::Sell => ("SELL", "BUY"),
+    };
+    let sides = [
+        (trade.maker_order_id, trade.maker_account_id, maker_side, "MAKER"),
+        (trade.taker_order_id, trade.taker_account_id, taker_side, "TAKER"),
+    ];
+
+    sides
+        .into_iter()
+        .filter(|(_, owner, _, _)| *owner == account_id)
+        .map(|(order_id, _, side, liquidity)| ExecutionUpdate {
+            order_id: format!("ord_{order_id}"),
+            symbol_id: trade.symbol,
+            exec_type: "TRADE".to_string(),
+            side: Some(side.to_string()),
+            price: Some(trade.price),
+            quantity: Some(trade.quantity),
+            liquidity: Some(liquidity.to_string()),
+            execution_id: Some(trade.execution_id),
+            reason: None,
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        })
+        .collect()
+}
+
+/// Report for an engine-confirmed cancel
+fn cancel_update(cancelled: &OrderCancelled) -> ExecutionUpdate {
+    ExecutionUpdate {
+        order_id: format!("ord_{}", cancelled.order_id),
+        symbol_id: cancelled.symbol,
+        exec_type: "CANCELLED".to_string(),
+        side: None,
+        price: None,
+        quantity: None,
+        liquidity: None,
+        execution_id: None,
+        reason: cancelled.reason.clone(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+    }
+}
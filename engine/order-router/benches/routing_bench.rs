@@ -4,7 +4,7 @@ use whistle::{InboundMsg, OrderType, Side};
 
 fn bench_route_hot_symbol(c: &mut Criterion) {
     let config = RouterConfig::default();
-    let mut router = OrderRouter::new(config);
+    let router = OrderRouter::new(config);
 
     // Pre-activate symbol by routing one message
     let warmup_msg = create_test_message(1, 999);
@@ -25,7 +25,7 @@ fn bench_route_hot_symbol(c: &mut Criterion) {
 
 fn bench_route_multi_symbol(c: &mut Criterion) {
     let config = RouterConfig { num_shards: 4, ..Default::default() };
-    let mut router = OrderRouter::new(config);
+    let router = OrderRouter::new(config);
 
     // Pre-activate multiple symbols
     for symbol_id in 1..=8 {
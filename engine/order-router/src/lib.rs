@@ -24,7 +24,7 @@ mod tests {
     #[test]
     fn test_basic_routing() {
         let config = RouterConfig::default();
-        let mut router = OrderRouter::new(config);
+        let router = OrderRouter::new(config);
 
         // Create a test message
         let msg = InboundMsgWithSymbol {
@@ -1,7 +1,8 @@
 use crate::sharding::{shard_for_symbol, ShardId};
 use crate::types::{InboundMsgWithSymbol, RouterMetrics, SymbolCoordinatorApi};
+use crossbeam_utils::CachePadded;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
 use whistle::{EnqSeq, InboundQueue, RejectReason, TickId};
 
 #[cfg(test)]
@@ -10,6 +11,8 @@ use whistle::InboundMsg;
 /// Router configuration
 #[derive(Debug, Clone)]
 pub struct RouterConfig {
+    /// Independently locked shards symbols are partitioned over (`symbol_id % num_shards`);
+    /// 16 by default, at least 1
     pub num_shards: u32,
     pub spsc_depth_default: usize,
     pub prewarm_top_k: u32,
@@ -21,7 +24,7 @@ pub struct RouterConfig {
 impl Default for RouterConfig {
     fn default() -> Self {
         Self {
-            num_shards: 1,
+            num_shards: 16,
             spsc_depth_default: 2048,
             prewarm_top_k: 128,
             burst_window_ticks: 4,
@@ -64,32 +67,68 @@ struct SymbolState {
     queue: Option<Arc<InboundQueue>>,
     is_active: bool,
     activation_requested: bool,
+    /// Activated ahead of its first order; may be re-activated under `Prewarm`
+    prewarmed: bool,
 }
 
 impl SymbolState {
     fn new() -> Self {
-        Self { enq_seq: 0, queue: None, is_active: false, activation_requested: false }
+        Self {
+            enq_seq: 0,
+            queue: None,
+            is_active: false,
+            activation_requested: false,
+            prewarmed: false,
+        }
     }
 }
 
-/// Main OrderRouter implementation
-pub struct OrderRouter {
-    config: RouterConfig,
+/// One router shard: the symbols with `symbol_id % num_shards == shard_id`
+///
+/// The shard is the single producer for its symbols' SPSC queues, and its lock is only
+/// ever taken by routes to its own symbols.
+#[derive(Debug, Default)]
+struct Shard {
     symbol_states: HashMap<u32, SymbolState>,
     metrics: RouterMetrics,
     current_tick: TickId,
+}
+
+/// A shard's lock, and the condition routes wait on while another route activates one
+/// of its symbols
+#[derive(Debug, Default)]
+struct ShardSlot {
+    shard: Mutex<Shard>,
+    activation_done: Condvar,
+}
+
+impl Shard {
+    /// Handle tick boundary - reset enq_seq for all symbols of the shard
+    fn on_tick_boundary(&mut self, next_tick: TickId) {
+        self.current_tick = next_tick;
+
+        // Reset enq_seq for all active symbols
+        for state in self.symbol_states.values_mut() {
+            state.enq_seq = 0;
+        }
+    }
+}
+
+/// Main OrderRouter implementation
+///
+/// Symbol state is partitioned into `num_shards` independently locked shards, so routes
+/// to symbols on different shards never contend.
+pub struct OrderRouter {
+    config: RouterConfig,
+    shards: Vec<CachePadded<ShardSlot>>,
     coordinator: Option<Box<dyn SymbolCoordinatorApi>>,
 }
 
 impl OrderRouter {
     pub fn new(config: RouterConfig) -> Self {
-        Self {
-            config,
-            symbol_states: HashMap::new(),
-            metrics: RouterMetrics::default(),
-            current_tick: 0,
-            coordinator: None,
-        }
+        let shards =
+            (0..config.num_shards.max(1)).map(|_| CachePadded::new(ShardSlot::default())).collect();
+        Self { config, shards, coordinator: None }
     }
 
     /// Set the SymbolCoordinator for symbol activation
@@ -98,44 +137,39 @@ impl OrderRouter {
     }
 
     /// Route a message to the appropriate symbol queue
-    pub fn route(
-        &mut self,
-        tick_now: TickId,
-        msg: InboundMsgWithSymbol,
-    ) -> Result<(), RouterError> {
+    pub fn route(&self, tick_now: TickId, msg: InboundMsgWithSymbol) -> Result<(), RouterError> {
         tracing::info!(
             "OrderRouter routing message for symbol {} at tick {}",
             msg.symbol_id,
             tick_now
         );
 
+        let symbol_id = msg.symbol_id;
+        let mut guard = self.lock_shard(symbol_id);
+
         // Update current tick if needed
-        if tick_now != self.current_tick {
-            self.on_tick_boundary(tick_now);
+        if tick_now != guard.current_tick {
+            guard.on_tick_boundary(tick_now);
         }
 
-        let symbol_id = msg.symbol_id;
-
         // Check if we need to activate the symbol
-        let needs_activation = {
-            let state = self.symbol_states.entry(symbol_id).or_insert_with(SymbolState::new);
-            !state.is_active && !state.activation_requested
-        };
-
-        if needs_activation {
-            // Mark activation as requested first
-            {
-                let state = self.symbol_states.get_mut(&symbol_id).unwrap();
-                state.activation_requested = true;
+        let state = guard.symbol_states.entry(symbol_id).or_insert_with(SymbolState::new);
+        if !state.is_active {
+            // Under Prewarm only prewarmed symbols are activated
+            if self.config.activation_policy == ActivationPolicy::Prewarm && !state.prewarmed {
+                guard.metrics.rejected_inactive += 1;
+                return Err(RouterError::SymbolInactive);
             }
-            self.metrics.activation_requests += 1;
 
-            // Activate symbol using SymbolCoordinator
+            // Activate symbol using SymbolCoordinator, without holding the shard
+            drop(guard);
             self.activate_symbol(symbol_id)?;
+            guard = self.lock_shard(symbol_id);
         }
+        let shard = &mut *guard;
 
         // Now safely get the state for routing
-        let state = self.symbol_states.get_mut(&symbol_id).unwrap();
+        let state = shard.symbol_states.get_mut(&symbol_id).unwrap();
 
         // Ensure we have a queue for this symbol
         if state.queue.is_none() {
@@ -149,9 +183,28 @@ impl OrderRouter {
 
         // Try to enqueue to SPSC using lock-free interface
         let queue = state.queue.as_ref().unwrap();
-        match queue.try_enqueue_lockfree(enriched_msg) {
+        let mut result = queue.try_enqueue_lockfree(enriched_msg.clone());
+
+        // A closed queue means the symbol was hibernated: re-activate it (which rehydrates
+        // its book) and resend once on the new queue
+        if result == Err(RejectReason::QueueClosed) {
+            state.queue = None;
+            state.is_active = false;
+            shard.metrics.active_symbols = shard.metrics.active_symbols.saturating_sub(1);
+            tracing::info!("OrderRouter re-activating hibernated symbol {}", symbol_id);
+            drop(guard);
+            self.activate_symbol(symbol_id)?;
+            guard = self.lock_shard(symbol_id);
+
+            let state = guard.symbol_states.get_mut(&symbol_id).unwrap();
+            let queue = state.queue.as_ref().ok_or(RouterError::SymbolInactive)?;
+            result = queue.try_enqueue_lockfree(enriched_msg);
+        }
+        let shard = &mut *guard;
+
+        match result {
             Ok(()) => {
-                self.metrics.enqueued += 1;
+                shard.metrics.enqueued += 1;
                 tracing::info!(
                     "OrderRouter successfully enqueued message for symbol {} to Whistle engine",
                     symbol_id
@@ -159,7 +212,7 @@ impl OrderRouter {
                 Ok(())
             }
             Err(_) => {
-                self.metrics.rejected_backpressure += 1;
+                shard.metrics.rejected_backpressure += 1;
                 tracing::warn!(
                     "OrderRouter failed to enqueue message for symbol {} due to backpressure",
                     symbol_id
@@ -169,9 +222,39 @@ impl OrderRouter {
         }
     }
 
+    /// Activate the hottest symbols ahead of their first order
+    ///
+    /// `symbols` is ordered hottest first (e.g. the player registry's top players); at
+    /// most `prewarm_top_k` are activated. Does nothing under `ActivationPolicy::OnDemand`.
+    /// Returns the number of symbols activated.
+    pub fn prewarm(&self, symbols: &[u32]) -> usize {
+        if self.config.activation_policy == ActivationPolicy::OnDemand {
+            return 0;
+        }
+
+        let mut activated = 0;
+        for &symbol_id in symbols.iter().take(self.config.prewarm_top_k as usize) {
+            {
+                let mut shard = self.lock_shard(symbol_id);
+                let state = shard.symbol_states.entry(symbol_id).or_insert_with(SymbolState::new);
+                state.prewarmed = true;
+                if state.is_active {
+                    continue;
+                }
+            }
+            match self.activate_symbol(symbol_id) {
+                Ok(()) => activated += 1,
+                Err(e) => tracing::warn!("Failed to prewarm symbol {}: {:?}", symbol_id, e),
+            }
+        }
+
+        tracing::info!("OrderRouter prewarmed {} symbols", activated);
+        activated
+    }
+
     /// Get shard ID for a symbol
     pub fn shard_for_symbol(&self, symbol_id: u32) -> ShardId {
-        shard_for_symbol(symbol_id, self.config.num_shards)
+        shard_for_symbol(symbol_id, self.shards.len() as u32)
     }
 
     /// Get router configuration
@@ -179,40 +262,81 @@ impl OrderRouter {
         &self.config
     }
 
-    /// Get current metrics
-    pub fn metrics(&self) -> &RouterMetrics {
-        &self.metrics
+    /// Get current metrics, summed over all shards
+    pub fn metrics(&self) -> RouterMetrics {
+        let mut metrics = RouterMetrics::default();
+        for slot in &self.shards {
+            metrics.merge(&slot.shard.lock().unwrap_or_else(PoisonError::into_inner).metrics);
+        }
+        metrics
     }
 
-    /// Handle tick boundary - reset enq_seq for all symbols
-    fn on_tick_boundary(&mut self, next_tick: TickId) {
-        self.current_tick = next_tick;
+    fn shard_slot(&self, symbol_id: u32) -> &ShardSlot {
+        &self.shards[self.shard_for_symbol(symbol_id) as usize]
+    }
 
-        // Reset enq_seq for all active symbols
-        for state in self.symbol_states.values_mut() {
-            state.enq_seq = 0;
-        }
+    fn lock_shard(&self, symbol_id: u32) -> MutexGuard<'_, Shard> {
+        // A panic while routing leaves the shard's state consistent enough to keep routing
+        self.shard_slot(symbol_id).shard.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Activate symbol using SymbolCoordinator
-    fn activate_symbol(&mut self, symbol_id: u32) -> Result<(), RouterError> {
-        let state = self.symbol_states.get_mut(&symbol_id).unwrap();
+    ///
+    /// Must be called without the symbol's shard locked: the coordinator may have to build
+    /// or rehydrate an engine, and other symbols of the shard keep routing meanwhile. A
+    /// route arriving while the symbol is being activated waits for that activation
+    /// instead of requesting another.
+    fn activate_symbol(&self, symbol_id: u32) -> Result<(), RouterError> {
+        let slot = self.shard_slot(symbol_id);
+        let mut shard = self.lock_shard(symbol_id);
+        loop {
+            let state = shard.symbol_states.entry(symbol_id).or_insert_with(SymbolState::new);
+            if state.is_active {
+                return Ok(());
+            }
+            if !state.activation_requested {
+                // Mark activation as requested first
+                state.activation_requested = true;
+                break;
+            }
+            shard = slot.activation_done.wait(shard).unwrap_or_else(PoisonError::into_inner);
+        }
+        shard.metrics.activation_requests += 1;
+        drop(shard);
+
+        let activated = self.ensure_queue(symbol_id);
+
+        let mut shard = self.lock_shard(symbol_id);
+        let shard = &mut *shard;
+        let state = shard.symbol_states.get_mut(&symbol_id).unwrap();
+        // On failure the next order retries the activation
+        state.activation_requested = false;
+        let result = match activated {
+            Ok(queue) => {
+                state.queue = Some(queue);
+                state.is_active = true;
+                shard.metrics.active_symbols += 1;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        slot.activation_done.notify_all();
+        result
+    }
 
+    /// The queue of a newly activated symbol
+    fn ensure_queue(&self, symbol_id: u32) -> Result<Arc<InboundQueue>, RouterError> {
         // Use real SymbolCoordinator if available
         if let Some(coordinator) = &self.coordinator {
             tracing::info!("OrderRouter using real SymbolCoordinator for symbol {}", symbol_id);
             match coordinator.ensure_active(symbol_id) {
                 Ok(ready_at) => {
-                    // Use the real queue from SymbolCoordinator
-                    state.queue = Some(ready_at.queue_writer.queue.clone());
-                    state.is_active = true;
-                    state.activation_requested = false;
-                    self.metrics.active_symbols += 1;
                     tracing::info!(
                         "OrderRouter successfully activated symbol {} with real queue",
                         symbol_id
                     );
-                    Ok(())
+                    // Use the real queue from SymbolCoordinator
+                    Ok(ready_at.queue_writer.queue.clone())
                 }
                 Err(e) => {
                     tracing::warn!(
@@ -226,24 +350,19 @@ impl OrderRouter {
         } else {
             // Fallback to placeholder implementation
             tracing::warn!("OrderRouter using FALLBACK placeholder implementation for symbol {} - NO REAL QUEUE CONNECTION!", symbol_id);
-            let queue = Arc::new(InboundQueue::new(self.config.spsc_depth_default));
-            state.queue = Some(queue);
-            state.is_active = true;
-            state.activation_requested = false;
-            self.metrics.active_symbols += 1;
-            Ok(())
+            Ok(Arc::new(InboundQueue::new(self.config.spsc_depth_default)))
         }
     }
 
     /// Get queue for a symbol (for testing)
     #[cfg(test)]
-    pub fn get_symbol_queue(&mut self, symbol_id: u32) -> Option<&Arc<InboundQueue>> {
-        self.symbol_states.get(&symbol_id)?.queue.as_ref()
+    pub fn get_symbol_queue(&self, symbol_id: u32) -> Option<Arc<InboundQueue>> {
+        self.lock_shard(symbol_id).symbol_states.get(&symbol_id)?.queue.clone()
     }
 
     /// Check if symbol is active
     pub fn is_symbol_active(&self, symbol_id: u32) -> bool {
-        self.symbol_states.get(&symbol_id).is_some_and(|s| s.is_active)
+        self.lock_shard(symbol_id).symbol_states.get(&symbol_id).is_some_and(|s| s.is_active)
     }
 }
 
@@ -277,7 +396,7 @@ mod tests {
     #[test]
     fn test_enq_seq_stamping() {
         let config = RouterConfig::default();
-        let mut router = OrderRouter::new(config);
+        let router = OrderRouter::new(config);
 
         let msg1 = create_test_message(1, 100);
         let msg2 = create_test_message(1, 101);
@@ -297,7 +416,7 @@ mod tests {
     #[test]
     fn test_tick_boundary_reset() {
         let config = RouterConfig::default();
-        let mut router = OrderRouter::new(config);
+        let router = OrderRouter::new(config);
 
         // Route message in tick 100
         let msg1 = create_test_message(1, 100);
@@ -315,7 +434,7 @@ mod tests {
     #[test]
     fn test_symbol_activation() {
         let config = RouterConfig::default();
-        let mut router = OrderRouter::new(config);
+        let router = OrderRouter::new(config);
 
         // Symbol should not be active initially
         assert!(!router.is_symbol_active(1));
@@ -336,7 +455,7 @@ mod tests {
             spsc_depth_default: 2, // Very small queue
             ..Default::default()
         };
-        let mut router = OrderRouter::new(config);
+        let router = OrderRouter::new(config);
 
         // First message should succeed
         let msg1 = create_test_message(1, 100);
@@ -349,4 +468,197 @@ mod tests {
         assert_eq!(result, Err(RouterError::Backpressure));
         assert_eq!(router.metrics().rejected_backpressure, 1);
     }
+
+    /// Hands out a fresh queue on every activation, like a coordinator rehydrating a symbol
+    struct ReactivatingCoordinator {
+        activations: std::sync::Mutex<Vec<Arc<InboundQueue>>>,
+    }
+
+    impl SymbolCoordinatorApi for Arc<ReactivatingCoordinator> {
+        fn ensure_active(
+            &self,
+            _symbol_id: u32,
+        ) -> Result<crate::types::ReadyAtTick, crate::types::CoordError> {
+            let queue = Arc::new(InboundQueue::new(8));
+            self.activations.lock().unwrap().push(queue.clone());
+            Ok(crate::types::ReadyAtTick {
+                next_tick: 0,
+                queue_writer: crate::types::OrderQueueWriter { queue },
+            })
+        }
+
+        fn release_if_idle(&self, _symbol_id: u32) {}
+    }
+
+    #[test]
+    fn test_closed_queue_reactivates_symbol() {
+        let coordinator =
+            Arc::new(ReactivatingCoordinator { activations: std::sync::Mutex::new(Vec::new()) });
+        let mut router = OrderRouter::new(RouterConfig::default());
+        router.set_coordinator(Box::new(coordinator.clone()));
+
+        router.route(100, create_test_message(1, 100)).unwrap();
+
+        // The symbol is hibernated
+        let first = coordinator.activations.lock().unwrap()[0].clone();
+        first.close();
+
+        router.route(100, create_test_message(1, 101)).unwrap();
+
+        let activations = coordinator.activations.lock().unwrap();
+        assert_eq!(activations.len(), 2);
+        assert_eq!(first.len(), 1);
+        assert_eq!(activations[1].len(), 1);
+        assert_eq!(router.metrics().active_symbols, 1);
+        assert_eq!(router.metrics().enqueued, 2);
+    }
+
+    #[test]
+    fn test_symbols_are_partitioned_across_shards() {
+        let config = RouterConfig { num_shards: 4, ..Default::default() };
+        let router = Arc::new(OrderRouter::new(config));
+
+        // Each thread routes its own symbol, on its own shard
+        let handles: Vec<_> = (0..4u32)
+            .map(|symbol_id| {
+                let router = router.clone();
+                std::thread::spawn(move || {
+                    for order_id in 0..100 {
+                        router.route(100, create_test_message(symbol_id, order_id)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for symbol_id in 0..4 {
+            assert_eq!(router.shard_for_symbol(symbol_id), symbol_id);
+            let queue = router.get_symbol_queue(symbol_id).unwrap();
+            assert_eq!(queue.len(), 100);
+            // enq_seq is per symbol: 0..100 in routing order
+            let seqs: Vec<EnqSeq> =
+                std::iter::from_fn(|| queue.try_dequeue_lockfree()).map(|m| m.enq_seq).collect();
+            assert_eq!(seqs, (0..100).collect::<Vec<_>>());
+        }
+        assert_eq!(router.metrics().enqueued, 400);
+        assert_eq!(router.metrics().active_symbols, 4);
+    }
+
+    #[test]
+    fn test_prewarm_policy_only_routes_prewarmed_symbols() {
+        let config = RouterConfig {
+            num_shards: 2,
+            prewarm_top_k: 2,
+            activation_policy: ActivationPolicy::Prewarm,
+            ..Default::default()
+        };
+        let router = OrderRouter::new(config);
+
+        // Hottest first; only the top two are prewarmed
+        assert_eq!(router.prewarm(&[7, 3, 9]), 2);
+        assert!(router.is_symbol_active(7));
+        assert!(router.is_symbol_active(3));
+        assert!(!router.is_symbol_active(9));
+
+        router.route(100, create_test_message(7, 1)).unwrap();
+        assert_eq!(router.route(100, create_test_message(9, 2)), Err(RouterError::SymbolInactive));
+        assert_eq!(router.metrics().rejected_inactive, 1);
+
+        // OnDemand never prewarms; Hybrid prewarms and still activates on demand
+        let on_demand = OrderRouter::new(RouterConfig {
+            activation_policy: ActivationPolicy::OnDemand,
+            ..Default::default()
+        });
+        assert_eq!(on_demand.prewarm(&[7]), 0);
+
+        let hybrid = OrderRouter::new(RouterConfig { prewarm_top_k: 1, ..Default::default() });
+        assert_eq!(hybrid.prewarm(&[7, 3]), 1);
+        hybrid.route(100, create_test_message(3, 1)).unwrap();
+        assert!(hybrid.is_symbol_active(3));
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = RouterConfig::default();
+        assert_eq!(config.num_shards, 16);
+        assert_eq!(config.activation_policy, ActivationPolicy::Hybrid);
+
+        let router = OrderRouter::new(config);
+        assert_eq!(router.shards.len(), 16);
+        assert_eq!(router.shard_for_symbol(17), 1);
+
+        // A zero shard count still routes, on a single shard
+        let router = OrderRouter::new(RouterConfig { num_shards: 0, ..Default::default() });
+        assert_eq!(router.shards.len(), 1);
+        router.route(100, create_test_message(17, 1)).unwrap();
+    }
+
+    /// Holds the activation of symbol 1 until the test lets it through
+    struct GatedCoordinator {
+        entered: std::sync::Mutex<std::sync::mpsc::Sender<()>>,
+        gate: std::sync::Mutex<std::sync::mpsc::Receiver<()>>,
+        activations: std::sync::Mutex<Vec<u32>>,
+    }
+
+    impl SymbolCoordinatorApi for Arc<GatedCoordinator> {
+        fn ensure_active(
+            &self,
+            symbol_id: u32,
+        ) -> Result<crate::types::ReadyAtTick, crate::types::CoordError> {
+            self.activations.lock().unwrap().push(symbol_id);
+            if symbol_id == 1 {
+                self.entered.lock().unwrap().send(()).unwrap();
+                self.gate.lock().unwrap().recv().unwrap();
+            }
+            Ok(crate::types::ReadyAtTick {
+                next_tick: 0,
+                queue_writer: crate::types::OrderQueueWriter {
+                    queue: Arc::new(InboundQueue::new(8)),
+                },
+            })
+        }
+
+        fn release_if_idle(&self, _symbol_id: u32) {}
+    }
+
+    #[test]
+    fn test_activation_does_not_hold_the_shard() {
+        let (entered_tx, entered_rx) = std::sync::mpsc::channel();
+        let (gate_tx, gate_rx) = std::sync::mpsc::channel();
+        let coordinator = Arc::new(GatedCoordinator {
+            entered: std::sync::Mutex::new(entered_tx),
+            gate: std::sync::Mutex::new(gate_rx),
+            activations: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut router = OrderRouter::new(RouterConfig { num_shards: 4, ..Default::default() });
+        router.set_coordinator(Box::new(coordinator.clone()));
+        let router = Arc::new(router);
+
+        let first = {
+            let router = router.clone();
+            std::thread::spawn(move || router.route(100, create_test_message(1, 1)))
+        };
+        entered_rx.recv().unwrap();
+
+        // A second order for the symbol waits for the activation in flight
+        let second = {
+            let router = router.clone();
+            std::thread::spawn(move || router.route(100, create_test_message(1, 2)))
+        };
+
+        // Symbol 5 shares symbol 1's shard and routes while it is being activated
+        assert_eq!(router.shard_for_symbol(5), router.shard_for_symbol(1));
+        router.route(100, create_test_message(5, 3)).unwrap();
+
+        gate_tx.send(()).unwrap();
+        first.join().unwrap().unwrap();
+        second.join().unwrap().unwrap();
+
+        assert_eq!(*coordinator.activations.lock().unwrap(), vec![1, 5]);
+        assert_eq!(router.get_symbol_queue(1).unwrap().len(), 2);
+        assert_eq!(router.metrics().active_symbols, 2);
+        assert_eq!(router.metrics().activation_requests, 2);
+    }
 }
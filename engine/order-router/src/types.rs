@@ -52,3 +52,14 @@ pub struct RouterMetrics {
     pub activation_requests: u64,
     pub active_symbols: u32,
 }
+
+impl RouterMetrics {
+    /// Add another shard's metrics
+    pub fn merge(&mut self, other: &RouterMetrics) {
+        self.enqueued += other.enqueued;
+        self.rejected_backpressure += other.rejected_backpressure;
+        self.rejected_inactive += other.rejected_inactive;
+        self.activation_requests += other.activation_requests;
+        self.active_symbols += other.active_symbols;
+    }
+}
@@ -0,0 +1,511 @@
+//! Backtesting and calibration for Fair Price 2.0
+//!
+//! Replays a season week by week through [`PriceCalculator::calculate_fair2_with_consistency`]
+//! and scores the resulting price path:
+//!
+//! - **Forecast error**: each price implies a points-per-game rate
+//!   (`(price - base_cents) / beta_cents_per_pt`), which is compared with the points the
+//!   player actually scores in their next game (MAE, RMSE, bias)
+//! - **Stability**: mean absolute week-over-week price change, in percent
+//! - **Turnover**: mean rank movement within the position, as a fraction of the position size
+//! - **Band-hit rate**: share of prices pinned to the ±`band_bps` band around F₀
+//!
+//! [`SearchSpace`] generates candidate [`Fair2Config`]s (full grid or seeded random
+//! sampling) and [`search`] ranks them by [`BacktestReport::score`].
+
+use anyhow::Context;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::calculator::PriceCalculator;
+use crate::config::{Fair2Config, RpeConfig};
+use crate::inputs::{PlayerProjection, RpeInputs};
+
+/// Season history to replay, keyed by symbol ID
+#[derive(Debug, Clone, Default)]
+pub struct BacktestData {
+    pub players: Vec<PlayerProjection>,
+    /// Weeks in ascending order
+    pub weeks: Vec<u32>,
+    /// Week -> symbol ID -> fantasy points
+    pub scores: BTreeMap<u32, HashMap<i32, f64>>,
+}
+
+impl BacktestData {
+    /// Load projections and every available week from an input source
+    pub async fn load(inputs: &dyn RpeInputs) -> anyhow::Result<Self> {
+        let players = inputs.projections().await.context("Failed to load projections")?;
+        let weeks = inputs.weeks().await.context("Failed to list weeks")?;
+
+        let mut scores = BTreeMap::new();
+        for &week in &weeks {
+            let week_scores = inputs
+                .week_scores(week)
+                .await
+                .with_context(|| format!("Failed to load week {week}"))?;
+            scores.insert(
+                week,
+                week_scores.into_iter().map(|s| (s.symbol_id, s.fantasy_points)).collect(),
+            );
+        }
+
+        Ok(Self { players, weeks, scores })
+    }
+
+    fn points(&self, week: u32, symbol_id: i32) -> Option<f64> {
+        self.scores.get(&week).and_then(|w| w.get(&symbol_id)).copied()
+    }
+}
+
+/// Backtest metrics for one position (or `ALL`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PositionMetrics {
+    pub position: String,
+    pub players: usize,
+    /// Number of (price, next-game points) pairs behind the error metrics
+    pub forecasts: usize,
+    pub mae: f64,
+    pub rmse: f64,
+    /// Mean of implied minus realized points; positive means prices run rich
+    pub bias: f64,
+    /// Mean absolute week-over-week price change, in percent
+    pub stability_pct: f64,
+    /// Mean |Δrank| within the position per week, as a fraction of the position size
+    pub turnover: f64,
+    pub band_hit_rate: f64,
+}
+
+/// Result of replaying one configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub overall: PositionMetrics,
+    pub by_position: Vec<PositionMetrics>,
+}
+
+impl BacktestReport {
+    /// Objective to minimise: forecast MAE plus a penalty per percent of average price movement
+    pub fn score(&self, stability_penalty: f64) -> f64 {
+        self.overall.mae + stability_penalty * self.overall.stability_pct
+    }
+}
+
+#[derive(Default)]
+struct Accumulator {
+    players: usize,
+    errors: Vec<f64>,
+    moves_pct: Vec<f64>,
+    rank_moves: Vec<f64>,
+    prices: usize,
+    band_hits: usize,
+}
+
+impl Accumulator {
+    fn merge(&mut self, other: &Accumulator) {
+        self.players += other.players;
+        self.errors.extend_from_slice(&other.errors);
+        self.moves_pct.extend_from_slice(&other.moves_pct);
+        self.rank_moves.extend_from_slice(&other.rank_moves);
+        self.prices += other.prices;
+        self.band_hits += other.band_hits;
+    }
+
+    fn finish(&self, position: &str) -> PositionMetrics {
+        let n = self.errors.len();
+        PositionMetrics {
+            position: position.to_string(),
+            players: self.players,
+            forecasts: n,
+            mae: mean(self.errors.iter().map(|e| e.abs())),
+            rmse: mean(self.errors.iter().map(|e| e * e)).sqrt(),
+            bias: mean(self.errors.iter().copied()),
+            stability_pct: mean(self.moves_pct.iter().copied()),
+            turnover: mean(self.rank_moves.iter().copied()),
+            band_hit_rate: if self.prices == 0 {
+                0.0
+            } else {
+                self.band_hits as f64 / self.prices as f64
+            },
+        }
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Replay the season under `config` (which must have Fair Price 2.0 enabled)
+pub fn run_backtest(data: &BacktestData, config: &RpeConfig) -> anyhow::Result<BacktestReport> {
+    let fair2 = config.get_fair2_config().context("Fair Price 2.0 not configured")?;
+    let calculator = PriceCalculator::new(config.clone());
+    let history_len =
+        (fair2.ema_delta.window.max(fair2.consistency.min_weeks_for_sigma) as usize).max(2);
+    let band = fair2.band_bps as f64 / 10000.0;
+
+    let mut by_position: BTreeMap<String, Accumulator> = BTreeMap::new();
+    // Price path per player, one entry per week (carried forward through byes)
+    let mut paths: Vec<Vec<i64>> = Vec::with_capacity(data.players.len());
+
+    for player in &data.players {
+        let acc = by_position.entry(player.position.clone()).or_default();
+        acc.players += 1;
+
+        let mut perf = calculator.initialize_fair2_perf(
+            player.symbol_id,
+            &player.position,
+            player.projected_points,
+        )?;
+        let lower = (perf.f0_cents as f64 * (1.0 - band)).round() as i64;
+        let upper = (perf.f0_cents as f64 * (1.0 + band)).round() as i64;
+        let mut history: Vec<f64> = Vec::new();
+        let mut path: Vec<i64> = Vec::with_capacity(data.weeks.len());
+
+        for (i, &week) in data.weeks.iter().enumerate() {
+            let price = match data.points(week, player.symbol_id) {
+                Some(points) => {
+                    if points > 0.0 {
+                        history.push(points);
+                        if history.len() > history_len {
+                            history.remove(0);
+                        }
+                    }
+                    let price =
+                        calculator.calculate_fair2_with_consistency(&mut perf, points, &history)?;
+                    acc.prices += 1;
+                    if price <= lower || price >= upper {
+                        acc.band_hits += 1;
+                    }
+                    price
+                }
+                None => perf.fair_cents,
+            };
+
+            if let Some(&previous) = path.last() {
+                if previous > 0 {
+                    acc.moves_pct.push((price - previous).abs() as f64 / previous as f64 * 100.0);
+                }
+            }
+            path.push(price);
+
+            // Forecast the player's next game from this week's price
+            let next = data.weeks[i + 1..]
+                .iter()
+                .filter_map(|&w| data.points(w, player.symbol_id))
+                .find(|&points| points > 0.0);
+            if let Some(realized) = next {
+                let implied = (price - fair2.base_cents) as f64 / fair2.beta_cents_per_pt as f64;
+                acc.errors.push(implied - realized);
+            }
+        }
+
+        paths.push(path);
+    }
+
+    // Rank turnover within each position
+    for (position, acc) in by_position.iter_mut() {
+        let members: Vec<usize> = data
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| &p.position == position)
+            .map(|(i, _)| i)
+            .collect();
+        if members.len() < 2 {
+            continue;
+        }
+
+        let mut previous: Option<HashMap<usize, usize>> = None;
+        for week in 0..data.weeks.len() {
+            let mut order = members.clone();
+            order.sort_by(|&a, &b| paths[b][week].cmp(&paths[a][week]).then(a.cmp(&b)));
+            let ranks: HashMap<usize, usize> =
+                order.into_iter().enumerate().map(|(rank, i)| (i, rank)).collect();
+
+            if let Some(prev) = &previous {
+                let moved: usize = members.iter().map(|i| ranks[i].abs_diff(prev[i])).sum();
+                acc.rank_moves.push(moved as f64 / (members.len() * members.len()) as f64);
+            }
+            previous = Some(ranks);
+        }
+    }
+
+    let mut overall = Accumulator::default();
+    for acc in by_position.values() {
+        overall.merge(acc);
+    }
+
+    Ok(BacktestReport {
+        overall: overall.finish("ALL"),
+        by_position: by_position.iter().map(|(pos, acc)| acc.finish(pos)).collect(),
+    })
+}
+
+/// Candidate values for each tunable; the grid is their cartesian product
+#[derive(Debug, Clone)]
+pub struct SearchSpace {
+    pub alpha_modes: Vec<String>,
+    pub alpha_exp_lambdas: Vec<f64>,
+    pub smoothings: Vec<f64>,
+    /// Multipliers applied to every position's kappa in the base config
+    pub kappa_scales: Vec<f64>,
+    pub consistency_scales: Vec<f64>,
+    pub band_bps: Vec<i64>,
+}
+
+impl Default for SearchSpace {
+    fn default() -> Self {
+        Self {
+            alpha_modes: vec!["linear".to_string(), "exp".to_string()],
+            alpha_exp_lambdas: vec![0.06, 0.12, 0.2],
+            smoothings: vec![0.1, 0.3, 0.5],
+            kappa_scales: vec![0.5, 1.0, 1.5],
+            consistency_scales: vec![5.0, 10.0, 20.0],
+            band_bps: vec![2000, 3000, 4000],
+        }
+    }
+}
+
+impl SearchSpace {
+    /// Every combination, applied on top of `base`. Lambda only varies for `exp` alpha.
+    pub fn grid(&self, base: &Fair2Config) -> Vec<Fair2Config> {
+        let mut configs = Vec::new();
+        for mode in &self.alpha_modes {
+            let lambdas: &[f64] =
+                if mode == "exp" { &self.alpha_exp_lambdas } else { &[base.alpha_exp_lambda] };
+            for &lambda in lambdas {
+                for &smoothing in &self.smoothings {
+                    for &kappa_scale in &self.kappa_scales {
+                        for &consistency_scale in &self.consistency_scales {
+                            for &band_bps in &self.band_bps {
+                                configs.push(Self::apply(
+                                    base,
+                                    mode,
+                                    lambda,
+                                    smoothing,
+                                    kappa_scale,
+                                    consistency_scale,
+                                    band_bps,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        configs
+    }
+
+    /// Draw a config uniformly from the range spanned by each list of values
+    pub fn sample(&self, base: &Fair2Config, rng: &mut impl Rng) -> Fair2Config {
+        let mode = &self.alpha_modes[rng.gen_range(0..self.alpha_modes.len().max(1))];
+        Self::apply(
+            base,
+            mode,
+            uniform(rng, &self.alpha_exp_lambdas),
+            uniform(rng, &self.smoothings),
+            uniform(rng, &self.kappa_scales),
+            uniform(rng, &self.consistency_scales),
+            uniform(rng, &self.band_bps.iter().map(|&b| b as f64).collect::<Vec<_>>()).round()
+                as i64,
+        )
+    }
+
+    fn apply(
+        base: &Fair2Config,
+        alpha_mode: &str,
+        alpha_exp_lambda: f64,
+        smoothing: f64,
+        kappa_scale: f64,
+        consistency_scale: f64,
+        band_bps: i64,
+    ) -> Fair2Config {
+        let mut config = base.clone();
+        config.alpha_mode = alpha_mode.to_string();
+        config.alpha_exp_lambda = alpha_exp_lambda;
+        config.ema_delta.smoothing = smoothing;
+        for kappa in config.kappa_cents_per_pt.values_mut() {
+            *kappa = (*kappa as f64 * kappa_scale).round() as i64;
+        }
+        config.consistency.scale = consistency_scale;
+        config.band_bps = band_bps;
+        config
+    }
+}
+
+fn uniform(rng: &mut impl Rng, values: &[f64]) -> f64 {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min < max {
+        rng.gen_range(min..=max)
+    } else {
+        min
+    }
+}
+
+/// A backtested candidate configuration
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub config: Fair2Config,
+    pub report: BacktestReport,
+    pub score: f64,
+}
+
+/// Backtest every candidate and return them best-first
+pub fn search(
+    data: &BacktestData,
+    base: &RpeConfig,
+    candidates: Vec<Fair2Config>,
+    stability_penalty: f64,
+) -> anyhow::Result<Vec<Candidate>> {
+    let mut results = Vec::with_capacity(candidates.len());
+    for fair2 in candidates {
+        let mut config = base.clone();
+        config.fair2 = Some(fair2.clone());
+        let report = run_backtest(data, &config)?;
+        let score = report.score(stability_penalty);
+        results.push(Candidate { config: fair2, report, score });
+    }
+    results.sort_by(|a, b| a.score.total_cmp(&b.score));
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::FixtureInputs;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn player(symbol_id: i32, position: &str, projected_points: f64) -> PlayerProjection {
+        PlayerProjection {
+            symbol_id,
+            name: format!("Player {symbol_id}"),
+            position: position.to_string(),
+            projected_points,
+        }
+    }
+
+    fn data(players: Vec<PlayerProjection>, weeks: &[(u32, i32, f64)]) -> BacktestData {
+        let mut data = BacktestData { players, ..Default::default() };
+        for &(week, symbol_id, points) in weeks {
+            data.scores.entry(week).or_default().insert(symbol_id, points);
+        }
+        data.weeks = data.scores.keys().copied().collect();
+        data
+    }
+
+    #[test]
+    fn test_on_projection_player_has_small_error() {
+        // 170 projected points = 10 per game, and they score exactly that every week
+        let weeks: Vec<_> = (1..=6).map(|w| (w, 1, 10.0)).collect();
+        let report =
+            run_backtest(&data(vec![player(1, "WR", 170.0)], &weeks), &RpeConfig::default())
+                .unwrap();
+
+        let overall = &report.overall;
+        assert_eq!(overall.forecasts, 5);
+        assert!(overall.mae < 0.01, "mae {}", overall.mae);
+        assert!(overall.stability_pct < 0.01);
+        assert_eq!(overall.band_hit_rate, 0.0);
+        assert_eq!(report.by_position.len(), 1);
+        assert_eq!(report.by_position[0].position, "WR");
+    }
+
+    #[test]
+    fn test_band_hits_and_bias_for_breakout() {
+        // Projected for 3 ppg, scores 40 every week: the price pins to the upper band
+        let weeks: Vec<_> = (1..=6).map(|w| (w, 1, 40.0)).collect();
+        let mut config = RpeConfig::default();
+        config.fair2.as_mut().unwrap().band_bps = 1000;
+
+        let report = run_backtest(&data(vec![player(1, "RB", 51.0)], &weeks), &config).unwrap();
+        assert_eq!(report.overall.band_hit_rate, 1.0);
+        // Capped prices imply far fewer points than realized
+        assert!(report.overall.bias < -30.0);
+    }
+
+    #[test]
+    fn test_byes_carry_price_and_forecast_next_game() {
+        let weeks = [(1, 1, 12.0), (1, 2, 8.0), (2, 2, 9.0), (3, 1, 14.0), (3, 2, 7.0)];
+        let players = vec![player(1, "QB", 204.0), player(2, "QB", 136.0)];
+        let report = run_backtest(&data(players, &weeks), &RpeConfig::default()).unwrap();
+
+        // Player 1: week 1 and week 2 (bye) both forecast week 3; player 2: weeks 1-2
+        assert_eq!(report.overall.forecasts, 4);
+        // Prices never cross, so ranks never change
+        assert_eq!(report.overall.turnover, 0.0);
+    }
+
+    #[test]
+    fn test_turnover_counts_rank_swaps() {
+        let weeks = [(1, 1, 0.0), (1, 2, 0.0), (2, 1, 1.0), (2, 2, 60.0)];
+        let players = vec![player(1, "TE", 180.0), player(2, "TE", 170.0)];
+        let report = run_backtest(&data(players, &weeks), &RpeConfig::default()).unwrap();
+
+        // Both players move one place out of two in a single transition
+        assert_eq!(report.by_position[0].turnover, 0.5);
+    }
+
+    #[test]
+    fn test_grid_size_and_lambda_only_varies_for_exp() {
+        let base = RpeConfig::default().fair2.unwrap();
+        let space = SearchSpace::default();
+        let grid = space.grid(&base);
+
+        // linear: 1 lambda, exp: 3 lambdas; then 3^4 for the other knobs
+        assert_eq!(grid.len(), (1 + 3) * 81);
+        assert!(grid
+            .iter()
+            .filter(|c| c.alpha_mode == "linear")
+            .all(|c| c.alpha_exp_lambda == base.alpha_exp_lambda));
+        assert!(grid.iter().any(|c| c.kappa_cents_per_pt["QB"] == 50));
+    }
+
+    #[test]
+    fn test_random_samples_stay_in_range_and_are_seeded() {
+        let base = RpeConfig::default().fair2.unwrap();
+        let space = SearchSpace::default();
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20).map(|_| space.sample(&base, &mut rng)).collect::<Vec<_>>()
+        };
+
+        let samples = draw(7);
+        for config in &samples {
+            assert!((0.1..=0.5).contains(&config.ema_delta.smoothing));
+            assert!((2000..=4000).contains(&config.band_bps));
+            assert!((50..=150).contains(&config.kappa_cents_per_pt["QB"]));
+        }
+        let again = draw(7);
+        assert_eq!(samples[3].ema_delta.smoothing, again[3].ema_delta.smoothing);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_candidates_best_first() {
+        let inputs = FixtureInputs::new()
+            .with_projection(1, "Alpha", "WR", 340.0)
+            .with_projection(2, "Beta", "WR", 170.0)
+            .with_week_score(1, 1, 5.0, None)
+            .with_week_score(1, 2, 22.0, None)
+            .with_week_score(2, 1, 6.0, None)
+            .with_week_score(2, 2, 24.0, None)
+            .with_week_score(3, 1, 4.0, None)
+            .with_week_score(3, 2, 21.0, None);
+        let data = BacktestData::load(&inputs).await.unwrap();
+        assert_eq!(data.weeks, vec![1, 2, 3]);
+
+        let base = RpeConfig::default();
+        let candidates = SearchSpace::default().grid(base.get_fair2_config().unwrap());
+        let results = search(&data, &base, candidates, 0.0).unwrap();
+
+        assert!(results.windows(2).all(|w| w[0].score <= w[1].score));
+        // Form diverges sharply from projections, so the fastest-decaying alpha wins
+        assert_eq!(results[0].config.alpha_mode, "exp");
+        assert_eq!(results[0].config.alpha_exp_lambda, 0.2);
+    }
+}
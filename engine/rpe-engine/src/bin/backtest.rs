@@ -0,0 +1,195 @@
+//! Backtest Fair Price 2.0 against historical weekly points and search for a better config
+//!
+//! Inputs come from the configured RPE source (`RPE_INPUT_SOURCE`, `RPE_DATA_DIR`,
+//! `RPE_SEASON`; see `RpeConfig::from_env`). The winning config is written as a `[fair2]`
+//! section that can be loaded back with `--base`.
+
+use anyhow::Context;
+use clap::{Parser, ValueEnum};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rpe_engine::backtest::{search, BacktestReport, Candidate};
+use rpe_engine::config::{Fair2Config, InputSource};
+use rpe_engine::inputs::inputs_from_config;
+use rpe_engine::{run_backtest, BacktestData, RpeConfig, SearchSpace};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "rpe-backtest")]
+#[command(about = "Backtest and calibrate Fair Price 2.0 parameters")]
+struct Cli {
+    /// Parameter search to run; `none` only backtests the base config
+    #[arg(long, value_enum, default_value = "grid")]
+    search: SearchMode,
+
+    /// Number of configs to draw for `--search random`
+    #[arg(long, default_value = "200")]
+    samples: usize,
+
+    /// Seed for `--search random`
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// Score penalty per percent of average weekly price movement (0 = MAE only)
+    #[arg(long, default_value = "0.0")]
+    stability_penalty: f64,
+
+    /// Starting `[fair2]` config (TOML or JSON, as written by `--output`)
+    #[arg(long)]
+    base: Option<PathBuf>,
+
+    /// Season to replay (overrides RPE_SEASON)
+    #[arg(long)]
+    season: Option<i32>,
+
+    /// Number of ranked candidates to print
+    #[arg(long, default_value = "5")]
+    top: usize,
+
+    /// Output format for the best config
+    #[arg(long, value_enum, default_value = "toml")]
+    format: OutputFormat,
+
+    /// Write the best config here instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchMode {
+    None,
+    Grid,
+    Random,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Fair2Section {
+    fair2: Fair2Config,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // The calculator logs every price at info level; keep the replay quiet
+    tracing_subscriber::fmt().with_max_level(tracing::Level::WARN).init();
+
+    let cli = Cli::parse();
+    let mut config = RpeConfig::from_env()?;
+    if let Some(season) = cli.season {
+        config.rpe.season = season;
+    }
+    if let Some(path) = &cli.base {
+        config.fair2 = Some(load_base(path)?);
+    }
+    let base = config.get_fair2_config().context("Fair Price 2.0 not configured")?.clone();
+
+    let pool = match config.inputs.source {
+        InputSource::Database => {
+            PgPool::connect(&config.database.url).await.context("Failed to connect to database")?
+        }
+        // Never connected by the JSON source
+        InputSource::Json => PgPool::connect_lazy(&config.database.url)?,
+    };
+    let inputs = inputs_from_config(&config, pool);
+    let data = BacktestData::load(inputs.as_ref()).await?;
+    anyhow::ensure!(!data.weeks.is_empty(), "No weekly stats found in {}", inputs.describe());
+    println!(
+        "Replaying {} players over weeks {:?} from {}",
+        data.players.len(),
+        data.weeks,
+        inputs.describe()
+    );
+
+    let baseline = run_backtest(&data, &config)?;
+    println!("\nBase config (score {:.3}):", baseline.score(cli.stability_penalty));
+    print_report(&baseline);
+
+    let candidates = match cli.search {
+        SearchMode::None => return Ok(()),
+        SearchMode::Grid => SearchSpace::default().grid(&base),
+        SearchMode::Random => {
+            let mut rng = StdRng::seed_from_u64(cli.seed);
+            let space = SearchSpace::default();
+            (0..cli.samples).map(|_| space.sample(&base, &mut rng)).collect()
+        }
+    };
+    println!("\nSearching {} candidate configs...", candidates.len());
+    let results = search(&data, &config, candidates, cli.stability_penalty)?;
+
+    for (i, candidate) in results.iter().take(cli.top).enumerate() {
+        println!("\n#{} {}", i + 1, describe(candidate));
+        print_report(&candidate.report);
+    }
+
+    let best = results.first().context("Search produced no candidates")?;
+    let section = Fair2Section { fair2: best.config.clone() };
+    let rendered = match cli.format {
+        OutputFormat::Toml => toml::to_string_pretty(&section)?,
+        OutputFormat::Json => serde_json::to_string_pretty(&section)?,
+    };
+    match &cli.output {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("\nBest config written to {}", path.display());
+        }
+        None => println!("\n{rendered}"),
+    }
+
+    Ok(())
+}
+
+fn load_base(path: &PathBuf) -> anyhow::Result<Fair2Config> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let section: Fair2Section = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text)?
+    } else {
+        toml::from_str(&text)?
+    };
+    Ok(section.fair2)
+}
+
+fn describe(candidate: &Candidate) -> String {
+    let config = &candidate.config;
+    let mut kappa: Vec<_> = config.kappa_cents_per_pt.iter().collect();
+    kappa.sort();
+    format!(
+        "score {:.3}: alpha {} (lambda {:.3}), smoothing {:.2}, consistency scale {:.1}, band {} bps, kappa {:?}",
+        candidate.score,
+        config.alpha_mode,
+        config.alpha_exp_lambda,
+        config.ema_delta.smoothing,
+        config.consistency.scale,
+        config.band_bps,
+        kappa
+    )
+}
+
+fn print_report(report: &BacktestReport) {
+    println!(
+        "  {:<4} {:>7} {:>9} {:>7} {:>7} {:>7} {:>9} {:>9} {:>9}",
+        "pos", "players", "forecasts", "mae", "rmse", "bias", "move %", "turnover", "band hit"
+    );
+    for metrics in report.by_position.iter().chain(std::iter::once(&report.overall)) {
+        println!(
+            "  {:<4} {:>7} {:>9} {:>7.2} {:>7.2} {:>7.2} {:>9.2} {:>9.3} {:>8.1}%",
+            metrics.position,
+            metrics.players,
+            metrics.forecasts,
+            metrics.mae,
+            metrics.rmse,
+            metrics.bias,
+            metrics.stability_pct,
+            metrics.turnover,
+            metrics.band_hit_rate * 100.0
+        );
+    }
+}
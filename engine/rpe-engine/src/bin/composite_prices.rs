@@ -0,0 +1,44 @@
+//! Calculate composite fair prices from `source_values` and write them to `fair_prices`
+//!
+//! Usage: `composite-prices [week] [season]`. Defaults come from `RPE_WEEK` / `RPE_SEASON`
+//! (see `RpeConfig::from_env`); week 0 prices from pre-season values only.
+
+use anyhow::Context;
+use rpe_engine::{CompositePricer, RpeConfig};
+use sqlx::PgPool;
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = RpeConfig::from_env()?;
+    let mut args = std::env::args().skip(1);
+    let week: u32 = match args.next() {
+        Some(week) => week.parse().context("week must be a number")?,
+        None => config.rpe.week,
+    };
+    let season: i32 = match args.next() {
+        Some(season) => season.parse().context("season must be a number")?,
+        None => config.rpe.season,
+    };
+
+    let pool =
+        PgPool::connect(&config.database.url).await.context("Failed to connect to database")?;
+    let prices = CompositePricer::new(pool).run(season, week).await?;
+
+    info!("Top prices for {} week {}:", season, week);
+    for price in prices.iter().take(20) {
+        println!(
+            "{:>6}  {:<28} ${:>7.2}  pct {:.4}  confidence {:.3}",
+            price.symbol_id,
+            price.player_name,
+            price.fair_price_cents as f64 / 100.0,
+            price.composite_percentile,
+            price.confidence
+        );
+    }
+    println!("Stored {} fair prices", prices.len());
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Intra-game live fair prices
+//!
+//! Polls the live stats `sportsdataio-fetcher` writes during game windows and publishes paced
+//! fair prices for `RPE_SEASON` / `RPE_WEEK` until stopped. Pacing follows `RPE_PACING_MODE`
+//! (`step` or `poll-step`); the stats poll interval is `RPE_LIVE_POLL_SECS` and the games
+//! per season used for weekly projections is `RPE_SEASON_GAMES`. When
+//! `FAIR_PRICE_BUS_SOCKET` is set, prices are also pushed to the exchange over that socket.
+
+use fair_price_bus::FairPriceBus;
+use rpe_engine::{LiveRpe, RpeConfig, RpeEvent};
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = RpeConfig::from_env()?;
+    let mut live = LiveRpe::new(config).await?;
+
+    if let Some(path) = fair_price_bus::socket_from_env() {
+        let bus = FairPriceBus::new();
+        live.set_fair_price_bus(bus.clone());
+        tokio::spawn(fair_price_bus::forward_unix(bus.subscribe(), path));
+    }
+
+    let mut events = live.subscribe();
+    tokio::spawn(async move {
+        while let Ok(event) = events.recv().await {
+            if let RpeEvent::FairPriceUpdated { player_id, fair_cents, delta_cents, .. } = event {
+                info!(
+                    "Symbol {}: ${:.2} ({:+} cents)",
+                    player_id,
+                    fair_cents as f64 / 100.0,
+                    delta_cents
+                );
+            }
+        }
+    });
+
+    tokio::select! {
+        result = live.run() => result,
+        _ = tokio::signal::ctrl_c() => {
+            info!("Live RPE stopped");
+            Ok(())
+        }
+    }
+}
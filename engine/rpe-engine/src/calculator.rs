@@ -323,16 +323,24 @@ impl PriceCalculator {
 
     /// Initialize PlayerPerf from JSON projection data
     pub fn initialize_player_perf_from_json(&self, projection: &SeasonProjectionJson) -> Result<PlayerPerf> {
+        self.initialize_fair2_perf(
+            projection.player_id.parse::<i32>().unwrap_or_default(),
+            &projection.position,
+            projection.projected_points,
+        )
+    }
+
+    /// Initialize PlayerPerf with the Fair Price 2.0 baseline for a season projection
+    pub fn initialize_fair2_perf(&self, player_id: i32, position: &str, season_proj: f64) -> Result<PlayerPerf> {
         let fair2_config = self.config.get_fair2_config()
             .context("Fair Price 2.0 not configured")?;
         
-        let season_proj = projection.projected_points;
         let f0_cents = fair2_config.base_cents + 
             (fair2_config.beta_cents_per_pt as f64 * (season_proj / 17.0)) as i64;
         
         Ok(PlayerPerf::new(
-            projection.player_id.parse::<i32>().unwrap_or_default(),
-            projection.position.clone(),
+            player_id,
+            position.to_string(),
             season_proj,
             f0_cents,
         ))
@@ -0,0 +1,681 @@
+//! Composite multi-source fair price model
+//!
+//! Crowd trade values (FantasyCalc, KTC), projections (`sleeper_proj`) and season
+//! performance (`sleeper_stats`) from `source_values` are each normalized to rank
+//! percentiles, blended with week-dependent weights and mapped to cents through the
+//! hybrid log-normal / elite curve configured in `pricing_config`. Results are written to
+//! `fair_prices` together with a snapshot of the configuration that produced them.
+
+use anyhow::Context;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Curve and blending parameters (one row of `pricing_config`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingConfig {
+    /// Config label, e.g. "default"
+    pub label: String,
+
+    /// Log-normal center
+    pub mu: f64,
+
+    /// Log-normal spread
+    pub sigma: f64,
+
+    /// Elite clustering exponent
+    pub gamma: f64,
+
+    /// Price ceiling in cents
+    pub p_max_cents: i64,
+
+    /// Price floor in cents
+    pub p_min_cents: i64,
+
+    /// Percentile where the elite curve takes over from the log-normal
+    pub crossover_pct: f64,
+
+    /// Minimum (pre-normalization) crowd weight
+    pub crowd_floor: f64,
+
+    /// Crowd weight decay per week
+    pub crowd_decay: f64,
+
+    /// Projection weight decay per week
+    pub proj_decay: f64,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            label: "default".to_string(),
+            mu: 7.6,
+            sigma: 1.0,
+            gamma: 0.65,
+            p_max_cents: 20000,
+            p_min_cents: 1,
+            crossover_pct: 0.95,
+            crowd_floor: 0.5,
+            crowd_decay: 0.2,
+            proj_decay: 0.25,
+        }
+    }
+}
+
+/// Group a `source_values.source` contributes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceGroup {
+    /// Trade values: `fantasycalc`, `ktc`
+    Crowd,
+    /// Projected points: `sleeper_proj`
+    Projection,
+    /// Actual points: `sleeper_stats`
+    Performance,
+}
+
+impl SourceGroup {
+    pub fn from_source(source: &str) -> Option<Self> {
+        match source {
+            "fantasycalc" | "ktc" => Some(Self::Crowd),
+            "sleeper_proj" => Some(Self::Projection),
+            "sleeper_stats" => Some(Self::Performance),
+            _ => None,
+        }
+    }
+}
+
+/// Per-group percentiles by symbol ID
+#[derive(Debug, Clone, Default)]
+pub struct SourcePercentiles {
+    pub crowd: HashMap<i32, f64>,
+    pub projection: HashMap<i32, f64>,
+    pub performance: HashMap<i32, f64>,
+}
+
+impl SourcePercentiles {
+    /// Normalize raw per-player values (already averaged within each group)
+    pub fn from_values(
+        crowd: &HashMap<i32, f64>,
+        projection: &HashMap<i32, f64>,
+        performance: &HashMap<i32, f64>,
+    ) -> Self {
+        Self {
+            crowd: normalize_to_percentiles(crowd),
+            projection: normalize_to_percentiles(projection),
+            performance: normalize_to_percentiles(performance),
+        }
+    }
+}
+
+/// Blend weights for a week; always sum to 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WeightSet {
+    pub crowd: f64,
+    pub projection: f64,
+    pub performance: f64,
+}
+
+/// Composite price for one player
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositePrice {
+    pub symbol_id: i32,
+    pub player_name: String,
+    pub fair_price_cents: i64,
+    pub composite_percentile: f64,
+    pub crowd_percentile: Option<f64>,
+    pub projection_percentile: Option<f64>,
+    pub performance_percentile: Option<f64>,
+    /// 0-1, from source coverage and agreement
+    pub confidence: f64,
+}
+
+/// Convert raw values to rank percentiles in (0, 1); ties share their average rank
+pub fn normalize_to_percentiles(values: &HashMap<i32, f64>) -> HashMap<i32, f64> {
+    if values.len() == 1 {
+        return values.keys().map(|id| (*id, 0.5)).collect();
+    }
+
+    let mut sorted: Vec<(i32, f64)> = values.iter().map(|(id, v)| (*id, *v)).collect();
+    sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let n = sorted.len() as f64;
+
+    let mut percentiles = HashMap::with_capacity(sorted.len());
+    let mut i = 0;
+    while i < sorted.len() {
+        let mut j = i;
+        while j < sorted.len() && sorted[j].1 == sorted[i].1 {
+            j += 1;
+        }
+        // 1-based average rank of the tie group, mapped to (rank - 0.5) / n
+        let avg_rank = (i + j + 1) as f64 / 2.0;
+        for (id, _) in &sorted[i..j] {
+            percentiles.insert(*id, (avg_rank - 0.5) / n);
+        }
+        i = j;
+    }
+    percentiles
+}
+
+/// Week-dependent weights: crowd-heavy pre-season, performance grows as actuals accumulate
+pub fn calculate_weights(week: u32, config: &PricingConfig) -> WeightSet {
+    let week = week as f64;
+    let crowd = (1.0 - config.crowd_decay * week).max(config.crowd_floor);
+    let projection = (1.0 - config.proj_decay * week).max(0.05);
+    let performance = if week > 0.0 { (0.1 * week).min(1.0) } else { 0.0 };
+
+    let total = crowd + projection + performance;
+    WeightSet {
+        crowd: crowd / total,
+        projection: projection / total,
+        performance: performance / total,
+    }
+}
+
+/// Inverse normal CDF (Acklam's rational approximation); `p` must be in (0, 1)
+pub fn probit(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] =
+        [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996e0, 3.754408661907416e0];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+/// Map a composite percentile to cents
+///
+/// Below `crossover_pct` the log-normal curve is rescaled onto
+/// `[p_min_cents, p_max_cents * crossover_pct^gamma]`; above it the concave
+/// `p_max_cents * pct^gamma` curve keeps the elite tier clustered.
+pub fn percentile_to_price(pct: f64, config: &PricingConfig) -> i64 {
+    let pct = pct.clamp(0.001, 0.999);
+    let p_min = config.p_min_cents as f64;
+    let p_max = config.p_max_cents as f64;
+
+    if pct <= config.crossover_pct {
+        let log_normal = |p: f64| (config.mu + config.sigma * probit(p)).exp();
+        let min_raw = log_normal(0.001);
+        let crossover_raw = log_normal(config.crossover_pct);
+        let crossover_price = p_max * config.crossover_pct.powf(config.gamma);
+
+        let t = (log_normal(pct) - min_raw) / (crossover_raw - min_raw);
+        ((p_min + t * (crossover_price - p_min)).round() as i64).max(config.p_min_cents)
+    } else {
+        ((p_max * pct.powf(config.gamma)).round() as i64).min(config.p_max_cents)
+    }
+}
+
+/// Confidence from the number of sources and how much they agree
+fn calculate_confidence(available: &[f64]) -> f64 {
+    // 1 source = 0.3, 2 = 0.6, 3 = 0.9
+    let coverage = (available.len() as f64 * 0.3).min(0.9);
+    if available.len() < 2 {
+        return coverage;
+    }
+
+    let mean = available.iter().sum::<f64>() / available.len() as f64;
+    let variance =
+        available.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / available.len() as f64;
+
+    // Up to +0.1 for full agreement; 0.25 is the largest possible variance in [0, 1]
+    (coverage + 0.1 * (1.0 - (variance / 0.25).min(1.0))).min(1.0)
+}
+
+/// Blend the source percentiles and price every player that has at least one source
+///
+/// Weights are renormalized per player over the groups they have data for. Results are
+/// sorted by price, highest first.
+pub fn calculate_prices(
+    players: &HashMap<i32, String>,
+    sources: &SourcePercentiles,
+    week: u32,
+    config: &PricingConfig,
+) -> Vec<CompositePrice> {
+    let weights = calculate_weights(week, config);
+
+    let mut prices: Vec<CompositePrice> = players
+        .iter()
+        .filter_map(|(&symbol_id, name)| {
+            let crowd = sources.crowd.get(&symbol_id).copied();
+            let projection = sources.projection.get(&symbol_id).copied();
+            let performance = sources.performance.get(&symbol_id).copied();
+
+            let weighted = [
+                (crowd, weights.crowd),
+                (projection, weights.projection),
+                (performance, weights.performance),
+            ];
+            let weight_sum: f64 =
+                weighted.iter().filter(|(p, _)| p.is_some()).map(|(_, w)| w).sum();
+            if weight_sum <= 0.0 {
+                return None;
+            }
+            let composite =
+                weighted.iter().filter_map(|(p, w)| p.map(|p| p * w)).sum::<f64>() / weight_sum;
+            let available: Vec<f64> =
+                [crowd, projection, performance].into_iter().flatten().collect();
+
+            Some(CompositePrice {
+                symbol_id,
+                player_name: name.clone(),
+                fair_price_cents: percentile_to_price(composite, config),
+                composite_percentile: composite,
+                crowd_percentile: crowd,
+                projection_percentile: projection,
+                performance_percentile: performance,
+                confidence: calculate_confidence(&available),
+            })
+        })
+        .collect();
+
+    prices.sort_by(|a, b| {
+        b.fair_price_cents.cmp(&a.fair_price_cents).then(a.symbol_id.cmp(&b.symbol_id))
+    });
+    prices
+}
+
+/// Normalize a player name for matching across sources
+///
+/// Lowercases, unifies apostrophes, drops Jr/Sr/II-V suffixes and anything that isn't a
+/// letter, space, hyphen or apostrophe.
+pub fn normalize_name(name: &str) -> String {
+    const SUFFIXES: [&str; 8] = ["jr", "jr.", "sr", "sr.", "ii", "iii", "iv", "v"];
+
+    let lowered: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if matches!(c, '\u{2018}' | '\u{2019}' | '`' | '\u{00B4}') { '\'' } else { c })
+        .collect();
+
+    let mut words: Vec<&str> = lowered.split_whitespace().collect();
+    if words.len() > 1 && words.last().is_some_and(|w| SUFFIXES.contains(w)) {
+        words.pop();
+    }
+
+    words
+        .iter()
+        .map(|w| w.chars().filter(|c| c.is_ascii_lowercase() || *c == '\'' || *c == '-'))
+        .map(String::from_iter)
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How many source rows could be matched to a symbol
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolutionStats {
+    pub resolved: usize,
+    pub unresolved: usize,
+    pub crowd: usize,
+    pub projection: usize,
+    pub performance: usize,
+}
+
+/// Everything a composite run priced from
+#[derive(Debug, Clone)]
+pub struct CompositeInputs {
+    /// symbol_id -> player name
+    pub players: HashMap<i32, String>,
+    pub sources: SourcePercentiles,
+    pub resolution: ResolutionStats,
+}
+
+/// Loads `source_values`, prices them and writes `fair_prices`
+pub struct CompositePricer {
+    pool: PgPool,
+}
+
+impl CompositePricer {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Load the active `pricing_config` row, falling back to the defaults
+    pub async fn load_config(&self) -> anyhow::Result<PricingConfig> {
+        let row = sqlx::query!(
+            r#"
+            SELECT label, mu, sigma, gamma, p_max_cents, p_min_cents, crossover_pct,
+                   crowd_floor, crowd_decay, proj_decay
+            FROM pricing_config
+            WHERE is_active
+            ORDER BY updated_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load pricing config")?;
+
+        let Some(row) = row else {
+            warn!("No active pricing_config row, using defaults");
+            return Ok(PricingConfig::default());
+        };
+
+        let num = |value: BigDecimal| value.to_f64().unwrap_or_default();
+        Ok(PricingConfig {
+            label: row.label,
+            mu: num(row.mu),
+            sigma: num(row.sigma),
+            gamma: num(row.gamma),
+            p_max_cents: row.p_max_cents as i64,
+            p_min_cents: row.p_min_cents as i64,
+            crossover_pct: num(row.crossover_pct),
+            crowd_floor: num(row.crowd_floor),
+            crowd_decay: num(row.crowd_decay),
+            proj_decay: num(row.proj_decay),
+        })
+    }
+
+    /// Load the latest value per source and player for a week and resolve it to symbols
+    ///
+    /// Players are matched through `player_source_mapping` first, then by exact and
+    /// normalized name against `player_metadata`. Several sources in the same group
+    /// (e.g. FantasyCalc and KTC) are averaged before normalizing.
+    pub async fn load_inputs(&self, season: i32, week: u32) -> anyhow::Result<CompositeInputs> {
+        let player_rows = sqlx::query!(
+            r#"SELECT symbol_id AS "symbol_id!", name FROM player_metadata WHERE symbol_id IS NOT NULL"#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load player metadata")?;
+
+        let players: HashMap<i32, String> =
+            player_rows.into_iter().map(|row| (row.symbol_id, row.name)).collect();
+        let mut by_name: HashMap<String, i32> = HashMap::new();
+        let mut by_normalized_name: HashMap<String, i32> = HashMap::new();
+        for (&symbol_id, name) in &players {
+            by_name.insert(name.to_lowercase(), symbol_id);
+            by_normalized_name.insert(normalize_name(name), symbol_id);
+        }
+
+        let mapping_rows =
+            sqlx::query!("SELECT symbol_id, source, source_name FROM player_source_mapping")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to load player source mappings")?;
+
+        let mut mappings: HashMap<(String, String), i32> = HashMap::new();
+        for row in mapping_rows {
+            mappings.insert((row.source, row.source_name.to_lowercase()), row.symbol_id);
+        }
+
+        let value_rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT ON (source, lower(player_name)) source, player_name, raw_value
+            FROM source_values
+            WHERE season = $1 AND week = $2
+            ORDER BY source, lower(player_name), fetched_at DESC
+            "#,
+            season,
+            week as i32
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load source values")?;
+
+        let resolve = |source: &str, player_name: &str| {
+            let lowered = player_name.to_lowercase();
+            // Mappings are stored per provider ("sleeper") while values are per feed
+            // ("sleeper_proj", "sleeper_stats")
+            let provider = source.split('_').next().unwrap_or(source);
+            [source, provider]
+                .iter()
+                .find_map(|s| mappings.get(&(s.to_string(), lowered.clone())).copied())
+                .or_else(|| by_name.get(&lowered).copied())
+                .or_else(|| by_normalized_name.get(&normalize_name(player_name)).copied())
+        };
+
+        let mut accumulated: HashMap<SourceGroup, HashMap<i32, Vec<f64>>> = HashMap::new();
+        let mut resolution = ResolutionStats::default();
+        for row in value_rows {
+            let Some(group) = SourceGroup::from_source(&row.source) else { continue };
+            match resolve(&row.source, &row.player_name) {
+                Some(symbol_id) => {
+                    resolution.resolved += 1;
+                    accumulated
+                        .entry(group)
+                        .or_default()
+                        .entry(symbol_id)
+                        .or_default()
+                        .push(row.raw_value.to_f64().unwrap_or_default());
+                }
+                None => resolution.unresolved += 1,
+            }
+        }
+
+        let averaged = |group: SourceGroup| -> HashMap<i32, f64> {
+            accumulated
+                .get(&group)
+                .map(|values| {
+                    values
+                        .iter()
+                        .map(|(id, v)| (*id, v.iter().sum::<f64>() / v.len() as f64))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let crowd = averaged(SourceGroup::Crowd);
+        let projection = averaged(SourceGroup::Projection);
+        let performance = averaged(SourceGroup::Performance);
+        resolution.crowd = crowd.len();
+        resolution.projection = projection.len();
+        resolution.performance = performance.len();
+
+        Ok(CompositeInputs {
+            players,
+            sources: SourcePercentiles::from_values(&crowd, &projection, &performance),
+            resolution,
+        })
+    }
+
+    /// Upsert prices into `fair_prices`, returning the number of rows written
+    pub async fn store(
+        &self,
+        season: i32,
+        week: u32,
+        prices: &[CompositePrice],
+        config: &PricingConfig,
+    ) -> anyhow::Result<usize> {
+        let calculated_at = chrono::Utc::now();
+        let snapshot = serde_json::json!({
+            "config": config,
+            "weights": calculate_weights(week, config),
+            "calculated_at": calculated_at,
+        });
+        let decimal = |value: f64| BigDecimal::from_f64(value).unwrap_or_default();
+
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        for price in prices {
+            sqlx::query!(
+                r#"
+                INSERT INTO fair_prices (symbol_id, season, week, fair_price_cents, composite_percentile,
+                    crowd_percentile, projection_percentile, performance_percentile, confidence,
+                    config_snapshot, calculated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT (symbol_id, season, week) DO UPDATE SET
+                    fair_price_cents = EXCLUDED.fair_price_cents,
+                    composite_percentile = EXCLUDED.composite_percentile,
+                    crowd_percentile = EXCLUDED.crowd_percentile,
+                    projection_percentile = EXCLUDED.projection_percentile,
+                    performance_percentile = EXCLUDED.performance_percentile,
+                    confidence = EXCLUDED.confidence,
+                    config_snapshot = EXCLUDED.config_snapshot,
+                    calculated_at = EXCLUDED.calculated_at
+                "#,
+                price.symbol_id,
+                season,
+                week as i32,
+                price.fair_price_cents,
+                decimal(price.composite_percentile),
+                price.crowd_percentile.map(decimal),
+                price.projection_percentile.map(decimal),
+                price.performance_percentile.map(decimal),
+                decimal(price.confidence),
+                snapshot,
+                calculated_at,
+            )
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to store fair price for symbol {}", price.symbol_id))?;
+        }
+        tx.commit().await.context("Failed to commit fair prices")?;
+
+        Ok(prices.len())
+    }
+
+    /// Load, price and store one season/week
+    pub async fn run(&self, season: i32, week: u32) -> anyhow::Result<Vec<CompositePrice>> {
+        let config = self.load_config().await?;
+        let inputs = self.load_inputs(season, week).await?;
+        info!(
+            "Composite pricing {} week {}: {} players, sources resolved {:?}",
+            season,
+            week,
+            inputs.players.len(),
+            inputs.resolution
+        );
+
+        let prices = calculate_prices(&inputs.players, &inputs.sources, week, &config);
+        if prices.is_empty() {
+            anyhow::bail!(
+                "No prices calculated for {season} week {week}; check source_values and player mappings"
+            );
+        }
+
+        let stored = self.store(season, week, &prices, &config).await?;
+        info!("Stored {} composite fair prices using config '{}'", stored, config.label);
+        Ok(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(i32, f64)]) -> HashMap<i32, f64> {
+        pairs.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_percentiles_average_ties() {
+        let pct = normalize_to_percentiles(&values(&[(1, 10.0), (2, 20.0), (3, 20.0), (4, 40.0)]));
+        assert_eq!(pct[&1], 0.125);
+        assert_eq!(pct[&2], 0.5);
+        assert_eq!(pct[&3], 0.5);
+        assert_eq!(pct[&4], 0.875);
+
+        assert_eq!(normalize_to_percentiles(&values(&[(7, 3.0)]))[&7], 0.5);
+        assert!(normalize_to_percentiles(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_weights_shift_from_crowd_to_performance() {
+        let config = PricingConfig::default();
+
+        let preseason = calculate_weights(0, &config);
+        assert_eq!(preseason.performance, 0.0);
+        assert_eq!(preseason.crowd, 0.5);
+
+        let late = calculate_weights(10, &config);
+        assert!(late.performance > late.crowd);
+        assert!(late.projection < late.crowd);
+        assert!((late.crowd + late.projection + late.performance - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_probit() {
+        assert!(probit(0.5).abs() < 1e-9);
+        assert!((probit(0.975) - 1.959964).abs() < 1e-3);
+        assert!((probit(0.01) + 2.326348).abs() < 1e-3);
+        assert!((probit(0.2) + probit(0.8)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_curve_is_monotonic_and_bounded() {
+        let config = PricingConfig::default();
+        let mut last = 0;
+        for i in 0..=1000 {
+            let price = percentile_to_price(i as f64 / 1000.0, &config);
+            assert!(price >= last, "price fell at percentile {i}");
+            assert!((config.p_min_cents..=config.p_max_cents).contains(&price));
+            last = price;
+        }
+        assert_eq!(percentile_to_price(0.0, &config), config.p_min_cents);
+        assert!(percentile_to_price(1.0, &config) > 19_000);
+    }
+
+    #[test]
+    fn test_calculate_prices_blends_available_sources() {
+        let config = PricingConfig::default();
+        let players: HashMap<i32, String> =
+            [(1, "Elite"), (2, "Middle"), (3, "Bench"), (4, "No Data")]
+                .into_iter()
+                .map(|(id, name)| (id, name.to_string()))
+                .collect();
+        let sources = SourcePercentiles::from_values(
+            &values(&[(1, 9000.0), (2, 5000.0), (3, 100.0)]),
+            &values(&[(1, 300.0), (2, 150.0)]),
+            &HashMap::new(),
+        );
+
+        let prices = calculate_prices(&players, &sources, 0, &config);
+        assert_eq!(prices.iter().map(|p| p.symbol_id).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let bench = &prices[2];
+        assert_eq!(bench.projection_percentile, None);
+        assert_eq!(bench.composite_percentile, bench.crowd_percentile.unwrap());
+        assert_eq!(bench.confidence, 0.3);
+
+        // Two sources: 0.6 coverage plus part of the 0.1 agreement bonus
+        let elite = &prices[0];
+        assert!(elite.confidence > 0.6 && elite.confidence < 0.7);
+        assert!(elite.composite_percentile > prices[1].composite_percentile);
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Odell Beckham Jr."), "odell beckham");
+        assert_eq!(normalize_name("Marvin Harrison  Jr"), "marvin harrison");
+        assert_eq!(normalize_name("Ja\u{2019}Marr Chase"), "ja'marr chase");
+        assert_eq!(normalize_name("Amon-Ra St. Brown"), "amon-ra st brown");
+        assert_eq!(normalize_name("Kenneth Walker III"), "kenneth walker");
+        assert_eq!(normalize_name("V"), "v");
+    }
+}
@@ -14,6 +14,75 @@ pub struct RpeConfig {
     
     /// Fair Price 2.0 configuration (new adaptive system)
     pub fair2: Option<Fair2Config>,
+    
+    /// Where projections and weekly stats are read from
+    #[serde(default)]
+    pub inputs: InputsConfig,
+    
+    /// Intra-game live pricing
+    #[serde(default)]
+    pub live: LiveConfig,
+}
+
+/// Source of the RPE's input data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputSource {
+    /// Scraper JSON files in `data_dir`
+    Json,
+    /// `projections_season` / `player_week_points` tables
+    Database,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputsConfig {
+    /// Which provider to read from
+    pub source: InputSource,
+    
+    /// Directory holding the JSON files (only used by the JSON source)
+    #[serde(default = "default_data_dir")]
+    pub data_dir: std::path::PathBuf,
+}
+
+impl Default for InputsConfig {
+    fn default() -> Self {
+        Self {
+            source: InputSource::Json,
+            data_dir: default_data_dir(),
+        }
+    }
+}
+
+/// `RPE_DATA_DIR` if set, otherwise `data/players` under the working directory
+fn default_data_dir() -> std::path::PathBuf {
+    match std::env::var_os("RPE_DATA_DIR") {
+        Some(data_dir) => data_dir.into(),
+        None => std::path::PathBuf::from("data/players"),
+    }
+}
+
+/// Intra-game live pricing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveConfig {
+    /// How often to poll for new live stats, in seconds
+    pub poll_interval_secs: u64,
+    
+    /// Expected game length in minutes, used to estimate the pacing step
+    pub game_minutes: u32,
+    
+    /// Games per season, used to turn season projections into a weekly expectation
+    pub season_games: u32,
+}
+
+impl Default for LiveConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 30,
+            game_minutes: 195, // 3h15m including breaks
+            season_games: 17,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +244,8 @@ impl Default for RpeConfig {
                 emission_interval_ms: 1000, // 1 second
             },
             fair2: Some(fair2_config), // Enable Fair Price 2.0 by default
+            inputs: InputsConfig::default(),
+            live: LiveConfig::default(),
         }
     }
 }
@@ -212,6 +283,29 @@ impl RpeConfig {
             config.rpe.ingame_band_bps = band_bps.parse().unwrap_or(3000);
         }
         
+        if let Ok(pacing_mode) = std::env::var("RPE_PACING_MODE") {
+            config.rpe.pacing_mode = match pacing_mode.as_str() {
+                "step" | "poll-step" => pacing_mode,
+                other => anyhow::bail!("Unknown RPE_PACING_MODE: {}", other),
+            };
+        }
+        
+        if let Ok(poll_secs) = std::env::var("RPE_LIVE_POLL_SECS") {
+            config.live.poll_interval_secs = poll_secs.parse().unwrap_or(30);
+        }
+        
+        if let Ok(season_games) = std::env::var("RPE_SEASON_GAMES") {
+            config.live.season_games = season_games.parse().unwrap_or(17);
+        }
+        
+        if let Ok(source) = std::env::var("RPE_INPUT_SOURCE") {
+            config.inputs.source = match source.to_lowercase().as_str() {
+                "json" => InputSource::Json,
+                "database" | "db" => InputSource::Database,
+                other => anyhow::bail!("Unknown RPE_INPUT_SOURCE: {}", other),
+            };
+        }
+        
         Ok(config)
     }
     
@@ -308,4 +402,16 @@ mod tests {
         assert_eq!(fair2.get_kappa_for_position("TE"), 150);
         assert_eq!(fair2.get_kappa_for_position("UNKNOWN"), 150); // Default
     }
+
+    #[test]
+    fn test_inputs_data_dir_resolved_at_runtime() {
+        let inputs: InputsConfig = toml::from_str("source = \"json\"").unwrap();
+        assert_eq!(inputs.data_dir, default_data_dir());
+        if std::env::var_os("RPE_DATA_DIR").is_none() {
+            assert_eq!(inputs.data_dir, std::path::Path::new("data/players"));
+        }
+        
+        let inputs: InputsConfig = toml::from_str("source = \"json\"\ndata_dir = \"/srv/players\"").unwrap();
+        assert_eq!(inputs.data_dir, std::path::Path::new("/srv/players"));
+    }
 }
\ No newline at end of file
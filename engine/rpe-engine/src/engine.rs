@@ -1,22 +1,27 @@
 use anyhow::Context;
 use std::collections::HashMap;
-use std::fs;
 use sqlx::PgPool;
 use tracing::info;
-use bigdecimal::{ToPrimitive, FromPrimitive};
+use bigdecimal::FromPrimitive;
+use fair_price_bus::FairPriceBus;
 
 use crate::{
     config::RpeConfig,
     calculator::{PriceCalculator, LeaderboardCalculator, PlayerPerformance},
-    models::{SeasonProjectionJson, WeeklyPlayerData, RpeEvent},
+    inputs::{inputs_from_config, RpeInputs},
+    models::RpeEvent,
 };
-use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
-struct SeasonProjectionsJson {
-    season: String,
-    last_updated: String,
-    players: Vec<SeasonProjectionJson>,
+/// Rank assumed for players missing from the leaderboard
+const UNRANKED: u32 = 500;
+
+/// Leaderboard fair price calculated for one player
+#[derive(Debug, Clone)]
+pub struct PlayerFairPrice {
+    pub performance: PlayerPerformance,
+    pub leaderboard_price: f64,
+    pub momentum_adjustment: f64,
+    pub fair_cents: i64,
 }
 
 pub struct RpeEngine {
@@ -24,122 +29,137 @@ pub struct RpeEngine {
     calculator: PriceCalculator,
     leaderboard_calc: LeaderboardCalculator,
     pool: PgPool,
+    inputs: Box<dyn RpeInputs>,
     last_prices: HashMap<i32, i64>,
+    bus: Option<FairPriceBus>,
 }
 
 impl RpeEngine {
     pub async fn new(config: RpeConfig) -> anyhow::Result<Self> {
         info!("🔧 Creating RPE Engine with Leaderboard-First Pricing");
         
-        let calculator = PriceCalculator::new(config.clone());
-        let leaderboard_calc = LeaderboardCalculator::new(config.clone());
         let pool = PgPool::connect(&config.database.url)
             .await
             .context("Failed to connect to database")?;
+        let inputs = inputs_from_config(&config, pool.clone());
         
         info!("✅ RPE Engine created successfully");
         
-        Ok(Self {
+        Ok(Self::with_inputs(config, pool, inputs))
+    }
+
+    /// Create an engine that reads from the given inputs instead of the configured source
+    pub fn with_inputs(config: RpeConfig, pool: PgPool, inputs: Box<dyn RpeInputs>) -> Self {
+        info!("📂 RPE inputs: {}", inputs.describe());
+        
+        Self {
+            calculator: PriceCalculator::new(config.clone()),
+            leaderboard_calc: LeaderboardCalculator::new(config.clone()),
             config,
-            calculator,
-            leaderboard_calc,
             pool,
+            inputs,
             last_prices: HashMap::new(),
-        })
+            bus: None,
+        }
     }
 
-    /// Process Fair Price 2.3 - NFL Leaderboard + Weekly Rankings + Season Projections
-    /// Combines real NFL rankings with weekly performance and season projections
-    pub async fn process_fair_price_2_0(&mut self) -> anyhow::Result<Vec<RpeEvent>> {
-        info!("🚀 Starting Fair Price 2.3 - NFL Leaderboard + Weekly Rankings + Season Projections");
-        
-        // Load season projections from JSON (relative to project root)
-        let projections_content = fs::read_to_string("../../data/players/season_projections_2025.json")?;
-        let projections_data: SeasonProjectionsJson = serde_json::from_str(&projections_content)?;
-        
-        // Load NFL Fantasy leaderboard data
-        let leaderboard_content = fs::read_to_string("../../data/players/nfl_fantasy_leaderboard_2025.json")?;
-        let leaderboard_data: serde_json::Value = serde_json::from_str(&leaderboard_content)?;
-        let nfl_players: Vec<serde_json::Value> = leaderboard_data["players"].as_array().unwrap().clone();
+    /// Publish every fair price update on `bus` as well as returning it
+    pub fn set_fair_price_bus(&mut self, bus: FairPriceBus) {
+        self.bus = Some(bus);
+    }
+
+    /// Load the inputs and calculate leaderboard fair prices without storing anything
+    pub async fn calculate_fair_prices(&self) -> anyhow::Result<Vec<PlayerFairPrice>> {
+        let projections = self.inputs.projections().await?;
+        let leaderboard_ranks = self.inputs.leaderboard_ranks().await?;
+        let weeks = self.inputs.weeks().await?;
         
-        // Load weekly data from JSON files
-        let mut weekly_data_map = HashMap::new();
-        for week in 1..=6 {
-            let filename = format!("../../data/players/week_{}_stats_2025.json", week);
-            if std::path::Path::new(&filename).exists() {
-                let weekly_content = fs::read_to_string(&filename)?;
-                let weekly_data: WeeklyPlayerData = serde_json::from_str(&weekly_content)?;
-                weekly_data_map.insert(week, weekly_data);
+        // symbol_id -> [(week, points)] and [(week, rank)], in week order
+        let mut weekly_points: HashMap<i32, Vec<(u32, f64)>> = HashMap::new();
+        let mut weekly_ranks: HashMap<i32, Vec<(u32, u32)>> = HashMap::new();
+        for &week in &weeks {
+            for score in self.inputs.week_scores(week).await? {
+                weekly_points.entry(score.symbol_id).or_default().push((week, score.fantasy_points));
+                if let Some(rank) = score.rank {
+                    weekly_ranks.entry(score.symbol_id).or_default().push((week, rank));
+                }
             }
         }
+        let current_week = weeks.last().copied().unwrap_or(0);
 
-        info!("📊 Loaded {} season projections", projections_data.players.len());
-        info!("🏆 Loaded {} NFL leaderboard players", nfl_players.len());
-        info!("📅 Loaded weekly data for {} weeks", weekly_data_map.len());
+        info!("📊 Loaded {} season projections", projections.len());
+        info!("🏆 Loaded {} leaderboard ranks", leaderboard_ranks.len());
+        info!("📅 Loaded weekly data for {} weeks (current week {})", weeks.len(), current_week);
 
         // Build player performance data for all players
-        let mut player_performances = Vec::new();
-        for projection in &projections_data.players {
-            // Find NFL leaderboard ranking for this player
-            let nfl_rank = nfl_players.iter()
-                .find(|p| p["name"].as_str() == Some(&projection.name))
-                .and_then(|p| p["rank"].as_u64())
-                .unwrap_or(500) as u32; // Default to rank 500 if not found
-            
-            // Find weekly data for this player by symbol_id
-            let mut player_weekly_points = Vec::new();
-            let mut player_weekly_ranks = Vec::new();
-            for (week, weekly_data) in &weekly_data_map {
-                if let Some(player) = weekly_data.players.iter().find(|p| p.symbol_id == Some(projection.symbol_id)) {
-                    player_weekly_points.push((*week, player.fantasy_points));
-                    if let Some(rank) = player.rank {
-                        player_weekly_ranks.push((*week, rank));
-                    }
-                }
-            }
-
-            let player_perf = self.leaderboard_calc.build_player_performance_with_rankings(
-                projection.symbol_id as i32,
-                &projection.name,
-                &projection.position,
-                projection.projected_points.to_f64().unwrap_or(0.0),
-                &player_weekly_points,
-                nfl_rank,
-                &player_weekly_ranks,
-            );
-            player_performances.push(player_perf);
-        }
+        let player_performances: Vec<PlayerPerformance> = projections
+            .iter()
+            .map(|projection| {
+                let symbol_id = projection.symbol_id;
+                self.leaderboard_calc.build_player_performance_with_rankings(
+                    symbol_id,
+                    &projection.name,
+                    &projection.position,
+                    projection.projected_points,
+                    weekly_points.get(&symbol_id).map(Vec::as_slice).unwrap_or_default(),
+                    leaderboard_ranks.get(&symbol_id).copied().unwrap_or(UNRANKED),
+                    weekly_ranks.get(&symbol_id).map(Vec::as_slice).unwrap_or_default(),
+                )
+            })
+            .collect();
 
         // Build cohort statistics
         let cohort_stats = self.leaderboard_calc.build_cohort_stats(&player_performances);
         info!("📈 Built cohort stats for {} players", player_performances.len());
 
-        let mut events = Vec::new();
-        let mut processed_count = 0;
-        let mut updated_count = 0;
-
-        info!("🔄 Processing {} players with leaderboard-first pricing...", player_performances.len());
-        
-        for player_perf in &player_performances {
-            info!("Processing player: {} (ID: {}, Total: {:.1} pts, PPG: {:.1})", 
-                  player_perf.name, player_perf.player_id, player_perf.total_points, player_perf.ppg_shrunk);
-
+        let mut fair_prices = Vec::with_capacity(player_performances.len());
+        for player_perf in player_performances {
             // Calculate leaderboard-based target price (primary driver - 80% weight)
             let leaderboard_price = self.leaderboard_calc.calculate_leaderboard_price(
-                player_perf,
+                &player_perf,
                 &cohort_stats,
-                6, // Current week
+                current_week,
             )?;
 
             // Calculate momentum adjustment (background - 20% weight)
-            let momentum_adjustment = self.calculate_momentum_adjustment(player_perf)?;
+            let momentum_adjustment = self.calculate_momentum_adjustment(&player_perf)?;
 
             // Final price = Leaderboard price + small momentum adjustment
             let final_price_dollars = leaderboard_price + momentum_adjustment;
-            let final_price_cents = (final_price_dollars * 100.0) as i64;
+            let fair_cents = (final_price_dollars * 100.0) as i64;
 
-            info!("💰 Final price for {}: ${:.2} (Leaderboard: ${:.2}, Momentum: ${:.2})", 
-                  player_perf.name, final_price_dollars, leaderboard_price, momentum_adjustment);
+            fair_prices.push(PlayerFairPrice {
+                performance: player_perf,
+                leaderboard_price,
+                momentum_adjustment,
+                fair_cents,
+            });
+        }
+        
+        Ok(fair_prices)
+    }
+
+    /// Process Fair Price 2.3 - NFL Leaderboard + Weekly Rankings + Season Projections
+    /// Combines real NFL rankings with weekly performance and season projections
+    pub async fn process_fair_price_2_0(&mut self) -> anyhow::Result<Vec<RpeEvent>> {
+        info!("🚀 Starting Fair Price 2.3 - NFL Leaderboard + Weekly Rankings + Season Projections");
+        
+        let fair_prices = self.calculate_fair_prices().await?;
+
+        let mut events = Vec::new();
+        let mut processed_count = 0;
+        let mut updated_count = 0;
+
+        info!("🔄 Processing {} players with leaderboard-first pricing...", fair_prices.len());
+        
+        for fair_price in &fair_prices {
+            let player_perf = &fair_price.performance;
+            let leaderboard_price = fair_price.leaderboard_price;
+            let momentum_adjustment = fair_price.momentum_adjustment;
+            let final_price_cents = fair_price.fair_cents;
+
+            info!("💰 Final price for {} (ID: {}): ${:.2} (Leaderboard: ${:.2}, Momentum: ${:.2})", 
+                  player_perf.name, player_perf.player_id, final_price_cents as f64 / 100.0, leaderboard_price, momentum_adjustment);
 
             // Store in database
             let result = sqlx::query!(
@@ -212,6 +232,12 @@ impl RpeEngine {
             processed_count += 1;
         }
         
+        if let Some(bus) = &self.bus {
+            for update in events.iter().filter_map(|event| event.to_fair_price_update("rpe")) {
+                bus.publish(update);
+            }
+        }
+
         events.push(RpeEvent::BatchCompleted {
             processed_count,
             updated_count,
@@ -238,4 +264,59 @@ impl RpeEngine {
         
         Ok(adjustment)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::FixtureInputs;
+
+    fn engine_with(inputs: FixtureInputs) -> RpeEngine {
+        // Never connected: calculate_fair_prices doesn't touch the database
+        let pool = PgPool::connect_lazy("postgresql://localhost/unused").unwrap();
+        RpeEngine::with_inputs(RpeConfig::default(), pool, Box::new(inputs))
+    }
+
+    #[tokio::test]
+    async fn test_calculate_fair_prices_offline() {
+        let inputs = FixtureInputs::new()
+            .with_projection(1, "Alpha", "QB", 320.0)
+            .with_projection(2, "Beta", "RB", 250.0)
+            .with_projection(3, "Gamma", "WR", 150.0)
+            .with_leaderboard_rank(1, 1)
+            .with_leaderboard_rank(2, 40)
+            .with_week_score(1, 1, 25.0, Some(1))
+            .with_week_score(1, 2, 12.0, Some(30))
+            .with_week_score(2, 1, 28.0, Some(2))
+            .with_week_score(2, 2, 14.0, Some(25));
+
+        let prices = engine_with(inputs).calculate_fair_prices().await.unwrap();
+        assert_eq!(prices.len(), 3);
+
+        let alpha = &prices[0];
+        assert_eq!(alpha.performance.player_id, 1);
+        assert_eq!(alpha.performance.games_played, 2);
+        assert_eq!(alpha.performance.total_points, 53.0);
+        assert_eq!(alpha.performance.weekly_ranks, vec![(1, 1), (2, 2)]);
+
+        // Unranked players fall to the bottom of the leaderboard
+        assert_eq!(prices[2].performance.nfl_rank, UNRANKED);
+        assert!(prices[0].fair_cents > prices[1].fair_cents);
+        assert!(prices[1].fair_cents > prices[2].fair_cents);
+    }
+
+    #[tokio::test]
+    async fn test_weeks_are_processed_in_order() {
+        // Weeks added out of order; recent form must use the latest three weeks
+        let inputs = FixtureInputs::new()
+            .with_projection(1, "Alpha", "QB", 300.0)
+            .with_projection(2, "Beta", "RB", 200.0)
+            .with_week_score(9, 1, 30.0, None)
+            .with_week_score(1, 1, 0.0, None)
+            .with_week_score(8, 1, 30.0, None)
+            .with_week_score(7, 1, 30.0, None);
+
+        let prices = engine_with(inputs).calculate_fair_prices().await.unwrap();
+        assert_eq!(prices[0].performance.recent_form, 30.0);
+        assert_eq!(prices[1].performance.games_played, 0);
+    }
+}
@@ -0,0 +1,528 @@
+//! Input data sources for the RPE
+//!
+//! [`RpeInputs`] hides where season projections, weekly fantasy points and leaderboard
+//! ranks come from. Every provider identifies players by their exchange `symbol_id`, so
+//! the pricing code never has to match players by name.
+//!
+//! - [`JsonInputs`] reads the scraper's JSON files from a data directory
+//! - [`DatabaseInputs`] reads `projections_season` / `player_week_points`, joined to
+//!   symbols through `player_id_mapping`
+//! - [`FixtureInputs`] holds everything in memory for tests and offline runs
+
+use anyhow::Context;
+use async_trait::async_trait;
+use bigdecimal::ToPrimitive;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::config::{InputSource, RpeConfig};
+use crate::models::{SeasonProjectionJson, WeeklyPlayerData};
+
+/// Season projection for one player
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerProjection {
+    pub symbol_id: i32,
+    pub name: String,
+    pub position: String,
+    pub projected_points: f64,
+}
+
+/// Fantasy points scored by one player in one week
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerWeekScore {
+    pub symbol_id: i32,
+    pub fantasy_points: f64,
+    /// Weekly ranking (1 = best), if the source provides one
+    pub rank: Option<u32>,
+}
+
+/// Source of everything the RPE prices from
+#[async_trait]
+pub trait RpeInputs: Send + Sync {
+    /// Human-readable name of the source, for logging
+    fn describe(&self) -> String;
+
+    /// Season projections for every priced player
+    async fn projections(&self) -> anyhow::Result<Vec<PlayerProjection>>;
+
+    /// Weeks that have stats available, in ascending order
+    async fn weeks(&self) -> anyhow::Result<Vec<u32>>;
+
+    /// Scores for a single week
+    async fn week_scores(&self, week: u32) -> anyhow::Result<Vec<PlayerWeekScore>>;
+
+    /// Season leaderboard rank (1 = best) by symbol ID
+    async fn leaderboard_ranks(&self) -> anyhow::Result<HashMap<i32, u32>>;
+}
+
+/// Build the provider selected in the configuration
+pub fn inputs_from_config(config: &RpeConfig, pool: PgPool) -> Box<dyn RpeInputs> {
+    match config.inputs.source {
+        InputSource::Json => {
+            Box::new(JsonInputs::new(config.inputs.data_dir.clone(), config.rpe.season))
+        }
+        InputSource::Database => Box::new(DatabaseInputs::new(pool, config.rpe.season)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectionsFile {
+    players: Vec<SeasonProjectionJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LeaderboardFile {
+    players: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LeaderboardEntry {
+    player_id: serde_json::Value,
+    #[serde(default)]
+    symbol_id: Option<i32>,
+    rank: u32,
+}
+
+/// Reads the JSON files written by the player scraper
+///
+/// Expects `season_projections_<season>.json`, `nfl_fantasy_leaderboard_<season>.json`
+/// and any number of `week_<n>_stats_<season>.json` files in `data_dir`.
+pub struct JsonInputs {
+    data_dir: PathBuf,
+    season: i32,
+}
+
+impl JsonInputs {
+    pub fn new(data_dir: impl Into<PathBuf>, season: i32) -> Self {
+        Self { data_dir: data_dir.into(), season }
+    }
+
+    fn path(&self, file_name: &str) -> PathBuf {
+        self.data_dir.join(file_name)
+    }
+
+    fn week_path(&self, week: u32) -> PathBuf {
+        self.path(&format!("week_{week}_stats_{}.json", self.season))
+    }
+
+    fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn load_projections(&self) -> anyhow::Result<Vec<SeasonProjectionJson>> {
+        let path = self.path(&format!("season_projections_{}.json", self.season));
+        Ok(Self::read_json::<ProjectionsFile>(&path)?.players)
+    }
+
+    /// Scraper player ID -> symbol ID, from the projections file
+    fn symbol_index(&self) -> anyhow::Result<HashMap<String, i32>> {
+        Ok(self
+            .load_projections()?
+            .into_iter()
+            .map(|p| (p.player_id, p.symbol_id as i32))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RpeInputs for JsonInputs {
+    fn describe(&self) -> String {
+        format!("JSON files in {}", self.data_dir.display())
+    }
+
+    async fn projections(&self) -> anyhow::Result<Vec<PlayerProjection>> {
+        Ok(self
+            .load_projections()?
+            .into_iter()
+            .map(|p| PlayerProjection {
+                symbol_id: p.symbol_id as i32,
+                name: p.name,
+                position: p.position,
+                projected_points: p.projected_points,
+            })
+            .collect())
+    }
+
+    async fn weeks(&self) -> anyhow::Result<Vec<u32>> {
+        let prefix = "week_";
+        let suffix = format!("_stats_{}.json", self.season);
+
+        let entries = fs::read_dir(&self.data_dir)
+            .with_context(|| format!("Failed to list {}", self.data_dir.display()))?;
+
+        let mut weeks = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name();
+            let Some(name) = name.to_str() else { continue };
+            if let Some(week) = name
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix(suffix.as_str()))
+                .and_then(|week| week.parse().ok())
+            {
+                weeks.push(week);
+            }
+        }
+        weeks.sort_unstable();
+        Ok(weeks)
+    }
+
+    async fn week_scores(&self, week: u32) -> anyhow::Result<Vec<PlayerWeekScore>> {
+        let data: WeeklyPlayerData = Self::read_json(&self.week_path(week))?;
+        let symbols = self.symbol_index()?;
+
+        let mut scores = Vec::with_capacity(data.players.len());
+        for player in data.players {
+            let symbol_id = player
+                .symbol_id
+                .map(|id| id as i32)
+                .or_else(|| symbols.get(&player.player_id).copied());
+            match symbol_id {
+                Some(symbol_id) => scores.push(PlayerWeekScore {
+                    symbol_id,
+                    fantasy_points: player.fantasy_points,
+                    rank: player.rank,
+                }),
+                None => debug!("Week {} player {} has no symbol, skipping", week, player.player_id),
+            }
+        }
+        Ok(scores)
+    }
+
+    async fn leaderboard_ranks(&self) -> anyhow::Result<HashMap<i32, u32>> {
+        let path = self.path(&format!("nfl_fantasy_leaderboard_{}.json", self.season));
+        let leaderboard: LeaderboardFile = Self::read_json(&path)?;
+        let symbols = self.symbol_index()?;
+
+        let mut ranks = HashMap::new();
+        let mut unmatched = 0;
+        for entry in leaderboard.players {
+            // The scraper writes numeric IDs here but strings in the projections file
+            let player_id = match &entry.player_id {
+                serde_json::Value::String(id) => id.clone(),
+                other => other.to_string(),
+            };
+            match entry.symbol_id.or_else(|| symbols.get(&player_id).copied()) {
+                Some(symbol_id) => {
+                    ranks.insert(symbol_id, entry.rank);
+                }
+                None => unmatched += 1,
+            }
+        }
+        if unmatched > 0 {
+            warn!("{} leaderboard players have no symbol and were ignored", unmatched);
+        }
+        Ok(ranks)
+    }
+}
+
+/// Reads projections and live points from Postgres
+///
+/// Source rows are keyed by SportsDataIO player ID and mapped to symbols through
+/// `player_id_mapping`; players without a mapping are ignored. When a week has several
+/// snapshots the latest one wins, and ranks are derived from points.
+pub struct DatabaseInputs {
+    pool: PgPool,
+    season: i32,
+}
+
+impl DatabaseInputs {
+    pub fn new(pool: PgPool, season: i32) -> Self {
+        Self { pool, season }
+    }
+}
+
+#[async_trait]
+impl RpeInputs for DatabaseInputs {
+    fn describe(&self) -> String {
+        format!("database (season {})", self.season)
+    }
+
+    async fn projections(&self) -> anyhow::Result<Vec<PlayerProjection>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT pim.our_symbol_id, pim.player_name, ps.fantasy_pos, ps.proj_points
+            FROM projections_season ps
+            JOIN player_id_mapping pim ON pim.sportsdataio_player_id = ps.player_id
+            WHERE ps.season = $1
+            ORDER BY pim.our_symbol_id
+            "#,
+            self.season
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load season projections")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PlayerProjection {
+                symbol_id: row.our_symbol_id,
+                name: row.player_name,
+                position: row.fantasy_pos,
+                projected_points: row.proj_points.to_f64().unwrap_or(0.0),
+            })
+            .collect())
+    }
+
+    async fn weeks(&self) -> anyhow::Result<Vec<u32>> {
+        let weeks = sqlx::query_scalar!(
+            "SELECT DISTINCT week FROM player_week_points WHERE season = $1 ORDER BY week",
+            self.season
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load available weeks")?;
+
+        Ok(weeks.into_iter().filter_map(|week| u32::try_from(week).ok()).collect())
+    }
+
+    async fn week_scores(&self, week: u32) -> anyhow::Result<Vec<PlayerWeekScore>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                pim.our_symbol_id AS "symbol_id!",
+                latest.fantasy_pts AS "fantasy_pts!",
+                RANK() OVER (ORDER BY latest.fantasy_pts DESC) AS "rank!"
+            FROM (
+                SELECT DISTINCT ON (player_id) player_id, fantasy_pts
+                FROM player_week_points
+                WHERE season = $1 AND week = $2
+                ORDER BY player_id, ts DESC
+            ) latest
+            JOIN player_id_mapping pim ON pim.sportsdataio_player_id = latest.player_id
+            "#,
+            self.season,
+            week as i32
+        )
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("Failed to load week {week} points"))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PlayerWeekScore {
+                symbol_id: row.symbol_id,
+                fantasy_points: row.fantasy_pts.to_f64().unwrap_or(0.0),
+                rank: u32::try_from(row.rank).ok(),
+            })
+            .collect())
+    }
+
+    async fn leaderboard_ranks(&self) -> anyhow::Result<HashMap<i32, u32>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                pim.our_symbol_id AS "symbol_id!",
+                RANK() OVER (ORDER BY SUM(latest.fantasy_pts) DESC) AS "rank!"
+            FROM (
+                SELECT DISTINCT ON (player_id, week) player_id, fantasy_pts
+                FROM player_week_points
+                WHERE season = $1
+                ORDER BY player_id, week, ts DESC
+            ) latest
+            JOIN player_id_mapping pim ON pim.sportsdataio_player_id = latest.player_id
+            GROUP BY pim.our_symbol_id
+            "#,
+            self.season
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load season leaderboard")?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((row.symbol_id, u32::try_from(row.rank).ok()?)))
+            .collect())
+    }
+}
+
+/// In-memory inputs for tests and offline runs
+#[derive(Debug, Clone, Default)]
+pub struct FixtureInputs {
+    projections: Vec<PlayerProjection>,
+    weeks: BTreeMap<u32, Vec<PlayerWeekScore>>,
+    ranks: HashMap<i32, u32>,
+}
+
+impl FixtureInputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a player's season projection
+    pub fn with_projection(
+        mut self,
+        symbol_id: i32,
+        name: &str,
+        position: &str,
+        projected_points: f64,
+    ) -> Self {
+        self.projections.push(PlayerProjection {
+            symbol_id,
+            name: name.to_string(),
+            position: position.to_string(),
+            projected_points,
+        });
+        self
+    }
+
+    /// Add a player's score for a week
+    pub fn with_week_score(
+        mut self,
+        week: u32,
+        symbol_id: i32,
+        fantasy_points: f64,
+        rank: Option<u32>,
+    ) -> Self {
+        self.weeks.entry(week).or_default().push(PlayerWeekScore {
+            symbol_id,
+            fantasy_points,
+            rank,
+        });
+        self
+    }
+
+    /// Set a player's season leaderboard rank
+    pub fn with_leaderboard_rank(mut self, symbol_id: i32, rank: u32) -> Self {
+        self.ranks.insert(symbol_id, rank);
+        self
+    }
+}
+
+#[async_trait]
+impl RpeInputs for FixtureInputs {
+    fn describe(&self) -> String {
+        "in-memory fixture".to_string()
+    }
+
+    async fn projections(&self) -> anyhow::Result<Vec<PlayerProjection>> {
+        Ok(self.projections.clone())
+    }
+
+    async fn weeks(&self) -> anyhow::Result<Vec<u32>> {
+        Ok(self.weeks.keys().copied().collect())
+    }
+
+    async fn week_scores(&self, week: u32) -> anyhow::Result<Vec<PlayerWeekScore>> {
+        Ok(self.weeks.get(&week).cloned().unwrap_or_default())
+    }
+
+    async fn leaderboard_ranks(&self) -> anyhow::Result<HashMap<i32, u32>> {
+        Ok(self.ranks.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, value: serde_json::Value) {
+        fs::write(dir.join(name), serde_json::to_string(&value).unwrap()).unwrap();
+    }
+
+    fn write_fixture_files(dir: &Path) {
+        write(
+            dir,
+            "season_projections_2025.json",
+            serde_json::json!({
+                "season": "2025",
+                "last_updated": "2025-09-01T00:00:00Z",
+                "players": [
+                    {"player_id": "100", "name": "Alpha", "position": "QB", "team": "AAA",
+                     "projected_points": 320.0, "symbol_id": 1, "rank": 1},
+                    {"player_id": "200", "name": "Beta", "position": "RB", "team": "BBB",
+                     "projected_points": 250.0, "symbol_id": 2, "rank": 2}
+                ]
+            }),
+        );
+        write(
+            dir,
+            "nfl_fantasy_leaderboard_2025.json",
+            serde_json::json!({
+                "season": 2025,
+                "week": 2,
+                "last_updated": "2025-09-20",
+                "players": [
+                    // Different spelling than the projections file; joined by ID
+                    {"player_id": 200, "name": "B. Beta", "position": "RB", "team": "BBB",
+                     "fantasy_points": 40.0, "rank": 1},
+                    {"player_id": 100, "name": "A. Alpha", "position": "QB", "team": "AAA",
+                     "fantasy_points": 35.0, "rank": 2},
+                    {"player_id": 999, "name": "Unknown", "position": "WR", "team": "CCC",
+                     "fantasy_points": 30.0, "rank": 3}
+                ]
+            }),
+        );
+        for (week, alpha, beta) in [(1, 20.0, 15.0), (2, 15.0, 25.0), (10, 30.0, 5.0)] {
+            write(
+                dir,
+                &format!("week_{week}_stats_2025.json"),
+                serde_json::json!({
+                    "season": "2025",
+                    "week": week,
+                    "last_updated": "2025-09-20T00:00:00Z",
+                    "players": [
+                        {"player_id": "100", "name": "Alpha", "position": "QB", "team": "AAA",
+                         "week": week, "fantasy_points": alpha, "opponent": "BBB",
+                         "symbol_id": 1, "rank": 1},
+                        // No symbol_id: resolved through the projections file
+                        {"player_id": "200", "name": "Beta", "position": "RB", "team": "BBB",
+                         "week": week, "fantasy_points": beta, "opponent": "AAA",
+                         "symbol_id": null, "rank": 2}
+                    ]
+                }),
+            );
+        }
+        // Other seasons and unrelated files are ignored
+        write(dir, "week_3_stats_2024.json", serde_json::json!({}));
+        write(dir, "notes.json", serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_json_inputs_discover_weeks_and_join_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture_files(dir.path());
+        let inputs = JsonInputs::new(dir.path(), 2025);
+
+        assert_eq!(inputs.weeks().await.unwrap(), vec![1, 2, 10]);
+
+        let projections = inputs.projections().await.unwrap();
+        assert_eq!(projections.len(), 2);
+        assert_eq!(projections[0].symbol_id, 1);
+
+        let ranks = inputs.leaderboard_ranks().await.unwrap();
+        assert_eq!(ranks, HashMap::from([(2, 1), (1, 2)]));
+
+        let week_two = inputs.week_scores(2).await.unwrap();
+        assert_eq!(week_two.len(), 2);
+        assert_eq!(week_two[1].symbol_id, 2);
+        assert_eq!(week_two[1].fantasy_points, 25.0);
+    }
+
+    #[tokio::test]
+    async fn test_json_inputs_missing_directory_is_an_error() {
+        let inputs = JsonInputs::new("/nonexistent/rpe-data", 2025);
+        assert!(inputs.weeks().await.is_err());
+        assert!(inputs.projections().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_inputs() {
+        let inputs = FixtureInputs::new()
+            .with_projection(1, "Alpha", "QB", 300.0)
+            .with_week_score(3, 1, 22.0, Some(4))
+            .with_week_score(1, 1, 18.0, None)
+            .with_leaderboard_rank(1, 7);
+
+        assert_eq!(inputs.weeks().await.unwrap(), vec![1, 3]);
+        assert_eq!(inputs.week_scores(3).await.unwrap()[0].rank, Some(4));
+        assert!(inputs.week_scores(2).await.unwrap().is_empty());
+        assert_eq!(inputs.leaderboard_ranks().await.unwrap().get(&1), Some(&7));
+    }
+}
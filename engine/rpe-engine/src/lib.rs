@@ -5,10 +5,18 @@
 
 pub mod config;
 pub mod engine;
+pub mod inputs;
 pub mod models;
 pub mod calculator;
+pub mod composite;
+pub mod backtest;
+pub mod live;
 
 pub use config::RpeConfig;
-pub use engine::RpeEngine;
+pub use engine::{PlayerFairPrice, RpeEngine};
+pub use inputs::{DatabaseInputs, FixtureInputs, JsonInputs, RpeInputs};
 pub use models::*;
 pub use calculator::PriceCalculator;
+pub use backtest::{run_backtest, BacktestData, BacktestReport, SearchSpace};
+pub use live::{LivePricer, LiveRpe};
+pub use composite::{CompositePrice, CompositePricer, PricingConfig};
@@ -0,0 +1,629 @@
+//! Intra-game live fair prices
+//!
+//! While games are in progress `sportsdataio-fetcher` appends the latest `PlayerGameStats`
+//! to `player_week_points`. [`LiveRpe`] polls those rows and [`LivePricer`] turns them into
+//! paced price moves inside the in-game band:
+//!
+//! - Each player's game starts at their pre-game price (F₀). The target price is
+//!   [`PriceCalculator::calculate_ft_with_pacing`], with the pacing step estimated from the
+//!   time since the player's first stats of the week (`live.game_minutes` per game). The
+//!   week's projection is the season projection over `live.season_games`.
+//! - `pacing_mode = "step"` publishes each new target as it is reached; `"poll-step"` walks
+//!   towards it, closing `1 / pacing_steps` of the gap on every emission tick.
+//! - When the stats report the game as over the price settles at the full-game value
+//!   (step N of N) without pacing. Stat corrections after the final re-settle the price.
+//!
+//! Every published move is written to `rpe_fair_prices`, broadcast as
+//! [`RpeEvent::FairPriceUpdated`] and, if configured, pushed onto the fair price bus.
+
+use anyhow::Context;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::{DateTime, Utc};
+use fair_price_bus::FairPriceBus;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use crate::calculator::PriceCalculator;
+use crate::config::RpeConfig;
+use crate::inputs::inputs_from_config;
+use crate::models::{CalculationReason, RpeEvent};
+
+/// Latest live stats for one player
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveStat {
+    pub symbol_id: i32,
+    pub fantasy_points: f64,
+    pub is_game_over: bool,
+    /// When stats for this player's game were first seen (treated as kickoff)
+    pub first_seen: DateTime<Utc>,
+    pub ts: DateTime<Utc>,
+}
+
+/// Pre-game reference for a player's live price
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveBaseline {
+    pub position: String,
+    /// Pre-game fair price (F₀); the in-game band is centred on it
+    pub f0_cents: i64,
+    /// Projected points for the week
+    pub week_proj: f64,
+}
+
+/// A published live price move
+#[derive(Debug, Clone)]
+pub struct LivePriceUpdate {
+    pub symbol_id: i32,
+    pub f0_cents: i64,
+    pub fair_cents: i64,
+    pub delta_cents: i64,
+    pub actual_pts: f64,
+    /// Points above (or below) the paced expectation
+    pub delta_pts: f64,
+    pub kappa: i64,
+    pub step: u32,
+    pub is_final: bool,
+    pub reason: CalculationReason,
+}
+
+#[derive(Debug, Clone)]
+struct LiveGame {
+    started_at: DateTime<Utc>,
+    points: f64,
+    is_game_over: bool,
+    /// Inputs the cached target was calculated from: (points, step)
+    target_inputs: Option<(f64, u32)>,
+    target_cents: i64,
+    published_cents: i64,
+    /// Points the price last settled at, once the game is final
+    settled_points: Option<f64>,
+}
+
+/// Pure intra-game pricing state; no I/O
+pub struct LivePricer {
+    config: RpeConfig,
+    calculator: PriceCalculator,
+    baselines: HashMap<i32, LiveBaseline>,
+    games: HashMap<i32, LiveGame>,
+}
+
+impl LivePricer {
+    pub fn new(config: RpeConfig, baselines: HashMap<i32, LiveBaseline>) -> Self {
+        Self {
+            calculator: PriceCalculator::new(config.clone()),
+            config,
+            baselines,
+            games: HashMap::new(),
+        }
+    }
+
+    /// Record the latest stats for a player; returns false for players without a baseline
+    pub fn apply_stat(&mut self, stat: &LiveStat) -> bool {
+        let Some(baseline) = self.baselines.get(&stat.symbol_id) else {
+            return false;
+        };
+
+        let game = self.games.entry(stat.symbol_id).or_insert_with(|| LiveGame {
+            started_at: stat.first_seen,
+            points: 0.0,
+            is_game_over: false,
+            target_inputs: None,
+            target_cents: baseline.f0_cents,
+            published_cents: baseline.f0_cents,
+            settled_points: None,
+        });
+        game.started_at = game.started_at.min(stat.first_seen);
+        game.points = stat.fantasy_points;
+        game.is_game_over = stat.is_game_over;
+        true
+    }
+
+    /// Number of games being priced
+    pub fn live_games(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Current published price for a player, if their game has started
+    pub fn price(&self, symbol_id: i32) -> Option<i64> {
+        self.games.get(&symbol_id).map(|game| game.published_cents)
+    }
+
+    /// Pacing step (1..=N) reached `now` for a game that started at `started_at`
+    pub fn step_at(&self, started_at: DateTime<Utc>, now: DateTime<Utc>) -> u32 {
+        let steps = self.config.rpe.pacing_steps.max(1);
+        let elapsed_min = (now - started_at).num_seconds().max(0) as f64 / 60.0;
+        let step = (elapsed_min * steps as f64 / self.config.live.game_minutes.max(1) as f64)
+            .floor() as u32
+            + 1;
+        step.min(steps)
+    }
+
+    /// Advance every game to `now` and return the price moves to publish
+    pub fn tick(&mut self, now: DateTime<Utc>) -> anyhow::Result<Vec<LivePriceUpdate>> {
+        let steps = self.config.rpe.pacing_steps.max(1);
+        let min_change = self.config.events.min_change_cents.max(1);
+        let paced = self.config.rpe.pacing_mode == "poll-step";
+
+        let mut symbols: Vec<i32> = self.games.keys().copied().collect();
+        symbols.sort_unstable();
+
+        let mut updates = Vec::new();
+        for symbol_id in symbols {
+            let baseline = &self.baselines[&symbol_id];
+            let step = {
+                let game = &self.games[&symbol_id];
+                if game.is_game_over {
+                    if game.settled_points == Some(game.points) {
+                        continue;
+                    }
+                    steps
+                } else {
+                    self.step_at(game.started_at, now)
+                }
+            };
+            let game = self.games.get_mut(&symbol_id).expect("game exists");
+
+            if game.target_inputs != Some((game.points, step)) {
+                game.target_cents = self.calculator.calculate_ft_with_pacing(
+                    symbol_id,
+                    &baseline.position,
+                    baseline.f0_cents,
+                    BigDecimal::from_f64(game.points).unwrap_or_default(),
+                    BigDecimal::from_f64(baseline.week_proj).unwrap_or_default(),
+                    step,
+                )?;
+                game.target_inputs = Some((game.points, step));
+            }
+
+            let fair_cents = if game.is_game_over || !paced {
+                game.target_cents
+            } else {
+                walk(game.published_cents, game.target_cents, steps, min_change)
+            };
+            let delta_cents = fair_cents - game.published_cents;
+
+            // The final settle is always published, even when the price didn't move
+            if !game.is_game_over && delta_cents.abs() < min_change {
+                continue;
+            }
+
+            let target_pts = baseline.week_proj * step as f64 / steps as f64;
+            let reason = if game.is_game_over {
+                game.settled_points = Some(game.points);
+                CalculationReason::PostGame {
+                    final_pts: BigDecimal::from_f64(game.points).unwrap_or_default(),
+                    week_proj: BigDecimal::from_f64(baseline.week_proj).unwrap_or_default(),
+                }
+            } else {
+                CalculationReason::PacingStep {
+                    step,
+                    total_steps: steps,
+                    target_pts: BigDecimal::from_f64(target_pts).unwrap_or_default(),
+                }
+            };
+            game.published_cents = fair_cents;
+
+            updates.push(LivePriceUpdate {
+                symbol_id,
+                f0_cents: baseline.f0_cents,
+                fair_cents,
+                delta_cents,
+                actual_pts: game.points,
+                delta_pts: game.points - target_pts,
+                kappa: self.config.get_kappa_for_position(&baseline.position),
+                step,
+                is_final: game.is_game_over,
+                reason,
+            });
+        }
+
+        Ok(updates)
+    }
+}
+
+/// Move from `current` towards `target` by `1 / steps` of the gap, snapping when close
+fn walk(current: i64, target: i64, steps: u32, min_change: i64) -> i64 {
+    let gap = target - current;
+    if gap.abs() <= min_change {
+        return target;
+    }
+    let step = (gap as f64 / steps as f64).round() as i64;
+    current + gap.signum() * step.abs().max(min_change)
+}
+
+/// Polls live stats, publishes paced prices and broadcasts the resulting events
+pub struct LiveRpe {
+    config: RpeConfig,
+    pool: PgPool,
+    pricer: LivePricer,
+    events: broadcast::Sender<RpeEvent>,
+    bus: Option<FairPriceBus>,
+    cursor: Option<DateTime<Utc>>,
+}
+
+impl LiveRpe {
+    pub async fn new(config: RpeConfig) -> anyhow::Result<Self> {
+        let pool =
+            PgPool::connect(&config.database.url).await.context("Failed to connect to database")?;
+        let baselines = Self::load_baselines(&config, &pool).await?;
+        info!(
+            "Live RPE ready for season {} week {}: {} players, pacing '{}' over {} steps",
+            config.rpe.season,
+            config.rpe.week,
+            baselines.len(),
+            config.rpe.pacing_mode,
+            config.rpe.pacing_steps
+        );
+
+        let (events, _) = broadcast::channel(1024);
+        Ok(Self {
+            pricer: LivePricer::new(config.clone(), baselines),
+            config,
+            pool,
+            events,
+            bus: None,
+            cursor: None,
+        })
+    }
+
+    /// Subscribe to published price events
+    pub fn subscribe(&self) -> broadcast::Receiver<RpeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Also publish every price move on `bus`
+    pub fn set_fair_price_bus(&mut self, bus: FairPriceBus) {
+        self.bus = Some(bus);
+    }
+
+    /// Pre-game prices from `rpe_fair_prices`, falling back to the projection-based F₀
+    async fn load_baselines(
+        config: &RpeConfig,
+        pool: &PgPool,
+    ) -> anyhow::Result<HashMap<i32, LiveBaseline>> {
+        let projections = inputs_from_config(config, pool.clone()).projections().await?;
+
+        // A restart mid-game must keep the original F₀, not the last live price
+        let rows = sqlx::query!(
+            r#"
+            SELECT player_id, fair_cents, week,
+                   (reason->'live'->>'f0_cents')::BIGINT AS live_f0_cents
+            FROM rpe_fair_prices
+            WHERE season = $1
+            "#,
+            config.rpe.season
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to load pre-game fair prices")?;
+        let stored: HashMap<i32, i64> = rows
+            .into_iter()
+            .map(|row| {
+                let f0 = match row.live_f0_cents {
+                    Some(f0) if row.week == Some(config.rpe.week as i32) => f0,
+                    _ => row.fair_cents,
+                };
+                (row.player_id, f0)
+            })
+            .collect();
+
+        let calculator = PriceCalculator::new(config.clone());
+        let mut baselines = HashMap::with_capacity(projections.len());
+        for projection in projections {
+            let f0_cents = match stored.get(&projection.symbol_id) {
+                Some(&f0) => f0,
+                None => calculator.calculate_f0(&crate::models::SeasonProjection {
+                    player_id: projection.symbol_id,
+                    season: config.rpe.season,
+                    proj_points: BigDecimal::from_f64(projection.projected_points)
+                        .unwrap_or_default(),
+                    fantasy_pos: projection.position.clone(),
+                    adp: None,
+                    source: "live".to_string(),
+                    ingested_at: Utc::now(),
+                })?,
+            };
+            baselines.insert(
+                projection.symbol_id,
+                LiveBaseline {
+                    position: projection.position,
+                    f0_cents,
+                    week_proj: projection.projected_points / config.live.season_games.max(1) as f64,
+                },
+            );
+        }
+        Ok(baselines)
+    }
+
+    /// Fetch stats written since the last poll and feed them to the pricer
+    ///
+    /// Only rows after the cursor are fetched, but `first_seen` is taken over the whole
+    /// week so a player's kickoff doesn't depend on which poll first saw them.
+    pub async fn poll_stats(&mut self) -> anyhow::Result<usize> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT ON (pwp.player_id)
+                   m.our_symbol_id AS symbol_id,
+                   pwp.fantasy_pts,
+                   pwp.is_game_over,
+                   pwp.ts,
+                   (SELECT MIN(first.ts)
+                    FROM player_week_points first
+                    WHERE first.player_id = pwp.player_id
+                      AND first.season = pwp.season
+                      AND first.week = pwp.week) AS "first_seen!"
+            FROM player_week_points pwp
+            JOIN player_id_mapping m ON m.sportsdataio_player_id = pwp.player_id
+            WHERE pwp.season = $1 AND pwp.week = $2
+              AND ($3::TIMESTAMPTZ IS NULL OR pwp.ts > $3)
+            ORDER BY pwp.player_id, pwp.ts DESC
+            "#,
+            self.config.rpe.season,
+            self.config.rpe.week as i32,
+            self.cursor
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to poll live stats")?;
+
+        let mut applied = 0;
+        for row in rows {
+            self.cursor = Some(self.cursor.map_or(row.ts, |cursor| cursor.max(row.ts)));
+            let stat = LiveStat {
+                symbol_id: row.symbol_id,
+                fantasy_points: row.fantasy_pts.to_f64().unwrap_or(0.0),
+                is_game_over: row.is_game_over.unwrap_or(false),
+                first_seen: row.first_seen,
+                ts: row.ts,
+            };
+            if self.pricer.apply_stat(&stat) {
+                applied += 1;
+            } else {
+                debug!("No live baseline for symbol {}", stat.symbol_id);
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Advance the pricer, store the moves and broadcast them
+    pub async fn publish(&mut self, now: DateTime<Utc>) -> anyhow::Result<Vec<RpeEvent>> {
+        let updates = self.pricer.tick(now)?;
+        let mut events = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            self.store(&update, now).await?;
+            if update.is_final {
+                info!(
+                    "Settled symbol {} at ${:.2} ({:.1} pts)",
+                    update.symbol_id,
+                    update.fair_cents as f64 / 100.0,
+                    update.actual_pts
+                );
+            }
+
+            let event = RpeEvent::FairPriceUpdated {
+                player_id: update.symbol_id,
+                fair_cents: update.fair_cents,
+                delta_cents: update.delta_cents,
+                reason: update.reason,
+                timestamp: now,
+            };
+            if let (Some(bus), Some(update)) = (&self.bus, event.to_fair_price_update("rpe_live")) {
+                bus.publish(update);
+            }
+            // No subscribers is fine; prices are already stored
+            let _ = self.events.send(event.clone());
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    async fn store(&self, update: &LivePriceUpdate, now: DateTime<Utc>) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO rpe_fair_prices (player_id, ts, season, week, fair_cents, band_bps, kappa_cents_per_pt, pacing_mode, actual_pts, delta_pts, reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (player_id) DO UPDATE SET
+                ts = EXCLUDED.ts,
+                season = EXCLUDED.season,
+                week = EXCLUDED.week,
+                fair_cents = EXCLUDED.fair_cents,
+                band_bps = EXCLUDED.band_bps,
+                kappa_cents_per_pt = EXCLUDED.kappa_cents_per_pt,
+                pacing_mode = EXCLUDED.pacing_mode,
+                actual_pts = EXCLUDED.actual_pts,
+                delta_pts = EXCLUDED.delta_pts,
+                reason = EXCLUDED.reason
+            "#,
+            update.symbol_id,
+            now,
+            self.config.rpe.season,
+            Some(self.config.rpe.week as i32),
+            update.fair_cents,
+            self.config.rpe.ingame_band_bps as i32,
+            update.kappa as i32,
+            self.config.rpe.pacing_mode,
+            BigDecimal::from_f64(update.actual_pts).unwrap_or_default(),
+            BigDecimal::from_f64(update.delta_pts).unwrap_or_default(),
+            serde_json::json!({
+                "live": {
+                    "f0_cents": update.f0_cents,
+                    "step": update.step,
+                    "total_steps": self.config.rpe.pacing_steps,
+                    "final": update.is_final,
+                    "calculation": update.reason,
+                }
+            }),
+        )
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to store live price for symbol {}", update.symbol_id))?;
+        Ok(())
+    }
+
+    /// Poll and publish until the task is cancelled
+    pub async fn run(&mut self) -> anyhow::Result<()> {
+        let mut poll =
+            tokio::time::interval(Duration::from_secs(self.config.live.poll_interval_secs));
+        let mut emit =
+            tokio::time::interval(Duration::from_millis(self.config.events.emission_interval_ms));
+
+        loop {
+            tokio::select! {
+                _ = poll.tick() => match self.poll_stats().await {
+                    Ok(0) => {}
+                    Ok(count) => debug!(
+                        "Applied {} live stat updates ({} live games)",
+                        count,
+                        self.pricer.live_games()
+                    ),
+                    Err(e) => warn!("Live stats poll failed: {:#}", e),
+                },
+                _ = emit.tick() => {
+                    if let Err(e) = self.publish(Utc::now()).await {
+                        warn!("Failed to publish live prices: {:#}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KICKOFF: &str = "2025-09-07T17:00:00Z";
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        KICKOFF.parse::<DateTime<Utc>>().unwrap() + chrono::Duration::minutes(minutes)
+    }
+
+    fn pricer(pacing_mode: &str) -> LivePricer {
+        let mut config = RpeConfig::default();
+        config.rpe.pacing_mode = pacing_mode.to_string();
+        config.rpe.pacing_steps = 6;
+        config.live.game_minutes = 180;
+        // WR kappa is $1.50/pt; 18 projected points for the week
+        let baselines = HashMap::from([(
+            7,
+            LiveBaseline { position: "WR".to_string(), f0_cents: 10000, week_proj: 18.0 },
+        )]);
+        LivePricer::new(config, baselines)
+    }
+
+    fn stat(points: f64, minutes: i64, is_game_over: bool) -> LiveStat {
+        LiveStat {
+            symbol_id: 7,
+            fantasy_points: points,
+            is_game_over,
+            first_seen: at(0),
+            ts: at(minutes),
+        }
+    }
+
+    #[test]
+    fn test_step_follows_game_clock() {
+        let pricer = pricer("step");
+        assert_eq!(pricer.step_at(at(0), at(0)), 1);
+        assert_eq!(pricer.step_at(at(0), at(29)), 1);
+        assert_eq!(pricer.step_at(at(0), at(30)), 2);
+        assert_eq!(pricer.step_at(at(0), at(179)), 6);
+        // Overtime stays on the last step
+        assert_eq!(pricer.step_at(at(0), at(240)), 6);
+    }
+
+    #[test]
+    fn test_kickoff_is_earliest_first_seen() {
+        let mut pricer = pricer("step");
+        pricer.apply_stat(&LiveStat { first_seen: at(30), ..stat(9.0, 30, false) });
+        assert_eq!(pricer.tick(at(31)).unwrap()[0].step, 1);
+
+        // A later poll that knows about earlier rows moves kickoff back, never forward
+        pricer.apply_stat(&stat(9.0, 31, false));
+        assert_eq!(pricer.tick(at(31)).unwrap()[0].step, 2);
+        pricer.apply_stat(&LiveStat { first_seen: at(60), ..stat(9.0, 60, false) });
+        assert_eq!(pricer.games[&7].started_at, at(0));
+    }
+
+    #[test]
+    fn test_unknown_player_is_ignored() {
+        let mut pricer = pricer("step");
+        assert!(!pricer.apply_stat(&LiveStat { symbol_id: 99, ..stat(10.0, 0, false) }));
+        assert_eq!(pricer.live_games(), 0);
+        assert!(pricer.tick(at(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_step_mode_jumps_to_target() {
+        let mut pricer = pricer("step");
+        pricer.apply_stat(&stat(9.0, 0, false));
+
+        // Step 1 expects 3 pts; 6 pts ahead at $1.50 = +$9
+        let updates = pricer.tick(at(1)).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].fair_cents, 10900);
+        assert_eq!(updates[0].delta_cents, 900);
+        assert_eq!(updates[0].step, 1);
+
+        // Nothing changed: nothing to publish
+        assert!(pricer.tick(at(2)).unwrap().is_empty());
+
+        // Step 2 expects 6 pts, so the lead shrinks as the clock runs
+        let updates = pricer.tick(at(30)).unwrap();
+        assert_eq!(updates[0].fair_cents, 10450);
+        assert!(matches!(updates[0].reason, CalculationReason::PacingStep { step: 2, .. }));
+    }
+
+    #[test]
+    fn test_poll_step_mode_walks_towards_target() {
+        let mut pricer = pricer("poll-step");
+        pricer.apply_stat(&stat(9.0, 0, false));
+
+        let mut prices = Vec::new();
+        for second in 0..40 {
+            let now = at(1) + chrono::Duration::seconds(second);
+            prices.extend(pricer.tick(now).unwrap().into_iter().map(|u| u.fair_cents));
+        }
+
+        // 1/6 of the 900 cent gap first, then ever smaller moves until the target is hit
+        assert_eq!(prices[0], 10150);
+        assert!(prices.windows(2).all(|w| w[1] > w[0]));
+        assert_eq!(*prices.last().unwrap(), 10900);
+        assert_eq!(pricer.price(7), Some(10900));
+    }
+
+    #[test]
+    fn test_price_stays_within_ingame_band() {
+        let mut pricer = pricer("step");
+        pricer.apply_stat(&stat(60.0, 0, false));
+        // ±30% around $100
+        assert_eq!(pricer.tick(at(1)).unwrap()[0].fair_cents, 13000);
+
+        pricer.apply_stat(&stat(0.0, 179, false));
+        assert_eq!(pricer.tick(at(179)).unwrap()[0].fair_cents, 7300);
+    }
+
+    #[test]
+    fn test_final_settles_without_pacing() {
+        let mut pricer = pricer("poll-step");
+        pricer.apply_stat(&stat(12.0, 0, false));
+        pricer.tick(at(1)).unwrap();
+
+        // Final with 24 pts vs 18 projected: +$9, published in one move
+        pricer.apply_stat(&stat(24.0, 200, true));
+        let updates = pricer.tick(at(200)).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert!(updates[0].is_final);
+        assert_eq!(updates[0].fair_cents, 10900);
+        assert_eq!(updates[0].step, 6);
+        assert!(matches!(updates[0].reason, CalculationReason::PostGame { .. }));
+
+        // Settled: later ticks are quiet until a stat correction
+        assert!(pricer.tick(at(300)).unwrap().is_empty());
+        pricer.apply_stat(&stat(22.0, 400, true));
+        assert_eq!(pricer.tick(at(400)).unwrap()[0].fair_cents, 10600);
+    }
+}
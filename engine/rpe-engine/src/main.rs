@@ -23,6 +23,13 @@ async fn main() -> anyhow::Result<()> {
     println!("✅ RPE engine created successfully");
     info!("Created RPE engine instance");
     
+    // Push updates to the exchange as well, if it is listening
+    let forwarder = fair_price_bus::socket_from_env().map(|path| {
+        let bus = fair_price_bus::FairPriceBus::new();
+        engine.set_fair_price_bus(bus.clone());
+        tokio::spawn(fair_price_bus::forward_unix(bus.subscribe(), path))
+    });
+    
     // Process Fair Price 2.0 using the proven test script logic
     println!("🔄 Processing Fair Price 2.0 for ALL players...");
     info!("Processing Fair Price 2.0 using proven test script logic...");
@@ -62,6 +69,12 @@ async fn main() -> anyhow::Result<()> {
         }
     }
     
+    // Dropping the engine closes the bus once the forwarder has sent everything
+    drop(engine);
+    if let Some(forwarder) = forwarder {
+        forwarder.await?;
+    }
+    
     println!("🎉 RPE Engine processing completed successfully!");
     info!("RPE Engine processing completed!");
     Ok(())
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use bigdecimal::BigDecimal;
+use fair_price_bus::FairPriceUpdate;
 
 /// Season projection data from database
 #[derive(Debug, Clone)]
@@ -75,6 +76,11 @@ pub enum CalculationReason {
         total_steps: u32,
         target_pts: BigDecimal,
     },
+    /// Settled intra-game price once the game is final
+    PostGame {
+        final_pts: BigDecimal,
+        week_proj: BigDecimal,
+    },
     /// Fair Price 2.0 calculation
     FairPrice2 {
         weeks_played: u8,
@@ -98,6 +104,7 @@ impl CalculationReason {
             CalculationReason::Projection { .. } => 0,
             CalculationReason::FantasyPointsDelta { .. } => 1,
             CalculationReason::PacingStep { step, .. } => *step as u8,
+            CalculationReason::PostGame { .. } => 1,
             CalculationReason::FairPrice2 { weeks_played, .. } => *weeks_played,
             CalculationReason::LeaderboardBased { .. } => 6, // Assume current week
         }
@@ -145,6 +152,24 @@ pub enum RpeEvent {
     },
 }
 
+impl RpeEvent {
+    /// Fair price bus update for a `FairPriceUpdated` event (`player_id` carries the symbol id)
+    pub fn to_fair_price_update(&self, source: &str) -> Option<FairPriceUpdate> {
+        match self {
+            RpeEvent::FairPriceUpdated { player_id, fair_cents, delta_cents, timestamp, .. } => {
+                Some(FairPriceUpdate {
+                    symbol_id: u32::try_from(*player_id).ok()?,
+                    fair_cents: *fair_cents,
+                    delta_cents: *delta_cents,
+                    source: source.to_string(),
+                    ts: *timestamp,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 impl FairPriceRecord {
     /// Create a new fair price record
     pub fn new(
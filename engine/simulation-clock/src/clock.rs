@@ -1,29 +1,30 @@
 //! Core SimulationClock implementation
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
-use std::thread;
 use std::time::{Duration, Instant};
 
-use futures::future::join_all;
 use tokio::sync::Mutex;
 
 use execution_manager::ExecutionManager;
 use order_router::OrderRouter;
 use persistence::PersistenceBackend;
 use symbol_coordinator::{SymbolCoordinator, SymbolCoordinatorApi, SymbolId};
-use whistle::TickId;
+use trading_calendar::{SessionEvent, SessionPhase, SessionScheduler};
+use whistle::{TickId, TradingState};
 
 use crate::config::{ClockConfig, ErrorRecovery, SymbolOrdering};
 use crate::error::ClockError;
 use crate::metrics::{ClockMetrics, MetricsCollector};
+use crate::source::ClockSource;
 
 /// The SimulationClock - the system heartbeat that drives all logical time progression
 pub struct SimulationClock {
     // Core state
     current_tick: AtomicU64,
     is_running: AtomicBool,
+    source: ClockSource,
 
     // Symbol management
     active_symbols: Arc<RwLock<BTreeMap<SymbolId, u32>>>, // For now, just track symbol IDs
@@ -34,6 +35,7 @@ pub struct SimulationClock {
     #[allow(dead_code)]
     order_router: Arc<OrderRouter>,
     persistence: Arc<dyn PersistenceBackend>,
+    session_scheduler: Option<SessionScheduler>,
 
     // Configuration
     config: ClockConfig,
@@ -81,11 +83,14 @@ impl SimulationClock {
         Ok(Self {
             current_tick: AtomicU64::new(initial_tick),
             is_running: AtomicBool::new(false),
+            source: ClockSource::new(config.mode, config.tick_cadence(), initial_tick)
+                .with_calendar_start(config.calendar_start.unwrap_or_else(chrono::Utc::now)),
             active_symbols: Arc::new(RwLock::new(BTreeMap::new())),
             symbol_coordinator,
             execution_manager,
             order_router,
             persistence,
+            session_scheduler: None,
             config,
             metrics_collector,
             symbol_retry_counts: Arc::new(RwLock::new(BTreeMap::new())),
@@ -93,28 +98,53 @@ impl SimulationClock {
         })
     }
 
-    /// Start the SimulationClock main loop (blocks forever)
+    /// Drive market phases and kickoff halts from a trading calendar
+    ///
+    /// The scheduler is polled at the start of every tick with the clock's calendar time.
+    /// Without one every engine stays open.
+    pub fn set_session_scheduler(&mut self, scheduler: SessionScheduler) {
+        self.session_scheduler = Some(scheduler);
+    }
+
+    /// Start the SimulationClock main loop (runs until `stop`)
+    ///
+    /// Ticks are paced by the configured `ClockMode`; under `ClockMode::Stepped` the loop
+    /// only runs the ticks asked for through `step`.
     pub async fn run_clock_loop(&self) -> Result<(), ClockError> {
         if self.is_running.swap(true, Ordering::SeqCst) {
             return Err(ClockError::ClockAlreadyRunning);
         }
 
-        tracing::info!("Starting SimulationClock main loop");
+        tracing::info!("Starting SimulationClock main loop in {:?} mode", self.source.mode());
+        self.source.start();
+        let result = self.clock_loop().await;
+        self.source.finish();
+        result
+    }
 
+    async fn clock_loop(&self) -> Result<(), ClockError> {
         // Register all active symbols from SymbolCoordinator
         self.register_existing_symbols()?;
 
+        if self.config.performance.enable_cpu_affinity {
+            let pinned = self.symbol_coordinator.pin_engine_threads();
+            tracing::info!("Pinning {} engine threads to CPU cores", pinned);
+        }
+
         let mut last_metrics_emission = Instant::now();
         let mut last_health_check = Instant::now();
         let mut last_snapshot_tick = 0u64;
 
-        while self.is_running.load(Ordering::Relaxed) {
+        while self.source.wait_for_tick_start(&self.is_running).await {
             let tick_start = Instant::now();
             let current_tick = self.current_tick.fetch_add(1, Ordering::SeqCst);
 
             // Update SymbolCoordinator with current tick
             self.symbol_coordinator.update_current_tick(current_tick);
 
+            // Session boundaries take effect before this tick's orders are matched
+            self.apply_session_events(current_tick).await;
+
             // Check for newly activated symbols BEFORE processing them
             self.check_for_new_symbols()?;
 
@@ -130,6 +160,14 @@ impl SimulationClock {
             // Process evictions at tick boundary
             self.process_evictions().await?;
 
+            // Hibernate symbols that have gone idle
+            for symbol_id in self.symbol_coordinator.evict_idle(current_tick) {
+                self.unregister_symbol(symbol_id)?;
+            }
+
+            // Spread engine load across engine threads
+            self.symbol_coordinator.rebalance(current_tick);
+
             // Create snapshot if interval has passed
             if current_tick - last_snapshot_tick >= self.config.snapshot_interval_ticks {
                 if let Err(e) = self.create_snapshot(current_tick).await {
@@ -152,17 +190,64 @@ impl SimulationClock {
                 last_health_check = Instant::now();
             }
 
-            // Wait for next tick (precise timing)
-            self.wait_for_next_tick(tick_start)?;
+            // Wait for next tick (precise timing in real time)
+            self.source.finish_tick(current_tick, tick_start).await;
         }
 
         tracing::info!("SimulationClock main loop stopped");
         Ok(())
     }
 
+    /// Apply whatever the session scheduler issues at the current calendar time
+    async fn apply_session_events(&self, tick: TickId) {
+        let Some(scheduler) = &self.session_scheduler else { return };
+
+        for event in scheduler.poll(self.source.calendar_time()) {
+            match event {
+                SessionEvent::PhaseChanged { phase, .. } => {
+                    let state = match phase {
+                        SessionPhase::Open => TradingState::Open,
+                        SessionPhase::PreOpen => TradingState::PreOpen,
+                        SessionPhase::Closed => TradingState::Halted,
+                    };
+                    tracing::info!("Session phase {:?} at tick {}", phase, tick);
+                    self.symbol_coordinator.set_market_state(state);
+                }
+                SessionEvent::Halted { symbols, .. } => {
+                    for symbol_id in symbols {
+                        self.symbol_coordinator.set_symbol_halted(symbol_id, true);
+                    }
+                }
+                SessionEvent::Resumed { symbols, .. } => {
+                    for symbol_id in symbols {
+                        self.symbol_coordinator.set_symbol_halted(symbol_id, false);
+                    }
+                }
+                SessionEvent::EndOfDay { trading_day, .. } => {
+                    if let Err(e) = self.create_snapshot(tick).await {
+                        tracing::error!("Failed to create end-of-day snapshot: {:?}", e);
+                    } else {
+                        tracing::info!("Created end-of-day snapshot for {}", trading_day);
+                    }
+                }
+                // Resting DAY orders are cancelled by the gateway, which records them
+                SessionEvent::ExpireDayOrders { .. } => {}
+            }
+        }
+    }
+
     /// Stop the SimulationClock
     pub fn stop(&self) {
         self.is_running.store(false, Ordering::Relaxed);
+        self.source.wake();
+    }
+
+    /// Run `ticks` more ticks and wait for them to complete (`ClockMode::Stepped` only)
+    ///
+    /// Returns the current tick afterwards. Fails with `ClockNotRunning` if the clock is
+    /// stopped before the ticks complete.
+    pub async fn step(&self, ticks: u64) -> Result<TickId, ClockError> {
+        self.source.step(ticks).await
     }
 
     /// Register a symbol with the SimulationClock
@@ -272,6 +357,10 @@ impl SimulationClock {
     }
 
     /// Process all symbols concurrently for current tick
+    ///
+    /// The SymbolCoordinator ticks every engine on its own engine thread and returns at the
+    /// tick barrier; the engine events are then handed to the ExecutionManager symbol by
+    /// symbol in the configured order.
     async fn process_tick_concurrent(&self, tick: TickId) -> Result<(u32, u32), ClockError> {
         let symbol_ids = self.get_active_symbols();
         let symbol_count = symbol_ids.len() as u32;
@@ -289,21 +378,26 @@ impl SimulationClock {
             );
         }
 
-        // Process symbols in parallel
-        let futures: Vec<_> = symbol_ids
-            .into_iter()
-            .map(|symbol_id| self.process_symbol_tick(symbol_id, tick))
-            .collect();
-
-        // Wait for all symbols to complete
-        let results = join_all(futures).await;
+        // Tick all engines in parallel and wait for every engine thread, off the async
+        // worker threads
+        let coordinator = self.symbol_coordinator.clone();
+        let report = tokio::task::spawn_blocking(move || coordinator.process_tick(tick))
+            .await
+            .map_err(|e| ClockError::Internal(format!("Engine tick task failed: {e}")))?;
+        let engine_times: HashMap<SymbolId, Duration> = report.ticked.into_iter().collect();
+        let failed: HashSet<SymbolId> = report.failed.into_iter().collect();
 
-        // Count successes and failures
         let mut failures = 0;
-        for result in results {
-            if let Err(symbol_id) = result {
-                self.handle_symbol_failure(symbol_id)?;
-                failures += 1;
+        for symbol_id in symbol_ids {
+            match engine_times.get(&symbol_id) {
+                Some(&engine_time) if !failed.contains(&symbol_id) => {
+                    self.process_symbol_events(symbol_id, tick, engine_time).await;
+                }
+                _ => {
+                    tracing::error!("Failed to process tick for symbol {}", symbol_id);
+                    self.handle_symbol_failure(symbol_id)?;
+                    failures += 1;
+                }
             }
         }
 
@@ -311,72 +405,58 @@ impl SimulationClock {
         Ok((successes, failures))
     }
 
-    /// Process a single symbol tick
-    async fn process_symbol_tick(&self, symbol_id: SymbolId, tick: TickId) -> Result<(), SymbolId> {
+    /// Hand a ticked symbol's engine events to the ExecutionManager
+    async fn process_symbol_events(
+        &self,
+        symbol_id: SymbolId,
+        tick: TickId,
+        engine_time: Duration,
+    ) {
         let start_time = Instant::now();
 
-        // Call SymbolCoordinator to process the tick
-        match self.symbol_coordinator.process_symbol_tick_concurrent(symbol_id, tick) {
-            Ok(_events) => {
-                // Get the Whistle engine's OutboundQueue and process events through ExecutionManager
-                match self.symbol_coordinator.get_outbound_queue(symbol_id) {
-                    Ok(outbound_queue) => {
-                        // Check if there are events to process
-                        let queue_len = outbound_queue.len();
-                        if queue_len > 0 {
-                            tracing::info!(
-                                "SimulationClock: Found {} events in OutboundQueue for symbol {} at tick {}, calling ExecutionManager",
-                                queue_len,
-                                symbol_id,
-                                tick
-                            );
-                        } else if tick % 1000 == 0 {
-                            tracing::debug!(
-                                "SimulationClock: No events in OutboundQueue for symbol {} at tick {}",
-                                symbol_id,
-                                tick
-                            );
-                        }
-
-                        // Process events through ExecutionManager (proper architectural flow)
-                        // SimulationClock coordinates with ExecutionManager to process events
-                        if let Err(e) =
-                            self.execution_manager.process_events(symbol_id, &outbound_queue).await
-                        {
-                            tracing::warn!(
-                                "Failed to process events for symbol {}: {:?}",
-                                symbol_id,
-                                e
-                            );
-                        } else if queue_len > 0 {
-                            tracing::info!(
-                                "Successfully processed {} events for symbol {} at tick {}",
-                                queue_len,
-                                symbol_id,
-                                tick
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to get OutboundQueue for symbol {}: {:?}",
-                            symbol_id,
-                            e
-                        );
-                    }
+        // Get the Whistle engine's OutboundQueue and process events through ExecutionManager
+        match self.symbol_coordinator.get_outbound_queue(symbol_id) {
+            Ok(outbound_queue) => {
+                // Check if there are events to process
+                let queue_len = outbound_queue.len();
+                if queue_len > 0 {
+                    tracing::info!(
+                        "SimulationClock: Found {} events in OutboundQueue for symbol {} at tick {}, calling ExecutionManager",
+                        queue_len,
+                        symbol_id,
+                        tick
+                    );
+                } else if tick % 1000 == 0 {
+                    tracing::debug!(
+                        "SimulationClock: No events in OutboundQueue for symbol {} at tick {}",
+                        symbol_id,
+                        tick
+                    );
                 }
 
-                // Record metrics
-                let processing_time = start_time.elapsed();
-                self.metrics_collector.record_symbol_processing(symbol_id, processing_time);
-
-                Ok(())
+                // Process events through ExecutionManager (proper architectural flow)
+                // SimulationClock coordinates with ExecutionManager to process events
+                if let Err(e) =
+                    self.execution_manager.process_events(symbol_id, &outbound_queue).await
+                {
+                    tracing::warn!("Failed to process events for symbol {}: {:?}", symbol_id, e);
+                } else if queue_len > 0 {
+                    tracing::info!(
+                        "Successfully processed {} events for symbol {} at tick {}",
+                        queue_len,
+                        symbol_id,
+                        tick
+                    );
+                }
             }
             Err(e) => {
-                tracing::error!("Failed to process tick for symbol {}: {:?}", symbol_id, e);
-                Err(symbol_id)
+                tracing::warn!("Failed to get OutboundQueue for symbol {}: {:?}", symbol_id, e);
             }
         }
+
+        // Record metrics (engine time on its thread plus event processing here)
+        let processing_time = engine_time + start_time.elapsed();
+        self.metrics_collector.record_symbol_processing(symbol_id, processing_time);
     }
 
     /// Get all active symbols in deterministic order
@@ -464,28 +544,10 @@ impl SimulationClock {
         Ok(())
     }
 
-    /// Wait for next tick (precise timing)
-    fn wait_for_next_tick(&self, tick_start: Instant) -> Result<(), ClockError> {
-        let elapsed = tick_start.elapsed();
-        let target_duration = self.config.tick_cadence();
-
-        if elapsed < target_duration {
-            let remaining = target_duration - elapsed;
-            thread::sleep(remaining);
-        } else {
-            // We're behind schedule - commented out all tick processing logs to reduce noise
-            // if elapsed > target_duration * 2 {
-            //     tracing::warn!("Tick processing took {:?}, target was {:?}", elapsed, target_duration);
-            // }
-            // tracing::debug!("Tick processing took {:?}, target was {:?}", elapsed, target_duration);
-        }
-
-        Ok(())
-    }
-
     /// Emit metrics to AnalyticsEngine
     fn emit_metrics(&self, tick: TickId, tick_duration: Duration) -> Result<(), ClockError> {
         let metrics = self.metrics_collector.get_metrics();
+        let placement = self.symbol_coordinator.placement_metrics();
 
         // For now, just log the metrics
         // In the future, we'll send to AnalyticsEngine
@@ -496,6 +558,17 @@ impl SimulationClock {
             tick_rate_hz = metrics.tick_rate_hz,
             "Tick metrics"
         );
+        tracing::debug!(
+            tick = tick,
+            imbalance_ratio = placement.imbalance_ratio,
+            thread_loads_ns = ?placement
+                .threads
+                .iter()
+                .map(|thread| thread.load_score as u64)
+                .collect::<Vec<_>>(),
+            migrations_total = placement.migrations_total,
+            "Engine placement metrics"
+        );
 
         Ok(())
     }
@@ -524,7 +597,6 @@ impl SimulationClock {
     /// Create a snapshot of the current system state
     async fn create_snapshot(&self, tick: TickId) -> Result<(), ClockError> {
         use persistence::snapshot::{OrderBookState, SystemConfig, SystemState, SystemStats};
-        use std::collections::HashMap;
 
         // Collect system state
         let active_symbols = self.symbol_coordinator.get_active_symbol_ids();
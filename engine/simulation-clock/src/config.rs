@@ -1,6 +1,7 @@
 //! Configuration for SimulationClock
 
 use crate::{DEFAULT_MAX_CONCURRENT_SYMBOLS, DEFAULT_METRICS_INTERVAL_MS, DEFAULT_TICK_CADENCE_MS};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -10,6 +11,15 @@ pub struct ClockConfig {
     /// Tick cadence in milliseconds (default: 1ms = 1kHz)
     pub tick_cadence_ms: u64,
 
+    /// What paces the ticks (default: real time at the tick cadence)
+    #[serde(default)]
+    pub mode: ClockMode,
+
+    /// Calendar time of the first tick when not running in real time (default: when the
+    /// clock is created); each tick then advances calendar time by one tick cadence
+    #[serde(default)]
+    pub calendar_start: Option<DateTime<Utc>>,
+
     /// How to order symbols for processing
     pub symbol_ordering: SymbolOrdering,
 
@@ -32,6 +42,18 @@ pub struct ClockConfig {
     pub monitoring: MonitoringConfig,
 }
 
+/// What paces the clock from one tick to the next
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockMode {
+    /// One tick per tick cadence of wall time
+    #[default]
+    RealTime,
+    /// Start each tick as soon as the previous one completes
+    AsFastAsPossible,
+    /// Run ticks only when `SimulationClock::step` asks for them
+    Stepped,
+}
+
 /// How symbols are ordered for processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SymbolOrdering {
@@ -96,6 +118,8 @@ impl Default for ClockConfig {
     fn default() -> Self {
         Self {
             tick_cadence_ms: DEFAULT_TICK_CADENCE_MS,
+            mode: ClockMode::RealTime,
+            calendar_start: None,
             symbol_ordering: SymbolOrdering::BySymbolId,
             max_concurrent_symbols: DEFAULT_MAX_CONCURRENT_SYMBOLS,
             error_recovery: ErrorRecovery::Continue,
@@ -30,6 +30,9 @@ pub enum ClockError {
     #[error("Clock is already running")]
     ClockAlreadyRunning,
 
+    #[error("Clock is not in stepped mode")]
+    NotStepped,
+
     #[error("Tick processing timeout")]
     TickTimeout,
 
@@ -10,14 +10,16 @@ pub mod clock;
 pub mod config;
 pub mod error;
 pub mod metrics;
+pub mod source;
 
 #[cfg(test)]
 mod tests;
 
 
 pub use clock::SimulationClock;
-pub use config::ClockConfig;
+pub use config::{ClockConfig, ClockMode};
 pub use error::{ClockError, SymbolError, SystemError};
+pub use source::ClockSource;
 
 pub use symbol_coordinator::{SymbolId, WhistleHandle};
 /// Re-export commonly used types
@@ -0,0 +1,152 @@
+//! Clock sources: what paces the SimulationClock from one tick to the next
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
+use whistle::TickId;
+
+use crate::config::ClockMode;
+use crate::error::ClockError;
+
+/// Progress of the clock loop, published after every tick
+#[derive(Debug, Clone, Copy, Default)]
+struct Progress {
+    /// Ticks completed since the clock was created
+    ticks_completed: u64,
+    /// The tick the loop will run next
+    current_tick: TickId,
+    /// The loop has exited
+    stopped: bool,
+}
+
+/// Paces the clock loop according to its `ClockMode`
+///
+/// Real-time sleeps out the rest of each tick's cadence, as-fast-as-possible starts the
+/// next tick immediately, and stepped runs a tick only once `step` has granted it.
+///
+/// It also keeps calendar time for the session scheduler: wall time in real time, otherwise
+/// one tick cadence per completed tick from `calendar_start`.
+pub struct ClockSource {
+    mode: ClockMode,
+    tick_cadence: Duration,
+    calendar_start: DateTime<Utc>,
+
+    /// Ticks granted by `step` since the clock was created
+    granted: watch::Sender<u64>,
+    progress: watch::Sender<Progress>,
+}
+
+impl ClockSource {
+    /// Create a clock source starting at `initial_tick`
+    pub fn new(mode: ClockMode, tick_cadence: Duration, initial_tick: TickId) -> Self {
+        let (granted, _) = watch::channel(0);
+        let (progress, _) =
+            watch::channel(Progress { current_tick: initial_tick, ..Default::default() });
+        Self { mode, tick_cadence, calendar_start: Utc::now(), granted, progress }
+    }
+
+    /// Start calendar time at `start` instead of now (ignored in real time)
+    pub fn with_calendar_start(mut self, start: DateTime<Utc>) -> Self {
+        self.calendar_start = start;
+        self
+    }
+
+    /// Get the pacing mode
+    pub fn mode(&self) -> ClockMode {
+        self.mode
+    }
+
+    /// Current calendar time
+    pub fn calendar_time(&self) -> DateTime<Utc> {
+        match self.mode {
+            ClockMode::RealTime => Utc::now(),
+            ClockMode::AsFastAsPossible | ClockMode::Stepped => {
+                let ticks = self.progress.borrow().ticks_completed;
+                let elapsed = self.tick_cadence.as_nanos().saturating_mul(ticks.into());
+                self.calendar_start
+                    + chrono::Duration::nanoseconds(elapsed.try_into().unwrap_or(i64::MAX))
+            }
+        }
+    }
+
+    /// Called when the clock loop starts
+    pub(crate) fn start(&self) {
+        self.progress.send_modify(|progress| progress.stopped = false);
+    }
+
+    /// Called when the clock loop exits; fails any `step` still waiting
+    pub(crate) fn finish(&self) {
+        self.progress.send_modify(|progress| progress.stopped = true);
+    }
+
+    /// Wake a loop waiting for a step so it notices it has been stopped
+    pub(crate) fn wake(&self) {
+        self.granted.send_modify(|_| {});
+    }
+
+    /// Wait until the next tick may start
+    ///
+    /// Returns `false` once the clock has been stopped.
+    pub(crate) async fn wait_for_tick_start(&self, is_running: &AtomicBool) -> bool {
+        if self.mode == ClockMode::Stepped {
+            let completed = self.progress.borrow().ticks_completed;
+            let mut granted = self.granted.subscribe();
+            // The sender lives in `self`, so the channel can't close under us
+            let _ = granted
+                .wait_for(|&granted| granted > completed || !is_running.load(Ordering::Relaxed))
+                .await;
+        }
+
+        is_running.load(Ordering::Relaxed)
+    }
+
+    /// Record that `tick` has completed and wait out the rest of its cadence
+    pub(crate) async fn finish_tick(&self, tick: TickId, tick_start: Instant) {
+        self.progress.send_modify(|progress| {
+            progress.ticks_completed += 1;
+            progress.current_tick = tick + 1;
+        });
+
+        match self.mode {
+            ClockMode::RealTime => {
+                let elapsed = tick_start.elapsed();
+                if elapsed < self.tick_cadence {
+                    thread::sleep(self.tick_cadence - elapsed);
+                }
+            }
+            // Let the gateway and other tasks on this runtime get a look in between ticks
+            ClockMode::AsFastAsPossible => tokio::task::yield_now().await,
+            ClockMode::Stepped => {}
+        }
+    }
+
+    /// Run `ticks` more ticks and wait for them to complete
+    ///
+    /// Returns the clock's current tick afterwards, i.e. the tick the next step will run.
+    /// Steps may be granted before the clock loop starts.
+    pub async fn step(&self, ticks: u64) -> Result<TickId, ClockError> {
+        if self.mode != ClockMode::Stepped {
+            return Err(ClockError::NotStepped);
+        }
+
+        let mut target = 0;
+        self.granted.send_modify(|granted| {
+            *granted += ticks;
+            target = *granted;
+        });
+
+        let mut progress = self.progress.subscribe();
+        let progress = *progress
+            .wait_for(|progress| progress.ticks_completed >= target || progress.stopped)
+            .await
+            .map_err(|_| ClockError::ClockNotRunning)?;
+
+        if progress.ticks_completed < target {
+            return Err(ClockError::ClockNotRunning);
+        }
+        Ok(progress.current_tick)
+    }
+}
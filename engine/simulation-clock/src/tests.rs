@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use crate::config::{ErrorRecovery, MonitoringConfig, PerformanceConfig, SymbolOrdering};
+use crate::config::{ClockMode, ErrorRecovery, MonitoringConfig, PerformanceConfig, SymbolOrdering};
 use crate::{
     ClockConfig, ClockError, SymbolError, SystemError, DEFAULT_MAX_CONCURRENT_SYMBOLS,
     DEFAULT_METRICS_INTERVAL_MS, DEFAULT_TICK_CADENCE_MS,
@@ -14,6 +14,8 @@ use crate::{
 fn create_test_config() -> ClockConfig {
     ClockConfig {
         tick_cadence_ms: 10, // 10ms for faster testing
+        mode: ClockMode::RealTime,
+        calendar_start: None,
         symbol_ordering: SymbolOrdering::BySymbolId,
         max_concurrent_symbols: 10,
         error_recovery: ErrorRecovery::Continue,
@@ -204,6 +206,12 @@ mod integration_tests {
 
         assert_eq!(config.tick_cadence_ms, deserialized.tick_cadence_ms);
         assert_eq!(config.max_concurrent_symbols, deserialized.max_concurrent_symbols);
+
+        // Configs written before clock modes existed run in real time
+        let mut legacy: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        legacy.as_object_mut().unwrap().remove("mode");
+        let deserialized: ClockConfig = serde_json::from_value(legacy).unwrap();
+        assert_eq!(deserialized.mode, ClockMode::RealTime);
     }
 
     #[test]
@@ -251,3 +259,125 @@ mod performance_tests {
     }
 
 }
+
+#[cfg(test)]
+mod source_tests {
+    use super::*;
+    use crate::ClockSource;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    /// Stand-in for `SimulationClock::run_clock_loop`: counts the ticks the source lets through
+    struct Loop {
+        source: ClockSource,
+        is_running: AtomicBool,
+        current_tick: AtomicU64,
+    }
+
+    impl Loop {
+        fn new(mode: ClockMode, tick_cadence: Duration, initial_tick: u64) -> Arc<Self> {
+            Arc::new(Self {
+                source: ClockSource::new(mode, tick_cadence, initial_tick),
+                is_running: AtomicBool::new(true),
+                current_tick: AtomicU64::new(initial_tick),
+            })
+        }
+
+        async fn run(&self) {
+            self.source.start();
+            while self.source.wait_for_tick_start(&self.is_running).await {
+                let tick_start = Instant::now();
+                let tick = self.current_tick.fetch_add(1, Ordering::SeqCst);
+                self.source.finish_tick(tick, tick_start).await;
+            }
+            self.source.finish();
+        }
+
+        fn stop(&self) {
+            self.is_running.store(false, Ordering::Relaxed);
+            self.source.wake();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stepped_runs_only_requested_ticks() {
+        let clock = Loop::new(ClockMode::Stepped, Duration::from_secs(1), 100);
+
+        // Steps granted before the loop starts are honoured once it does
+        let early = tokio::spawn({
+            let clock = clock.clone();
+            async move { clock.source.step(2).await }
+        });
+        let handle = tokio::spawn({
+            let clock = clock.clone();
+            async move { clock.run().await }
+        });
+        assert_eq!(early.await.unwrap().unwrap(), 102);
+
+        assert_eq!(clock.source.step(3).await.unwrap(), 105);
+        assert_eq!(clock.source.step(0).await.unwrap(), 105);
+
+        // Nothing runs between steps
+        tokio::task::yield_now().await;
+        assert_eq!(clock.current_tick.load(Ordering::SeqCst), 105);
+
+        // Stopping wakes the idle loop, and later steps fail
+        clock.stop();
+        handle.await.unwrap();
+        assert!(matches!(clock.source.step(1).await, Err(ClockError::ClockNotRunning)));
+        assert_eq!(clock.current_tick.load(Ordering::SeqCst), 105);
+    }
+
+    #[tokio::test]
+    async fn test_as_fast_as_possible_ignores_cadence() {
+        let clock = Loop::new(ClockMode::AsFastAsPossible, Duration::from_secs(1), 0);
+        let handle = tokio::spawn({
+            let clock = clock.clone();
+            async move { clock.run().await }
+        });
+
+        // A thousand one-second ticks in well under a second
+        let start = Instant::now();
+        while clock.current_tick.load(Ordering::SeqCst) < 1000 {
+            tokio::task::yield_now().await;
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        clock.stop();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_calendar_time_advances_one_cadence_per_tick() {
+        let start: chrono::DateTime<chrono::Utc> = "2025-09-04T12:59:59Z".parse().unwrap();
+        let clock = Arc::new(Loop {
+            source: ClockSource::new(ClockMode::Stepped, Duration::from_millis(250), 0)
+                .with_calendar_start(start),
+            is_running: AtomicBool::new(true),
+            current_tick: AtomicU64::new(0),
+        });
+        let handle = tokio::spawn({
+            let clock = clock.clone();
+            async move { clock.run().await }
+        });
+
+        assert_eq!(clock.source.calendar_time(), start);
+        clock.source.step(4).await.unwrap();
+        assert_eq!(clock.source.calendar_time(), start + chrono::Duration::seconds(1));
+
+        clock.stop();
+        handle.await.unwrap();
+
+        // Real time ignores the configured start
+        let source = ClockSource::new(ClockMode::RealTime, Duration::from_millis(1), 0)
+            .with_calendar_start(start);
+        assert!(source.calendar_time() > start + chrono::Duration::days(365));
+    }
+
+    #[tokio::test]
+    async fn test_step_requires_stepped_mode() {
+        let source = ClockSource::new(ClockMode::RealTime, Duration::from_millis(1), 0);
+        assert!(matches!(source.step(1).await, Err(ClockError::NotStepped)));
+    }
+}
@@ -1,15 +1,22 @@
 use crate::SymbolCoordinatorApi;
+use crate::hibernation::{HibernatedBook, HibernationStore};
+use crate::load::{Migration, PlacementMetrics, SymbolPlacement, plan_migrations};
 use crate::placement::{EngineThreadPool, PlacementPolicy, RoundRobinPolicy};
 use crate::queue::QueueAllocator;
 use crate::registry::SymbolRegistry;
-use crate::types::{CoordError, CoordinatorConfig, ReadyAtTick, SymbolId};
+use crate::types::{
+    CoordError, CoordinatorConfig, EvictionConfig, ReadyAtTick, RebalanceConfig, SymbolId,
+    SymbolState, ThreadId,
+};
+use crate::worker::{EngineWorkers, TickReport};
 use execution_manager::ExecutionManager;
-use std::sync::{Arc, Mutex};
-use whistle::TickId;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
 use whistle::{
-    BandMode, Bands, EngineCfg, ExecIdMode, PriceDomain, ReferencePriceSource, SelfMatchPolicy,
-    Whistle,
+    BandMode, Bands, EngineCfg, ExecIdMode, InboundQueue, PriceDomain, ReferencePriceSource,
+    SelfMatchPolicy, Whistle,
 };
+use whistle::{Price, TickId, TradingState};
 
 /// Type alias for order book state to reduce complexity
 type OrderBookState = (Vec<(u32, u64)>, Vec<(u32, u64)>);
@@ -19,8 +26,18 @@ type TradeInfo = (Option<u64>, Option<u64>, Option<chrono::DateTime<chrono::Utc>
 
 /// Main SymbolCoordinator implementation
 /// Uses interior mutability to allow mutation through immutable references
+///
+/// Engines are owned by the engine threads in `workers`; `inner` only tracks lifecycle
+/// state and is never held while an engine runs or a book is written to disk. A symbol
+/// whose engine is moved with `inner` unlocked is marked in transition meanwhile, and
+/// activations of it wait on `transition_done`.
 pub struct SymbolCoordinator {
     inner: Arc<Mutex<SymbolCoordinatorInner>>,
+    transition_done: Condvar,
+    workers: EngineWorkers,
+    eviction: EvictionConfig,
+    hibernation: HibernationStore,
+    rebalance: RebalanceConfig,
 }
 
 /// Internal state that can be mutated
@@ -32,6 +49,43 @@ struct SymbolCoordinatorInner {
     queue_allocator: QueueAllocator,
     current_tick: TickId,
     execution_manager: Arc<ExecutionManager>,
+    /// Band reference prices, kept so symbols activated later start banded
+    reference_prices: HashMap<SymbolId, Price>,
+    /// Session state for every engine, set by the session scheduler
+    market_state: TradingState,
+    /// Symbols halted individually, e.g. at their game's kickoff
+    halted_symbols: HashSet<SymbolId>,
+    /// Symbols whose book is on disk, rehydrated on their next activation
+    hibernated: HashSet<SymbolId>,
+    /// Queues of hibernated symbols still referenced by a writer; pooled once released
+    retired_queues: Vec<Arc<InboundQueue>>,
+    /// Symbols being hibernated, migrated or released with the lock dropped
+    in_transition: HashSet<SymbolId>,
+    rebalancing: RebalanceHistory,
+}
+
+/// Migrations made so far
+#[derive(Default)]
+struct RebalanceHistory {
+    rebalances_total: u64,
+    migrations_total: u64,
+    last_rebalance_tick: Option<TickId>,
+    recent: VecDeque<Migration>,
+    /// Tick each symbol last moved at, for the cooldown
+    last_moved: HashMap<SymbolId, TickId>,
+}
+
+impl RebalanceHistory {
+    const RECENT_MIGRATIONS: usize = 32;
+
+    fn record(&mut self, migration: Migration) {
+        self.migrations_total += 1;
+        self.last_moved.insert(migration.symbol_id, migration.tick);
+        if self.recent.len() == Self::RECENT_MIGRATIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(migration);
+    }
 }
 
 // SAFETY: SymbolCoordinatorInner is safe to send and sync because:
@@ -46,6 +100,22 @@ impl SymbolCoordinator {
         let spsc_depth = config.spsc_depth;
         let placement_policy = Box::new(RoundRobinPolicy::new(num_threads));
         let queue_allocator = QueueAllocator::new(spsc_depth);
+        let eviction = config.eviction.clone();
+        let rebalance = config.rebalance.clone();
+        let hibernation = HibernationStore::new(&eviction.hibernation_dir);
+
+        // Books hibernated before a restart are still on disk
+        let hibernated = match hibernation.list() {
+            Ok(symbols) => symbols.into_iter().collect(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to list hibernated symbols in {}: {}",
+                    hibernation.dir().display(),
+                    e
+                );
+                HashSet::new()
+            }
+        };
 
         let inner = SymbolCoordinatorInner {
             config,
@@ -55,9 +125,23 @@ impl SymbolCoordinator {
             queue_allocator,
             current_tick: 0,
             execution_manager,
+            reference_prices: HashMap::new(),
+            market_state: TradingState::Open,
+            halted_symbols: HashSet::new(),
+            hibernated,
+            retired_queues: Vec::new(),
+            in_transition: HashSet::new(),
+            rebalancing: RebalanceHistory::default(),
         };
 
-        Self { inner: Arc::new(Mutex::new(inner)) }
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            transition_done: Condvar::new(),
+            workers: EngineWorkers::spawn(num_threads),
+            eviction,
+            hibernation,
+            rebalance,
+        }
     }
 
     /// Create default EngineCfg for a symbol
@@ -65,7 +149,7 @@ impl SymbolCoordinator {
         EngineCfg {
             symbol: symbol_id,
             price_domain: PriceDomain { floor: 100, ceil: 100000, tick: 1 }, // $1.00 to $1000.00, $0.01 tick
-            bands: Bands { mode: BandMode::Percent(10) },                    // 10% bands
+            bands: Bands { mode: BandMode::Percent(1000) },                  // 10% bands (bps)
             batch_max: spsc_depth as u32,
             arena_capacity: 1024, // Max 1024 open orders (power of 2)
             elastic_arena: false,
@@ -143,20 +227,24 @@ impl SymbolCoordinator {
         }
     }
 
+    /// Engine thread of an active symbol
+    fn engine_thread(&self, symbol_id: SymbolId) -> Option<ThreadId> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .registry
+            .get_entry(symbol_id)
+            .filter(|entry| entry.state == SymbolState::Active)
+            .map(|entry| entry.thread_id)
+    }
+
     /// Get order book state for a specific symbol
     pub fn get_order_book_state(&self, symbol_id: SymbolId) -> Option<OrderBookState> {
-        if let Ok(inner) = self.inner.lock() {
-            if let Some(entry) = inner.registry.get_entry(symbol_id) {
-                if entry.state == crate::types::SymbolState::Active {
-                    let buy_orders =
-                        entry.whistle_handle.engine.get_order_book_levels(whistle::Side::Buy);
-                    let sell_orders =
-                        entry.whistle_handle.engine.get_order_book_levels(whistle::Side::Sell);
-                    return Some((buy_orders, sell_orders));
-                }
-            }
-        }
-        None
+        let thread_id = self.engine_thread(symbol_id)?;
+        self.workers.call(thread_id, symbol_id, |engine| {
+            let buy_orders = engine.get_order_book_levels(whistle::Side::Buy);
+            let sell_orders = engine.get_order_book_levels(whistle::Side::Sell);
+            (buy_orders, sell_orders)
+        })
     }
 
     /// Restore order book state for a specific symbol
@@ -169,20 +257,21 @@ impl SymbolCoordinator {
         last_trade_quantity: Option<u64>,
         last_trade_timestamp: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if let Ok(mut inner) = self.inner.lock() {
-            if let Some(entry) = inner.registry.get_entry_mut(symbol_id) {
-                if entry.state == crate::types::SymbolState::Active {
-                    entry.whistle_handle.engine.restore_order_book_state(
-                        buy_orders,
-                        sell_orders,
-                        last_trade_price,
-                        last_trade_quantity,
-                        last_trade_timestamp,
-                    );
-                    tracing::info!("Restored order book state for symbol {}", symbol_id);
-                    return Ok(());
-                }
-            }
+        let (buy_orders, sell_orders) = (buy_orders.clone(), sell_orders.clone());
+        let restored = self.engine_thread(symbol_id).and_then(|thread_id| {
+            self.workers.call(thread_id, symbol_id, move |engine| {
+                engine.restore_order_book_state(
+                    &buy_orders,
+                    &sell_orders,
+                    last_trade_price,
+                    last_trade_quantity,
+                    last_trade_timestamp,
+                );
+            })
+        });
+        if restored.is_some() {
+            tracing::info!("Restored order book state for symbol {}", symbol_id);
+            return Ok(());
         }
         Err(format!("Failed to restore order book state for symbol {symbol_id}").into())
     }
@@ -192,14 +281,86 @@ impl SymbolCoordinator {
         &self,
         symbol_id: SymbolId,
     ) -> Result<TradeInfo, Box<dyn std::error::Error>> {
-        if let Ok(inner) = self.inner.lock() {
+        self.engine_thread(symbol_id)
+            .and_then(|thread_id| {
+                self.workers.call(thread_id, symbol_id, |engine| engine.get_last_trade_info())
+            })
+            .ok_or_else(|| format!("Failed to get trade info for symbol {symbol_id}").into())
+    }
+
+    /// Set the price band reference for a symbol (`None` disables bands)
+    ///
+    /// Applied immediately if the symbol is active, otherwise when it is next activated.
+    pub fn set_reference_price(&self, symbol_id: SymbolId, price: Option<Price>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            match price {
+                Some(price) => inner.reference_prices.insert(symbol_id, price),
+                None => inner.reference_prices.remove(&symbol_id),
+            };
             if let Some(entry) = inner.registry.get_entry(symbol_id) {
-                if entry.state == crate::types::SymbolState::Active {
-                    return Ok(entry.whistle_handle.engine.get_last_trade_info());
-                }
+                self.workers.post(entry.thread_id, symbol_id, move |engine| {
+                    engine.set_reference_price(price)
+                });
+            }
+        }
+    }
+
+    /// Set the session state of every engine
+    ///
+    /// Individually halted symbols stay halted. Symbols activated later start in this state.
+    pub fn set_market_state(&self, state: TradingState) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.market_state = state;
+            for symbol_id in inner.registry.get_active_symbols() {
+                self.post_trading_state(&inner, symbol_id);
+            }
+        }
+    }
+
+    /// Halt or resume a single symbol, on top of the market-wide state
+    pub fn set_symbol_halted(&self, symbol_id: SymbolId, halted: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            let changed = if halted {
+                inner.halted_symbols.insert(symbol_id)
+            } else {
+                inner.halted_symbols.remove(&symbol_id)
+            };
+            if changed {
+                self.post_trading_state(&inner, symbol_id);
             }
         }
-        Err(format!("Failed to get trade info for symbol {symbol_id}").into())
+    }
+
+    /// Current market-wide session state
+    pub fn market_state(&self) -> TradingState {
+        self.inner.lock().map(|inner| inner.market_state).unwrap_or_default()
+    }
+
+    /// Symbols currently halted individually
+    pub fn halted_symbols(&self) -> Vec<SymbolId> {
+        let mut symbols: Vec<_> = self
+            .inner
+            .lock()
+            .map(|inner| inner.halted_symbols.iter().copied().collect())
+            .unwrap_or_default();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    fn trading_state(inner: &SymbolCoordinatorInner, symbol_id: SymbolId) -> TradingState {
+        if inner.halted_symbols.contains(&symbol_id) {
+            TradingState::Halted
+        } else {
+            inner.market_state
+        }
+    }
+
+    fn post_trading_state(&self, inner: &SymbolCoordinatorInner, symbol_id: SymbolId) {
+        if let Some(entry) = inner.registry.get_entry(symbol_id) {
+            let state = Self::trading_state(inner, symbol_id);
+            self.workers
+                .post(entry.thread_id, symbol_id, move |engine| engine.set_trading_state(state));
+        }
     }
 
     /// Process a tick for a specific symbol
@@ -209,28 +370,355 @@ impl SymbolCoordinator {
         symbol_id: u32,
         tick: TickId,
     ) -> Option<Vec<whistle::EngineEvent>> {
+        self.update_current_tick(tick);
+
+        // Call tick() on the Whistle engine, on its engine thread
+        let thread_id = self.engine_thread(symbol_id)?;
+        self.workers.call(thread_id, symbol_id, move |engine| engine.tick(tick))
+    }
+
+    /// Tick every active symbol, each on its own engine thread
+    ///
+    /// Fans the tick out to all engine threads and returns once every thread has finished,
+    /// so all engine events of the tick are in their OutboundQueues on return. Symbols whose
+    /// engine panicked are listed in `failed` and released (see `release_failed`).
+    pub fn process_tick(&self, tick: TickId) -> TickReport {
+        let report = self.workers.tick(tick);
+        for &symbol_id in &report.failed {
+            self.release_failed(symbol_id, tick);
+        }
+        report
+    }
+
+    /// Release a symbol whose engine panicked and was dropped by its thread
+    ///
+    /// The queue is closed so writers get `QueueClosed` and re-activate the symbol with a
+    /// fresh engine instead of enqueueing orders nothing will process. The orders that rested
+    /// on the lost book are cancelled through the ExecutionManager, which releases their
+    /// reservations and publishes the cancels, before the symbol is removed; meanwhile the
+    /// symbol is in transition, so a re-activation waits and gets a fresh engine.
+    fn release_failed(&self, symbol_id: SymbolId, tick: TickId) {
+        let (queue, execution_manager) = {
+            let Ok(mut inner) = self.inner.lock() else {
+                return;
+            };
+            let Some(entry) = inner.registry.get_entry(symbol_id) else {
+                return;
+            };
+            let queue = entry.whistle_handle.order_tx.queue.clone();
+            queue.close();
+            inner.in_transition.insert(symbol_id);
+            (queue, inner.execution_manager.clone())
+        };
+        let dropped = queue.len();
+
+        let cancelled = block_on_runtime(execution_manager.cancel_symbol_orders(
+            symbol_id,
+            tick,
+            "engine failure",
+        ));
+        match cancelled {
+            Some(Ok(count)) => {
+                tracing::info!("Cancelled {} open orders of failed symbol {}", count, symbol_id)
+            }
+            Some(Err(e)) => {
+                tracing::error!("Failed to cancel open orders of symbol {}: {}", symbol_id, e)
+            }
+            None => tracing::error!(
+                "No runtime to cancel open orders of symbol {}; they stay open",
+                symbol_id
+            ),
+        }
+
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.in_transition.remove(&symbol_id);
+        self.transition_done.notify_all();
+        let Some(entry) = inner.registry.get_entry(symbol_id) else {
+            return;
+        };
+        let thread_id = entry.thread_id;
+        if let Err(e) = inner.thread_pool.unassign_symbol(thread_id) {
+            tracing::warn!("Failed to unassign failed symbol {}: {}", symbol_id, e);
+        }
+        if let Err(e) = inner.registry.remove_symbol(symbol_id) {
+            tracing::warn!("Failed to remove failed symbol {}: {}", symbol_id, e);
+        }
+        tracing::error!(
+            "Released symbol {} after its engine panicked; {} queued messages dropped",
+            symbol_id,
+            dropped
+        );
+    }
+
+    /// Pin each engine thread to a CPU core; returns the number of threads pinned
+    pub fn pin_engine_threads(&self) -> usize {
+        self.workers.pin_to_cores()
+    }
+
+    /// Number of engine threads
+    pub fn engine_thread_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Hibernate symbols that have been idle under the eviction policy; returns the
+    /// symbols hibernated
+    ///
+    /// Called by the SimulationClock at the tick boundary, after the tick's engine events
+    /// have been drained. Only does work every `check_interval_ticks`.
+    pub fn evict_idle(&self, tick: TickId) -> Vec<SymbolId> {
+        if !self.eviction.enabled
+            || self.eviction.check_interval_ticks == 0
+            || tick % self.eviction.check_interval_ticks != 0
+        {
+            return Vec::new();
+        }
+
         if let Ok(mut inner) = self.inner.lock() {
-            // Update current tick
-            inner.current_tick = tick;
-            inner.registry.update_tick(tick);
+            Self::pool_retired_queues(&mut inner);
+        }
+
+        let idle = self.workers.idle_symbols(
+            tick,
+            self.eviction.idle_ticks,
+            self.eviction.max_resting_orders,
+        );
+        idle.into_iter()
+            .filter(|&symbol_id| match self.hibernate(symbol_id) {
+                Ok(hibernated) => hibernated,
+                Err(e) => {
+                    tracing::error!("Failed to hibernate symbol {}: {}", symbol_id, e);
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// Move symbols off the busiest engine threads; returns the migrations made
+    ///
+    /// Called by the SimulationClock at the tick boundary. Only does work every
+    /// `interval_ticks`. A migrated engine keeps its queues, so no queued order is lost and
+    /// each symbol's orders and events stay in sequence. Each migration's engine round-trip
+    /// runs with the lock dropped and the symbol marked in transition.
+    pub fn rebalance(&self, tick: TickId) -> Vec<Migration> {
+        if !self.rebalance.enabled
+            || self.rebalance.interval_ticks == 0
+            || tick % self.rebalance.interval_ticks != 0
+        {
+            return Vec::new();
+        }
 
-            // Process the specific symbol
-            if let Some(entry) = inner.registry.get_entry_mut(symbol_id) {
-                if entry.state == crate::types::SymbolState::Active {
-                    // Call tick() on the Whistle engine
-                    let events = entry.whistle_handle.engine.tick(tick);
-                    return Some(events);
+        let placements = self.symbol_placements();
+        let plan = {
+            let Ok(mut inner) = self.inner.lock() else {
+                return Vec::new();
+            };
+            inner.rebalancing.rebalances_total += 1;
+            inner.rebalancing.last_rebalance_tick = Some(tick);
+
+            let history = &inner.rebalancing;
+            plan_migrations(
+                &placements,
+                self.workers.len() as u32,
+                &self.rebalance,
+                tick,
+                |symbol_id| {
+                    history.last_moved.get(&symbol_id).is_none_or(|&moved_at| {
+                        tick.saturating_sub(moved_at) >= self.rebalance.cooldown_ticks
+                    })
+                },
+            )
+        };
+
+        let mut migrations = Vec::new();
+        for migration in plan {
+            // The registry may have moved on since the loads were read
+            {
+                let Ok(mut inner) = self.inner.lock() else {
+                    break;
+                };
+                let owner =
+                    inner.registry.get_entry(migration.symbol_id).map(|entry| entry.thread_id);
+                if owner != Some(migration.from) || !inner.in_transition.insert(migration.symbol_id)
+                {
+                    continue;
                 }
             }
+
+            let moved = self.workers.migrate(migration.symbol_id, migration.from, migration.to);
+
+            let Ok(mut inner) = self.inner.lock() else {
+                break;
+            };
+            inner.in_transition.remove(&migration.symbol_id);
+            self.transition_done.notify_all();
+            if !moved {
+                continue;
+            }
+            let _ = inner.registry.reassign_thread(migration.symbol_id, migration.to);
+            let _ = inner.thread_pool.unassign_symbol(migration.from);
+            let _ = inner.thread_pool.assign_symbol(migration.to);
+            inner.rebalancing.record(migration.clone());
+            drop(inner);
+
+            tracing::info!(
+                "Migrated symbol {} from engine thread {} to {} (load {:.0}ns/tick)",
+                migration.symbol_id,
+                migration.from,
+                migration.to,
+                migration.load_score
+            );
+            migrations.push(migration);
         }
+        migrations
+    }
 
-        None
+    /// Per-thread and per-symbol load, plus migration history
+    pub fn placement_metrics(&self) -> PlacementMetrics {
+        let symbols = self.symbol_placements();
+        let threads = PlacementMetrics::thread_loads(self.workers.len() as u32, &symbols);
+        let imbalance_ratio = PlacementMetrics::imbalance(&threads);
+
+        let mut metrics =
+            PlacementMetrics { threads, symbols, imbalance_ratio, ..Default::default() };
+        if let Ok(inner) = self.inner.lock() {
+            metrics.rebalances_total = inner.rebalancing.rebalances_total;
+            metrics.migrations_total = inner.rebalancing.migrations_total;
+            metrics.last_rebalance_tick = inner.rebalancing.last_rebalance_tick;
+            metrics.recent_migrations = inner.rebalancing.recent.iter().cloned().collect();
+        }
+        metrics
+    }
+
+    /// Load and thread of every active symbol
+    fn symbol_placements(&self) -> Vec<SymbolPlacement> {
+        let loads = self.workers.loads();
+        let Ok(inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        loads
+            .into_iter()
+            .filter_map(|(symbol_id, load)| {
+                let entry = inner.registry.get_entry(symbol_id)?;
+                (entry.state == SymbolState::Active).then(|| SymbolPlacement {
+                    symbol_id,
+                    thread_id: entry.thread_id,
+                    busy_ns: load.busy_ns,
+                    messages_per_tick: load.messages_per_tick,
+                    load_score: load.score(self.rebalance.message_cost_ns),
+                })
+            })
+            .collect()
+    }
+
+    /// Symbols currently hibernated to disk
+    pub fn hibernated_symbols(&self) -> Vec<SymbolId> {
+        if let Ok(inner) = self.inner.lock() {
+            let mut symbols: Vec<SymbolId> = inner.hibernated.iter().copied().collect();
+            symbols.sort_unstable();
+            symbols
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Write a symbol's book to disk and release its engine and queue
+    ///
+    /// Returns `Ok(false)` if the symbol is not active, is already in transition or has
+    /// orders in flight. The queue is closed first, so writers still holding it get
+    /// `QueueClosed` and re-activate the symbol instead of enqueueing into a dropped engine;
+    /// that activation waits until the book is on disk. The engine round-trip and the file
+    /// write run with the lock dropped.
+    fn hibernate(&self, symbol_id: SymbolId) -> Result<bool, String> {
+        let (thread_id, queue, tick) = {
+            let mut inner =
+                self.inner.lock().map_err(|_| "coordinator lock poisoned".to_string())?;
+            let Some(entry) = inner.registry.get_entry(symbol_id) else {
+                return Ok(false);
+            };
+            if entry.state != SymbolState::Active || inner.in_transition.contains(&symbol_id) {
+                return Ok(false);
+            }
+            let thread_id = entry.thread_id;
+            let queue = entry.whistle_handle.order_tx.queue.clone();
+
+            queue.close();
+            if !queue.is_empty() {
+                queue.reopen();
+                return Ok(false);
+            }
+            inner.in_transition.insert(symbol_id);
+            (thread_id, queue, inner.current_tick)
+        };
+
+        let saved = match self.workers.remove(thread_id, symbol_id) {
+            Some(engine) => {
+                let book = HibernatedBook::capture(symbol_id, &engine, tick);
+                match self.hibernation.save(&book) {
+                    Ok(()) => Ok((engine, book)),
+                    Err(e) => {
+                        // Keep trading rather than lose the book
+                        self.workers.install(thread_id, symbol_id, engine, tick);
+                        Err(format!("failed to write hibernation file: {e}"))
+                    }
+                }
+            }
+            None => Err(format!("engine thread {thread_id} does not own the engine")),
+        };
+
+        let mut inner = self.inner.lock().map_err(|_| "coordinator lock poisoned".to_string())?;
+        inner.in_transition.remove(&symbol_id);
+        self.transition_done.notify_all();
+        let (engine, book) = match saved {
+            Ok(saved) => saved,
+            Err(e) => {
+                queue.reopen();
+                return Err(e);
+            }
+        };
+
+        inner.registry.evict_symbol(symbol_id)?;
+        inner.thread_pool.unassign_symbol(thread_id)?;
+        let entry = inner.registry.remove_symbol(symbol_id)?;
+        drop(engine);
+        drop(entry);
+
+        inner.retired_queues.push(queue);
+        Self::pool_retired_queues(&mut inner);
+        inner.hibernated.insert(symbol_id);
+        drop(inner);
+
+        tracing::info!(
+            "Hibernated symbol {} with {} resting orders to {}",
+            symbol_id,
+            book.orders.len(),
+            self.hibernation.dir().display()
+        );
+        Ok(true)
+    }
+
+    /// Return retired queues nobody writes to any more to the queue pool
+    fn pool_retired_queues(inner: &mut SymbolCoordinatorInner) {
+        let retired = std::mem::take(&mut inner.retired_queues);
+        for queue in retired {
+            match Arc::try_unwrap(queue) {
+                Ok(queue) => inner.queue_allocator.return_to_pool(queue),
+                Err(queue) => inner.retired_queues.push(queue),
+            }
+        }
     }
 }
 
 impl SymbolCoordinatorApi for SymbolCoordinator {
     fn ensure_active(&self, symbol_id: u32) -> Result<ReadyAtTick, CoordError> {
         if let Ok(mut inner) = self.inner.lock() {
+            // Let a hibernation, migration or release in progress finish; a hibernated
+            // symbol is then rehydrated and a released one gets a fresh engine
+            while inner.in_transition.contains(&symbol_id) {
+                inner = self.transition_done.wait(inner).map_err(|_| CoordError::Unknown)?;
+            }
+
             // Check if symbol is already active
             if inner.registry.is_symbol_active(symbol_id) {
                 return Ok(ReadyAtTick {
@@ -254,11 +742,31 @@ impl SymbolCoordinatorApi for SymbolCoordinator {
             // Create Whistle instance (this validates the config)
             let _whistle = Whistle::new(engine_cfg);
 
-            // Create SPSC queue for order routing
-            let spsc_queue = inner.queue_allocator.create_queue();
+            // A hibernated symbol comes back with its book; read it before touching any state
+            let hibernated_book = if inner.hibernated.contains(&symbol_id) {
+                match self.hibernation.load(symbol_id) {
+                    Ok(book) => book,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to read hibernated book for symbol {}: {}",
+                            symbol_id,
+                            e
+                        );
+                        return Err(CoordError::Faulted);
+                    }
+                }
+            } else {
+                None
+            };
+
+            // Reuse a pooled SPSC queue for order routing if there is one
+            let spsc_queue = match inner.queue_allocator.get_from_pool() {
+                Some(queue) => queue,
+                None => inner.queue_allocator.create_queue(),
+            };
 
             // Register symbol in registry
-            inner
+            let mut engine = inner
                 .registry
                 .register_symbol(symbol_id, thread_id, spsc_queue)
                 .map_err(|_| CoordError::Unknown)?;
@@ -276,6 +784,43 @@ impl SymbolCoordinatorApi for SymbolCoordinator {
             // Activate the symbol
             inner.registry.activate_symbol(symbol_id).map_err(|_| CoordError::Unknown)?;
 
+            // Band from the latest published reference price, else the hibernated one
+            let mut reference_price = inner.reference_prices.get(&symbol_id).copied();
+            if let Some(book) = &hibernated_book {
+                let restored = book.restore(&mut engine);
+                reference_price = reference_price.or(book.reference_price);
+                tracing::info!(
+                    "Rehydrated symbol {} with {}/{} resting orders",
+                    symbol_id,
+                    restored,
+                    book.orders.len()
+                );
+            }
+            engine.set_reference_price(reference_price);
+            engine.set_trading_state(Self::trading_state(&inner, symbol_id));
+
+            // Hand the engine to its thread; it is ticked from the next tick on
+            let tick = inner.current_tick;
+            if !self.workers.install(thread_id, symbol_id, engine, tick) {
+                tracing::error!(
+                    "Engine thread {} is gone; cannot activate symbol {}",
+                    thread_id,
+                    symbol_id
+                );
+                return Err(CoordError::Faulted);
+            }
+
+            // The book lives in the engine again
+            if inner.hibernated.remove(&symbol_id) {
+                if let Err(e) = self.hibernation.remove(symbol_id) {
+                    tracing::warn!(
+                        "Failed to remove hibernation file for symbol {}: {}",
+                        symbol_id,
+                        e
+                    );
+                }
+            }
+
             // Get the queue writer for OrderRouter
             let queue_writer = inner
                 .registry
@@ -291,11 +836,21 @@ impl SymbolCoordinatorApi for SymbolCoordinator {
         }
     }
 
-    fn release_if_idle(&self, _symbol_id: u32) {
-        // For now, do nothing - in a real implementation, this would:
-        // 1. Check if symbol is idle (no recent activity)
-        // 2. If idle, mark for eviction
-        // 3. Schedule cleanup at next tick boundary
+    fn release_if_idle(&self, symbol_id: u32) {
+        if !self.eviction.enabled {
+            return;
+        }
+        let tick = self.current_tick();
+        let idle = self.workers.idle_symbols(
+            tick,
+            self.eviction.idle_ticks,
+            self.eviction.max_resting_orders,
+        );
+        if idle.contains(&symbol_id) {
+            if let Err(e) = self.hibernate(symbol_id) {
+                tracing::error!("Failed to hibernate symbol {}: {}", symbol_id, e);
+            }
+        }
     }
 }
 
@@ -310,61 +865,22 @@ impl SymbolCoordinator {
     }
 
     /// Process a symbol tick (for SimulationClock integration)
-    /// This method can be called concurrently and handles the mutable access internally
+    /// Ticks one symbol on its engine thread, emitting its events to the OutboundQueue;
+    /// `process_tick` ticks all symbols at once
     pub fn process_symbol_tick_concurrent(
         &self,
         symbol_id: u32,
         tick: TickId,
     ) -> Result<Vec<whistle::EngineEvent>, CoordError> {
-        if let Ok(mut inner) = self.inner.lock() {
-            // Update current tick
-            inner.current_tick = tick;
-            inner.registry.update_tick(tick);
+        self.update_current_tick(tick);
 
-            // Process the specific symbol
-            if let Some(entry) = inner.registry.get_entry_mut(symbol_id) {
-                if entry.state == crate::types::SymbolState::Active {
-                    // Debug: Check inbound queue length before processing
-                    let inbound_queue_len = entry.whistle_handle.engine.queue_stats().0;
-                    if inbound_queue_len > 0 {
-                        tracing::info!(
-                            "Symbol {} has {} messages in inbound queue before processing tick {}",
-                            symbol_id,
-                            inbound_queue_len,
-                            tick
-                        );
-                    }
-
-                    // Call tick_with_queue_emission() on the Whistle engine
-                    // This emits events directly to the OutboundQueue instead of returning them
-                    entry.whistle_handle.engine.tick_with_queue_emission(tick);
-
-                    // Check if there are events in the outbound queue
-                    let queue_len = entry.whistle_handle.outbound_queue.len();
-                    if queue_len > 0 {
-                        tracing::info!(
-                            "Symbol {} has {} events in OutboundQueue at tick {}",
-                            symbol_id,
-                            queue_len,
-                            tick
-                        );
-                    } else if tick % 1000 == 0 {
-                        tracing::debug!(
-                            "Symbol {} has 0 events in OutboundQueue at tick {}",
-                            symbol_id,
-                            tick
-                        );
-                    }
-
-                    // Return empty events since they're now in the OutboundQueue
-                    return Ok(Vec::new());
-                }
-            }
+        let thread_id = self.engine_thread(symbol_id).ok_or(CoordError::Unknown)?;
+        self.workers
+            .call(thread_id, symbol_id, move |engine| engine.tick_with_queue_emission(tick))
+            .ok_or(CoordError::Faulted)?;
 
-            Err(CoordError::Unknown)
-        } else {
-            Err(CoordError::Unknown)
-        }
+        // Events are in the OutboundQueue rather than returned
+        Ok(Vec::new())
     }
 
     /// Update the current tick (called by SimulationClock)
@@ -391,3 +907,14 @@ impl SymbolCoordinator {
         }
     }
 }
+
+/// Run a future to completion on the Tokio runtime the caller is in, if any
+fn block_on_runtime<F: std::future::Future>(future: F) -> Option<F::Output> {
+    let handle = tokio::runtime::Handle::try_current().ok()?;
+    match handle.runtime_flavor() {
+        tokio::runtime::RuntimeFlavor::MultiThread => {
+            Some(tokio::task::block_in_place(|| handle.block_on(future)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,226 @@
+//! Hibernated order books
+//!
+//! An idle symbol's engine is dropped to keep memory bounded; its resting orders, band
+//! reference and last trade are written to `symbol-<id>.json` in the hibernation directory
+//! and read back when the symbol is next activated. Files survive restarts.
+
+use crate::types::SymbolId;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use whistle::{RestingOrder, Side, TickId, Whistle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HibernatedSide {
+    Buy,
+    Sell,
+}
+
+/// A resting order as stored on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HibernatedOrder {
+    pub order_id: u64,
+    pub account_id: u64,
+    pub side: HibernatedSide,
+    pub price: u32,
+    pub qty_open: u64,
+    pub ts_norm: u64,
+    pub enq_seq: u32,
+    pub order_type: u8,
+}
+
+impl From<&RestingOrder> for HibernatedOrder {
+    fn from(order: &RestingOrder) -> Self {
+        Self {
+            order_id: order.order_id,
+            account_id: order.account_id,
+            side: match order.side {
+                Side::Buy => HibernatedSide::Buy,
+                Side::Sell => HibernatedSide::Sell,
+            },
+            price: order.price,
+            qty_open: order.qty_open,
+            ts_norm: order.ts_norm,
+            enq_seq: order.enq_seq,
+            order_type: order.order_type,
+        }
+    }
+}
+
+impl From<&HibernatedOrder> for RestingOrder {
+    fn from(order: &HibernatedOrder) -> Self {
+        Self {
+            order_id: order.order_id,
+            account_id: order.account_id,
+            side: match order.side {
+                HibernatedSide::Buy => Side::Buy,
+                HibernatedSide::Sell => Side::Sell,
+            },
+            price: order.price,
+            qty_open: order.qty_open,
+            ts_norm: order.ts_norm,
+            enq_seq: order.enq_seq,
+            order_type: order.order_type,
+        }
+    }
+}
+
+/// Everything needed to bring a symbol's engine back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HibernatedBook {
+    pub symbol_id: SymbolId,
+    pub hibernated_at_tick: TickId,
+    pub reference_price: Option<u32>,
+    pub last_trade_price: Option<u64>,
+    pub last_trade_quantity: Option<u64>,
+    pub last_trade_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// Bids best first, then asks best first, each level in time priority
+    pub orders: Vec<HibernatedOrder>,
+}
+
+impl HibernatedBook {
+    /// Capture an engine's book
+    pub fn capture(symbol_id: SymbolId, engine: &Whistle, tick: TickId) -> Self {
+        let (last_trade_price, last_trade_quantity, last_trade_timestamp) =
+            engine.get_last_trade_info();
+        Self {
+            symbol_id,
+            hibernated_at_tick: tick,
+            reference_price: engine.reference_price(),
+            last_trade_price,
+            last_trade_quantity,
+            last_trade_timestamp,
+            orders: engine.resting_orders().iter().map(HibernatedOrder::from).collect(),
+        }
+    }
+
+    /// Restore the book into a fresh engine; returns the number of orders restored
+    pub fn restore(&self, engine: &mut Whistle) -> usize {
+        let orders: Vec<RestingOrder> = self.orders.iter().map(RestingOrder::from).collect();
+        engine.set_last_trade_info(
+            self.last_trade_price,
+            self.last_trade_quantity,
+            self.last_trade_timestamp,
+        );
+        engine.restore_resting_orders(&orders)
+    }
+}
+
+/// Directory of hibernated books
+#[derive(Debug, Clone)]
+pub struct HibernationStore {
+    dir: PathBuf,
+}
+
+impl HibernationStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, symbol_id: SymbolId) -> PathBuf {
+        self.dir.join(format!("symbol-{symbol_id}.json"))
+    }
+
+    /// Write a book, replacing any earlier one for the symbol
+    pub fn save(&self, book: &HibernatedBook) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_vec(book)?;
+
+        // Write then rename so a crash never leaves a truncated book behind
+        let path = self.path(book.symbol_id);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)
+    }
+
+    /// Read a symbol's book, if it is hibernated
+    pub fn load(&self, symbol_id: SymbolId) -> std::io::Result<Option<HibernatedBook>> {
+        match std::fs::read(self.path(symbol_id)) {
+            Ok(json) => Ok(Some(serde_json::from_slice(&json)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete a symbol's book once it has been restored
+    pub fn remove(&self, symbol_id: SymbolId) -> std::io::Result<()> {
+        match std::fs::remove_file(self.path(symbol_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Symbols with a book on disk
+    pub fn list(&self) -> std::io::Result<Vec<SymbolId>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut symbols = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name();
+            let symbol_id = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("symbol-"))
+                .and_then(|name| name.strip_suffix(".json"))
+                .and_then(|id| id.parse::<SymbolId>().ok());
+            symbols.extend(symbol_id);
+        }
+        symbols.sort_unstable();
+        Ok(symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(symbol_id: SymbolId) -> HibernatedBook {
+        HibernatedBook {
+            symbol_id,
+            hibernated_at_tick: 1_000,
+            reference_price: Some(2_500),
+            last_trade_price: Some(2_450),
+            last_trade_quantity: Some(3),
+            last_trade_timestamp: None,
+            orders: vec![HibernatedOrder {
+                order_id: 7,
+                account_id: 42,
+                side: HibernatedSide::Sell,
+                price: 2_600,
+                qty_open: 5,
+                ts_norm: 123,
+                enq_seq: 0,
+                order_type: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn save_load_list_remove() {
+        let dir = std::env::temp_dir().join(format!(
+            "hibernation-test-{}-{}",
+            std::process::id(),
+            line!()
+        ));
+        let store = HibernationStore::new(&dir);
+        assert_eq!(store.list().unwrap(), Vec::<SymbolId>::new());
+        assert_eq!(store.load(3).unwrap(), None);
+
+        store.save(&book(3)).unwrap();
+        store.save(&book(11)).unwrap();
+        assert_eq!(store.list().unwrap(), vec![3, 11]);
+        assert_eq!(store.load(3).unwrap(), Some(book(3)));
+
+        store.remove(3).unwrap();
+        store.remove(3).unwrap();
+        assert_eq!(store.list().unwrap(), vec![11]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -2,15 +2,22 @@
 #![allow(dead_code)]
 
 mod coordinator;
+mod hibernation;
+mod load;
 mod placement;
 mod queue;
 mod registry;
 mod types;
+mod worker;
 
 pub use coordinator::SymbolCoordinator;
+pub use hibernation::{HibernatedBook, HibernationStore};
+pub use load::{Migration, PlacementMetrics, SymbolLoad, SymbolPlacement, ThreadLoad};
 pub use types::{
-    CoordError, CoordinatorConfig, OrderQueueWriter, ReadyAtTick, SymbolId, WhistleHandle,
+    CoordError, CoordinatorConfig, EvictionConfig, OrderQueueWriter, ReadyAtTick, RebalanceConfig,
+    SymbolId, WhistleHandle,
 };
+pub use worker::TickReport;
 
 // Define the trait locally for OrderRouter compatibility
 pub trait SymbolCoordinatorApi: Send + Sync {
@@ -0,0 +1,239 @@
+//! Engine load tracking and rebalancing
+//!
+//! Each engine thread keeps moving averages of how long each of its engines takes per tick
+//! and how many inbound messages it finds per tick. The coordinator turns those into a load
+//! score per symbol, sums the scores per thread and, at tick boundaries, moves symbols off
+//! the busiest thread onto the least busy one.
+
+use crate::types::{RebalanceConfig, SymbolId, ThreadId};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use whistle::TickId;
+
+/// Moving averages of one engine's work per tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SymbolLoad {
+    /// Time spent in the engine per tick, in nanoseconds
+    pub busy_ns: f64,
+    /// Inbound messages waiting per tick
+    pub messages_per_tick: f64,
+}
+
+impl SymbolLoad {
+    /// Weight of the latest tick; about one second of history at 1kHz
+    const ALPHA: f64 = 1.0 / 1024.0;
+
+    pub(crate) fn record(&mut self, busy: Duration, messages: usize) {
+        self.busy_ns += (busy.as_nanos() as f64 - self.busy_ns) * Self::ALPHA;
+        self.messages_per_tick += (messages as f64 - self.messages_per_tick) * Self::ALPHA;
+    }
+
+    /// Load score in nanoseconds per tick, counting each message at `message_cost_ns`
+    pub fn score(&self, message_cost_ns: f64) -> f64 {
+        self.busy_ns + self.messages_per_tick * message_cost_ns
+    }
+}
+
+/// A symbol moved from one engine thread to another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Migration {
+    pub symbol_id: SymbolId,
+    pub from: ThreadId,
+    pub to: ThreadId,
+    /// The symbol's load score when it was moved
+    pub load_score: f64,
+    pub tick: TickId,
+}
+
+/// Load of one engine thread
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadLoad {
+    pub thread_id: ThreadId,
+    pub symbols: u32,
+    pub load_score: f64,
+}
+
+/// Where a symbol runs and how much work it is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolPlacement {
+    pub symbol_id: SymbolId,
+    pub thread_id: ThreadId,
+    pub busy_ns: f64,
+    pub messages_per_tick: f64,
+    pub load_score: f64,
+}
+
+/// Placement and migration metrics of the coordinator
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlacementMetrics {
+    pub threads: Vec<ThreadLoad>,
+    pub symbols: Vec<SymbolPlacement>,
+    /// Busiest thread's load over the mean thread load (1.0 when balanced)
+    pub imbalance_ratio: f64,
+    pub rebalances_total: u64,
+    pub migrations_total: u64,
+    pub last_rebalance_tick: Option<TickId>,
+    /// Latest migrations, oldest first
+    pub recent_migrations: Vec<Migration>,
+}
+
+impl PlacementMetrics {
+    /// Sum symbol placements into per-thread loads
+    pub(crate) fn thread_loads(num_threads: u32, symbols: &[SymbolPlacement]) -> Vec<ThreadLoad> {
+        let mut threads: Vec<ThreadLoad> = (0..num_threads)
+            .map(|thread_id| ThreadLoad { thread_id, symbols: 0, load_score: 0.0 })
+            .collect();
+        for symbol in symbols {
+            if let Some(thread) = threads.get_mut(symbol.thread_id as usize) {
+                thread.symbols += 1;
+                thread.load_score += symbol.load_score;
+            }
+        }
+        threads
+    }
+
+    /// Busiest thread's load over the mean thread load
+    pub(crate) fn imbalance(threads: &[ThreadLoad]) -> f64 {
+        let total: f64 = threads.iter().map(|thread| thread.load_score).sum();
+        if threads.is_empty() || total <= 0.0 {
+            return 1.0;
+        }
+        let mean = total / threads.len() as f64;
+        threads.iter().map(|thread| thread.load_score).fold(0.0, f64::max) / mean
+    }
+}
+
+/// Plan moves from the busiest thread to the least busy one until the busiest thread is
+/// within `imbalance_threshold` of the mean
+///
+/// Each move takes the symbol that best halves the gap between the two threads; a symbol
+/// at least as heavy as the gap is never moved, since that would only swap the roles, and
+/// no symbol is moved twice in one plan.
+pub(crate) fn plan_migrations(
+    symbols: &[SymbolPlacement],
+    num_threads: u32,
+    config: &RebalanceConfig,
+    tick: TickId,
+    movable: impl Fn(SymbolId) -> bool,
+) -> Vec<Migration> {
+    let mut placed: Vec<SymbolPlacement> = symbols.to_vec();
+    let mut migrations = Vec::new();
+
+    while migrations.len() < config.max_migrations {
+        let threads = PlacementMetrics::thread_loads(num_threads, &placed);
+        let by_load = |a: &&ThreadLoad, b: &&ThreadLoad| a.load_score.total_cmp(&b.load_score);
+        let (Some(hottest), Some(coldest)) =
+            (threads.iter().max_by(by_load), threads.iter().min_by(by_load))
+        else {
+            break;
+        };
+        if hottest.load_score < config.min_thread_load_ns
+            || PlacementMetrics::imbalance(&threads) < config.imbalance_threshold
+        {
+            break;
+        }
+
+        let gap = hottest.load_score - coldest.load_score;
+        let candidate = placed
+            .iter_mut()
+            .filter(|symbol| symbol.thread_id == hottest.thread_id)
+            .filter(|symbol| symbol.load_score > 0.0 && symbol.load_score < gap)
+            .filter(|symbol| {
+                !migrations.iter().any(|m: &Migration| m.symbol_id == symbol.symbol_id)
+            })
+            .filter(|symbol| movable(symbol.symbol_id))
+            .min_by(|a, b| {
+                (a.load_score - gap / 2.0).abs().total_cmp(&(b.load_score - gap / 2.0).abs())
+            });
+        let Some(symbol) = candidate else {
+            break;
+        };
+
+        migrations.push(Migration {
+            symbol_id: symbol.symbol_id,
+            from: symbol.thread_id,
+            to: coldest.thread_id,
+            load_score: symbol.load_score,
+            tick,
+        });
+        symbol.thread_id = coldest.thread_id;
+    }
+
+    migrations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(symbol_id: SymbolId, thread_id: ThreadId, load_score: f64) -> SymbolPlacement {
+        SymbolPlacement {
+            symbol_id,
+            thread_id,
+            busy_ns: load_score,
+            messages_per_tick: 0.0,
+            load_score,
+        }
+    }
+
+    fn config() -> RebalanceConfig {
+        RebalanceConfig { min_thread_load_ns: 1_000.0, max_migrations: 4, ..Default::default() }
+    }
+
+    #[test]
+    fn load_averages_converge() {
+        let mut load = SymbolLoad::default();
+        for _ in 0..20_000 {
+            load.record(Duration::from_micros(10), 4);
+        }
+        assert!((load.busy_ns - 10_000.0).abs() < 1.0);
+        assert!((load.messages_per_tick - 4.0).abs() < 0.001);
+        assert!((load.score(500.0) - 12_000.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn hot_thread_sheds_symbols_to_idle_threads() {
+        // Thread 0 has all the game-day players
+        let symbols = vec![
+            placement(1, 0, 40_000.0),
+            placement(2, 0, 30_000.0),
+            placement(3, 0, 20_000.0),
+            placement(4, 1, 5_000.0),
+            placement(5, 2, 5_000.0),
+        ];
+        let migrations = plan_migrations(&symbols, 3, &config(), 7, |_| true);
+
+        assert_eq!(
+            migrations.iter().map(|m| (m.symbol_id, m.from, m.to)).collect::<Vec<_>>(),
+            vec![(1, 0, 1), (3, 0, 2), (4, 1, 2)]
+        );
+        assert!(migrations.iter().all(|m| m.tick == 7));
+
+        let mut after = symbols.clone();
+        for migration in &migrations {
+            after.iter_mut().find(|s| s.symbol_id == migration.symbol_id).unwrap().thread_id =
+                migration.to;
+        }
+        let threads = PlacementMetrics::thread_loads(3, &after);
+        assert!(PlacementMetrics::imbalance(&threads) < config().imbalance_threshold);
+    }
+
+    #[test]
+    fn balanced_light_or_pinned_loads_stay_put() {
+        let balanced = vec![placement(1, 0, 10_000.0), placement(2, 1, 11_000.0)];
+        assert!(plan_migrations(&balanced, 2, &config(), 0, |_| true).is_empty());
+
+        // Imbalanced but below the minimum load worth moving for
+        let light = vec![placement(1, 0, 500.0), placement(2, 0, 400.0)];
+        assert!(plan_migrations(&light, 2, &config(), 0, |_| true).is_empty());
+
+        // One heavy symbol cannot be split
+        let single = vec![placement(1, 0, 50_000.0)];
+        assert!(plan_migrations(&single, 2, &config(), 0, |_| true).is_empty());
+
+        // Symbols in their cooldown are not moved
+        let hot = vec![placement(1, 0, 20_000.0), placement(2, 0, 20_000.0)];
+        assert!(plan_migrations(&hot, 2, &config(), 0, |_| false).is_empty());
+        assert_eq!(plan_migrations(&hot, 2, &config(), 0, |symbol| symbol == 2).len(), 1);
+    }
+}
@@ -35,10 +35,10 @@ impl QueueAllocator {
         self.queue_pool.pop()
     }
 
-    /// Return a queue to the pool (for reuse)
+    /// Return a queue to the pool (for reuse); it is cleared and reopened first
     pub fn return_to_pool(&mut self, queue: InboundQueue) {
-        // Note: In a real implementation, we'd want to ensure the queue is empty
-        // and reset its state before returning to pool
+        queue.clear_lockfree();
+        queue.reopen();
         self.queue_pool.push(queue);
     }
 
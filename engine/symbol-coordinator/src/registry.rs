@@ -11,12 +11,13 @@ pub struct SymbolEntry {
 }
 
 impl SymbolEntry {
+    /// Create the entry and the engine that its engine thread will own
     pub fn new(
         symbol_id: SymbolId,
         thread_id: ThreadId,
         spsc_queue: whistle::InboundQueue,
         current_tick: TickId,
-    ) -> Self {
+    ) -> (Self, whistle::Whistle) {
         let metadata = EngineMetadata {
             symbol_id,
             thread_id,
@@ -31,7 +32,7 @@ impl SymbolEntry {
         let engine_cfg = whistle::EngineCfg {
             symbol: symbol_id,
             price_domain: whistle::PriceDomain { floor: 100, ceil: 100000, tick: 1 },
-            bands: whistle::Bands { mode: whistle::BandMode::Percent(10) },
+            bands: whistle::Bands { mode: whistle::BandMode::Percent(1000) },
             batch_max: 100,
             arena_capacity: 1024,
             elastic_arena: false,
@@ -45,19 +46,20 @@ impl SymbolEntry {
         // Create shared queue reference for both OrderQueueWriter and Whistle engine
         let shared_queue = std::sync::Arc::new(spsc_queue);
 
+        let engine = whistle::Whistle::new_with_queues(
+            engine_cfg,
+            shared_queue.clone(),
+            outbound_queue.clone(),
+        );
+
         let whistle_handle = WhistleHandle {
-            order_tx: crate::types::OrderQueueWriter { queue: shared_queue.clone() },
+            order_tx: crate::types::OrderQueueWriter { queue: shared_queue },
             metadata,
             tick_flag: std::sync::atomic::AtomicBool::new(false),
-            engine: whistle::Whistle::new_with_queues(
-                engine_cfg,
-                shared_queue.clone(),
-                outbound_queue.clone(),
-            ),
             outbound_queue,
         };
 
-        Self { symbol_id, state: SymbolState::Registered, whistle_handle, thread_id }
+        (Self { symbol_id, state: SymbolState::Registered, whistle_handle, thread_id }, engine)
     }
 
     pub fn activate(&mut self, current_tick: TickId) {
@@ -88,19 +90,20 @@ impl SymbolRegistry {
         Self { entries: HashMap::new(), current_tick: 0 }
     }
 
+    /// Register a symbol, returning the engine to hand to its engine thread
     pub fn register_symbol(
         &mut self,
         symbol_id: SymbolId,
         thread_id: ThreadId,
         spsc_queue: whistle::InboundQueue,
-    ) -> Result<(), String> {
+    ) -> Result<whistle::Whistle, String> {
         if self.entries.contains_key(&symbol_id) {
             return Err(format!("Symbol {symbol_id} already registered"));
         }
 
-        let entry = SymbolEntry::new(symbol_id, thread_id, spsc_queue, self.current_tick);
+        let (entry, engine) = SymbolEntry::new(symbol_id, thread_id, spsc_queue, self.current_tick);
         self.entries.insert(symbol_id, entry);
-        Ok(())
+        Ok(engine)
     }
 
     pub fn get_entry(&self, symbol_id: SymbolId) -> Option<&SymbolEntry> {
@@ -129,15 +132,31 @@ impl SymbolRegistry {
         }
     }
 
-    pub fn remove_symbol(&mut self, symbol_id: SymbolId) -> Result<(), String> {
+    /// Record that a symbol's engine now runs on `thread_id`
+    pub fn reassign_thread(
+        &mut self,
+        symbol_id: SymbolId,
+        thread_id: ThreadId,
+    ) -> Result<(), String> {
         if let Some(entry) = self.entries.get_mut(&symbol_id) {
-            entry.mark_evicted();
+            entry.thread_id = thread_id;
+            entry.whistle_handle.metadata.thread_id = thread_id;
             Ok(())
         } else {
             Err(format!("Symbol {symbol_id} not found"))
         }
     }
 
+    /// Drop a symbol from the registry, returning its entry
+    pub fn remove_symbol(&mut self, symbol_id: SymbolId) -> Result<SymbolEntry, String> {
+        if let Some(mut entry) = self.entries.remove(&symbol_id) {
+            entry.mark_evicted();
+            Ok(entry)
+        } else {
+            Err(format!("Symbol {symbol_id} not found"))
+        }
+    }
+
     pub fn is_symbol_active(&self, symbol_id: SymbolId) -> bool {
         self.entries
             .get(&symbol_id)
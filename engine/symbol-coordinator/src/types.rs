@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use whistle::TickId;
@@ -46,20 +47,15 @@ pub struct EngineMetadata {
 }
 
 /// Handle for Whistle engine operations
-/// Stores the actual Whistle instance for SimulationClock to access
-/// NO LOCKS ON HOT PATH - SimulationClock needs direct access to call tick()
+/// The engine itself is owned by its engine thread (see `worker`); the handle keeps the
+/// queues into and out of it - NO LOCKS ON HOT PATH
 pub struct WhistleHandle {
     pub order_tx: OrderQueueWriter,
     pub metadata: EngineMetadata,
     pub tick_flag: AtomicBool,
-    pub engine: whistle::Whistle, // Direct access for SimulationClock - no locks
     pub outbound_queue: Arc<whistle::OutboundQueue>, // For ExecutionManager integration
 }
 
-// TODO: Implement proper Clone for WhistleHandle
-// This requires Whistle to implement Clone, which may not be feasible
-// For now, we'll use a different approach in SimulationClock
-
 /// Write handle for OrderRouter to enqueue orders
 /// Lock-free SPSC queue access - NO LOCKS ON HOT PATH
 ///
@@ -87,10 +83,76 @@ pub struct CoordinatorConfig {
     pub num_threads: u32,
     pub spsc_depth: usize,
     pub max_symbols_per_thread: u32,
+    pub eviction: EvictionConfig,
+    pub rebalance: RebalanceConfig,
 }
 
 impl Default for CoordinatorConfig {
     fn default() -> Self {
-        Self { num_threads: 4, spsc_depth: 2048, max_symbols_per_thread: 64 }
+        Self {
+            num_threads: 4,
+            spsc_depth: 2048,
+            max_symbols_per_thread: 64,
+            eviction: EvictionConfig::default(),
+            rebalance: RebalanceConfig::default(),
+        }
+    }
+}
+
+/// When idle symbols are hibernated to disk
+#[derive(Debug, Clone)]
+pub struct EvictionConfig {
+    pub enabled: bool,
+    /// Ticks without an inbound order before a symbol counts as idle
+    pub idle_ticks: u64,
+    /// Only symbols with at most this many resting orders are hibernated
+    pub max_resting_orders: usize,
+    /// How often idle symbols are looked for
+    pub check_interval_ticks: u64,
+    /// Where hibernated books are written
+    pub hibernation_dir: PathBuf,
+}
+
+impl Default for EvictionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_ticks: 600_000, // 10 minutes at 1kHz
+            max_resting_orders: 32,
+            check_interval_ticks: 10_000,
+            hibernation_dir: PathBuf::from("./data/hibernation"),
+        }
+    }
+}
+
+/// When symbols are moved between engine threads
+#[derive(Debug, Clone)]
+pub struct RebalanceConfig {
+    pub enabled: bool,
+    /// How often thread loads are compared
+    pub interval_ticks: u64,
+    /// Rebalance once the busiest thread is this far above the mean thread load
+    pub imbalance_threshold: f64,
+    /// Leave threads alone while the busiest one is below this load (ns per tick)
+    pub min_thread_load_ns: f64,
+    /// Load score of one inbound message per tick (ns)
+    pub message_cost_ns: f64,
+    /// Most symbols moved per rebalance
+    pub max_migrations: usize,
+    /// Ticks before a moved symbol may be moved again
+    pub cooldown_ticks: u64,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ticks: 1_000,
+            imbalance_threshold: 1.25,
+            min_thread_load_ns: 50_000.0, // 5% of a 1ms tick
+            message_cost_ns: 500.0,
+            max_migrations: 2,
+            cooldown_ticks: 10_000,
+        }
     }
 }
@@ -0,0 +1,584 @@
+//! Engine worker threads
+//!
+//! Every active symbol's `Whistle` is owned by exactly one engine thread, picked by the
+//! placement policy. A thread ticks its engines back to back without taking any lock;
+//! everything else that needs an engine (book snapshots, restores, reference prices) is
+//! sent to the owning thread as a job and runs between ticks.
+//!
+//! A tick fans out to all threads at once and completes when every thread has reported
+//! back, so the clock still sees one tick boundary across all symbols.
+//!
+//! Threads also remember the last tick each engine had inbound orders, which is what idle
+//! eviction goes by, and keep each engine's load (see `load`).
+//!
+//! A symbol migrates by its old thread handing the engine, together with its inbound and
+//! outbound queues, straight to the new thread between ticks; queued orders stay where they
+//! are and jobs that still reach the old thread are forwarded.
+//!
+//! Waiting for engine threads blocks the caller. Called from a multi-threaded Tokio runtime,
+//! the wait runs in `block_in_place` so other tasks keep running on the runtime.
+//!
+//! An engine that panics during a tick is dropped by its thread and reported in the
+//! [`TickReport`]; a panicking job leaves the engine in place. Both are logged with the
+//! panic message.
+
+use crate::load::SymbolLoad;
+use crate::types::{SymbolId, ThreadId};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use whistle::{TickId, Whistle};
+
+/// Work run on an engine thread against one of its engines
+type EngineJob = Box<dyn FnOnce(&mut Whistle) + Send>;
+
+enum Command {
+    /// Take ownership of a symbol's engine
+    Install {
+        symbol_id: SymbolId,
+        slot: Slot,
+    },
+    /// Give up a symbol's engine
+    Remove {
+        symbol_id: SymbolId,
+        reply: Sender<Option<Slot>>,
+    },
+    /// Hand a symbol's engine to another thread and forward its jobs there
+    Migrate {
+        symbol_id: SymbolId,
+        to: Sender<Command>,
+        done: Sender<bool>,
+    },
+    /// Run a job against a symbol's engine (dropped if the thread does not own it)
+    Run {
+        symbol_id: SymbolId,
+        job: EngineJob,
+    },
+    /// Tick every owned engine and report back
+    Tick {
+        tick: TickId,
+        done: Sender<TickReport>,
+    },
+    /// Report engines without inbound orders for `idle_ticks` and a small enough book
+    Idle {
+        tick: TickId,
+        idle_ticks: u64,
+        max_resting_orders: usize,
+        reply: Sender<Vec<SymbolId>>,
+    },
+    /// Report the load of every owned engine
+    Loads {
+        reply: Sender<Vec<(SymbolId, SymbolLoad)>>,
+    },
+    /// Pin the thread to a core
+    Pin {
+        core: core_affinity::CoreId,
+    },
+    Shutdown,
+}
+
+/// Outcome of one tick on one or more engine threads
+#[derive(Debug, Default)]
+pub struct TickReport {
+    /// Symbols ticked, with the time their engine took
+    pub ticked: Vec<(SymbolId, Duration)>,
+    /// Symbols whose engine panicked; their engines have been dropped
+    pub failed: Vec<SymbolId>,
+}
+
+impl TickReport {
+    fn merge(&mut self, other: TickReport) {
+        self.ticked.extend(other.ticked);
+        self.failed.extend(other.failed);
+    }
+}
+
+/// An engine owned by a thread
+struct Slot {
+    engine: Box<Whistle>,
+    /// Last tick the engine had inbound orders (or was installed)
+    last_order_tick: TickId,
+    load: SymbolLoad,
+}
+
+struct Worker {
+    commands: Sender<Command>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// The engine threads of a coordinator
+pub struct EngineWorkers {
+    workers: Vec<Worker>,
+}
+
+impl EngineWorkers {
+    /// Spawn `num_threads` engine threads (at least one)
+    pub fn spawn(num_threads: u32) -> Self {
+        let workers = (0..num_threads.max(1))
+            .map(|thread_id| {
+                let (commands, inbox) = mpsc::channel();
+                let handle = std::thread::Builder::new()
+                    .name(format!("whistle-engine-{thread_id}"))
+                    .spawn(move || run(thread_id, inbox))
+                    .expect("failed to spawn engine thread");
+                Worker { commands, handle: Some(handle) }
+            })
+            .collect();
+        Self { workers }
+    }
+
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    /// Hand a symbol's engine to its thread; it is ticked from the next tick on
+    pub fn install(
+        &self,
+        thread_id: ThreadId,
+        symbol_id: SymbolId,
+        engine: Whistle,
+        tick: TickId,
+    ) -> bool {
+        let slot =
+            Slot { engine: Box::new(engine), last_order_tick: tick, load: SymbolLoad::default() };
+        self.send(thread_id, Command::Install { symbol_id, slot })
+    }
+
+    /// Take a symbol's engine back from its thread
+    pub fn remove(&self, thread_id: ThreadId, symbol_id: SymbolId) -> Option<Whistle> {
+        let (reply, slot) = mpsc::channel();
+        if !self.send(thread_id, Command::Remove { symbol_id, reply }) {
+            return None;
+        }
+        wait(|| slot.recv().ok().flatten().map(|slot| *slot.engine))
+    }
+
+    /// Move a symbol's engine from thread `from` to thread `to`
+    ///
+    /// Must be called between ticks. The engine keeps its queues, load and idle state; on
+    /// return the new thread owns it, so it is ticked there from the next tick on. Returns
+    /// `false` (leaving the engine where it was) if `from` does not own the symbol.
+    pub fn migrate(&self, symbol_id: SymbolId, from: ThreadId, to: ThreadId) -> bool {
+        let Some(target) = self.workers.get(to as usize) else {
+            return false;
+        };
+        let (done, moved) = mpsc::channel();
+        let command = Command::Migrate { symbol_id, to: target.commands.clone(), done };
+        self.send(from, command) && wait(|| moved.recv().unwrap_or(false))
+    }
+
+    /// Load of every engine on every thread
+    pub fn loads(&self) -> Vec<(SymbolId, SymbolLoad)> {
+        let (reply, loads) = mpsc::channel();
+        let dispatched = self
+            .workers
+            .iter()
+            .filter(|worker| worker.commands.send(Command::Loads { reply: reply.clone() }).is_ok())
+            .count();
+        drop(reply);
+
+        let mut loads: Vec<_> = wait(|| loads.iter().take(dispatched).flatten().collect());
+        loads.sort_unstable_by_key(|(symbol_id, _)| *symbol_id);
+        loads
+    }
+
+    /// Symbols on any thread without inbound orders for `idle_ticks` and with at most
+    /// `max_resting_orders` resting orders
+    pub fn idle_symbols(
+        &self,
+        tick: TickId,
+        idle_ticks: u64,
+        max_resting_orders: usize,
+    ) -> Vec<SymbolId> {
+        let (reply, idle) = mpsc::channel();
+        let dispatched = self
+            .workers
+            .iter()
+            .filter(|worker| {
+                let command =
+                    Command::Idle { tick, idle_ticks, max_resting_orders, reply: reply.clone() };
+                worker.commands.send(command).is_ok()
+            })
+            .count();
+        drop(reply);
+
+        let mut symbols: Vec<SymbolId> = wait(|| idle.iter().take(dispatched).flatten().collect());
+        symbols.sort_unstable();
+        symbols
+    }
+
+    /// Run `f` against a symbol's engine without waiting for it
+    pub fn post<F>(&self, thread_id: ThreadId, symbol_id: SymbolId, f: F) -> bool
+    where
+        F: FnOnce(&mut Whistle) + Send + 'static,
+    {
+        self.send(thread_id, Command::Run { symbol_id, job: Box::new(f) })
+    }
+
+    /// Run `f` against a symbol's engine and wait for the result
+    ///
+    /// Returns `None` if the thread does not own the symbol.
+    pub fn call<F, R>(&self, thread_id: ThreadId, symbol_id: SymbolId, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Whistle) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply, result) = mpsc::channel();
+        let posted = self.post(thread_id, symbol_id, move |engine| {
+            let _ = reply.send(f(engine));
+        });
+        if !posted {
+            return None;
+        }
+        wait(|| result.recv().ok())
+    }
+
+    /// Tick every engine on every thread and wait until all threads are done
+    pub fn tick(&self, tick: TickId) -> TickReport {
+        let (done, reports) = mpsc::channel();
+        let dispatched = self
+            .workers
+            .iter()
+            .filter(|worker| {
+                worker.commands.send(Command::Tick { tick, done: done.clone() }).is_ok()
+            })
+            .count();
+        drop(done);
+
+        wait(|| {
+            let mut report = TickReport::default();
+            for thread_report in reports.iter().take(dispatched) {
+                report.merge(thread_report);
+            }
+            report
+        })
+    }
+
+    /// Pin each thread to its own core (round-robin when there are more threads than cores)
+    ///
+    /// Returns the number of threads asked to pin.
+    pub fn pin_to_cores(&self) -> usize {
+        let Some(cores) = core_affinity::get_core_ids().filter(|cores| !cores.is_empty()) else {
+            tracing::warn!("CPU affinity requested but core IDs are unavailable");
+            return 0;
+        };
+        self.workers
+            .iter()
+            .enumerate()
+            .filter(|(i, worker)| {
+                worker.commands.send(Command::Pin { core: cores[i % cores.len()] }).is_ok()
+            })
+            .count()
+    }
+
+    fn send(&self, thread_id: ThreadId, command: Command) -> bool {
+        self.workers
+            .get(thread_id as usize)
+            .is_some_and(|worker| worker.commands.send(command).is_ok())
+    }
+}
+
+impl Drop for EngineWorkers {
+    fn drop(&mut self) {
+        for worker in &self.workers {
+            let _ = worker.commands.send(Command::Shutdown);
+        }
+        for worker in &mut self.workers {
+            if let Some(handle) = worker.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+/// Block on engine threads without stalling the Tokio runtime the caller may be running on
+fn wait<R>(f: impl FnOnce() -> R) -> R {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// Message of a caught panic
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+/// Engine thread loop
+fn run(thread_id: ThreadId, inbox: Receiver<Command>) {
+    // Ordered so a thread always ticks its symbols in the same order
+    let mut engines: BTreeMap<SymbolId, Slot> = BTreeMap::new();
+    // Threads that symbols migrated to
+    let mut moved: HashMap<SymbolId, Sender<Command>> = HashMap::new();
+
+    for command in inbox {
+        match command {
+            Command::Install { symbol_id, slot } => {
+                moved.remove(&symbol_id);
+                engines.insert(symbol_id, slot);
+            }
+            Command::Remove { symbol_id, reply } => {
+                let _ = reply.send(engines.remove(&symbol_id));
+            }
+            Command::Migrate { symbol_id, to, done } => {
+                let migrated = match engines.remove(&symbol_id) {
+                    Some(slot) => match to.send(Command::Install { symbol_id, slot }) {
+                        Ok(()) => {
+                            moved.insert(symbol_id, to);
+                            true
+                        }
+                        Err(mpsc::SendError(command)) => {
+                            // The other thread is gone; keep the engine
+                            if let Command::Install { slot, .. } = command {
+                                engines.insert(symbol_id, slot);
+                            }
+                            false
+                        }
+                    },
+                    None => false,
+                };
+                let _ = done.send(migrated);
+            }
+            Command::Run { symbol_id, job } if !engines.contains_key(&symbol_id) => {
+                // Posted before the symbol migrated; the new owner runs it
+                if let Some(to) = moved.get(&symbol_id) {
+                    let _ = to.send(Command::Run { symbol_id, job });
+                }
+            }
+            Command::Run { symbol_id, job } => {
+                if let Some(slot) = engines.get_mut(&symbol_id) {
+                    if let Err(panic) = catch_unwind(AssertUnwindSafe(|| job(&mut slot.engine))) {
+                        tracing::error!(
+                            "Engine job for symbol {} panicked on thread {}: {}",
+                            symbol_id,
+                            thread_id,
+                            panic_message(panic.as_ref())
+                        );
+                    }
+                }
+            }
+            Command::Tick { tick, done } => {
+                let mut report = TickReport::default();
+                for (&symbol_id, slot) in engines.iter_mut() {
+                    let messages = slot.engine.queue_stats().0;
+                    if messages > 0 {
+                        slot.last_order_tick = tick;
+                    }
+                    let start = Instant::now();
+                    let engine = &mut slot.engine;
+                    match catch_unwind(AssertUnwindSafe(|| engine.tick_with_queue_emission(tick))) {
+                        Ok(()) => {
+                            let elapsed = start.elapsed();
+                            slot.load.record(elapsed, messages);
+                            report.ticked.push((symbol_id, elapsed));
+                        }
+                        Err(panic) => {
+                            tracing::error!(
+                                "Engine for symbol {} panicked at tick {} on thread {}: {}; \
+                                 dropping it",
+                                symbol_id,
+                                tick,
+                                thread_id,
+                                panic_message(panic.as_ref())
+                            );
+                            report.failed.push(symbol_id);
+                        }
+                    }
+                }
+                for symbol_id in &report.failed {
+                    engines.remove(symbol_id);
+                }
+                let _ = done.send(report);
+            }
+            Command::Idle { tick, idle_ticks, max_resting_orders, reply } => {
+                let idle = engines
+                    .iter()
+                    .filter(|(_, slot)| tick.saturating_sub(slot.last_order_tick) >= idle_ticks)
+                    .filter(|(_, slot)| slot.engine.resting_orders().len() <= max_resting_orders)
+                    .map(|(&symbol_id, _)| symbol_id)
+                    .collect();
+                let _ = reply.send(idle);
+            }
+            Command::Loads { reply } => {
+                let _ = reply.send(
+                    engines.iter().map(|(&symbol_id, slot)| (symbol_id, slot.load)).collect(),
+                );
+            }
+            Command::Pin { core } => {
+                if core_affinity::set_for_current(core) {
+                    tracing::info!("Pinned engine thread {} to core {}", thread_id, core.id);
+                } else {
+                    tracing::warn!("Failed to pin engine thread {} to core {}", thread_id, core.id);
+                }
+            }
+            Command::Shutdown => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use whistle::{
+        BackpressurePolicy, BandMode, Bands, EngineCfg, ExecIdMode, InboundMsg, InboundQueue,
+        OrderType, OutboundQueue, PriceDomain, ReferencePriceSource, SelfMatchPolicy, Side,
+    };
+
+    fn engine(symbol_id: SymbolId) -> Whistle {
+        let cfg = EngineCfg {
+            symbol: symbol_id,
+            price_domain: PriceDomain { floor: 100, ceil: 100000, tick: 1 },
+            bands: Bands { mode: BandMode::Percent(1000) },
+            batch_max: 64,
+            arena_capacity: 1024,
+            elastic_arena: false,
+            exec_shift_bits: 16,
+            exec_id_mode: ExecIdMode::Sharded,
+            self_match_policy: SelfMatchPolicy::Skip,
+            allow_market_cold_start: false,
+            reference_price_source: ReferencePriceSource::MidpointOnWarm,
+        };
+        Whistle::new_with_queues(
+            cfg,
+            Arc::new(InboundQueue::new(64)),
+            Arc::new(OutboundQueue::new(1024, BackpressurePolicy::Fatal)),
+        )
+    }
+
+    fn ticked(report: &TickReport) -> Vec<SymbolId> {
+        let mut symbols: Vec<_> = report.ticked.iter().map(|(symbol_id, _)| *symbol_id).collect();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    #[test]
+    fn tick_fans_out_to_every_thread() {
+        let workers = EngineWorkers::spawn(3);
+        for symbol_id in 1..=7 {
+            assert!(workers.install(symbol_id % 3, symbol_id, engine(symbol_id), 0));
+        }
+
+        let report = workers.tick(1);
+        assert_eq!(ticked(&report), (1..=7).collect::<Vec<_>>());
+        assert!(report.failed.is_empty());
+    }
+
+    #[test]
+    fn jobs_run_on_the_owning_thread_only() {
+        let workers = EngineWorkers::spawn(2);
+        workers.install(1, 42, engine(42), 0);
+
+        let owner = workers.call(1, 42, |_| std::thread::current().name().map(str::to_string));
+        assert_eq!(owner, Some(Some("whistle-engine-1".to_string())));
+        assert_eq!(workers.call(0, 42, |_| ()), None);
+        assert_eq!(workers.call(9, 42, |_| ()), None);
+    }
+
+    #[test]
+    fn panicking_job_does_not_take_down_the_thread() {
+        let workers = EngineWorkers::spawn(1);
+        workers.install(0, 1, engine(1), 0);
+
+        assert_eq!(workers.call(0, 1, |_| -> u32 { panic!("boom") }), None);
+        assert_eq!(ticked(&workers.tick(1)), vec![1]);
+    }
+
+    #[test]
+    fn calls_from_async_code_leave_the_runtime_running() {
+        let runtime =
+            tokio::runtime::Builder::new_multi_thread().worker_threads(1).build().unwrap();
+        let workers = Arc::new(EngineWorkers::spawn(1));
+        workers.install(0, 1, engine(1), 0);
+
+        // The job can only finish once another task on the single runtime thread has run
+        let (ready, go) = mpsc::channel();
+        let task_workers = workers.clone();
+        let result = runtime.block_on(async move {
+            tokio::spawn(async move {
+                tokio::spawn(async move { ready.send(()).unwrap() });
+                task_workers.call(0, 1, move |engine| {
+                    go.recv().unwrap();
+                    engine.symbol()
+                })
+            })
+            .await
+            .unwrap()
+        });
+        assert_eq!(result, Some(1));
+    }
+
+    #[test]
+    fn panic_messages_are_extracted() {
+        let panic = catch_unwind(|| panic!("engine {} broke", 7)).unwrap_err();
+        assert_eq!(panic_message(panic.as_ref()), "engine 7 broke");
+        let panic = catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(panic_message(panic.as_ref()), "boom");
+    }
+
+    #[test]
+    fn idle_symbols_have_had_no_orders_for_idle_ticks() {
+        let workers = EngineWorkers::spawn(2);
+        workers.install(0, 1, engine(1), 0);
+        workers.install(1, 2, engine(2), 0);
+        workers.install(1, 3, engine(3), 50);
+
+        let order =
+            InboundMsg::submit(1, 7, Side::Buy, OrderType::Limit, Some(150), 10, 1000, 0, 0);
+        workers.post(0, 1, move |engine| engine.enqueue_message(order).unwrap());
+        workers.tick(60);
+
+        assert_eq!(workers.idle_symbols(100, 60, 10), vec![2]);
+        assert_eq!(workers.idle_symbols(110, 50, 10), vec![1, 2, 3]);
+        // Symbol 1 has a resting order
+        assert_eq!(workers.idle_symbols(110, 50, 0), vec![2, 3]);
+
+        let engine = workers.remove(1, 2).expect("thread 1 owns symbol 2");
+        assert_eq!(engine.symbol(), 2);
+        assert!(workers.remove(1, 2).is_none());
+        assert_eq!(ticked(&workers.tick(111)), vec![1, 3]);
+    }
+
+    #[test]
+    fn migrated_engine_keeps_queued_orders_and_load() {
+        let workers = EngineWorkers::spawn(2);
+        let mut engine = engine(5);
+        let order =
+            InboundMsg::submit(1, 7, Side::Buy, OrderType::Limit, Some(150), 10, 1000, 0, 0);
+        engine.enqueue_message(order).unwrap();
+        workers.install(0, 5, engine, 0);
+        workers.tick(1);
+        let order =
+            InboundMsg::submit(2, 7, Side::Buy, OrderType::Limit, Some(151), 10, 1001, 0, 0);
+        workers.post(0, 5, move |engine| engine.enqueue_message(order).unwrap());
+
+        let load_before = workers.loads()[0].1;
+        assert!(load_before.messages_per_tick > 0.0);
+
+        assert!(workers.migrate(5, 0, 1));
+        assert!(!workers.migrate(5, 0, 1));
+        assert_eq!(workers.loads(), vec![(5, load_before)]);
+
+        // The order queued before the move is processed on the new thread
+        assert_eq!(ticked(&workers.tick(2)), vec![5]);
+        assert_eq!(workers.call(1, 5, |engine| engine.resting_orders().len()), Some(2));
+
+        // Jobs still sent to the old thread are forwarded
+        assert_eq!(workers.call(0, 5, |engine| engine.symbol()), Some(5));
+        assert!(workers.remove(0, 5).is_none());
+        assert!(workers.remove(1, 5).is_some());
+    }
+}
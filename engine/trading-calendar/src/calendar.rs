@@ -0,0 +1,357 @@
+//! Season calendar: trading weeks, daily market hours and game kickoffs
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::CalendarError;
+
+/// Minutes a team's players stay halted after kickoff unless configured otherwise
+pub const DEFAULT_HALT_MINUTES: u32 = 210;
+
+/// Market phase at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPhase {
+    /// Nothing is accepted
+    Closed,
+    /// Orders may rest but nothing trades
+    PreOpen,
+    /// Continuous trading
+    Open,
+}
+
+/// Daily market hours, in UTC
+///
+/// The times are read in order pre-open → open → close, each wrapping past midnight when it is
+/// earlier than the one before, so a session may run overnight (e.g. open 13:00, close 04:00).
+/// A session belongs to the trading day its pre-open falls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketHours {
+    pub pre_open: NaiveTime,
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+impl MarketHours {
+    /// The session starting on `trading_day`
+    pub fn session(&self, trading_day: NaiveDate) -> Session {
+        let pre_open = trading_day.and_time(self.pre_open).and_utc();
+        let open = pre_open + wrapping_span(self.pre_open, self.open);
+        let close = open + wrapping_span(self.open, self.close);
+        Session { trading_day, pre_open, open, close }
+    }
+}
+
+/// Time from `from` forward to the next `to`, wrapping past midnight
+fn wrapping_span(from: NaiveTime, to: NaiveTime) -> Duration {
+    let span = to - from;
+    if span < Duration::zero() {
+        span + Duration::days(1)
+    } else {
+        span
+    }
+}
+
+/// One trading day's session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Session {
+    pub trading_day: NaiveDate,
+    pub pre_open: DateTime<Utc>,
+    pub open: DateTime<Utc>,
+    pub close: DateTime<Utc>,
+}
+
+impl Session {
+    /// Phase of this session at `now`
+    pub fn phase_at(&self, now: DateTime<Utc>) -> SessionPhase {
+        if now < self.pre_open || now >= self.close {
+            SessionPhase::Closed
+        } else if now < self.open {
+            SessionPhase::PreOpen
+        } else {
+            SessionPhase::Open
+        }
+    }
+}
+
+/// A scheduled game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Game {
+    pub home: String,
+    pub away: String,
+    pub kickoff: DateTime<Utc>,
+}
+
+/// An NFL week; the market trades on every day from `starts` to `ends` inclusive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Week {
+    pub week: u32,
+    pub starts: NaiveDate,
+    pub ends: NaiveDate,
+    #[serde(default)]
+    pub games: Vec<Game>,
+}
+
+impl Week {
+    fn contains(&self, day: NaiveDate) -> bool {
+        self.starts <= day && day <= self.ends
+    }
+}
+
+/// Season calendar driving the session scheduler
+///
+/// Loaded from TOML. Games can be listed per week or merged in from a SportsDataIO
+/// `Schedules` response named by `schedule_file` (relative to the calendar file).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradingCalendar {
+    pub season: i32,
+    pub hours: MarketHours,
+    /// Halt a team's players from kickoff until `halt_minutes` later
+    #[serde(default)]
+    pub halt_at_kickoff: bool,
+    #[serde(default = "default_halt_minutes")]
+    pub halt_minutes: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule_file: Option<PathBuf>,
+    #[serde(default)]
+    pub weeks: Vec<Week>,
+}
+
+fn default_halt_minutes() -> u32 {
+    DEFAULT_HALT_MINUTES
+}
+
+/// Entry of a SportsDataIO `Schedules/{season}` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScheduleEntry {
+    week: u32,
+    home_team: String,
+    away_team: String,
+    /// Missing for byes and unscheduled games
+    #[serde(rename = "DateTimeUTC")]
+    date_time_utc: Option<NaiveDateTime>,
+}
+
+impl TradingCalendar {
+    /// Load and validate a calendar file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CalendarError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        Self::load(&content, path.parent().unwrap_or(Path::new(".")))
+    }
+
+    /// Parse and validate a calendar, resolving `schedule_file` against the working directory
+    pub fn from_toml(content: &str) -> Result<Self, CalendarError> {
+        Self::load(content, Path::new("."))
+    }
+
+    fn load(content: &str, base_dir: &Path) -> Result<Self, CalendarError> {
+        let mut calendar: TradingCalendar = toml::from_str(content)?;
+        if let Some(schedule_file) = &calendar.schedule_file {
+            let schedule = std::fs::read_to_string(base_dir.join(schedule_file))?;
+            calendar.merge_schedule(&schedule)?;
+        }
+        calendar.validate()?;
+        Ok(calendar)
+    }
+
+    /// Add the games of a SportsDataIO `Schedules` response to their weeks
+    ///
+    /// A game already listed (same home and away team) takes the schedule's kickoff, since
+    /// games get moved. Byes and games in weeks the calendar doesn't have are skipped.
+    /// Returns the number of games merged.
+    pub fn merge_schedule(&mut self, json: &str) -> Result<usize, CalendarError> {
+        let entries: Vec<ScheduleEntry> = serde_json::from_str(json)?;
+        let mut merged = 0;
+        for entry in entries {
+            let Some(kickoff) = entry.date_time_utc else { continue };
+            let Some(week) = self.weeks.iter_mut().find(|week| week.week == entry.week) else {
+                debug!(
+                    "Skipping {} @ {}: no week {}",
+                    entry.away_team, entry.home_team, entry.week
+                );
+                continue;
+            };
+            let kickoff = kickoff.and_utc();
+            match week
+                .games
+                .iter_mut()
+                .find(|game| game.home == entry.home_team && game.away == entry.away_team)
+            {
+                Some(game) => game.kickoff = kickoff,
+                None => {
+                    week.games.push(Game { home: entry.home_team, away: entry.away_team, kickoff })
+                }
+            }
+            merged += 1;
+        }
+        Ok(merged)
+    }
+
+    /// Check the calendar is usable
+    pub fn validate(&self) -> Result<(), CalendarError> {
+        if self.hours.open == self.hours.close {
+            return Err(CalendarError::Invalid("market opens and closes at the same time".into()));
+        }
+        // Sessions must not overlap the next day's
+        let session = self.hours.session(NaiveDate::default());
+        if session.close - session.pre_open > Duration::days(1) {
+            return Err(CalendarError::Invalid("session is longer than a day".into()));
+        }
+        if self.halt_at_kickoff && self.halt_minutes == 0 {
+            return Err(CalendarError::Invalid("halt_minutes must be positive".into()));
+        }
+        for (i, week) in self.weeks.iter().enumerate() {
+            if week.starts > week.ends {
+                return Err(CalendarError::Invalid(format!(
+                    "week {} ends before it starts",
+                    week.week
+                )));
+            }
+            if let Some(previous) = i.checked_sub(1).map(|i| &self.weeks[i]) {
+                if week.starts <= previous.ends {
+                    return Err(CalendarError::Invalid(format!(
+                        "week {} overlaps or precedes week {}",
+                        week.week, previous.week
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Week trading on `day`
+    pub fn week_of(&self, day: NaiveDate) -> Option<&Week> {
+        self.weeks.iter().find(|week| week.contains(day))
+    }
+
+    /// Session in progress (pre-open or open) at `now`
+    pub fn session_at(&self, now: DateTime<Utc>) -> Option<Session> {
+        let today = now.date_naive();
+        // An overnight session started yesterday may still be running
+        [today.pred_opt(), Some(today)]
+            .into_iter()
+            .flatten()
+            .filter(|day| self.week_of(*day).is_some())
+            .map(|day| self.hours.session(day))
+            .find(|session| session.phase_at(now) != SessionPhase::Closed)
+    }
+
+    /// Market phase at `now`
+    pub fn phase_at(&self, now: DateTime<Utc>) -> SessionPhase {
+        self.session_at(now).map_or(SessionPhase::Closed, |session| session.phase_at(now))
+    }
+
+    /// Teams whose players are halted at `now` because their game is in progress
+    pub fn teams_in_play(&self, now: DateTime<Utc>) -> HashSet<&str> {
+        if !self.halt_at_kickoff {
+            return HashSet::new();
+        }
+        let halt = Duration::minutes(self.halt_minutes.into());
+        self.weeks
+            .iter()
+            .flat_map(|week| &week.games)
+            .filter(|game| game.kickoff <= now && now < game.kickoff + halt)
+            .flat_map(|game| [game.home.as_str(), game.away.as_str()])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = r#"
+        season = 2025
+        halt_at_kickoff = true
+
+        [hours]
+        pre_open = "12:00:00"
+        open = "13:00:00"
+        close = "04:00:00"
+
+        [[weeks]]
+        week = 1
+        starts = "2025-09-02"
+        ends = "2025-09-08"
+
+        [[weeks.games]]
+        home = "PHI"
+        away = "DAL"
+        kickoff = "2025-09-05T00:20:00Z"
+
+        [[weeks]]
+        week = 2
+        starts = "2025-09-09"
+        ends = "2025-09-15"
+    "#;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn overnight_session_belongs_to_the_day_it_opens() {
+        let calendar = TradingCalendar::from_toml(CALENDAR).unwrap();
+        assert_eq!(calendar.halt_minutes, DEFAULT_HALT_MINUTES);
+
+        let session = calendar.session_at(at("2025-09-04T02:00:00Z")).unwrap();
+        assert_eq!(session.trading_day, NaiveDate::from_ymd_opt(2025, 9, 3).unwrap());
+        assert_eq!(session.close, at("2025-09-04T04:00:00Z"));
+
+        assert_eq!(calendar.phase_at(at("2025-09-04T02:00:00Z")), SessionPhase::Open);
+        assert_eq!(calendar.phase_at(at("2025-09-04T04:00:00Z")), SessionPhase::Closed);
+        assert_eq!(calendar.phase_at(at("2025-09-04T12:30:00Z")), SessionPhase::PreOpen);
+        assert_eq!(calendar.phase_at(at("2025-09-04T13:00:00Z")), SessionPhase::Open);
+
+        // Before week 1 nothing opens; the last day of week 2 still closes overnight
+        assert_eq!(calendar.phase_at(at("2025-09-01T14:00:00Z")), SessionPhase::Closed);
+        assert_eq!(calendar.phase_at(at("2025-09-16T03:00:00Z")), SessionPhase::Open);
+        assert_eq!(calendar.phase_at(at("2025-09-16T14:00:00Z")), SessionPhase::Closed);
+    }
+
+    #[test]
+    fn teams_are_in_play_from_kickoff_for_the_halt_window() {
+        let mut calendar = TradingCalendar::from_toml(CALENDAR).unwrap();
+
+        assert!(calendar.teams_in_play(at("2025-09-05T00:19:59Z")).is_empty());
+        assert_eq!(
+            calendar.teams_in_play(at("2025-09-05T00:20:00Z")),
+            HashSet::from(["PHI", "DAL"])
+        );
+        assert!(calendar.teams_in_play(at("2025-09-05T03:50:00Z")).is_empty());
+
+        calendar.halt_at_kickoff = false;
+        assert!(calendar.teams_in_play(at("2025-09-05T01:00:00Z")).is_empty());
+    }
+
+    #[test]
+    fn schedule_merges_into_weeks() {
+        let mut calendar = TradingCalendar::from_toml(CALENDAR).unwrap();
+        let schedule = r#"[
+            {"Week": 1, "HomeTeam": "PHI", "AwayTeam": "DAL", "DateTimeUTC": "2025-09-05T00:25:00"},
+            {"Week": 2, "HomeTeam": "GB", "AwayTeam": "WAS", "DateTimeUTC": "2025-09-12T00:15:00"},
+            {"Week": 2, "HomeTeam": "ATL", "AwayTeam": "BYE", "DateTimeUTC": null},
+            {"Week": 9, "HomeTeam": "KC", "AwayTeam": "BUF", "DateTimeUTC": "2025-11-03T01:20:00"}
+        ]"#;
+
+        assert_eq!(calendar.merge_schedule(schedule).unwrap(), 2);
+        assert_eq!(calendar.weeks[0].games.len(), 1);
+        assert_eq!(calendar.weeks[0].games[0].kickoff, at("2025-09-05T00:25:00Z"));
+        assert_eq!(calendar.weeks[1].games[0].home, "GB");
+    }
+
+    #[test]
+    fn invalid_calendars_are_rejected() {
+        let same_hours = CALENDAR.replace("close = \"04:00:00\"", "close = \"13:00:00\"");
+        assert!(matches!(TradingCalendar::from_toml(&same_hours), Err(CalendarError::Invalid(_))));
+
+        let overlapping = CALENDAR.replace("starts = \"2025-09-09\"", "starts = \"2025-09-08\"");
+        assert!(matches!(TradingCalendar::from_toml(&overlapping), Err(CalendarError::Invalid(_))));
+    }
+}
@@ -0,0 +1,40 @@
+//! # Trading Calendar
+//!
+//! The NFL season as the exchange trades it: weeks, daily pre-open/open/close times and game
+//! kickoffs. The `SessionScheduler` evaluates the calendar against the simulation clock and
+//! issues the transitions engines act on: market phase changes, per-player halts while their
+//! game is on, DAY-order expiry and end-of-day snapshots. Admins can override the phase and
+//! individual halts.
+//!
+//! The exchange loads a calendar from the file named by `WAIVER_CALENDAR_FILE`; without one
+//! the market is always open.
+
+pub mod calendar;
+pub mod scheduler;
+
+pub use calendar::{Game, MarketHours, Session, SessionPhase, TradingCalendar, Week};
+pub use scheduler::{PhaseOverride, SessionEvent, SessionScheduler, SessionStatus};
+
+/// Environment variable naming the calendar file
+pub const CALENDAR_ENV: &str = "WAIVER_CALENDAR_FILE";
+
+/// Calendar file path from `WAIVER_CALENDAR_FILE`, if configured
+pub fn calendar_file_from_env() -> Option<std::path::PathBuf> {
+    std::env::var_os(CALENDAR_ENV).filter(|path| !path.is_empty()).map(Into::into)
+}
+
+/// Errors loading a calendar
+#[derive(Debug, thiserror::Error)]
+pub enum CalendarError {
+    #[error("Failed to read calendar: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse calendar: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Failed to parse schedule: {0}")]
+    Schedule(#[from] serde_json::Error),
+
+    #[error("Invalid calendar: {0}")]
+    Invalid(String),
+}
@@ -0,0 +1,410 @@
+//! Session scheduler: turns the calendar into phase transitions for the exchange
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::info;
+
+use crate::calendar::{SessionPhase, TradingCalendar};
+
+/// Events buffered per subscriber
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How often the calendar is re-evaluated, in calendar time; admin overrides apply at once
+const EVALUATION_INTERVAL: Duration = Duration::seconds(1);
+
+/// Something the exchange must do at a session boundary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// Every engine moves to `phase`
+    PhaseChanged { phase: SessionPhase, at: DateTime<Utc> },
+    /// These symbols stop trading, e.g. because their game kicked off
+    Halted { symbols: Vec<u32>, at: DateTime<Utc> },
+    /// These symbols trade with the rest of the market again
+    Resumed { symbols: Vec<u32>, at: DateTime<Utc> },
+    /// The session closed: cancel every resting DAY order
+    ExpireDayOrders { trading_day: NaiveDate, at: DateTime<Utc> },
+    /// The session closed: take the end-of-day snapshot
+    EndOfDay { trading_day: NaiveDate, at: DateTime<Utc> },
+}
+
+/// Admin override of the calendar's phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseOverride {
+    pub phase: SessionPhase,
+    /// The calendar takes over again at this time; `None` holds until cleared
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// What the scheduler last told the exchange, plus the admin overrides
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionStatus {
+    pub season: i32,
+    pub phase: SessionPhase,
+    pub trading_day: Option<NaiveDate>,
+    pub week: Option<u32>,
+    pub halted_symbols: Vec<u32>,
+    pub phase_override: Option<PhaseOverride>,
+    /// Per-symbol halt overrides: `true` halts, `false` keeps trading through kickoff
+    pub symbol_overrides: Vec<(u32, bool)>,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// State last applied to the exchange
+struct Applied {
+    phase: SessionPhase,
+    /// Trading day of the session in progress, for the end-of-day events
+    trading_day: Option<NaiveDate>,
+    halted: HashSet<u32>,
+    at: DateTime<Utc>,
+}
+
+struct State {
+    calendar: TradingCalendar,
+    symbol_teams: HashMap<u32, String>,
+    phase_override: Option<PhaseOverride>,
+    symbol_overrides: HashMap<u32, bool>,
+    applied: Option<Applied>,
+    /// An override changed since the last evaluation
+    dirty: bool,
+}
+
+/// Drives market phases, kickoff halts and end-of-day work from a `TradingCalendar`
+///
+/// The simulation clock calls `poll` every tick with the current calendar time and applies
+/// the events it returns to the engines; other components (the gateway's DAY-order expiry)
+/// `subscribe` to the same events. Cloning gives another handle to the same scheduler.
+#[derive(Clone)]
+pub struct SessionScheduler {
+    state: Arc<Mutex<State>>,
+    sender: broadcast::Sender<SessionEvent>,
+}
+
+impl SessionScheduler {
+    pub fn new(calendar: TradingCalendar) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let state = State {
+            calendar,
+            symbol_teams: HashMap::new(),
+            phase_override: None,
+            symbol_overrides: HashMap::new(),
+            applied: None,
+            dirty: false,
+        };
+        Self { state: Arc::new(Mutex::new(state)), sender }
+    }
+
+    /// Set the team each symbol's player is on, for kickoff halts
+    pub fn set_symbol_teams(&self, symbol_teams: HashMap<u32, String>) {
+        let mut state = self.state.lock();
+        state.symbol_teams = symbol_teams;
+        state.dirty = true;
+    }
+
+    /// Receive every event issued from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Evaluate the calendar at `now` and issue the events for whatever changed
+    ///
+    /// The first poll issues the current phase and halts so engines start in sync. Closing
+    /// a session issues `ExpireDayOrders` and `EndOfDay` for its trading day.
+    pub fn poll(&self, now: DateTime<Utc>) -> Vec<SessionEvent> {
+        let mut state = self.state.lock();
+        if let Some(applied) = &state.applied {
+            // Calendar time may also jump backwards when the clock is recreated
+            let due = now >= applied.at + EVALUATION_INTERVAL || now < applied.at;
+            if !due && !state.dirty {
+                return Vec::new();
+            }
+        }
+        state.dirty = false;
+
+        if state.phase_override.is_some_and(|o| o.until.is_some_and(|until| now >= until)) {
+            info!("Session phase override expired");
+            state.phase_override = None;
+        }
+
+        let session = state.calendar.session_at(now);
+        let phase = match state.phase_override {
+            Some(phase_override) => phase_override.phase,
+            None => session.map_or(SessionPhase::Closed, |session| session.phase_at(now)),
+        };
+        let halted = state.halted_symbols(now);
+
+        let mut events = Vec::new();
+        let previous = state.applied.take();
+        let trading_day = match phase {
+            SessionPhase::Closed => None,
+            _ => previous
+                .as_ref()
+                .and_then(|applied| applied.trading_day)
+                .or(session.map(|session| session.trading_day))
+                .or(Some(now.date_naive())),
+        };
+
+        match &previous {
+            None => events.push(SessionEvent::PhaseChanged { phase, at: now }),
+            Some(applied) if applied.phase != phase => {
+                events.push(SessionEvent::PhaseChanged { phase, at: now });
+                if let (SessionPhase::Closed, Some(trading_day)) = (phase, applied.trading_day) {
+                    events.push(SessionEvent::ExpireDayOrders { trading_day, at: now });
+                    events.push(SessionEvent::EndOfDay { trading_day, at: now });
+                }
+            }
+            Some(_) => {}
+        }
+
+        let empty = HashSet::new();
+        let was_halted = previous.as_ref().map_or(&empty, |applied| &applied.halted);
+        let newly_halted = sorted(halted.difference(was_halted));
+        let resumed = sorted(was_halted.difference(&halted));
+        if !newly_halted.is_empty() {
+            events.push(SessionEvent::Halted { symbols: newly_halted, at: now });
+        }
+        if !resumed.is_empty() {
+            events.push(SessionEvent::Resumed { symbols: resumed, at: now });
+        }
+
+        state.applied = Some(Applied { phase, trading_day, halted, at: now });
+        drop(state);
+
+        for event in &events {
+            info!("Session event: {:?}", event);
+            // No subscribers is fine
+            let _ = self.sender.send(event.clone());
+        }
+        events
+    }
+
+    /// Force the market into `phase`, until `until` or until cleared
+    pub fn override_phase(&self, phase: SessionPhase, until: Option<DateTime<Utc>>) {
+        let mut state = self.state.lock();
+        state.phase_override = Some(PhaseOverride { phase, until });
+        state.dirty = true;
+    }
+
+    /// Hand the phase back to the calendar
+    pub fn clear_phase_override(&self) {
+        let mut state = self.state.lock();
+        state.phase_override = None;
+        state.dirty = true;
+    }
+
+    /// Halt a symbol (`true`) or keep it trading through its kickoff (`false`)
+    pub fn override_symbol(&self, symbol_id: u32, halted: bool) {
+        let mut state = self.state.lock();
+        state.symbol_overrides.insert(symbol_id, halted);
+        state.dirty = true;
+    }
+
+    /// Hand a symbol's halts back to the calendar
+    pub fn clear_symbol_override(&self, symbol_id: u32) -> bool {
+        let mut state = self.state.lock();
+        let cleared = state.symbol_overrides.remove(&symbol_id).is_some();
+        state.dirty |= cleared;
+        cleared
+    }
+
+    /// Current state as last issued, plus pending overrides
+    pub fn status(&self) -> SessionStatus {
+        let state = self.state.lock();
+        let applied = state.applied.as_ref();
+        let mut symbol_overrides: Vec<_> = state
+            .symbol_overrides
+            .iter()
+            .map(|(symbol_id, halted)| (*symbol_id, *halted))
+            .collect();
+        symbol_overrides.sort_unstable();
+        SessionStatus {
+            season: state.calendar.season,
+            phase: applied.map_or(SessionPhase::Closed, |applied| applied.phase),
+            trading_day: applied.and_then(|applied| applied.trading_day),
+            week: applied
+                .and_then(|applied| state.calendar.week_of(applied.at.date_naive()))
+                .map(|week| week.week),
+            halted_symbols: applied.map(|applied| sorted(&applied.halted)).unwrap_or_default(),
+            phase_override: state.phase_override,
+            symbol_overrides,
+            as_of: applied.map(|applied| applied.at),
+        }
+    }
+}
+
+impl State {
+    fn halted_symbols(&self, now: DateTime<Utc>) -> HashSet<u32> {
+        let teams = self.calendar.teams_in_play(now);
+        let mut halted: HashSet<u32> = self
+            .symbol_teams
+            .iter()
+            .filter(|(_, team)| teams.contains(team.as_str()))
+            .map(|(symbol_id, _)| *symbol_id)
+            .collect();
+        for (symbol_id, halt) in &self.symbol_overrides {
+            if *halt {
+                halted.insert(*symbol_id);
+            } else {
+                halted.remove(symbol_id);
+            }
+        }
+        halted
+    }
+}
+
+fn sorted<'a>(symbols: impl IntoIterator<Item = &'a u32>) -> Vec<u32> {
+    let mut symbols: Vec<_> = symbols.into_iter().copied().collect();
+    symbols.sort_unstable();
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{Game, MarketHours, Week};
+    use chrono::NaiveTime;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn scheduler() -> SessionScheduler {
+        let calendar = TradingCalendar {
+            season: 2025,
+            hours: MarketHours {
+                pre_open: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+                open: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+                close: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            },
+            halt_at_kickoff: true,
+            halt_minutes: 210,
+            schedule_file: None,
+            weeks: vec![Week {
+                week: 1,
+                starts: day("2025-09-02"),
+                ends: day("2025-09-08"),
+                games: vec![Game {
+                    home: "PHI".into(),
+                    away: "DAL".into(),
+                    kickoff: at("2025-09-05T00:20:00Z"),
+                }],
+            }],
+        };
+        let scheduler = SessionScheduler::new(calendar);
+        scheduler.set_symbol_teams(HashMap::from([
+            (1, "PHI".to_string()),
+            (2, "DAL".to_string()),
+            (3, "KC".to_string()),
+        ]));
+        scheduler
+    }
+
+    #[test]
+    fn first_poll_syncs_then_only_changes_are_issued() {
+        let scheduler = scheduler();
+        let now = at("2025-09-04T12:30:00Z");
+        assert_eq!(
+            scheduler.poll(now),
+            vec![SessionEvent::PhaseChanged { phase: SessionPhase::PreOpen, at: now }]
+        );
+        assert!(scheduler.poll(now + Duration::seconds(5)).is_empty());
+
+        let open = at("2025-09-04T13:00:00Z");
+        assert_eq!(
+            scheduler.poll(open),
+            vec![SessionEvent::PhaseChanged { phase: SessionPhase::Open, at: open }]
+        );
+        assert_eq!(scheduler.status().trading_day, Some(day("2025-09-04")));
+        assert_eq!(scheduler.status().week, Some(1));
+    }
+
+    #[test]
+    fn kickoff_halts_players_and_close_ends_the_day() {
+        let scheduler = scheduler();
+        scheduler.poll(at("2025-09-04T13:00:00Z"));
+
+        let kickoff = at("2025-09-05T00:20:00Z");
+        assert_eq!(
+            scheduler.poll(kickoff),
+            vec![SessionEvent::Halted { symbols: vec![1, 2], at: kickoff }]
+        );
+
+        // Close at 04:00 falls after the game's halt window ends at 03:50
+        let resume = at("2025-09-05T03:50:00Z");
+        assert_eq!(
+            scheduler.poll(resume),
+            vec![SessionEvent::Resumed { symbols: vec![1, 2], at: resume }]
+        );
+
+        let close = at("2025-09-05T04:00:00Z");
+        let trading_day = day("2025-09-04");
+        assert_eq!(
+            scheduler.poll(close),
+            vec![
+                SessionEvent::PhaseChanged { phase: SessionPhase::Closed, at: close },
+                SessionEvent::ExpireDayOrders { trading_day, at: close },
+                SessionEvent::EndOfDay { trading_day, at: close },
+            ]
+        );
+        assert_eq!(scheduler.status().trading_day, None);
+    }
+
+    #[test]
+    fn admin_overrides_apply_on_the_next_poll() {
+        let scheduler = scheduler();
+        let mut events = scheduler.subscribe();
+        let now = at("2025-09-04T14:00:00Z");
+        scheduler.poll(now);
+
+        // Halting the whole market ends the day like a scheduled close
+        scheduler.override_phase(SessionPhase::Closed, Some(now + Duration::minutes(30)));
+        scheduler.override_symbol(3, true);
+        let polled = scheduler.poll(now);
+        assert_eq!(
+            polled,
+            vec![
+                SessionEvent::PhaseChanged { phase: SessionPhase::Closed, at: now },
+                SessionEvent::ExpireDayOrders { trading_day: day("2025-09-04"), at: now },
+                SessionEvent::EndOfDay { trading_day: day("2025-09-04"), at: now },
+                SessionEvent::Halted { symbols: vec![3], at: now },
+            ]
+        );
+        assert_eq!(scheduler.status().halted_symbols, vec![3]);
+
+        // Subscribers see the same events as the poller
+        let open = SessionEvent::PhaseChanged { phase: SessionPhase::Open, at: now };
+        assert_eq!(events.try_recv().unwrap(), open);
+        for event in polled {
+            assert_eq!(events.try_recv().unwrap(), event);
+        }
+
+        // The override lapses and a kickoff exemption keeps a player trading
+        scheduler.override_symbol(1, false);
+        let kickoff = at("2025-09-05T00:20:00Z");
+        assert_eq!(
+            scheduler.poll(kickoff),
+            vec![
+                SessionEvent::PhaseChanged { phase: SessionPhase::Open, at: kickoff },
+                SessionEvent::Halted { symbols: vec![2], at: kickoff },
+            ]
+        );
+        assert_eq!(scheduler.status().phase_override, None);
+
+        assert!(scheduler.clear_symbol_override(3));
+        assert!(!scheduler.clear_symbol_override(3));
+        let later = kickoff + Duration::minutes(1);
+        assert_eq!(
+            scheduler.poll(later),
+            vec![SessionEvent::Resumed { symbols: vec![3], at: later }]
+        );
+    }
+}
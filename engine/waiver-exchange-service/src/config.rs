@@ -194,6 +194,27 @@ fn load_from_env(config: &mut ServiceConfig) -> Result<()> {
         config.service.max_symbols = max_symbols.parse().unwrap_or(1000);
     }
 
+    // Idle symbol hibernation
+    let eviction = &mut config.symbol_coordinator.eviction;
+    eviction.hibernation_dir = config.service.data_dir.join("hibernation");
+
+    if let Ok(enabled) = std::env::var("WAIVER_HIBERNATION_ENABLED") {
+        eviction.enabled = enabled.parse().unwrap_or(true);
+    }
+
+    if let Ok(idle_ticks) = std::env::var("WAIVER_HIBERNATE_IDLE_TICKS") {
+        eviction.idle_ticks = idle_ticks.parse().unwrap_or(600_000);
+    }
+
+    if let Ok(max_resting) = std::env::var("WAIVER_HIBERNATE_MAX_RESTING_ORDERS") {
+        eviction.max_resting_orders = max_resting.parse().unwrap_or(32);
+    }
+
+    // Engine thread rebalancing
+    if let Ok(enabled) = std::env::var("WAIVER_REBALANCE_ENABLED") {
+        config.symbol_coordinator.rebalance.enabled = enabled.parse().unwrap_or(true);
+    }
+
     Ok(())
 }
 
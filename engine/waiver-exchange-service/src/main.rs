@@ -43,6 +43,15 @@ async fn main() -> Result<()> {
     let shutdown_signal = setup_signal_handlers(service_state.clone())?;
     info!("Signal handlers configured");
 
+    // Apply pushed fair prices before anything trades
+    service_state.start_fair_price_bus().await?;
+
+    // Feed book changes to equity valuation for book-mid marks
+    service_state.start_equity_marks().await?;
+
+    // Rank contests on equity updates
+    service_state.start_contests().await?;
+
     // Start the simulation clock in a separate task
     info!("Starting SimulationClock...");
     let clock_handle = {
@@ -7,15 +7,17 @@ use tracing::{error, info, warn};
 
 use crate::config::ServiceConfig;
 use account_service::{AccountService, AccountServiceConfig};
-use equity_service::{EquityValuationService, EquityServiceConfig};
+use equity_service::{ContestService, EquityValuationService, EquityServiceConfig};
 use execution_manager::ExecutionManager;
-use market_maker::{MarketMakerService, MarketMakerConfig};
+use fair_price_bus::FairPriceBus;
+use market_maker::{KillSwitch, MarketMakerService, MarketMakerConfig};
 use order_gateway::{GatewayConfig, OrderGateway};
 use order_router::OrderRouter;
 use persistence::PersistenceBackend;
 use player_registry::PlayerRegistry;
 use simulation_clock::SimulationClock;
 use symbol_coordinator::{SymbolCoordinator, SymbolCoordinatorApi};
+use trading_calendar::{SessionScheduler, TradingCalendar};
 
 /// Service state containing all initialized components
 pub struct ServiceState {
@@ -46,12 +48,24 @@ pub struct ServiceState {
     /// EquityValuationService instance
     pub equity_service: Arc<EquityValuationService>,
 
+    /// Contests ranked on EVS equity updates
+    pub contest_service: Arc<ContestService>,
+
     /// PlayerRegistry instance
     pub player_registry: Arc<RwLock<Option<PlayerRegistry>>>,
 
     /// MarketMakerService instance
     pub market_maker: Arc<RwLock<Option<MarketMakerService>>>,
 
+    /// Pulls every market maker quote while engaged
+    pub market_maker_kill_switch: KillSwitch,
+
+    /// Fair prices pushed by the RPE
+    pub fair_price_bus: FairPriceBus,
+
+    /// Trading calendar, if `WAIVER_CALENDAR_FILE` is set; without one the market stays open
+    pub session_scheduler: Option<SessionScheduler>,
+
     /// Service running state
     pub is_running: Arc<RwLock<bool>>,
 }
@@ -105,6 +119,13 @@ impl ServiceState {
         );
         info!("EquityValuationService initialized successfully");
 
+        // Load open contests
+        let contest_svc = Arc::new(
+            ContestService::load(equity_svc.db_pool().clone())
+                .await
+                .context("Failed to load contests")?
+        );
+
         // Initialize ExecutionManager with persistence integration
         info!("Initializing ExecutionManager...");
         let mut execution_manager = ExecutionManager::new_with_persistence(
@@ -133,7 +154,7 @@ impl ServiceState {
 
         // Initialize SimulationClock
         info!("Initializing SimulationClock...");
-        let simulation_clock = SimulationClock::new(
+        let mut simulation_clock = SimulationClock::new(
             symbol_coordinator.clone(),
             execution_manager.clone(),
             order_router.clone(),
@@ -153,16 +174,49 @@ impl ServiceState {
             .map_err(|e| anyhow::anyhow!("Failed to load player data: {}", e))?;
         info!("PlayerRegistry loaded with {} players", player_registry.symbol_count());
 
+        // Initialize the trading calendar, if one is configured
+        let session_scheduler = match trading_calendar::calendar_file_from_env() {
+            Some(path) => {
+                info!("Loading trading calendar from: {:?}", path);
+                let calendar = TradingCalendar::from_file(&path)
+                    .with_context(|| format!("Failed to load trading calendar {path:?}"))?;
+                let scheduler = SessionScheduler::new(calendar);
+                scheduler.set_symbol_teams(
+                    player_registry
+                        .get_all_symbols()
+                        .into_iter()
+                        .map(|symbol| (symbol.symbol_id, symbol.team.clone()))
+                        .collect(),
+                );
+                simulation_clock.set_session_scheduler(scheduler.clone());
+                Some(scheduler)
+            }
+            None => {
+                info!("No trading calendar configured, the market stays open");
+                None
+            }
+        };
+
         // Initialize OrderGateway
         info!("Initializing OrderGateway...");
         let mut gateway_config = GatewayConfig::default();
         gateway_config.server.port = 8081; // Use port 8081 to avoid conflict with pgAdmin
-        let order_gateway = OrderGateway::new(
+        let mut order_gateway = OrderGateway::new(
             gateway_config,
             symbol_coordinator.clone(),
             player_registry,
             account_svc.clone()
         );
+        order_gateway.set_execution_manager(execution_manager.clone());
+        order_gateway.set_equity_service(equity_svc.clone());
+        order_gateway.set_contest_service(contest_svc.clone());
+
+        // Fair prices pushed by the RPE (see `start_fair_price_bus`)
+        let fair_price_bus = FairPriceBus::new();
+        order_gateway.set_fair_price_bus(fair_price_bus.clone());
+        if let Some(scheduler) = &session_scheduler {
+            order_gateway.set_session_scheduler(scheduler.clone());
+        }
 
         let service_state = Self {
             config,
@@ -174,8 +228,12 @@ impl ServiceState {
             order_gateway: Arc::new(RwLock::new(Some(order_gateway))),
             account_service: account_svc,
             equity_service: equity_svc,
+            contest_service: contest_svc,
             player_registry: Arc::new(RwLock::new(None)), // PlayerRegistry is now owned by OrderGateway
             market_maker: Arc::new(RwLock::new(None)), // MarketMaker will be initialized later
+            market_maker_kill_switch: KillSwitch::new(),
+            fair_price_bus,
+            session_scheduler,
             is_running: Arc::new(RwLock::new(false)),
         };
 
@@ -269,7 +327,7 @@ impl ServiceState {
     pub async fn recreate_simulation_clock(&self, initial_tick: u64) -> Result<()> {
         info!("Recreating SimulationClock with initial tick: {}", initial_tick);
 
-        let new_clock = SimulationClock::new_with_initial_tick(
+        let mut new_clock = SimulationClock::new_with_initial_tick(
             self.symbol_coordinator.clone(),
             self.execution_manager.clone(),
             self.order_router.clone(),
@@ -278,6 +336,9 @@ impl ServiceState {
             initial_tick,
         )
         .context("Failed to recreate SimulationClock")?;
+        if let Some(scheduler) = &self.session_scheduler {
+            new_clock.set_session_scheduler(scheduler.clone());
+        }
 
         let mut clock_guard = self.simulation_clock.write().await;
         *clock_guard = Some(new_clock);
@@ -366,6 +427,75 @@ impl ServiceState {
         Ok(())
     }
 
+    /// Start receiving fair prices and applying them to price bands and equity valuation
+    ///
+    /// RPE processes in other processes reach the bus through the Unix socket named by
+    /// `FAIR_PRICE_BUS_SOCKET`; without it only in-process publishers are heard.
+    pub async fn start_fair_price_bus(&self) -> Result<()> {
+        if let Some(path) = fair_price_bus::socket_from_env() {
+            let bus = self.fair_price_bus.clone();
+            tokio::spawn(async move {
+                if let Err(e) = fair_price_bus::serve_unix(bus, &path).await {
+                    error!("Fair price bus socket {} failed: {}", path.display(), e);
+                }
+            });
+        } else {
+            info!("FAIR_PRICE_BUS_SOCKET not set, fair price bus is in-process only");
+        }
+
+        let mut subscription = self.fair_price_bus.subscribe();
+        let symbol_coordinator = self.symbol_coordinator.clone();
+        let equity_service = self.equity_service.clone();
+        tokio::spawn(async move {
+            while let Some(update) = subscription.recv().await {
+                // Bands are measured from the fair price; out-of-range prices disable them
+                let reference = whistle::Price::try_from(update.fair_cents).ok();
+                symbol_coordinator.set_reference_price(update.symbol_id, reference);
+                equity_service.update_fair_price(update.symbol_id, update.fair_cents).await;
+            }
+        });
+
+        info!("Fair price bus started");
+        Ok(())
+    }
+
+    /// Feed ExecutionManager events to the EquityValuationService
+    ///
+    /// Book deltas maintain the book mids used by `book_mid` and `blend` marks; trades and
+    /// tick completions already reach it as a post-settlement callback.
+    pub async fn start_equity_marks(&self) -> Result<()> {
+        let mut events = self.execution_manager.subscribe_events();
+        let equity_service = self.equity_service.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = equity_service.process_event(event).await {
+                            warn!("Equity valuation failed to process event: {}", e);
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Equity valuation lagged, skipped {} events", skipped);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        info!("Equity valuation subscribed to execution events");
+        Ok(())
+    }
+
+    /// Rank contest entrants on equity updates; contests start, publish their leaderboards
+    /// and finalize once a second
+    pub async fn start_contests(&self) -> Result<()> {
+        let updates = self.equity_service.subscribe_updates();
+        self.contest_service.clone().start(updates, std::time::Duration::from_secs(1));
+
+        info!("Contests subscribed to equity updates");
+        Ok(())
+    }
+
     /// Start the MarketMaker service
     pub async fn start_market_maker(&self) -> Result<()> {
         info!("Starting MarketMaker service...");
@@ -378,6 +508,8 @@ impl ServiceState {
         let mut market_maker_service = MarketMakerService::new(market_maker_config)
             .await
             .context("Failed to create MarketMaker service")?;
+        market_maker_service.set_fair_price_bus(&self.fair_price_bus);
+        market_maker_service.set_kill_switch(self.market_maker_kill_switch.clone());
 
         // Start the market maker in a separate task
         tokio::spawn(async move {
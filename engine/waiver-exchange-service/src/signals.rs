@@ -72,6 +72,9 @@ pub async fn graceful_shutdown(
 ) -> Result<()> {
     info!("Starting graceful shutdown...");
 
+    // Pull the market maker's quotes while the gateway can still cancel them
+    service_state.market_maker_kill_switch.engage("service shutdown");
+
     // Stop the simulation clock
     if let Err(e) = service_state.stop_simulation_clock().await {
         error!("Failed to stop simulation clock: {}", e);
@@ -0,0 +1,112 @@
+//! OrderRouter scaling across shards
+//!
+//! N threads each route to their own symbol, as concurrent gateway connections trading
+//! different players do. With one shard every route takes the same lock; with a shard per
+//! thread the routes never contend, so throughput should grow with the thread count.
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use order_router::{
+    CoordError, InboundMsgWithSymbol, OrderQueueWriter, OrderRouter, ReadyAtTick, RouterConfig,
+    SymbolCoordinatorApi,
+};
+use std::sync::{Arc, Barrier, Mutex};
+use std::time::{Duration, Instant};
+use whistle::{InboundMsg, InboundQueue, OrderType, Side};
+
+/// Messages each thread routes per iteration
+const MESSAGES_PER_THREAD: u64 = 10_000;
+
+/// Hands out queues deep enough for one iteration and keeps them so they can be drained
+#[derive(Clone, Default)]
+struct QueueCoordinator {
+    queues: Arc<Mutex<Vec<Arc<InboundQueue>>>>,
+}
+
+impl SymbolCoordinatorApi for QueueCoordinator {
+    fn ensure_active(&self, _symbol_id: u32) -> Result<ReadyAtTick, CoordError> {
+        let queue = Arc::new(InboundQueue::new(2 * MESSAGES_PER_THREAD as usize));
+        self.queues.lock().unwrap().push(queue.clone());
+        Ok(ReadyAtTick { next_tick: 0, queue_writer: OrderQueueWriter { queue } })
+    }
+
+    fn release_if_idle(&self, _symbol_id: u32) {}
+}
+
+fn order(symbol_id: u32, order_id: u64) -> InboundMsgWithSymbol {
+    InboundMsgWithSymbol {
+        symbol_id,
+        msg: InboundMsg::submit(
+            order_id,
+            1,
+            Side::Buy,
+            OrderType::Limit,
+            Some(150),
+            10,
+            1000,
+            0,
+            0,
+        ),
+    }
+}
+
+/// Route `MESSAGES_PER_THREAD` orders from each of `threads` threads, one symbol per thread
+fn route_concurrently(router: &Arc<OrderRouter>, threads: u32) -> Duration {
+    let start_line = Arc::new(Barrier::new(threads as usize + 1));
+    let handles: Vec<_> = (0..threads)
+        .map(|symbol_id| {
+            let router = router.clone();
+            let start_line = start_line.clone();
+            std::thread::spawn(move || {
+                start_line.wait();
+                for order_id in 0..MESSAGES_PER_THREAD {
+                    black_box(router.route(100, order(symbol_id, order_id))).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    start_line.wait();
+    let start = Instant::now();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn bench_router_sharding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("router_sharding");
+
+    for threads in [1u32, 2, 4, 8] {
+        group.throughput(Throughput::Elements(threads as u64 * MESSAGES_PER_THREAD));
+
+        // One lock for everyone vs a shard per thread
+        let mut shard_counts = vec![1, threads];
+        shard_counts.dedup();
+        for num_shards in shard_counts {
+            let coordinator = QueueCoordinator::default();
+            let mut router = OrderRouter::new(RouterConfig { num_shards, ..Default::default() });
+            router.set_coordinator(Box::new(coordinator.clone()));
+            let router = Arc::new(router);
+
+            let id = format!("{threads}_threads/{num_shards}_shards");
+            group.bench_function(id, |b| {
+                b.iter_custom(|iters| {
+                    let mut elapsed = Duration::ZERO;
+                    for _ in 0..iters {
+                        elapsed += route_concurrently(&router, threads);
+                        // Play the engines: empty the queues outside the measurement
+                        for queue in coordinator.queues.lock().unwrap().iter() {
+                            queue.clear_lockfree();
+                        }
+                    }
+                    elapsed
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_router_sharding);
+criterion_main!(benches);
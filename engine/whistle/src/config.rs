@@ -10,6 +10,18 @@ pub struct Bands {
     pub mode: BandMode,
 }
 
+impl Bands {
+    /// Whether `price` lies within the band around `reference` (percent is in basis points)
+    #[inline]
+    pub fn contains(&self, price: Price, reference: Price) -> bool {
+        let width = match self.mode {
+            BandMode::Abs(abs) => abs,
+            BandMode::Percent(bp) => ((reference as u64 * bp as u64) / 10_000) as Price,
+        };
+        price >= reference.saturating_sub(width) && price <= reference.saturating_add(width)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SelfMatchPolicy {
     Skip,
@@ -54,6 +54,7 @@ pub enum RejectReason {
     UnknownOrder = 12,     // cancel for non-existent
     SelfMatchBlocked = 13, // if policy=prevent on submit
     MarketHalted = 14,
+    QueueClosed = 15, // symbol hibernated; re-activate and resend
 }
 
 #[derive(Debug, Clone, Copy)]
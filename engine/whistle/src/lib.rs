@@ -28,7 +28,10 @@ pub use order_index::OrderIndex;
 pub use outbound_queue::{BackpressurePolicy, OutboundQueue};
 pub use price_domain::{Price, PriceDomain, PriceIdx};
 pub use queue::InboundQueue;
-pub use types::{AccountId, EnqSeq, H_NONE, OrderHandle, OrderId, OrderType, Qty, Side, TsNorm};
+pub use types::{
+    AccountId, EnqSeq, H_NONE, OrderHandle, OrderId, OrderType, Qty, RestingOrder, Side,
+    TradingState, TsNorm,
+};
 
 pub type TickId = u64;
 
@@ -47,6 +50,7 @@ pub struct Whistle {
 
     // State tracking
     reference_price: Option<Price>, // Current reference price for bands
+    trading_state: TradingState,    // Session state; halted/pre-open restrict admission
     modified_levels: std::collections::HashSet<(Side, PriceIdx)>, // Track levels modified during tick
 
     // Trade tracking
@@ -79,6 +83,7 @@ impl Whistle {
             book,
             order_index,
             reference_price: None,
+            trading_state: TradingState::Open,
             modified_levels: std::collections::HashSet::new(),
             last_trade_price: None,
             last_trade_quantity: None,
@@ -112,6 +117,7 @@ impl Whistle {
             book,
             order_index,
             reference_price: None,
+            trading_state: TradingState::Open,
             modified_levels: std::collections::HashSet::new(),
             last_trade_price: None,
             last_trade_quantity: None,
@@ -144,6 +150,7 @@ impl Whistle {
             book,
             order_index,
             reference_price: None,
+            trading_state: TradingState::Open,
             modified_levels: std::collections::HashSet::new(),
             last_trade_price: None,
             last_trade_quantity: None,
@@ -475,6 +482,122 @@ impl Whistle {
         tracing::info!("Order book state restored successfully");
     }
 
+    /// Every order resting in the book: bids best first, then asks best first, each level
+    /// in time priority
+    pub fn resting_orders(&self) -> Vec<RestingOrder> {
+        let mut orders = Vec::new();
+
+        let mut bid = self.book.best_bid();
+        while let Some(price_idx) = bid {
+            self.collect_level(Side::Buy, price_idx, &mut orders);
+            bid = price_idx.checked_sub(1).and_then(|i| self.book.prev_bid_at_or_below(i));
+        }
+
+        let mut ask = self.book.best_ask();
+        while let Some(price_idx) = ask {
+            self.collect_level(Side::Sell, price_idx, &mut orders);
+            ask = self.book.next_ask_at_or_above(price_idx + 1);
+        }
+
+        orders
+    }
+
+    fn collect_level(&self, side: Side, price_idx: PriceIdx, orders: &mut Vec<RestingOrder>) {
+        let mut handle = self.book.level_head(side, price_idx);
+        while handle != H_NONE {
+            let order = self.arena.get(handle);
+            orders.push(RestingOrder {
+                order_id: order.id,
+                account_id: order.acct,
+                side: order.side,
+                price: self.dom.price(order.price_idx),
+                qty_open: order.qty_open,
+                ts_norm: order.ts_norm,
+                enq_seq: order.enq_seq,
+                order_type: order.typ,
+            });
+            handle = order.next;
+        }
+    }
+
+    /// Replace the book with `orders`, keeping their order as time priority within a level
+    ///
+    /// Unlike `restore_order_book_state` this keeps order IDs and accounts, so fills after a
+    /// restore are attributed to the original orders. Orders outside the price domain or
+    /// beyond the arena capacity are dropped; returns how many were restored.
+    pub fn restore_resting_orders(&mut self, orders: &[RestingOrder]) -> usize {
+        self.book = Book::new(self.dom);
+        self.order_index = OrderIndex::with_capacity_pow2(self.cfg.arena_capacity as usize * 2);
+        self.arena = Arena::with_capacity(self.cfg.arena_capacity);
+
+        let mut restored = 0;
+        for resting in orders {
+            let Some(price_idx) = self.dom.idx(resting.price) else {
+                tracing::warn!(
+                    "Symbol {}: dropping resting order {} outside the price domain",
+                    self.cfg.symbol,
+                    resting.order_id
+                );
+                continue;
+            };
+            let Some(handle) = self.arena.alloc() else {
+                tracing::warn!(
+                    "Symbol {}: arena full, dropping {} resting orders",
+                    self.cfg.symbol,
+                    orders.len() - restored
+                );
+                break;
+            };
+
+            *self.arena.get_mut(handle) = Order {
+                id: resting.order_id,
+                acct: resting.account_id,
+                side: resting.side,
+                price_idx,
+                qty_open: resting.qty_open,
+                ts_norm: resting.ts_norm,
+                enq_seq: resting.enq_seq,
+                typ: resting.order_type,
+                ..Default::default()
+            };
+            self.book.insert_tail(&mut self.arena, resting.side, handle, price_idx, resting.qty_open);
+            restored += 1;
+        }
+        restored
+    }
+
+    /// Restore the last trade reported by `get_last_trade_info`
+    pub fn set_last_trade_info(
+        &mut self,
+        price: Option<u64>,
+        quantity: Option<u64>,
+        timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    ) {
+        self.last_trade_price = price;
+        self.last_trade_quantity = quantity;
+        self.last_trade_timestamp = timestamp;
+    }
+
+    /// Set the reference price that price bands are measured from (`None` disables bands)
+    pub fn set_reference_price(&mut self, price: Option<Price>) {
+        self.reference_price = price;
+    }
+
+    /// Current reference price for bands
+    pub fn reference_price(&self) -> Option<Price> {
+        self.reference_price
+    }
+
+    /// Set the session state; takes effect for messages processed from the next tick
+    pub fn set_trading_state(&mut self, state: TradingState) {
+        self.trading_state = state;
+    }
+
+    /// Current session state
+    pub fn trading_state(&self) -> TradingState {
+        self.trading_state
+    }
+
     /// Get the latest trade information
     pub fn get_last_trade_info(
         &self,
@@ -497,16 +620,38 @@ impl Whistle {
             MsgKind::Submit => {
                 let submit = msg.submit.as_ref().unwrap();
 
+                // Market state: nothing is admitted while halted, and nothing may trade
+                // during pre-open
+                match self.trading_state {
+                    TradingState::Open => {}
+                    TradingState::Halted => return Err(RejectReason::MarketHalted),
+                    TradingState::PreOpen => match submit.typ {
+                        OrderType::Market => return Err(RejectReason::MarketDisallowed),
+                        OrderType::Ioc => return Err(RejectReason::IocDisallowed),
+                        OrderType::Limit | OrderType::PostOnly => {}
+                    },
+                }
+
                 // Basic validation
                 if let Some(price) = submit.price {
                     // Check tick size alignment and price domain
                     if self.dom.idx(price).is_none() {
                         return Err(RejectReason::BadTick);
                     }
+
+                    // Bands apply once a reference price has been published
+                    if let Some(reference) = self.reference_price {
+                        if !self.cfg.bands.contains(price, reference) {
+                            return Err(RejectReason::OutOfBand);
+                        }
+                    }
                 }
 
-                // POST-ONLY cross prevention check
-                if submit.typ == OrderType::PostOnly {
+                // POST-ONLY cross prevention check; during pre-open every limit is post-only
+                let post_only = submit.typ == OrderType::PostOnly
+                    || (submit.typ == OrderType::Limit
+                        && self.trading_state == TradingState::PreOpen);
+                if post_only {
                     if let Some(price) = submit.price {
                         if let Some(price_idx) = self.dom.idx(price) {
                             if self.would_cross(submit.side, price_idx) {
@@ -573,7 +718,10 @@ impl Whistle {
             let (order_id, order_side, order_price_idx, order_qty, order_typ, order_acct) =
                 order_data;
 
-            if order_typ == 3 && self.would_cross(order_side, order_price_idx) {
+            // Nothing may trade during pre-open, so every limit behaves as POST-ONLY
+            let post_only =
+                order_typ == 3 || (order_typ == 0 && self.trading_state == TradingState::PreOpen);
+            if post_only && self.would_cross(order_side, order_price_idx) {
                 rejections.push((order_id, RejectReason::PostOnlyCross));
                 self.arena.free(*handle);
                 continue;
@@ -1657,4 +1805,141 @@ mod tests {
         let (len, _) = eng.outbound_queue_stats();
         assert_eq!(len, 0);
     }
+
+    #[test]
+    fn resting_orders_roundtrip() {
+        let cfg = EngineCfg {
+            symbol: 42,
+            price_domain: PriceDomain { floor: 100, ceil: 200, tick: 5 },
+            bands: Bands { mode: BandMode::Percent(1000) },
+            batch_max: 1024,
+            arena_capacity: 4096,
+            elastic_arena: false,
+            exec_shift_bits: 12,
+            exec_id_mode: ExecIdMode::Sharded,
+            self_match_policy: SelfMatchPolicy::Skip,
+            allow_market_cold_start: false,
+            reference_price_source: ReferencePriceSource::SnapshotLastTrade,
+        };
+        let mut eng = Whistle::new(cfg);
+
+        for msg in [
+            InboundMsg::submit(1, 7, Side::Buy, OrderType::Limit, Some(150), 10, 1000, 0, 1),
+            InboundMsg::submit(2, 8, Side::Buy, OrderType::Limit, Some(150), 20, 1001, 0, 2),
+            InboundMsg::submit(3, 7, Side::Buy, OrderType::Limit, Some(140), 5, 1002, 0, 3),
+            InboundMsg::submit(4, 9, Side::Sell, OrderType::Limit, Some(170), 15, 1003, 0, 4),
+        ] {
+            eng.enqueue_message(msg).unwrap();
+        }
+        eng.tick(100);
+
+        let resting = eng.resting_orders();
+        let ids: Vec<_> = resting.iter().map(|o| (o.order_id, o.account_id, o.price)).collect();
+        assert_eq!(ids, vec![(1, 7, 150), (2, 8, 150), (3, 7, 140), (4, 9, 170)]);
+
+        let mut restored = Whistle::new(cfg);
+        assert_eq!(restored.restore_resting_orders(&resting), 4);
+        assert_eq!(restored.resting_orders(), resting);
+        assert_eq!(restored.get_order_book_levels(Side::Buy), vec![(150, 30), (140, 5)]);
+        assert_eq!(restored.get_order_book_levels(Side::Sell), vec![(170, 15)]);
+    }
+
+    #[test]
+    fn trading_state_restricts_admission() {
+        let cfg = EngineCfg {
+            symbol: 42,
+            price_domain: PriceDomain { floor: 100, ceil: 200, tick: 5 },
+            bands: Bands { mode: BandMode::Percent(1000) },
+            batch_max: 1024,
+            arena_capacity: 4096,
+            elastic_arena: false,
+            exec_shift_bits: 12,
+            exec_id_mode: ExecIdMode::Sharded,
+            self_match_policy: SelfMatchPolicy::Skip,
+            allow_market_cold_start: false,
+            reference_price_source: ReferencePriceSource::SnapshotLastTrade,
+        };
+        let mut eng = Whistle::new(cfg);
+        assert_eq!(eng.trading_state(), TradingState::Open);
+
+        fn rejections(events: &[EngineEvent]) -> Vec<(OrderId, Option<RejectReason>)> {
+            events
+                .iter()
+                .filter_map(|e| if let EngineEvent::Lifecycle(ev) = e { Some(ev) } else { None })
+                .filter(|ev| ev.kind == LifecycleKind::Rejected)
+                .map(|ev| (ev.order_id, ev.reason))
+                .collect()
+        }
+
+        // Pre-open: resting limits are admitted, anything that would trade is not
+        eng.set_trading_state(TradingState::PreOpen);
+        eng.enqueue_message(InboundMsg::submit(
+            1,
+            1,
+            Side::Sell,
+            OrderType::Limit,
+            Some(150),
+            10,
+            1000,
+            0,
+            1,
+        ))
+        .unwrap();
+        assert!(rejections(&eng.tick(99)).is_empty());
+
+        for msg in [
+            InboundMsg::submit(2, 2, Side::Buy, OrderType::Limit, Some(150), 10, 1001, 0, 2),
+            InboundMsg::submit(3, 2, Side::Buy, OrderType::Market, None, 10, 1002, 0, 3),
+            InboundMsg::submit(4, 2, Side::Buy, OrderType::Ioc, Some(150), 10, 1003, 0, 4),
+            InboundMsg::submit(5, 2, Side::Buy, OrderType::Limit, Some(140), 10, 1004, 0, 5),
+        ] {
+            eng.enqueue_message(msg).unwrap();
+        }
+        let events = eng.tick(100);
+        assert_eq!(
+            rejections(&events),
+            vec![
+                (2, Some(RejectReason::PostOnlyCross)),
+                (3, Some(RejectReason::MarketDisallowed)),
+                (4, Some(RejectReason::IocDisallowed)),
+            ]
+        );
+        assert!(!events.iter().any(|e| matches!(e, EngineEvent::Trade(_))));
+        assert_eq!(eng.get_order_book_levels(Side::Buy), vec![(140, 10)]);
+
+        // Halted: every submit is rejected
+        eng.set_trading_state(TradingState::Halted);
+        eng.enqueue_message(InboundMsg::submit(
+            6,
+            2,
+            Side::Buy,
+            OrderType::Limit,
+            Some(130),
+            10,
+            1005,
+            0,
+            6,
+        ))
+        .unwrap();
+        let events = eng.tick(101);
+        assert_eq!(rejections(&events), vec![(6, Some(RejectReason::MarketHalted))]);
+
+        // Open again: crossing orders trade
+        eng.set_trading_state(TradingState::Open);
+        eng.enqueue_message(InboundMsg::submit(
+            7,
+            2,
+            Side::Buy,
+            OrderType::Limit,
+            Some(150),
+            10,
+            1006,
+            0,
+            7,
+        ))
+        .unwrap();
+        let events = eng.tick(102);
+        assert!(rejections(&events).is_empty());
+        assert!(events.iter().any(|e| matches!(e, EngineEvent::Trade(_))));
+    }
 }
@@ -3,6 +3,9 @@
 use crate::{InboundMsg, RejectReason};
 use std::cell::UnsafeCell;
 
+/// Bit of `tail` set once the queue is closed, so a close and an enqueue cannot interleave
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
 /// SPSC ring buffer for inbound messages from OrderRouter
 ///
 /// This implements a lock-free queue optimized for the order processing pipeline:
@@ -12,6 +15,7 @@ use std::cell::UnsafeCell;
 /// - Fixed capacity to prevent unbounded memory growth
 /// - Atomic operations for thread safety
 /// - Interior mutability for lock-free access
+/// - Closable: once its symbol is hibernated, enqueues fail with `QueueClosed`
 #[derive(Debug)]
 pub struct InboundQueue {
     buffer: Box<[UnsafeCell<Option<InboundMsg>>]>,
@@ -56,14 +60,13 @@ impl InboundQueue {
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.head.load(std::sync::atomic::Ordering::Acquire)
-            == self.tail.load(std::sync::atomic::Ordering::Acquire)
+        self.head.load(std::sync::atomic::Ordering::Acquire) == self.tail_index()
     }
 
     #[inline]
     pub fn is_full(&self) -> bool {
         let head = self.head.load(std::sync::atomic::Ordering::Acquire);
-        let tail = self.tail.load(std::sync::atomic::Ordering::Acquire);
+        let tail = self.tail_index();
 
         (tail + 1) & self.mask == head
     }
@@ -71,7 +74,7 @@ impl InboundQueue {
     #[inline]
     pub fn len(&self) -> usize {
         let head = self.head.load(std::sync::atomic::Ordering::Acquire);
-        let tail = self.tail.load(std::sync::atomic::Ordering::Acquire);
+        let tail = self.tail_index();
 
         if tail >= head { tail - head } else { self.capacity - (head - tail) }
     }
@@ -95,6 +98,9 @@ impl InboundQueue {
     #[inline]
     pub fn try_enqueue_lockfree(&self, msg: InboundMsg) -> Result<(), RejectReason> {
         let tail = self.tail.load(std::sync::atomic::Ordering::Acquire);
+        if tail & CLOSED != 0 {
+            return Err(RejectReason::QueueClosed);
+        }
         let next_tail = (tail + 1) & self.mask;
         let head = self.head.load(std::sync::atomic::Ordering::Acquire);
 
@@ -115,6 +121,7 @@ impl InboundQueue {
                 }
                 Ok(())
             }
+            Err(current) if current & CLOSED != 0 => Err(RejectReason::QueueClosed),
             Err(_) => {
                 // Another thread modified tail, retry or fail
                 Err(RejectReason::QueueBackpressure)
@@ -122,6 +129,30 @@ impl InboundQueue {
         }
     }
 
+    /// Tail position without the closed bit
+    #[inline]
+    fn tail_index(&self) -> usize {
+        self.tail.load(std::sync::atomic::Ordering::Acquire) & !CLOSED
+    }
+
+    /// Stop accepting messages; messages already enqueued stay dequeueable
+    ///
+    /// Every enqueue either completes before the close (and is counted by `len()` once it
+    /// returns) or fails with `QueueClosed`.
+    pub fn close(&self) {
+        self.tail.fetch_or(CLOSED, std::sync::atomic::Ordering::AcqRel);
+    }
+
+    /// Accept messages again
+    pub fn reopen(&self) {
+        self.tail.fetch_and(!CLOSED, std::sync::atomic::Ordering::AcqRel);
+    }
+
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.tail.load(std::sync::atomic::Ordering::Acquire) & CLOSED != 0
+    }
+
     /// Try to dequeue a message (consumer operation)
     ///
     /// Returns:
@@ -132,7 +163,7 @@ impl InboundQueue {
     #[inline]
     pub fn try_dequeue(&mut self) -> Option<InboundMsg> {
         let head = self.head.load(std::sync::atomic::Ordering::Acquire);
-        let tail = self.tail.load(std::sync::atomic::Ordering::Acquire);
+        let tail = self.tail_index();
 
         if head == tail {
             return None;
@@ -159,7 +190,7 @@ impl InboundQueue {
     #[inline]
     pub fn try_dequeue_lockfree(&self) -> Option<InboundMsg> {
         let head = self.head.load(std::sync::atomic::Ordering::Acquire);
-        let tail = self.tail.load(std::sync::atomic::Ordering::Acquire);
+        let tail = self.tail_index();
 
         if head == tail {
             return None;
@@ -351,4 +382,25 @@ mod tests {
         assert!(queue.is_empty());
         assert_eq!(queue.len(), 0);
     }
+
+    #[test]
+    fn test_closed_queue_rejects_until_reopened() {
+        let queue = InboundQueue::new(4);
+        let msg = |id| InboundMsg::submit(id, 1, Side::Buy, OrderType::Limit, Some(100), 10, 1000, 0, 0);
+
+        assert!(queue.try_enqueue_lockfree(msg(1)).is_ok());
+        queue.close();
+        assert!(queue.is_closed());
+        assert_eq!(queue.try_enqueue_lockfree(msg(2)), Err(RejectReason::QueueClosed));
+
+        // Messages enqueued before the close are still there
+        assert_eq!(queue.len(), 1);
+        assert!(queue.try_dequeue_lockfree().is_some());
+        assert!(queue.is_empty());
+
+        queue.reopen();
+        assert!(!queue.is_closed());
+        assert!(queue.try_enqueue_lockfree(msg(3)).is_ok());
+        assert_eq!(queue.len(), 1);
+    }
 }
@@ -24,6 +24,19 @@ impl Side {
     }
 }
 
+/// An order resting in the book, as exported for hibernation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RestingOrder {
+    pub order_id: OrderId,
+    pub account_id: AccountId,
+    pub side: Side,
+    pub price: crate::Price,
+    pub qty_open: Qty,
+    pub ts_norm: TsNorm,
+    pub enq_seq: EnqSeq,
+    pub order_type: u8,
+}
+
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OrderType {
@@ -33,6 +46,18 @@ pub enum OrderType {
     PostOnly = 3,
 }
 
+/// Session state of a symbol's market, set by the session scheduler between ticks
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TradingState {
+    /// Normal continuous trading
+    #[default]
+    Open,
+    /// Orders may rest but nothing trades: MARKET/IOC and crossing limits are rejected
+    PreOpen,
+    /// Every submit is rejected with `MarketHalted`
+    Halted,
+}
+
 #[repr(transparent)]
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct OrderHandle(pub u32);
@@ -0,0 +1,36 @@
+-- Phase 6: Persistent API keys
+-- Replaces the hard-coded ApiKeyStore test keys with per-account keys that are
+-- hashed at rest, scoped to permissions, and optionally pinned to IPs/symbols
+
+-- ============================================================================
+-- 1. api_keys — Programmatic credentials for the order gateway
+-- ============================================================================
+
+CREATE TABLE api_keys (
+  id                      BIGSERIAL PRIMARY KEY,
+  key_id                  TEXT NOT NULL UNIQUE,      -- public identifier, 'ak_...'
+  account_id              BIGINT NOT NULL REFERENCES accounts(id),
+  label                   TEXT NOT NULL DEFAULT '',
+  secret_hash             TEXT NOT NULL,             -- hex SHA-256 of the secret; also the HMAC signing key
+  permissions             TEXT[] NOT NULL DEFAULT ARRAY['trade', 'market_data'],
+  allowed_ips             TEXT[] NOT NULL DEFAULT '{}',  -- empty = any; entries are IPs or CIDR blocks
+  allowed_symbols         INT[],                     -- NULL = any symbol
+  orders_per_second       INT NOT NULL DEFAULT 100,
+  market_data_per_second  INT NOT NULL DEFAULT 1000,
+  burst_limit             INT NOT NULL DEFAULT 10,
+  created_at              TIMESTAMPTZ NOT NULL DEFAULT now(),
+  rotated_at              TIMESTAMPTZ,
+  last_used_at            TIMESTAMPTZ,
+  expires_at              TIMESTAMPTZ,
+  revoked_at              TIMESTAMPTZ
+);
+
+CREATE INDEX idx_api_keys_account ON api_keys (account_id);
+CREATE INDEX idx_api_keys_active ON api_keys (key_id) WHERE revoked_at IS NULL;
+
+-- ============================================================================
+-- 2. Row Level Security
+-- ============================================================================
+
+-- api_keys: no client access at all; the gateway and admin endpoints use the service role
+ALTER TABLE api_keys ENABLE ROW LEVEL SECURITY;
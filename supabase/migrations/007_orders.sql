@@ -0,0 +1,41 @@
+-- Phase 7: Persistent order records
+-- Every order accepted by the gateway is recorded here so client_order_id
+-- submissions can be deduplicated across gateway restarts and order status
+-- can be queried after the fact
+
+-- ============================================================================
+-- 1. orders — One row per order routed to the matching engine
+-- ============================================================================
+
+CREATE TABLE orders (
+  order_id         BIGINT PRIMARY KEY,            -- engine order ID (also used for reservations)
+  account_id       BIGINT NOT NULL REFERENCES accounts(id),
+  symbol_id        INT NOT NULL,
+  client_order_id  TEXT,                          -- optional client-supplied idempotency key
+  side             TEXT NOT NULL,                 -- 'BUY' | 'SELL'
+  order_type       TEXT NOT NULL,                 -- 'LIMIT' | 'MARKET' | 'IOC' | 'POST_ONLY'
+  price            BIGINT NOT NULL,               -- cents (0 for market orders)
+  quantity         BIGINT NOT NULL,
+  filled_quantity  BIGINT NOT NULL DEFAULT 0,
+  status           TEXT NOT NULL DEFAULT 'PENDING',
+  -- 'PENDING' | 'ACCEPTED' | 'PARTIALLY_FILLED' | 'FILLED' | 'CANCELLED' | 'REJECTED'
+  created_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at       TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_orders_account ON orders (account_id, created_at DESC);
+CREATE INDEX idx_orders_client_order_id ON orders (account_id, client_order_id, created_at DESC)
+  WHERE client_order_id IS NOT NULL;
+CREATE INDEX idx_orders_open ON orders (account_id)
+  WHERE status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED');
+
+-- ============================================================================
+-- 2. Row Level Security
+-- ============================================================================
+
+-- orders: users can read their own orders; writes go through the service role
+ALTER TABLE orders ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users can view own orders"
+  ON orders FOR SELECT
+  USING (account_id = public.get_my_account_id());
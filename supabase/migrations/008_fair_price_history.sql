@@ -0,0 +1,49 @@
+-- Phase 8: Fair price history
+-- rpe_fair_prices only keeps the current price of each player. Every write to it is
+-- also appended here so fills can be compared with the fair prices that followed them
+-- (market maker markouts and P&L attribution)
+
+-- ============================================================================
+-- 1. fair_price_history — One row per fair price published by the RPE
+-- ============================================================================
+
+CREATE TABLE fair_price_history (
+  id          BIGSERIAL PRIMARY KEY,
+  player_id   INT NOT NULL,                 -- same key as rpe_fair_prices.player_id
+  ts          TIMESTAMPTZ NOT NULL,
+  fair_cents  BIGINT NOT NULL,
+  source      TEXT
+);
+
+CREATE INDEX idx_fair_price_history_player_ts ON fair_price_history (player_id, ts);
+
+-- ============================================================================
+-- 2. Append on every rpe_fair_prices insert or update
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION public.record_fair_price_history()
+RETURNS TRIGGER AS $$
+BEGIN
+  INSERT INTO public.fair_price_history (player_id, ts, fair_cents, source)
+  VALUES (NEW.player_id, NEW.ts, NEW.fair_cents, NEW.source);
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS on_rpe_fair_price_written ON rpe_fair_prices;
+CREATE TRIGGER on_rpe_fair_price_written
+  AFTER INSERT OR UPDATE ON rpe_fair_prices
+  FOR EACH ROW
+  EXECUTE FUNCTION public.record_fair_price_history();
+
+-- ============================================================================
+-- 3. Row Level Security
+-- ============================================================================
+
+-- fair_price_history: all authenticated users can read, like rpe_fair_prices
+ALTER TABLE fair_price_history ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Authenticated users can view fair price history"
+  ON fair_price_history FOR SELECT
+  TO authenticated
+  USING (true);
@@ -0,0 +1,14 @@
+-- Phase 9: Order time in force
+-- DAY orders are cancelled when the trading session closes; GTC orders rest until
+-- filled or cancelled. Orders recorded before this migration are GTC
+
+-- ============================================================================
+-- 1. orders.time_in_force
+-- ============================================================================
+
+ALTER TABLE orders
+  ADD COLUMN time_in_force TEXT NOT NULL DEFAULT 'GTC';   -- 'GTC' | 'DAY'
+
+-- Open DAY orders, scanned at every session close
+CREATE INDEX idx_orders_open_day ON orders (order_id)
+  WHERE time_in_force = 'DAY' AND status IN ('PENDING', 'ACCEPTED', 'PARTIALLY_FILLED');
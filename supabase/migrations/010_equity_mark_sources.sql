@@ -0,0 +1,12 @@
+-- Phase 10: Mark sources on equity snapshots
+-- EquityValuationService marks each symbol by a configurable policy (last trade, book mid,
+-- fair price or a staleness-weighted blend). Every snapshot records the source used for
+-- each symbol so equity marked differently can be told apart, e.g. on leaderboards
+
+-- ============================================================================
+-- 1. equity_timeseries.mark_sources
+-- ============================================================================
+
+-- {"<symbol_id>": "last_trade" | "book_mid" | "fair_price" | "blend" | "cost_basis" | "zero"}
+ALTER TABLE equity_timeseries
+  ADD COLUMN IF NOT EXISTS mark_sources JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,22 @@
+-- Phase 12: Salted API key verifiers
+-- The digest stored in api_keys.secret_hash used to double as the HMAC signing key, so
+-- anyone able to read the table could sign requests. Secrets are now derived from a
+-- server-held master key (API_KEY_MASTER_KEY) and a per-key salt, and the table only
+-- keeps the salt and a salted verifier
+
+-- ============================================================================
+-- 1. api_keys.secret_salt
+-- ============================================================================
+
+ALTER TABLE api_keys
+  ADD COLUMN IF NOT EXISTS secret_salt TEXT NOT NULL DEFAULT '';
+
+COMMENT ON COLUMN api_keys.secret_hash IS 'hex HKDF-SHA256 verifier of the secret, salted with secret_salt';
+
+-- ============================================================================
+-- 2. Existing keys
+-- ============================================================================
+
+-- Keys issued before this migration have unsalted digests that are also their signing
+-- keys; they cannot be converted and must be reissued
+UPDATE api_keys SET revoked_at = now() WHERE secret_salt = '' AND revoked_at IS NULL;
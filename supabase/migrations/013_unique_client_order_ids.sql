@@ -0,0 +1,29 @@
+-- Phase 13: Unique client order IDs
+-- client_order_id deduplication used to look the key up and then insert, so two
+-- concurrent submissions could both pass the check and both be routed. Each key in use
+-- now maps to its order in client_order_ids, unique per account; orders are recorded in
+-- the same transaction as their mapping, and a mapping past its expiry is replaced by the
+-- next order that reuses the key. orders.client_order_id keeps every order's key.
+
+-- ============================================================================
+-- 1. client_order_ids — The order a client_order_id is held by
+-- ============================================================================
+
+CREATE TABLE client_order_ids (
+  account_id       BIGINT NOT NULL REFERENCES accounts(id),
+  client_order_id  TEXT NOT NULL,
+  order_id         BIGINT NOT NULL REFERENCES orders(order_id) ON DELETE CASCADE,
+  expires_at       TIMESTAMPTZ NOT NULL,          -- end of the dedupe window
+  PRIMARY KEY (account_id, client_order_id)
+);
+
+-- ============================================================================
+-- 2. Existing keys map to their most recent order, for the default 24h window
+-- ============================================================================
+
+INSERT INTO client_order_ids (account_id, client_order_id, order_id, expires_at)
+SELECT DISTINCT ON (account_id, client_order_id)
+  account_id, client_order_id, order_id, created_at + INTERVAL '24 hours'
+FROM orders
+WHERE client_order_id IS NOT NULL
+ORDER BY account_id, client_order_id, created_at DESC, order_id DESC;